@@ -21,8 +21,8 @@ use tracing::{error, info, instrument, warn};
 
 use super::path_to_socket;
 use crate::agent::errors::AgentError;
-use crate::agent::proto;
 use crate::agent::start::start_daemon;
+use crate::agent::{flyctl, proto};
 use crate::auth::read_access_token;
 use crate::fly_rust::request_builder::RequestBuilderGraphql;
 use crate::state::RdrResult;
@@ -33,6 +33,11 @@ use crate::wireguard::{
 const OK_PREFIX: &[u8] = b"ok ";
 const ERROR_PREFIX: &[u8] = b"err ";
 pub const AGENT_NOT_RUNNING: &str = "agent not running";
+#[cfg(windows)]
+const UNIX_SOCKET_HINT: &str =
+    " (the flyctl agent requires unix socket support, available since Windows 10 version 1803)";
+#[cfg(not(windows))]
+const UNIX_SOCKET_HINT: &str = "";
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct ConfigPeer {
@@ -98,7 +103,7 @@ fn extract_error(data: &[u8]) -> eyre::Error {
 
 //INFO: flyradar-specific version checking for flyctl
 async fn get_current_version() -> RdrResult<String> {
-    let output = Command::new("fly")
+    let output = Command::new(flyctl())
         .arg("version")
         .output()
         .await
@@ -193,13 +198,16 @@ impl Client {
         }
     }
 
+    //INFO: There is no named pipe fallback for older Windows versions. The flyctl agent only
+    // listens on a unix socket, which Windows supports since 10 (1803), and the stream type is
+    // owned by the nats fork as the same stream is handed to it for log streaming.
     async fn dial_context(&self) -> Result<Arc<Mutex<NativeIpcStream>>, io::Error> {
         info!("running dial context.");
         let stream = connect_ipc(&self.address).await.map_err(|e| {
             error!("failed to connect to agent with err: {}", e);
             io::Error::new(
                 io::ErrorKind::Other,
-                format!("failed to connect to agent: {}", e),
+                format!("failed to connect to agent: {}{}", e, UNIX_SOCKET_HINT),
             )
         })?;
         Ok(Arc::new(Mutex::new(stream)))
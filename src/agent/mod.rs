@@ -4,16 +4,45 @@ pub mod proto;
 mod set_sys_proc_attributes;
 pub mod start;
 
+use std::env;
 use std::path::PathBuf;
 
+use lazy_static::lazy_static;
+
 use crate::config::helpers::get_config_directory;
 
+pub const FLYCTL_UNAVAILABLE_MESSAGE: &str =
+    "flyctl is not found in PATH, live log streaming and token refresh are unavailable";
+
+lazy_static! {
+    static ref FLYCTL: Option<&'static str> = find_flyctl();
+}
+
 pub fn path_to_socket() -> PathBuf {
     get_config_directory()
         .expect("Failed to get config directory")
         .join("fly-agent.sock")
 }
 
+/// The agent and the token refresh are run through flyctl, everything else talks to the APIs
+/// directly.
+pub fn flyctl_available() -> bool {
+    FLYCTL.is_some()
+}
+
+/// Name of the flyctl binary found in PATH, installs may have only one of `fly` and `flyctl`.
+pub fn flyctl() -> &'static str {
+    FLYCTL.unwrap_or("fly")
+}
+
+fn find_flyctl() -> Option<&'static str> {
+    let paths = env::var_os("PATH")?;
+    ["fly", "flyctl"].into_iter().find(|name| {
+        let binary = format!("{name}{}", env::consts::EXE_SUFFIX);
+        env::split_paths(&paths).any(|dir| dir.join(&binary).is_file())
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct Instances {
     pub labels: Vec<String>,
@@ -13,7 +13,7 @@ use tokio::process::Command;
 use tracing::{error, info};
 
 use super::client::{default_client, Client};
-use crate::agent::set_sys_proc_attributes;
+use crate::agent::{flyctl, set_sys_proc_attributes};
 use crate::auth::read_access_token;
 use crate::config::helpers::get_config_directory;
 use crate::state::RdrResult;
@@ -121,7 +121,7 @@ pub async fn start_daemon() -> RdrResult<Client> {
     let log_file = create_log_file().await?;
 
     // Prepare command
-    let mut command = Command::new("flyctl");
+    let mut command = Command::new(flyctl());
     command.args(["agent", "run"]);
     command.arg(&log_file);
 
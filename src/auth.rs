@@ -1,35 +1,290 @@
-use color_eyre::eyre;
-use tracing::debug;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
 
-use crate::config::helpers::get_config_file_path;
-use crate::config::{self};
+use color_eyre::eyre::{
+    Context, {self},
+};
+use reqwest::{Client, StatusCode};
+use tokio::process::Command;
+use tracing::{debug, info};
+
+use crate::agent::{flyctl, flyctl_available};
+use crate::config::helpers::{get_config_directory, get_config_file_path};
+use crate::config::{self, DEFAULT_API_BASE_URL};
+use crate::fly_rust::request_builder::SharedAccessToken;
 use crate::state::RdrResult;
 
+const KEYRING_SERVICE: &str = "flyradar";
+const KEYRING_USER: &str = "access_token";
+
+const ACCESS_TOKEN_ENV: &str = "FLY_ACCESS_TOKEN";
+const CONFIG_DIR_ENV: &str = "FLY_CONFIG_DIR";
+const NO_UPDATE_CHECK: &str = "FLY_NO_UPDATE_CHECK";
+//INFO: Discharge tokens of macaroons live for hours, refreshing well before that is enough.
+const TOKEN_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const TOKEN_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+const NOT_AUTHENTICATED_MESSAGE: &str =
+    "Make sure to be authenticated to Fly.io to use flyradar. Try \"fly auth signup\" to create an
+account, or \"fly auth login\" to log in to an existing account.";
+const CONFIG_NOT_FOUND_MESSAGE: &str = "Your fly.io config file is not found. Make sure to be authenticated to Fly.io to use flyradar. Try \"fly auth signup\" to create an
+account, or \"fly auth login\" to log in to an existing account.";
+
+/// Reads the access token from the fly config file. The file is owned by flyctl, which is logged
+/// out once its token is removed, so a file without a token means logged out for flyradar too.
 pub async fn read_access_token() -> RdrResult<String> {
-    let config_file_path = get_config_file_path();
-
-    match config_file_path {
-        Ok(config_file_path) => match config::file::read_access_token(config_file_path).await {
-            Ok(token) => {
-                if token.is_empty() {
-                    println!("Make sure to be authenticated to Fly.io to use flyradar. Try \"fly auth signup\" to create an
-account, or \"fly auth login\" to log in to an existing account.");
-                    Err(eyre::eyre!("Token is empty."))
-                } else {
-                    Ok(token)
-                }
-            }
-            Err(err) => {
-                debug!("Auth failed: {:#?}", err);
-                println!("Make sure to be authenticated to Fly.io to use flyradar. Try \"fly auth signup\" to create an
-account, or \"fly auth login\" to log in to an existing account.");
-                Err(eyre::eyre!("Auth failed: {:#?}", err))
+    load_access_token().await.map_err(|(message, err)| {
+        println!("{}", message);
+        err
+    })
+}
+
+async fn load_access_token() -> Result<String, (&'static str, eyre::Report)> {
+    read_file_access_token().await
+}
+
+/// Why the session can't start, shown on the startup screen.
+#[derive(Debug)]
+pub enum TokenProblem {
+    Missing(String),
+    Invalid(String),
+    Unreachable(String),
+}
+
+/// Reads the access token and asks the API who it belongs to, so a missing, expired or revoked
+/// token is told apart from the API being unreachable before the session starts.
+pub async fn check_access_token() -> Result<String, TokenProblem> {
+    let token = load_access_token()
+        .await
+        .map_err(|(message, _)| TokenProblem::Missing(message.to_string()))?;
+    let response = Client::new()
+        .post(format!("{DEFAULT_API_BASE_URL}/graphql"))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({ "query": "query { viewer { id } }" }))
+        .timeout(TOKEN_CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| TokenProblem::Unreachable(err.to_string()))?;
+    if matches!(
+        response.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ) {
+        return Err(TokenProblem::Invalid(response.status().to_string()));
+    }
+    let body = response
+        .error_for_status()
+        .map_err(|err| TokenProblem::Unreachable(err.to_string()))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|err| TokenProblem::Unreachable(err.to_string()))?;
+    //INFO: The API answers the queries of unauthenticated viewers with errors, not a status
+    if let Some(errors) = body["errors"]
+        .as_array()
+        .filter(|errors| !errors.is_empty())
+    {
+        return Err(TokenProblem::Invalid(
+            errors
+                .iter()
+                .filter_map(|error| error["message"].as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(token)
+}
+
+/// Runs the login flow of flyctl, which opens the browser and waits until it's completed there,
+/// then reads the token it stored.
+pub async fn login() -> RdrResult<String> {
+    if !flyctl_available() {
+        return Err(eyre::eyre!(
+            "flyctl is needed to log in. Run \"fly auth login\" once it's installed and restart flyradar."
+        ));
+    }
+    let status = Command::new(flyctl())
+        .args(["auth", "login"])
+        .env_remove(ACCESS_TOKEN_ENV)
+        .env(NO_UPDATE_CHECK, "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .wrap_err("failed to execute fly auth login command")?;
+    if !status.success() {
+        return Err(eyre::eyre!("fly auth login command failed"));
+    }
+    load_access_token()
+        .await
+        .map_err(|(message, _)| eyre::eyre!(message))
+}
+
+/// Removes the token from the fly config file, through flyctl when it's there, and the copy older
+/// versions kept in the keychain.
+pub async fn logout() -> RdrResult<()> {
+    if flyctl_available() {
+        let status = Command::new(flyctl())
+            .args(["auth", "logout"])
+            .env(NO_UPDATE_CHECK, "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .wrap_err("failed to execute fly auth logout command")?;
+        if !status.success() {
+            return Err(eyre::eyre!("fly auth logout command failed"));
+        }
+    } else {
+        config::file::set_access_token(get_config_file_path()?, String::new()).await?;
+    }
+    match keyring_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn read_file_access_token() -> Result<String, (&'static str, eyre::Report)> {
+    let config_file_path = get_config_file_path().map_err(|_| {
+        (
+            CONFIG_NOT_FOUND_MESSAGE,
+            eyre::eyre!("Config file not found."),
+        )
+    })?;
+    read_access_token_from(config_file_path).await
+}
+
+async fn read_access_token_from(
+    config_file_path: PathBuf,
+) -> Result<String, (&'static str, eyre::Report)> {
+    match config::file::read_access_token(config_file_path).await {
+        Ok(token) if token.is_empty() => {
+            Err((NOT_AUTHENTICATED_MESSAGE, eyre::eyre!("Token is empty.")))
+        }
+        Ok(token) => Ok(token),
+        Err(err) => {
+            debug!("Auth failed: {:#?}", err);
+            Err((
+                NOT_AUTHENTICATED_MESSAGE,
+                eyre::eyre!("Auth failed: {:#?}", err),
+            ))
+        }
+    }
+}
+
+fn keyring_entry() -> RdrResult<keyring::Entry> {
+    Ok(keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?)
+}
+
+/// Macaroon tokens carry discharge tokens that expire and need refreshing, legacy tokens don't.
+fn supports_refresh(token: &str) -> bool {
+    token.starts_with("FlyV1 ") || token.starts_with("fm1") || token.starts_with("fm2_")
+}
+
+/// Periodically refreshes the shared access token so long-running sessions don't start failing
+/// once the token expires.
+pub async fn rotate_access_token(access_token: SharedAccessToken) {
+    if !flyctl_available() {
+        return;
+    }
+    let mut interval = tokio::time::interval(TOKEN_REFRESH_INTERVAL);
+    // The first tick completes immediately and the token was just read on startup
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let current = access_token.read().clone();
+        if !supports_refresh(&current) {
+            continue;
+        }
+        match refresh_access_token(&current).await {
+            Ok(token) if token != current => {
+                info!("Access token is refreshed.");
+                *access_token.write() = token;
             }
-        },
-        Err(_) => {
-            println!("Your fly.io config file is not found. Make sure to be authenticated to Fly.io to use flyradar. Try \"fly auth signup\" to create an
-account, or \"fly auth login\" to log in to an existing account.");
-            Err(eyre::eyre!("Config file not found."))
+            Ok(_) => {}
+            Err(err) => debug!("Refreshing the access token failed: {:#?}", err),
+        }
+    }
+}
+
+/// Runs flyctl against the token of its config file, which renews the expiring discharge tokens
+/// of a macaroon and writes the renewed token back to the file, then reads the token from there
+/// again.
+//INFO: flyctl only writes back the tokens it read from its config file, a token passed in
+// FLY_ACCESS_TOKEN is renewed in memory and thrown away once the command exits.
+async fn refresh_access_token(current: &str) -> RdrResult<String> {
+    refresh_file_access_token(Path::new(flyctl()), &get_config_directory()?, current).await
+}
+
+async fn refresh_file_access_token(
+    flyctl: &Path,
+    config_dir: &Path,
+    current: &str,
+) -> RdrResult<String> {
+    let config_file_path = config_dir.join("config.yml");
+    match read_access_token_from(config_file_path.clone()).await {
+        Ok(token) if token == current => {}
+        // The token was removed or replaced in the file since it was read
+        _ => {
+            return Err(eyre::eyre!(
+                "The token isn't in the fly config file to refresh."
+            ))
         }
     }
+    let status = Command::new(flyctl)
+        .args(["auth", "token"])
+        .env(CONFIG_DIR_ENV, config_dir)
+        .env_remove(ACCESS_TOKEN_ENV)
+        .env(NO_UPDATE_CHECK, "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .wrap_err("failed to execute fly auth token command")?;
+    if !status.success() {
+        return Err(eyre::eyre!("fly auth token command failed"));
+    }
+
+    read_access_token_from(config_file_path)
+        .await
+        .map_err(|(_, err)| err)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    // Stands in for flyctl, renewing the expiring token of its config file unless it's given one
+    // in the environment
+    const FAKE_FLYCTL: &str = r#"#!/bin/sh
+[ -z "$FLY_ACCESS_TOKEN" ] || exit 1
+printf 'access_token: FlyV1 fm2_renewed\n' > "$FLY_CONFIG_DIR/config.yml"
+"#;
+
+    #[tokio::test]
+    async fn test_refreshing_an_expiring_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let fly = dir.path().join("fly");
+        std::fs::write(&fly, FAKE_FLYCTL).unwrap();
+        std::fs::set_permissions(&fly, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(
+            dir.path().join("config.yml"),
+            "access_token: FlyV1 fm2_expiring\n",
+        )
+        .unwrap();
+
+        let token = refresh_file_access_token(&fly, dir.path(), "FlyV1 fm2_expiring")
+            .await
+            .unwrap();
+        assert_eq!(token, "FlyV1 fm2_renewed");
+        assert!(
+            refresh_file_access_token(&fly, dir.path(), "FlyV1 fm2_expiring")
+                .await
+                .is_err()
+        );
+    }
 }
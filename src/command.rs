@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use color_eyre::eyre::{eyre, Error};
@@ -10,6 +11,14 @@ pub const COMMANDS: &[&str] = &[
     "machines",
     "volumes",
     "secrets",
+    "certificates",
+    "extensions",
+    "hosts",
+    "peers",
+    "logs",
+    "capture",
+    "login",
+    "logout",
     "quit",
 ];
 
@@ -20,9 +29,36 @@ pub enum Command {
     Machines,
     Volumes,
     Secrets,
+    Certificates,
+    Extensions,
+    Hosts,
+    Peers,
+    Logs,
+    Capture,
+    Login,
+    Logout,
     Quit,
 }
 
+/// What a command accepts, shared by the hint of the command mode and the commands popup.
+pub struct CommandSpec {
+    /// Arguments in order, the optional ones in brackets.
+    pub args: &'static [&'static str],
+    pub flags: &'static [&'static str],
+    pub description: &'static str,
+}
+
+impl CommandSpec {
+    pub fn usage(&self) -> String {
+        self.args
+            .iter()
+            .chain(self.flags.iter())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 impl FromStr for Command {
     type Err = Error;
 
@@ -33,6 +69,14 @@ impl FromStr for Command {
             "m" | "mac" | "machine" | "machines" => Ok(Self::Machines),
             "v" | "vol" | "volume" | "volumes" => Ok(Self::Volumes),
             "s" | "sec" | "secret" | "secrets" => Ok(Self::Secrets),
+            "c" | "cert" | "certs" | "certificates" => Ok(Self::Certificates),
+            "e" | "ext" | "extension" | "extensions" => Ok(Self::Extensions),
+            "h" | "host" | "hosts" => Ok(Self::Hosts),
+            "p" | "peer" | "peers" => Ok(Self::Peers),
+            "l" | "log" | "logs" => Ok(Self::Logs),
+            "capture" => Ok(Self::Capture),
+            "login" => Ok(Self::Login),
+            "logout" => Ok(Self::Logout),
             "q" | "q!" | "quit" => Ok(Self::Quit),
             _ => Err(eyre!("Unknown command: {}", s)),
         }
@@ -47,9 +91,196 @@ impl Command {
             Command::Machines => &["m", "mac", "machine", "machines"],
             Command::Volumes => &["v", "vol", "volume", "volumes"],
             Command::Secrets => &["s", "sec", "secret", "secrets"],
+            Command::Certificates => &["c", "cert", "certs", "certificates"],
+            Command::Extensions => &["e", "ext", "extension", "extensions"],
+            Command::Hosts => &["h", "host", "hosts"],
+            Command::Peers => &["p", "peer", "peers"],
+            Command::Logs => &["l", "log", "logs"],
+            Command::Capture => &["capture"],
+            Command::Login => &["login"],
+            Command::Logout => &["logout"],
             Command::Quit => &["q", "q!", "quit"],
         }
     }
+
+    pub fn spec(&self) -> CommandSpec {
+        let (args, flags, description): (&'static [&'static str], &'static [&'static str], _) =
+            match self {
+                Command::Organizations => (
+                    &["[org-slug]"],
+                    &[],
+                    "List the organizations, or switch to the given one",
+                ),
+                Command::Apps => (
+                    &["[org-slug]"],
+                    &[],
+                    "List the apps of the organization, or of the given one",
+                ),
+                Command::Machines => (
+                    &["[machine-id]"],
+                    &[],
+                    "List the machines of the app, or jump to the given one",
+                ),
+                Command::Volumes => (&[], &[], "List the volumes of the app"),
+                Command::Secrets => (&[], &[], "List the secrets of the app"),
+                Command::Certificates => (&[], &[], "List the TLS certificates of the app"),
+                Command::Extensions => (&[], &[], "List the extensions of the organization"),
+                Command::Hosts => (&[], &[], "List the dedicated hosts of the organization"),
+                Command::Peers => (&[], &[], "List the WireGuard peers of the organization"),
+                Command::Logs => (
+                    &[],
+                    &["--region <code>", "--no-tail"],
+                    "Stream the logs of the app",
+                ),
+                Command::Capture => (
+                    &["[on|off|export]"],
+                    &[],
+                    "Record the API calls without their headers and bodies, to attach to a bug report",
+                ),
+                Command::Login => (
+                    &[],
+                    &[],
+                    "Log in to Fly.io through flyctl, e.g. as another user after logging out",
+                ),
+                Command::Logout => (&[], &[], "Log out of Fly.io"),
+                Command::Quit => (&[], &[], "Quit flyradar"),
+            };
+        CommandSpec {
+            args,
+            flags,
+            description,
+        }
+    }
+}
+
+/// A command line parsed against the spec of its command, like `logs --region ams --no-tail`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommandLine {
+    pub command: Command,
+    pub args: Vec<String>,
+    /// Values of the given flags by name, `None` for the flags that don't take one.
+    pub flags: HashMap<&'static str, Option<String>>,
+}
+
+impl CommandLine {
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+
+    pub fn flag_value(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).and_then(|value| value.as_deref())
+    }
+}
+
+impl FromStr for CommandLine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> RdrResult<Self> {
+        let mut words = split_words(s)?.into_iter();
+        let name = words.next().ok_or_else(|| eyre!("Empty command"))?;
+        let command = name.parse::<Command>()?;
+        let spec = command.spec();
+        let mut args = vec![];
+        let mut flags = HashMap::new();
+        while let Some(word) = words.next() {
+            if !word.starts_with("--") {
+                args.push(word);
+                continue;
+            }
+            //INFO: A flag takes a value when its spec has a placeholder, like `--region <code>`
+            let (flag, takes_value) = spec
+                .flags
+                .iter()
+                .find_map(|flag| {
+                    let (flag_name, placeholder) = flag
+                        .split_once(' ')
+                        .map_or((*flag, None), |(n, p)| (n, Some(p)));
+                    (flag_name == word).then_some((flag_name, placeholder.is_some()))
+                })
+                .ok_or_else(|| eyre!("Unknown flag for {}: {}", name, word))?;
+            let value = if takes_value {
+                Some(
+                    words
+                        .next()
+                        .ok_or_else(|| eyre!("Missing the value of {}", word))?,
+                )
+            } else {
+                None
+            };
+            flags.insert(flag, value);
+        }
+        if args.len() > spec.args.len() {
+            return Err(eyre!("Too many arguments for {}: {}", name, args.join(" ")));
+        }
+        Ok(Self {
+            command,
+            args,
+            flags,
+        })
+    }
+}
+
+/// Splits on whitespace, keeping the parts in single or double quotes together.
+fn split_words(s: &str) -> RdrResult<Vec<String>> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in s.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            None => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(eyre!("Unterminated quote in: {}", s));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Command lines completing the first argument with the candidates of its placeholder, like the
+/// org slugs of `[org-slug]`.
+pub fn complete_arg<'a>(s: &str, candidates: impl Fn(&str) -> &'a [String]) -> Vec<String> {
+    let Some((name, arg)) = s.split_once(' ') else {
+        return vec![];
+    };
+    //INFO: Only a single argument is completed, the ones after it are free-form
+    if arg.contains(char::is_whitespace) {
+        return vec![];
+    }
+    let Some(placeholder) = name
+        .parse::<Command>()
+        .ok()
+        .and_then(|command| command.spec().args.first().copied())
+    else {
+        return vec![];
+    };
+    candidates(placeholder)
+        .iter()
+        .filter(|candidate| candidate.starts_with(arg))
+        .map(|candidate| format!("{} {}", name, candidate))
+        .collect()
 }
 
 pub fn match_command(s: &str) -> &str {
@@ -86,4 +317,59 @@ mod tests {
         assert_eq!(match_command("q"), "q!");
         assert_eq!(match_command("invalid"), "invalid");
     }
+
+    #[test]
+    fn test_arg_completion() {
+        let org_slugs = vec![
+            String::from("personal"),
+            String::from("prod"),
+            String::from("staging"),
+        ];
+        let candidates = |placeholder: &str| match placeholder {
+            "[org-slug]" => org_slugs.as_slice(),
+            _ => &[],
+        };
+        assert_eq!(
+            complete_arg("org p", candidates),
+            vec!["org personal", "org prod"]
+        );
+        assert_eq!(complete_arg("apps ", candidates).len(), 3);
+        assert_eq!(complete_arg("a st", candidates), vec!["a staging"]);
+        assert!(complete_arg("org x", candidates).is_empty());
+        assert!(complete_arg("org", candidates).is_empty());
+        assert!(complete_arg("machines p", candidates).is_empty());
+        assert!(complete_arg("org prod more", candidates).is_empty());
+    }
+
+    #[test]
+    fn test_command_line_parsing() {
+        let line = "logs --region ams --no-tail"
+            .parse::<CommandLine>()
+            .unwrap();
+        assert_eq!(line.command, Command::Logs);
+        assert_eq!(line.flag_value("--region"), Some("ams"));
+        assert!(line.has_flag("--no-tail"));
+        assert_eq!(line.flag_value("--no-tail"), None);
+
+        let line = "capture export".parse::<CommandLine>().unwrap();
+        assert_eq!(line.command, Command::Capture);
+        assert_eq!(line.arg(0), Some("export"));
+
+        let line = "apps my-org".parse::<CommandLine>().unwrap();
+        assert_eq!(line.command, Command::Apps);
+        assert_eq!(line.arg(0), Some("my-org"));
+
+        let line = "org 'my org'".parse::<CommandLine>().unwrap();
+        assert_eq!(line.arg(0), Some("my org"));
+        assert_eq!(
+            "a \"\"".parse::<CommandLine>().unwrap().args,
+            vec![String::new()]
+        );
+
+        assert!("logs --region".parse::<CommandLine>().is_err());
+        assert!("logs --tail".parse::<CommandLine>().is_err());
+        assert!("apps one two".parse::<CommandLine>().is_err());
+        assert!("apps 'my-org".parse::<CommandLine>().is_err());
+        assert!("invalid".parse::<CommandLine>().is_err());
+    }
 }
@@ -1,21 +1,19 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use fslock::LockFile;
 use serde::Deserialize;
 use tokio::fs::{File, OpenOptions};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-use super::{FullConfig, TokenConfig, WIREGUARD_STATE_FILE_KEY};
-use crate::config::helpers::get_config_directory;
+use super::{RdrConfig, RdrState, TokenConfig, RDR_PINS_KEY, WIREGUARD_STATE_FILE_KEY};
 use crate::state::RdrResult;
 use crate::wireguard::WireGuardStates;
 
 /// Read a value from the config file.
 async fn read<T: for<'de> Deserialize<'de>>(path: impl Into<PathBuf>) -> RdrResult<T> {
     let path = path.into();
-    let lock_path = lock_path()?;
-    let mut lock = LockFile::open(&lock_path)?;
+    let mut lock = LockFile::open(&lock_path(&path))?;
     lock.lock()?;
 
     let mut file = File::open(&path).await?;
@@ -33,8 +31,7 @@ async fn set(
     values: HashMap<String, serde_json::Value>,
 ) -> RdrResult<()> {
     let path = path.into();
-    let lock_path = lock_path()?;
-    let mut lock = LockFile::open(&lock_path)?;
+    let mut lock = LockFile::open(&lock_path(&path))?;
     lock.lock()?;
 
     // Read existing config or create new
@@ -71,9 +68,17 @@ async fn set(
     Ok(())
 }
 
+#[derive(Deserialize)]
+struct WireGuardStateConfig {
+    wire_guard_state: Option<serde_json::Value>,
+}
+
 pub async fn read_wg_state(path: impl Into<PathBuf>) -> RdrResult<Option<WireGuardStates>> {
-    let config: FullConfig = read(path).await?;
-    Ok(config.wire_guard_state)
+    let config: WireGuardStateConfig = read(path).await?;
+    match config.wire_guard_state {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(None),
+    }
 }
 
 pub async fn set_wg_state(path: impl Into<PathBuf>, states: WireGuardStates) -> RdrResult<()> {
@@ -96,8 +101,42 @@ pub async fn set_access_token(path: impl Into<PathBuf>, token: String) -> RdrRes
     set(path, values).await
 }
 
-pub fn lock_path() -> RdrResult<String> {
-    let config_dir = get_config_directory()?;
-    let config_dir = config_dir.to_string_lossy();
-    Ok(format!("{config_dir}/flyctl.config.lock"))
+pub async fn set_rdr_pins(
+    path: impl Into<PathBuf>,
+    pins: HashMap<String, Vec<String>>,
+) -> RdrResult<()> {
+    let mut values = HashMap::new();
+    values.insert(RDR_PINS_KEY.to_string(), serde_json::to_value(pins)?);
+    set(path, values).await
+}
+
+/// Read flyradar's own config, falling back to the defaults if it doesn't exist.
+pub async fn read_rdr_config(path: impl Into<PathBuf>) -> RdrResult<RdrConfig> {
+    match File::open(path.into()).await {
+        Ok(mut file) => {
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).await?;
+            Ok(serde_yaml::from_str::<Option<RdrConfig>>(&contents)?.unwrap_or_default())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RdrConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Read the state flyradar keeps for itself, empty if it wasn't written yet.
+pub async fn read_rdr_state(path: impl Into<PathBuf>) -> RdrResult<RdrState> {
+    match File::open(path.into()).await {
+        Ok(mut file) => {
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).await?;
+            Ok(serde_yaml::from_str::<Option<RdrState>>(&contents)?.unwrap_or_default())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RdrState::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Lock flyctl takes in its config directory, which the file is in.
+fn lock_path(path: &Path) -> PathBuf {
+    path.with_file_name("flyctl.config.lock")
 }
@@ -24,3 +24,11 @@ pub fn get_config_directory() -> RdrResult<PathBuf> {
 pub fn get_config_file_path() -> RdrResult<PathBuf> {
     get_config_directory().map(|config_dir| config_dir.join("config.yml"))
 }
+
+pub fn get_rdr_config_file_path() -> RdrResult<PathBuf> {
+    get_config_directory().map(|config_dir| config_dir.join("flyradar.yml"))
+}
+
+pub fn get_rdr_state_file_path() -> RdrResult<PathBuf> {
+    get_config_directory().map(|config_dir| config_dir.join("flyradar_state.yml"))
+}
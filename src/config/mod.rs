@@ -1,5 +1,13 @@
-use serde::Deserialize;
+use std::collections::HashMap;
 
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::config::helpers::{get_rdr_config_file_path, get_rdr_state_file_path};
+use crate::keybindings::Action;
+use crate::state::RdrResult;
+use crate::widgets::log_viewer::LevelFilter;
 use crate::wireguard::WireGuardStates;
 
 pub mod file;
@@ -8,6 +16,43 @@ pub mod helpers;
 pub const DEFAULT_API_BASE_URL: &str = "https://api.fly.io";
 pub const DEFAULT_FLAPS_BASE_URL: &str = "https://api.machines.dev";
 pub const WIREGUARD_STATE_FILE_KEY: &str = "wire_guard_state";
+pub const RDR_PINS_KEY: &str = "pins";
+/// Frames drawn per second at most whatever the config says, a faster frame rate rounds down to
+/// a zero frame duration.
+pub const MAX_FRAME_RATE: f64 = 120.0;
+/// Operations whose confirmation can be configured, named like their hooks.
+pub const CONFIRMED_OPERATIONS: &[&str] = &[
+    "destroy_organization",
+    "remove_member",
+    "destroy_app",
+    "restart_app",
+    "rollback_release",
+    "create_deploy_token",
+    "cleanup_app",
+    "update_services",
+    "update_autoscaling",
+    "destroy_machine",
+    "destroy_last_machine",
+    "kill_machine",
+    "restart_machines",
+    "start_machines",
+    "suspend_machines",
+    "stop_machines",
+    "cordon_machines",
+    "uncordon_machines",
+    "align_machines",
+    "exec_machine",
+    "scale_machine",
+    "rename_machine",
+    "update_machines_image",
+    "destroy_volume",
+    "snapshot_volume",
+    "detach_volume",
+    "fork_volume",
+    "unset_secrets",
+    "deploy_secrets",
+    "remove_wireguard_peer",
+];
 
 #[derive(Debug, Deserialize)]
 pub struct TokenConfig {
@@ -19,3 +64,312 @@ pub struct FullConfig {
     pub token_config: TokenConfig,
     pub wire_guard_state: Option<WireGuardStates>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookStage {
+    Before,
+    After,
+}
+
+/// Shell command run around an operation, e.g. to announce a destroyed machine.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Hook {
+    /// Operation to run for, like `destroy_machine`, or `*` for all of them.
+    pub on: String,
+    pub when: HookStage,
+    pub run: String,
+}
+
+/// Condition checked against an app on every poll of the watches.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "when", rename_all = "snake_case")]
+pub enum WatchCondition {
+    /// Any machine of the app is in the state, like `stopped`.
+    MachineState { state: String },
+    /// The app logged more errors than `above` in the last minute.
+    ErrorLogRate { above: usize },
+}
+
+/// Raises an alert while the condition holds for the app.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Watch {
+    pub app: String,
+    #[serde(flatten)]
+    pub condition: WatchCondition,
+    /// Also show a desktop notification when the alert is raised.
+    #[serde(default)]
+    pub notify: bool,
+}
+
+/// How an operation is confirmed before it runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confirmation {
+    /// Runs right away.
+    None,
+    /// Asks with a Cancel/OK popup.
+    #[default]
+    Popup,
+    /// Asks with a popup that only takes OK once the name of the resource is typed.
+    Typed,
+}
+
+/// Settings of the logs of an app.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppLogsConfig {
+    /// Display level the logs open with, instead of trace.
+    pub level: Option<LevelFilter>,
+}
+
+/// Settings of an app.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub logs: AppLogsConfig,
+    /// Overrides `image_max_age_days` for the app.
+    pub image_max_age_days: Option<i64>,
+}
+
+/// View shown on launch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "view", rename_all = "snake_case")]
+pub enum StartupView {
+    /// The organizations to pick one from.
+    #[default]
+    Organizations,
+    /// The apps of the org, or of all the orgs without one.
+    Apps { org: Option<String> },
+    /// The machines of the app, found among the apps of the org or of all the orgs.
+    Machines { org: Option<String>, app: String },
+}
+
+/// Units the sizes are shown in, the values are powers of 1024 either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ByteUnits {
+    /// KB, MB and GB, like the Fly.io dashboard.
+    #[default]
+    Gb,
+    /// KiB, MiB and GiB.
+    Gib,
+}
+
+/// Colors the terminal can show, the palette is brought down to them when drawing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorDepth {
+    /// Detected from `NO_COLOR`, `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    /// The 256 indexed colors.
+    Indexed,
+    /// The 16 ANSI colors.
+    Basic,
+    /// No colors, highlighted cells are reversed instead.
+    Monochrome,
+}
+
+/// Colors the theme starts from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// The purple and pink palette on a black background.
+    #[default]
+    Default,
+    /// Darker colors on a light background.
+    Light,
+    /// No colors, like the monochrome color depth.
+    Monochrome,
+}
+
+/// Colors of the UI.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    /// Colors replacing the ones of the preset, keyed by palette name like `purple` or
+    /// `background`. The values are like `#5b21b6`, `magenta` or a 256-color index.
+    pub colors: HashMap<String, String>,
+}
+
+/// Format the logs are dumped in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogDumpFormat {
+    /// A line of text per log, like the logs view shows them.
+    #[default]
+    Text,
+    /// A JSON object per line with the metadata of the log, like its http request and error.
+    Ndjson,
+}
+
+/// How the numbers, sizes and dates are shown in the tables and the popups.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FormattingConfig {
+    /// Put between the groups of thousands, like `,` or `.`.
+    pub thousands_separator: String,
+    pub byte_units: ByteUnits,
+    /// chrono format of the dates, like `%Y-%m-%d`.
+    pub date_format: String,
+    /// chrono format of the times of the day, like `%I:%M %p`.
+    pub time_format: String,
+}
+
+impl Default for FormattingConfig {
+    fn default() -> Self {
+        Self {
+            thousands_separator: String::new(),
+            byte_units: ByteUnits::default(),
+            date_format: String::from("%b %d %Y"),
+            time_format: String::from("%H:%M"),
+        }
+    }
+}
+
+/// flyradar's own settings, kept next to the fly config as `flyradar.yml`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RdrConfig {
+    /// Skips the Organizations view on launch, e.g. to the apps of the only org used.
+    pub startup_view: StartupView,
+    /// Files to append the streamed logs to while the logs view is open, keyed by app name.
+    pub log_dump: HashMap<String, String>,
+    /// Format the logs are dumped in with Ctrl-s by default, the extension of the path picks it
+    /// in the end.
+    pub log_dump_format: LogDumpFormat,
+    /// Minutes of logs fetched when the logs open, shown before the streamed ones. 0 turns it off.
+    pub log_history_minutes: i64,
+    /// Show a desktop notification when a machine starts crash looping.
+    pub crash_loop_notifications: bool,
+    /// Days a machine has to be stopped for to be listed by the app cleanup.
+    pub stale_machine_days: i64,
+    /// Days after which the image a machine runs is flagged as old in the Machines view.
+    pub image_max_age_days: i64,
+    /// Commands run before or after the operations changing resources.
+    pub hooks: Vec<Hook>,
+    /// Scripts adding custom resource views, see the plugins module for the script interface.
+    pub plugins: Vec<String>,
+    /// Conditions raising an alert, checked in the background whatever the current view is.
+    pub watches: Vec<Watch>,
+    /// Address to serve the observed state on as Prometheus metrics, like `127.0.0.1:9464`.
+    pub metrics_address: Option<String>,
+    /// Confirmation levels keyed by operation, like `destroy_app`. The rest are confirmed with a
+    /// popup.
+    pub confirmations: HashMap<String, Confirmation>,
+    /// Settings of the apps, keyed by app name.
+    pub apps: HashMap<String, AppConfig>,
+    /// Milliseconds between the ticks checking for new logs.
+    pub tick_rate: u64,
+    /// Frames drawn per second at most, frames are only drawn when something changed.
+    pub frame_rate: f64,
+    /// Skip the splash and the banner and render with ASCII only, for high-latency terminals.
+    pub low_bandwidth: bool,
+    /// Thousands separator, byte units and date formats.
+    pub formatting: FormattingConfig,
+    /// Overrides the detected color support of the terminal.
+    pub color_depth: ColorDepth,
+    /// Preset and colors of the UI.
+    pub theme: ThemeConfig,
+    /// Keys of the actions, like `ctrl-x` for `destroy`. The rest keep their default keys.
+    pub keybindings: HashMap<Action, String>,
+}
+
+impl Default for RdrConfig {
+    fn default() -> Self {
+        Self {
+            startup_view: StartupView::default(),
+            log_dump: HashMap::new(),
+            log_dump_format: LogDumpFormat::default(),
+            log_history_minutes: 10,
+            crash_loop_notifications: false,
+            stale_machine_days: 7,
+            image_max_age_days: 30,
+            hooks: vec![],
+            plugins: vec![],
+            watches: vec![],
+            metrics_address: None,
+            confirmations: HashMap::new(),
+            apps: HashMap::new(),
+            tick_rate: 250,
+            frame_rate: 30.0,
+            low_bandwidth: false,
+            formatting: FormattingConfig::default(),
+            color_depth: ColorDepth::default(),
+            theme: ThemeConfig::default(),
+            keybindings: HashMap::new(),
+        }
+    }
+}
+
+impl RdrConfig {
+    /// Keys of the confirmations that don't name an operation, they would be ignored.
+    pub fn unknown_confirmations(&self) -> Vec<&str> {
+        self.confirmations
+            .keys()
+            .map(String::as_str)
+            .filter(|operation| !CONFIRMED_OPERATIONS.contains(operation))
+            .sorted()
+            .collect()
+    }
+}
+
+/// Reads flyradar's config, the defaults if there is no config file. A config that can't be parsed
+/// is an error rather than the defaults, so a typo isn't silently ignored.
+pub async fn read_rdr_config() -> RdrResult<RdrConfig> {
+    let mut rdr_config = match get_rdr_config_file_path() {
+        Ok(path) => file::read_rdr_config(path).await?,
+        Err(_) => RdrConfig::default(),
+    };
+    rdr_config.frame_rate = match rdr_config.frame_rate {
+        frame_rate if frame_rate.is_nan() => RdrConfig::default().frame_rate,
+        frame_rate => frame_rate.clamp(1.0, MAX_FRAME_RATE),
+    };
+    Ok(rdr_config)
+}
+
+/// What flyradar writes on its own, kept apart from the hand-edited `flyradar.yml`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RdrState {
+    /// Ids of the rows pinned to the top of the app's tables, keyed by app name.
+    pub pins: HashMap<String, Vec<String>>,
+}
+
+/// Pins of the app, none if the state can't be read.
+pub async fn read_rdr_pins(app_name: &str) -> Vec<String> {
+    let state = match get_rdr_state_file_path() {
+        Ok(path) => file::read_rdr_state(path).await,
+        Err(err) => Err(err),
+    };
+    match state {
+        Ok(mut state) => state.pins.remove(app_name).unwrap_or_default(),
+        Err(err) => {
+            debug!("Reading flyradar state failed: {:#?}", err);
+            vec![]
+        }
+    }
+}
+
+/// Pins the row to the top of the app's tables, or unpins it if it's already pinned, and returns
+/// the app's pins. Nothing is written if the state can't be read, so the other apps' pins aren't
+/// lost.
+pub async fn toggle_rdr_pin(app_name: &str, id: &str) -> RdrResult<Vec<String>> {
+    let path = get_rdr_state_file_path()?;
+    let mut pins = file::read_rdr_state(&path).await?.pins;
+    let app_pins = pins.entry(app_name.to_string()).or_default();
+    match app_pins.iter().position(|pin| pin == id) {
+        Some(index) => {
+            app_pins.remove(index);
+        }
+        None => app_pins.push(id.to_string()),
+    }
+    let app_pins = app_pins.clone();
+    if app_pins.is_empty() {
+        pins.remove(app_name);
+    }
+    file::set_rdr_pins(path, pins).await?;
+    Ok(app_pins)
+}
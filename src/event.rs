@@ -8,16 +8,22 @@ use tokio::sync::mpsc;
 use crate::state::RdrResult;
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Terminal tick.
     Tick,
+    /// Render tick, the frame is drawn if anything changed.
+    Render,
     /// Key press.
     Key(KeyEvent),
     /// Mouse click/scroll.
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Pasted text.
+    Paste(String),
+    /// Terminal focus gained or lost.
+    Focus(bool),
 }
 
 /// Terminal event handler.
@@ -30,19 +36,39 @@ pub struct EventHandler {
     receiver: mpsc::UnboundedReceiver<Event>,
     /// Event handler thread.
     handler: tokio::task::JoinHandle<()>,
+    tick_rate: Duration,
+    frame_rate: Duration,
 }
 
 impl EventHandler {
     /// Constructs a new instance of [`EventHandler`].
-    pub fn new(tick_rate: u64) -> Self {
-        let tick_rate = Duration::from_millis(tick_rate);
+    pub fn new(tick_rate: u64, frame_rate: f64) -> Self {
+        //INFO: Zero would make the intervals panic
+        let tick_rate = Duration::from_millis(tick_rate.max(1));
+        let frame_rate = Duration::from_secs_f64(1.0 / frame_rate.max(1.0));
         let (sender, receiver) = mpsc::unbounded_channel();
-        let _sender = sender.clone();
-        let handler = tokio::spawn(async move {
+        let handler = Self::spawn(sender.clone(), tick_rate, frame_rate);
+        Self {
+            sender,
+            receiver,
+            handler,
+            tick_rate,
+            frame_rate,
+        }
+    }
+
+    fn spawn(
+        _sender: mpsc::UnboundedSender<Event>,
+        tick_rate: Duration,
+        frame_rate: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
             let mut reader = crossterm::event::EventStream::new();
             let mut tick = tokio::time::interval(tick_rate);
+            let mut render = tokio::time::interval(frame_rate);
             loop {
                 let tick_delay = tick.tick();
+                let render_delay = render.tick();
                 let crossterm_event = reader.next().fuse();
                 tokio::select! {
                   _ = _sender.closed() => {
@@ -52,6 +78,9 @@ impl EventHandler {
                     _sender.send(Event::Tick).unwrap();
 
                   }
+                  _ = render_delay => {
+                    _sender.send(Event::Render).unwrap();
+                  }
                   Some(Ok(evt)) = crossterm_event => {
                     match evt {
                       CrosstermEvent::Key(key) => {
@@ -66,21 +95,31 @@ impl EventHandler {
                         _sender.send(Event::Resize(x, y)).unwrap();
                       },
                       CrosstermEvent::FocusLost => {
+                        _sender.send(Event::Focus(false)).unwrap();
                       },
                       CrosstermEvent::FocusGained => {
+                        _sender.send(Event::Focus(true)).unwrap();
                       },
-                      CrosstermEvent::Paste(_) => {
+                      CrosstermEvent::Paste(text) => {
+                        _sender.send(Event::Paste(text)).unwrap();
                       },
                     }
                   }
                 };
             }
-        });
-        Self {
-            sender,
-            receiver,
-            handler,
-        }
+        })
+    }
+
+    /// Stops reading the terminal so another process can take it over.
+    pub fn pause(&mut self) {
+        self.handler.abort();
+    }
+
+    /// Reads the terminal again after [`pause`].
+    ///
+    /// [`pause`]: EventHandler::pause
+    pub fn resume(&mut self) {
+        self.handler = Self::spawn(self.sender.clone(), self.tick_rate, self.frame_rate);
     }
 
     /// Receive the next event from the handler thread.
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use tracing::span::Id;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+const API_TARGET: &str = "flyradar::fly_rust";
+
+lazy_static! {
+    static ref API_STATS: Mutex<BTreeMap<&'static str, ApiStats>> = Mutex::new(BTreeMap::new());
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ApiStats {
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+impl ApiStats {
+    pub fn error_rate(&self) -> f64 {
+        let total = self.succeeded + self.failed;
+        if total == 0 {
+            0.0
+        } else {
+            self.failed as f64 / total as f64
+        }
+    }
+}
+
+/// Counts of the API calls made in the current session, by endpoint
+pub fn api_stats() -> Vec<(&'static str, ApiStats)> {
+    API_STATS
+        .lock()
+        .iter()
+        .map(|(endpoint, stats)| (*endpoint, *stats))
+        .collect()
+}
+
+struct ApiCallFailed;
+
+/// Every API call is an instrumented function in fly_rust, `#[instrument(err)]` emits an error
+/// event within its span when the call fails.
+pub struct ApiStatsLayer;
+
+impl<S> Layer<S> for ApiStatsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR {
+            return;
+        }
+        if let Some(span) = ctx.event_span(event) {
+            if span.metadata().target().starts_with(API_TARGET) {
+                span.extensions_mut().replace(ApiCallFailed);
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if !span.metadata().target().starts_with(API_TARGET) {
+                return;
+            }
+            let failed = span.extensions().get::<ApiCallFailed>().is_some();
+            let mut api_stats = API_STATS.lock();
+            let stats = api_stats.entry(span.metadata().name()).or_default();
+            if failed {
+                stats.failed += 1;
+            } else {
+                stats.succeeded += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use reqwest::Url;
+
+/// Calls kept by the capture, the oldest ones are dropped first
+const CAPTURE_CAPACITY: usize = 500;
+
+static CAPTURE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref CAPTURE: Mutex<VecDeque<CapturedCall>> =
+        Mutex::new(VecDeque::with_capacity(CAPTURE_CAPACITY));
+}
+
+/// What is kept of an API call for a bug report. Headers and bodies are never kept, they carry
+/// the access token and the secrets.
+#[derive(Clone, Debug)]
+pub struct CapturedCall {
+    pub sent_at: DateTime<Local>,
+    pub method: String,
+    pub url: String,
+    /// Operation name of the GraphQL queries, they are all sent to the same URL.
+    pub operation: Option<String>,
+    /// Status code, or the error when no response came back.
+    pub outcome: Result<u16, String>,
+    pub duration: Duration,
+}
+
+impl CapturedCall {
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} {} {}{} {} {}ms",
+            self.sent_at.format("%F %H:%M:%S%.3f"),
+            self.method,
+            self.url,
+            self.operation
+                .as_ref()
+                .map(|operation| format!(" ({})", operation))
+                .unwrap_or_default(),
+            match &self.outcome {
+                Ok(status) => status.to_string(),
+                Err(err) => format!("error: {}", err),
+            },
+            self.duration.as_millis()
+        )
+    }
+}
+
+pub fn set_capture_enabled(enabled: bool) {
+    CAPTURE_ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_capture_enabled() -> bool {
+    CAPTURE_ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn record(call: CapturedCall) {
+    let mut capture = CAPTURE.lock();
+    if capture.len() == CAPTURE_CAPACITY {
+        capture.pop_front();
+    }
+    capture.push_back(call);
+}
+
+/// The captured calls, oldest first.
+pub fn captured_calls() -> Vec<CapturedCall> {
+    CAPTURE.lock().iter().cloned().collect()
+}
+
+/// Only the names of the query parameters are kept, their values can be anything.
+pub fn sanitize_url(url: &Url) -> String {
+    let mut sanitized = format!(
+        "{}://{}{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default(),
+        url.path()
+    );
+    let keys = url
+        .query_pairs()
+        .map(|(key, _)| key.into_owned())
+        .collect::<Vec<_>>();
+    if !keys.is_empty() {
+        sanitized.push('?');
+        sanitized.push_str(&keys.join("&"));
+    }
+    sanitized
+}
@@ -27,6 +27,9 @@ pub const MACHINE_STATE_SUSPENDED: &str = "suspended";
 pub const MACHINE_STATE_CREATED: &str = "created";
 pub const DEFAULT_VM_SIZE: &str = "shared-cpu-1x";
 pub const DEFAULT_GPU_VM_SIZE: &str = "performance-8x";
+/// A machine restarting this many times within the window is considered crash looping
+pub const CRASH_LOOP_RESTARTS: usize = 3;
+pub const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(10 * 60);
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -162,12 +165,51 @@ impl Machine {
         res
     }
 
+    pub fn host_dedication_id(&self) -> Option<String> {
+        self.get_config()
+            .and_then(|c| c.guest.as_ref()?.host_dedication_id.clone())
+            .filter(|id| !id.is_empty())
+    }
+
+    // Events only hold the recent history, the restart count reported by the machine is preferred
+    // when it's there
+    pub fn restart_count(&self) -> i32 {
+        let reported = self
+            .restart_events()
+            .filter_map(|event| event.request.as_ref()?.restart_count)
+            .max()
+            .unwrap_or_default();
+        reported.max(self.restart_events().count() as i32)
+    }
+
+    pub fn is_crash_looping(&self) -> bool {
+        let window_start = Utc::now().timestamp_millis() - CRASH_LOOP_WINDOW.as_millis() as i64;
+        self.restart_events()
+            .filter(|event| event.timestamp >= window_start)
+            .count()
+            >= CRASH_LOOP_RESTARTS
+    }
+
+    fn restart_events(&self) -> impl Iterator<Item = &MachineEvent> {
+        self.events
+            .iter()
+            .flatten()
+            .filter(|event| event.is_restart())
+    }
+
     pub fn is_release_command_machine(&self) -> bool {
         self.has_process_group(MACHINE_PROCESS_GROUP_FLY_APP_RELEASE_COMMAND)
             || self.get_metadata_by_key("process_group") == "release_command"
     }
 }
 
+/// A past config of a machine. The config is kept as is since it's only compared.
+#[derive(Clone, Debug, Deserialize)]
+pub struct MachineVersion {
+    pub version: String,
+    pub user_config: serde_json::Value,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct HealthCheckStatus {
     pub total: i32,
@@ -195,6 +237,43 @@ pub struct MachineEvent {
     pub timestamp: i64,
 }
 
+impl MachineEvent {
+    /// Restarts are either explicit or exits the machine restarts after
+    pub fn is_restart(&self) -> bool {
+        self.r#type == "restart"
+            || self
+                .exit_event()
+                .is_some_and(|exit_event| exit_event.restarting)
+    }
+
+    pub fn is_oom_kill(&self) -> bool {
+        self.exit_event()
+            .is_some_and(|exit_event| exit_event.oom_killed)
+    }
+
+    /// What happened, for the events worth surfacing.
+    pub fn describe(&self) -> Option<&'static str> {
+        if self.is_oom_kill() {
+            Some("was OOM killed")
+        } else if self.is_restart() {
+            Some("restarted")
+        } else {
+            None
+        }
+    }
+
+    fn exit_event(&self) -> Option<&MachineExitEvent> {
+        if self.r#type != "exit" {
+            return None;
+        }
+        let request = self.request.as_ref()?;
+        request.exit_event.as_ref().or(request
+            .monitor_event
+            .as_ref()
+            .and_then(|monitor_event| monitor_event.exit_event.as_ref()))
+    }
+}
+
 #[derive(Clone, Deserialize, Debug)]
 pub struct MachineRequest {
     pub exit_event: Option<MachineExitEvent>,
@@ -652,7 +731,7 @@ pub struct EnvFrom {
     pub field_ref: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MachineExecRequest {
     pub cmd: String,
     pub timeout: i32,
@@ -661,7 +740,9 @@ pub struct MachineExecRequest {
 #[derive(Clone, Debug, Deserialize)]
 pub struct MachineExecResponse {
     pub exit_code: i32,
+    #[serde(default)]
     pub stdout: String,
+    #[serde(default)]
     pub stderr: String,
 }
 
@@ -10,7 +10,8 @@ use serde_json::json;
 use tracing::{error, info, instrument};
 
 use super::machine_types::{
-    Machine, MachineLease, RemoveMachineInput, RestartMachineInput, StopMachineInput,
+    Machine, MachineExecRequest, MachineExecResponse, MachineLease, MachinePsResponse,
+    MachineVersion, RemoveMachineInput, RestartMachineInput, StopMachineInput,
 };
 use super::request_builder::RequestBuilderMachines;
 use crate::fly_rust::request_builder::find_err;
@@ -95,6 +96,7 @@ pub async fn list_machines<T: Debug + DeserializeOwned>(
     Ok(list)
 }
 
+#[instrument(err)]
 pub async fn list_fly_apps_machines(
     request_builder_machines: &RequestBuilderMachines,
     app_name: &str,
@@ -142,6 +144,26 @@ pub async fn list_fly_apps_machines(
     Ok((machines, release_cmd_machine))
 }
 
+/// List Machine Versions
+#[instrument(err)]
+pub async fn list_machine_versions(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+) -> RdrResult<Vec<MachineVersion>> {
+    let response = request_builder_machines
+        .get(format!(
+            "/v1/apps/{app_name}/machines/{machine_id}/versions"
+        ))
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+    let versions: Vec<MachineVersion> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    Ok(versions)
+}
+
 /// Get Machine
 #[instrument(err)]
 pub async fn get_machine(
@@ -160,6 +182,90 @@ pub async fn get_machine(
     Ok(machine)
 }
 
+/// Get Machine Config
+//INFO: The config is kept as raw json, the typed `MachineConfig` doesn't cover every field and
+//an update posting it back would drop the rest.
+#[instrument(err)]
+pub async fn get_machine_config(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+) -> RdrResult<serde_json::Value> {
+    let response = request_builder_machines
+        .get(format!("/v1/apps/{app_name}/machines/{machine_id}"))
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+    let mut machine: serde_json::Value =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    Ok(machine["config"].take())
+}
+
+/// Update Machine, renaming it if a name is given. With `skip_launch` only the config is updated,
+/// so a stopped machine stays stopped
+#[instrument(err, skip(config))]
+pub async fn update_machine(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+    name: Option<&str>,
+    config: &serde_json::Value,
+    nonce: &str,
+    skip_launch: bool,
+) -> RdrResult<()> {
+    let mut body = json!({ "config": config, "skip_launch": skip_launch });
+    if let Some(name) = name {
+        body["name"] = name.into();
+    }
+    request_builder_machines
+        .post(format!("/v1/apps/{app_name}/machines/{machine_id}"))
+        .header(NONCE_HEADER, nonce)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Exec Machine
+#[instrument(err)]
+pub async fn exec_machine(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+    request: &MachineExecRequest,
+) -> RdrResult<MachineExecResponse> {
+    let response = request_builder_machines
+        .post(format!("/v1/apps/{app_name}/machines/{machine_id}/exec"))
+        .json(request)
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+    let exec_response: MachineExecResponse =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    Ok(exec_response)
+}
+
+/// List Machine Processes
+#[instrument(err)]
+pub async fn list_machine_processes(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+) -> RdrResult<MachinePsResponse> {
+    let response = request_builder_machines
+        .get(format!("/v1/apps/{app_name}/machines/{machine_id}/ps"))
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+    let processes: MachinePsResponse =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    Ok(processes)
+}
+
 /// Restart Machine
 #[derive(Debug, Serialize)]
 struct RestartMachineQuery {
@@ -293,6 +399,44 @@ pub async fn cordon_machine(
     Ok(())
 }
 
+/// Set Machine Metadata
+#[instrument(err)]
+pub async fn set_machine_metadata(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+    key: &str,
+    value: &str,
+) -> RdrResult<()> {
+    request_builder_machines
+        .post(format!(
+            "/v1/apps/{app_name}/machines/{machine_id}/metadata/{key}"
+        ))
+        .json(&json!({ "value": value }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Delete Machine Metadata
+#[instrument(err)]
+pub async fn delete_machine_metadata(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+    key: &str,
+) -> RdrResult<()> {
+    request_builder_machines
+        .delete(format!(
+            "/v1/apps/{app_name}/machines/{machine_id}/metadata/{key}"
+        ))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
 #[instrument(err)]
 pub async fn uncordon_machine(
     request_builder_machines: &RequestBuilderMachines,
@@ -1,12 +1,19 @@
+#[cfg(debug_assertions)]
+pub mod api_stats;
+pub mod capture;
 pub mod custom_humantime_serde;
 pub mod machine_types;
 pub mod machines;
 pub mod request_builder;
 pub mod resource_apps;
+pub mod resource_certificates;
+pub mod resource_extensions;
 pub mod resource_logs;
 pub mod resource_organizations;
+pub mod resource_regions;
 pub mod resource_releases;
 pub mod resource_secrets;
+pub mod resource_viewer;
 pub mod resource_wireguard;
 pub mod volume_types;
 pub mod volumes;
@@ -1,22 +1,43 @@
-use reqwest::{Client, RequestBuilder as ReqwestRequestBuilder};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use color_eyre::eyre::eyre;
+use futures::future::join_all;
+use parking_lot::RwLock;
+use reqwest::{Client, RequestBuilder as ReqwestRequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+use tracing::debug;
+
+use super::capture::{is_capture_enabled, record, sanitize_url, CapturedCall};
+use crate::state::RdrResult;
+
+/// Queries issued within this window are sent together
+const BATCH_WINDOW: Duration = Duration::from_millis(10);
+const MAX_BATCH_SIZE: usize = 10;
+
+/// Access token shared by all request builders so a refreshed token is picked up everywhere.
+pub type SharedAccessToken = Arc<RwLock<String>>;
 
 #[derive(Clone, Debug)]
 pub struct RequestBuilderMachines(RequestBuilder);
 #[derive(Clone, Debug)]
-pub struct RequestBuilderGraphql(RequestBuilder);
+pub struct RequestBuilderGraphql(RequestBuilder, GraphqlBatcher);
 #[derive(Clone, Debug)]
 pub struct RequestBuilderFly(RequestBuilder);
 
 #[derive(Clone, Debug)]
 struct RequestBuilder {
     http_client: Client,
-    /// These fields won't change after init, don't need to Arc them
+    /// This field won't change after init, doesn't need to Arc it
     base_url: String,
-    access_token: String,
+    access_token: SharedAccessToken,
 }
 
 impl RequestBuilder {
-    pub fn new(http_client: Client, base_url: String, access_token: String) -> Self {
+    pub fn new(http_client: Client, base_url: String, access_token: SharedAccessToken) -> Self {
         RequestBuilder {
             http_client,
             base_url,
@@ -26,50 +47,314 @@ impl RequestBuilder {
 }
 
 impl RequestBuilderMachines {
-    pub fn new(http_client: Client, base_url: String, access_token: String) -> Self {
+    pub fn new(http_client: Client, base_url: String, access_token: SharedAccessToken) -> Self {
         RequestBuilderMachines(RequestBuilder::new(http_client, base_url, access_token))
     }
-    pub fn get(&self, path: String) -> ReqwestRequestBuilder {
-        self.0
-            .http_client
-            .get(format!("{}{path}", self.0.base_url))
-            .bearer_auth(&self.0.access_token)
+    pub fn get(&self, path: String) -> CapturedRequest {
+        CapturedRequest::new(
+            self.0
+                .http_client
+                .get(format!("{}{path}", self.0.base_url))
+                .bearer_auth(self.0.access_token.read().as_str()),
+        )
     }
-    pub fn post(&self, path: String) -> ReqwestRequestBuilder {
-        self.0
-            .http_client
-            .post(format!("{}{path}", self.0.base_url))
-            .bearer_auth(&self.0.access_token)
+    pub fn post(&self, path: String) -> CapturedRequest {
+        CapturedRequest::new(
+            self.0
+                .http_client
+                .post(format!("{}{path}", self.0.base_url))
+                .bearer_auth(self.0.access_token.read().as_str()),
+        )
     }
-    pub fn delete(&self, path: String) -> ReqwestRequestBuilder {
-        self.0
-            .http_client
-            .delete(format!("{}{path}", self.0.base_url))
-            .bearer_auth(&self.0.access_token)
+    pub fn delete(&self, path: String) -> CapturedRequest {
+        CapturedRequest::new(
+            self.0
+                .http_client
+                .delete(format!("{}{path}", self.0.base_url))
+                .bearer_auth(self.0.access_token.read().as_str()),
+        )
     }
 }
 
 impl RequestBuilderGraphql {
-    pub fn new(http_client: Client, base_url: String, access_token: String) -> Self {
-        RequestBuilderGraphql(RequestBuilder::new(http_client, base_url, access_token))
+    pub fn new(http_client: Client, base_url: String, access_token: SharedAccessToken) -> Self {
+        let request_builder = RequestBuilder::new(http_client, base_url, access_token);
+        let batcher = GraphqlBatcher::new(request_builder.clone());
+        RequestBuilderGraphql(request_builder, batcher)
+    }
+    pub fn query(&self) -> CapturedRequest {
+        CapturedRequest::new(
+            self.0
+                .http_client
+                .post(&self.0.base_url)
+                .bearer_auth(self.0.access_token.read().as_str()),
+        )
+    }
+    /// Sends the query along with the other queries issued in the same tick and returns the raw
+    /// response body. Meant for read-only queries that views fire together.
+    pub async fn batched_query(&self, request_body: &impl Serialize) -> RdrResult<Vec<u8>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.1
+            .query_tx
+            .send(BatchedQuery {
+                request_body: serde_json::to_value(request_body)?,
+                response_tx,
+            })
+            .map_err(|_| eyre!("GraphQL batcher is not running"))?;
+        response_rx.await?
+    }
+}
+
+/// A request that is recorded by the debug capture when it's sent, while the capture is on.
+#[derive(Debug)]
+pub struct CapturedRequest {
+    request_builder: ReqwestRequestBuilder,
+    operation: Option<String>,
+}
+
+impl CapturedRequest {
+    fn new(request_builder: ReqwestRequestBuilder) -> Self {
+        CapturedRequest {
+            request_builder,
+            operation: None,
+        }
+    }
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.request_builder = self.request_builder.header(key, value);
+        self
+    }
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.request_builder = self.request_builder.query(query);
+        self
+    }
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        //INFO: Only the operation names of the GraphQL bodies are kept, batches have several
+        if is_capture_enabled() {
+            let operations = match serde_json::to_value(json) {
+                Ok(serde_json::Value::Array(bodies)) => bodies,
+                Ok(body) => vec![body],
+                Err(_) => vec![],
+            }
+            .iter()
+            .filter_map(|body| body["operationName"].as_str().map(String::from))
+            .collect::<Vec<_>>();
+            self.operation = (!operations.is_empty()).then(|| operations.join(", "));
+        }
+        self.request_builder = self.request_builder.json(json);
+        self
+    }
+    pub async fn send(self) -> reqwest::Result<Response> {
+        if !is_capture_enabled() {
+            return self.request_builder.send().await;
+        }
+        let (client, request) = self.request_builder.build_split();
+        let request = request?;
+        let method = request.method().to_string();
+        let url = sanitize_url(request.url());
+        let sent_at = Local::now();
+        let started_at = Instant::now();
+        let response = client.execute(request).await;
+        record(CapturedCall {
+            sent_at,
+            method,
+            url,
+            operation: self.operation,
+            outcome: match &response {
+                Ok(response) => Ok(response.status().as_u16()),
+                // The errors of reqwest carry the URL with its query, it's shown sanitized already
+                Err(err) => Err(if err.is_timeout() {
+                    String::from("timed out")
+                } else if err.is_connect() {
+                    String::from("connection failed")
+                } else {
+                    String::from("request failed")
+                }),
+            },
+            duration: started_at.elapsed(),
+        });
+        response
+    }
+}
+
+#[derive(Debug)]
+struct BatchedQuery {
+    request_body: serde_json::Value,
+    response_tx: oneshot::Sender<RdrResult<Vec<u8>>>,
+}
+
+/// Combines the GraphQL queries issued within a tick into a single request. Falls back to sending
+/// them one by one for good if the API doesn't accept batches, or only for the batch if its
+/// request failed otherwise.
+#[derive(Clone, Debug)]
+struct GraphqlBatcher {
+    query_tx: mpsc::UnboundedSender<BatchedQuery>,
+}
+
+impl GraphqlBatcher {
+    fn new(request_builder: RequestBuilder) -> Self {
+        let (query_tx, query_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_batcher(
+            request_builder,
+            query_rx,
+            Arc::new(AtomicBool::new(true)),
+        ));
+        GraphqlBatcher { query_tx }
     }
-    pub fn query(&self) -> ReqwestRequestBuilder {
-        self.0
+}
+
+async fn run_batcher(
+    request_builder: RequestBuilder,
+    mut query_rx: mpsc::UnboundedReceiver<BatchedQuery>,
+    supports_batching: Arc<AtomicBool>,
+) {
+    while let Some(query) = query_rx.recv().await {
+        let mut batch = vec![query];
+        let window = tokio::time::sleep(BATCH_WINDOW);
+        tokio::pin!(window);
+        while batch.len() < MAX_BATCH_SIZE {
+            tokio::select! {
+                _ = &mut window => break,
+                query = query_rx.recv() => match query {
+                    Some(query) => batch.push(query),
+                    None => break,
+                },
+            }
+        }
+        tokio::spawn(send_batch(
+            request_builder.clone(),
+            batch,
+            supports_batching.clone(),
+        ));
+    }
+}
+
+async fn send_batch(
+    request_builder: RequestBuilder,
+    batch: Vec<BatchedQuery>,
+    supports_batching: Arc<AtomicBool>,
+) {
+    if batch.len() > 1 && supports_batching.load(Ordering::Relaxed) {
+        let request_bodies = batch
+            .iter()
+            .map(|query| &query.request_body)
+            .collect::<Vec<_>>();
+        match send_graphql_batch(&request_builder, &request_bodies).await {
+            Ok(Some(responses)) if responses.len() == batch.len() => {
+                for (query, response) in batch.into_iter().zip(responses) {
+                    let _ = query
+                        .response_tx
+                        .send(serde_json::to_vec(&response).map_err(Into::into));
+                }
+                return;
+            }
+            Ok(_) => {
+                debug!("GraphQL batches aren't supported, sending the queries one by one");
+                supports_batching.store(false, Ordering::Relaxed);
+            }
+            Err(err) => debug!("GraphQL batch request failed: {:#?}", err),
+        }
+    }
+
+    join_all(batch.into_iter().map(|query| {
+        let request_builder = request_builder.clone();
+        async move {
+            let response = send_graphql::<serde_json::Value>(&request_builder, &query.request_body)
+                .await
+                .and_then(|response| Ok(serde_json::to_vec(&response)?));
+            let _ = query.response_tx.send(response);
+        }
+    }))
+    .await;
+}
+
+/// Sends the queries as a batch, none if the API answered that it doesn't take batches with a
+/// client error or a response that isn't an array. Errors like a timeout or a server error are
+/// returned, they don't tell whether batches are supported.
+async fn send_graphql_batch(
+    request_builder: &RequestBuilder,
+    request_bodies: &[&serde_json::Value],
+) -> RdrResult<Option<Vec<serde_json::Value>>> {
+    let request = CapturedRequest::new(
+        request_builder
+            .http_client
+            .post(&request_builder.base_url)
+            .bearer_auth(request_builder.access_token.read().as_str()),
+    )
+    .json(request_bodies);
+    let response = request.send().await?;
+    let status = response.status();
+    //INFO: A rejected token or the rate limit fail the queries sent one by one too, they don't
+    // tell whether batches are supported
+    if status.is_client_error()
+        && !matches!(
+            status,
+            StatusCode::UNAUTHORIZED
+                | StatusCode::FORBIDDEN
+                | StatusCode::REQUEST_TIMEOUT
+                | StatusCode::TOO_MANY_REQUESTS
+        )
+    {
+        return Ok(None);
+    }
+    match error_for_graphql_status(response).await?.json().await? {
+        serde_json::Value::Array(responses) => Ok(Some(responses)),
+        _ => Ok(None),
+    }
+}
+
+async fn send_graphql<T: serde::de::DeserializeOwned>(
+    request_builder: &RequestBuilder,
+    request_body: &impl Serialize,
+) -> RdrResult<T> {
+    let request = CapturedRequest::new(
+        request_builder
             .http_client
-            .post(&self.0.base_url)
-            .bearer_auth(&self.0.access_token)
+            .post(&request_builder.base_url)
+            .bearer_auth(request_builder.access_token.read().as_str()),
+    )
+    .json(request_body);
+    let response = error_for_graphql_status(request.send().await?).await?;
+    Ok(response.json().await?)
+}
+
+/// Turns a failed response into an error with the messages the API gave in its body, the status
+/// alone doesn't tell e.g. a validation error from a missing permission.
+async fn error_for_graphql_status(response: Response) -> RdrResult<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await?;
+    let messages = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|body| {
+            body["errors"].as_array().map(|errors| {
+                errors
+                    .iter()
+                    .filter_map(|error| error["message"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        })
+        .filter(|messages| !messages.is_empty())
+        .unwrap_or(body);
+    if messages.trim().is_empty() {
+        return Err(eyre!("{}", status));
     }
+    Err(eyre!("{}: {}", status, messages.trim()))
 }
 
 impl RequestBuilderFly {
-    pub fn new(http_client: Client, base_url: String, access_token: String) -> Self {
+    pub fn new(http_client: Client, base_url: String, access_token: SharedAccessToken) -> Self {
         RequestBuilderFly(RequestBuilder::new(http_client, base_url, access_token))
     }
-    pub fn get(&self, path: String) -> ReqwestRequestBuilder {
-        self.0
-            .http_client
-            .get(format!("{}{path}", self.0.base_url))
-            .bearer_auth(&self.0.access_token)
+    pub fn get(&self, path: String) -> CapturedRequest {
+        CapturedRequest::new(
+            self.0
+                .http_client
+                .get(format!("{}{path}", self.0.base_url))
+                .bearer_auth(self.0.access_token.read().as_str()),
+        )
     }
 }
 
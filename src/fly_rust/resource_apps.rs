@@ -106,12 +106,9 @@ pub async fn get_app_compact(
 ) -> RdrResult<Option<get_app_compact::ResponseData>> {
     let variables = get_app_compact::Variables { app_name };
     let request_body = GetAppCompact::build_query(variables);
-    let response = request_builder_graphql
-        .query()
-        .json(&request_body)
-        .send()
-        .await?;
-    let response_body: Response<get_app_compact::ResponseData> = response.json().await?;
+    let bytes = request_builder_graphql.batched_query(&request_body).await?;
+    let response_body: Response<get_app_compact::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
     if let Some(errors) = response_body.errors {
         return Err(eyre!(
             "{}",
@@ -140,12 +137,40 @@ pub async fn get_app_basic(
 ) -> RdrResult<Option<get_app_basic::ResponseData>> {
     let variables = get_app_basic::Variables { app_name };
     let request_body = GetAppBasic::build_query(variables);
-    let response = request_builder_graphql
-        .query()
-        .json(&request_body)
-        .send()
-        .await?;
-    let response_body: Response<get_app_basic::ResponseData> = response.json().await?;
+    let bytes = request_builder_graphql.batched_query(&request_body).await?;
+    let response_body: Response<get_app_basic::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
+
+/// Get App IP Addresses
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_app_ip_addresses_schema.graphql",
+    query_path = "src/fly_rust/queries/get_app_ip_addresses.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetAppIpAddresses;
+#[instrument(err)]
+pub async fn get_app_ip_addresses(
+    request_builder_graphql: &RequestBuilderGraphql,
+    app_name: String,
+) -> RdrResult<Option<get_app_ip_addresses::ResponseData>> {
+    let variables = get_app_ip_addresses::Variables { app_name };
+    let request_body = GetAppIpAddresses::build_query(variables);
+    let bytes = request_builder_graphql.batched_query(&request_body).await?;
+    let response_body: Response<get_app_ip_addresses::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
     if let Some(errors) = response_body.errors {
         return Err(eyre!(
             "{}",
@@ -159,6 +184,11 @@ pub async fn get_app_basic(
     Ok(response_body.data)
 }
 
+//INFO: There is no rename next to delete, Fly.io doesn't support changing the name of an app.
+//The name is the app's identity in the API, its *.fly.dev hostname and its internal DNS, so
+//neither the GraphQL API nor the Machines API has a mutation for it. Moving to a new name means
+//creating a new app, moving the volumes, secrets and certificates over and deploying it, which
+//is a migration rather than an action that can be confirmed in a popup.
 /// Delete App
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -192,3 +222,50 @@ pub async fn delete_app(
     }
     Ok(response_body.data)
 }
+
+/// Create Deploy Token
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/create_deploy_token_schema.graphql",
+    query_path = "src/fly_rust/queries/create_deploy_token.graphql",
+    response_derives = "Debug"
+)]
+struct CreateDeployToken;
+#[instrument(err, skip_all)]
+pub async fn create_deploy_token(
+    request_builder_graphql: &RequestBuilderGraphql,
+    organization_id: String,
+    app_id: String,
+    name: String,
+    expiry: String,
+) -> RdrResult<Option<create_deploy_token::ResponseData>> {
+    let variables = create_deploy_token::Variables {
+        input: create_deploy_token::CreateLimitedAccessTokenInput {
+            name,
+            organization_id,
+            profile: String::from("deploy"),
+            profile_params: create_deploy_token::DeployTokenProfileParams { app_id },
+            expiry,
+        },
+    };
+    let request_body = CreateDeployToken::build_query(variables);
+    let response = request_builder_graphql
+        .query()
+        .json(&request_body)
+        .send()
+        .await?;
+    let bytes = response.bytes().await?;
+    let response_body: Response<create_deploy_token::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
@@ -0,0 +1,78 @@
+use backon::{ConstantBuilder, Retryable};
+use color_eyre::eyre::eyre;
+use graphql_client::{GraphQLQuery, Response};
+use tracing::{info, instrument};
+
+use super::request_builder::{find_err, RequestBuilderGraphql};
+use crate::state::RdrResult;
+use crate::transformations::ListCertificate;
+
+pub async fn get_all_app_certificates(
+    request_builder_graphql: &RequestBuilderGraphql,
+    app_name: String,
+) -> RdrResult<Vec<ListCertificate>> {
+    let mut all_certificates = vec![];
+    if let Some(response) = get_app_certificates(request_builder_graphql, app_name).await? {
+        all_certificates.extend(response.app.certificates.nodes.iter().map(|certificate| {
+            ListCertificate {
+                hostname: certificate.hostname.clone(),
+                status: certificate.client_status.clone(),
+                dns_configured: certificate.acme_dns_configured,
+                //INFO: A certificate is issued per key type, the first one to expire is shown
+                expires_at: certificate
+                    .issued
+                    .nodes
+                    .iter()
+                    .map(|issued| issued.expires_at.clone())
+                    .min()
+                    .unwrap_or_default(),
+                created_at: certificate.created_at.clone(),
+            }
+        }));
+    }
+    info!("List of certificates: {:#?}", all_certificates);
+    Ok(all_certificates)
+}
+
+/// Get App Certificates
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_app_certificates_schema.graphql",
+    query_path = "src/fly_rust/queries/get_app_certificates.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetAppCertificates;
+#[instrument(err)]
+pub async fn get_app_certificates(
+    request_builder_graphql: &RequestBuilderGraphql,
+    app_name: String,
+) -> RdrResult<Option<get_app_certificates::ResponseData>> {
+    let variables = get_app_certificates::Variables { app_name };
+    let request_body = GetAppCertificates::build_query(variables);
+
+    let response = (|| async {
+        request_builder_graphql
+            .query()
+            .json(&request_body)
+            .send()
+            .await
+    })
+    .retry(ConstantBuilder::default())
+    .when(|e| find_err(e, "connection closed before message completed"))
+    .await?;
+
+    let bytes = response.bytes().await?;
+    let response_body: Response<get_app_certificates::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
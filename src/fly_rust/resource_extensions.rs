@@ -0,0 +1,116 @@
+use backon::{ConstantBuilder, Retryable};
+use color_eyre::eyre::eyre;
+use graphql_client::{GraphQLQuery, Response};
+use tracing::{info, instrument};
+
+use super::request_builder::{find_err, RequestBuilderGraphql};
+use crate::state::RdrResult;
+use crate::transformations::ListExtension;
+
+pub async fn get_all_organization_extensions(
+    request_builder_graphql: &RequestBuilderGraphql,
+    org_slug: String,
+) -> RdrResult<Vec<ListExtension>> {
+    let mut all_extensions = vec![];
+    if let Some(response) = get_organization_add_ons(request_builder_graphql, org_slug).await? {
+        all_extensions.extend(response.organization.add_ons.nodes.iter().map(|add_on| {
+            ListExtension {
+                id: add_on.id.clone(),
+                name: add_on.name.clone(),
+                provider: add_on.add_on_provider.name.clone(),
+                plan: add_on
+                    .add_on_plan
+                    .as_ref()
+                    .map(|plan| plan.display_name.clone())
+                    .unwrap_or_default(),
+                app: add_on
+                    .app
+                    .as_ref()
+                    .map(|app| app.name.clone())
+                    .unwrap_or_default(),
+                status: add_on.status.clone().unwrap_or_default(),
+                region: add_on.primary_region.clone().unwrap_or_default(),
+            }
+        }));
+    }
+    info!("List of extensions: {:#?}", all_extensions);
+    Ok(all_extensions)
+}
+
+/// Get Organization Add-ons
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_organization_add_ons_schema.graphql",
+    query_path = "src/fly_rust/queries/get_organization_add_ons.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetOrganizationAddOns;
+#[instrument(err)]
+pub async fn get_organization_add_ons(
+    request_builder_graphql: &RequestBuilderGraphql,
+    slug: String,
+) -> RdrResult<Option<get_organization_add_ons::ResponseData>> {
+    let variables = get_organization_add_ons::Variables { slug };
+    let request_body = GetOrganizationAddOns::build_query(variables);
+
+    let response = (|| async {
+        request_builder_graphql
+            .query()
+            .json(&request_body)
+            .send()
+            .await
+    })
+    .retry(ConstantBuilder::default())
+    .when(|e| find_err(e, "connection closed before message completed"))
+    .await?;
+
+    let bytes = response.bytes().await?;
+    let response_body: Response<get_organization_add_ons::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
+
+/// Get Add-on
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_add_on_schema.graphql",
+    query_path = "src/fly_rust/queries/get_add_on.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetAddOn;
+#[instrument(err)]
+pub async fn get_add_on(
+    request_builder_graphql: &RequestBuilderGraphql,
+    name: String,
+    provider: String,
+) -> RdrResult<Option<get_add_on::ResponseData>> {
+    let variables = get_add_on::Variables { name, provider };
+    let request_body = GetAddOn::build_query(variables);
+    let response = request_builder_graphql
+        .query()
+        .json(&request_body)
+        .send()
+        .await?;
+    let response_body: Response<get_add_on::ResponseData> = response.json().await?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
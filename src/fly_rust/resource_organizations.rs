@@ -7,9 +7,11 @@ use super::request_builder::{find_err, RequestBuilderGraphql};
 use crate::state::RdrResult;
 use crate::transformations::ListOrganization;
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct OrganizationFilter {
     admin: bool,
+    /// Set when the access token is scoped to a single org.
+    slug: Option<String>,
 }
 
 impl OrganizationFilter {
@@ -26,9 +28,18 @@ impl OrganizationFilter {
         self
     }
 
+    pub fn slug(mut self, value: Option<String>) -> Self {
+        self.slug = value;
+        self
+    }
+
     pub fn is_admin_only(&self) -> bool {
         self.admin
     }
+
+    pub fn scoped_slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
 }
 
 pub async fn get_all_organizations(
@@ -36,12 +47,14 @@ pub async fn get_all_organizations(
     filter: OrganizationFilter,
 ) -> RdrResult<Vec<ListOrganization>> {
     let mut all_orgs = vec![];
+    let slug = filter.slug.clone();
     if let Some(response) = get_organizations(request_builder_graphql, filter).await? {
         all_orgs.extend(
             response
                 .organizations
                 .nodes
                 .iter()
+                .filter(|org| slug.as_ref().map_or(true, |slug| &org.slug == slug))
                 .map(|org| ListOrganization {
                     id: org.id.clone(),
                     name: org.name.clone(),
@@ -115,12 +128,7 @@ pub async fn get_detailed_organization_by_slug(
 ) -> RdrResult<Option<get_detailed_organization_by_slug::ResponseData>> {
     let variables = get_detailed_organization_by_slug::Variables { slug: org_slug };
     let request_body = GetDetailedOrganizationBySlug::build_query(variables);
-    let response = request_builder_graphql
-        .query()
-        .json(&request_body)
-        .send()
-        .await?;
-    let bytes = response.bytes().await?;
+    let bytes = request_builder_graphql.batched_query(&request_body).await?;
     let response_body: Response<get_detailed_organization_by_slug::ResponseData> =
         serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
     if let Some(errors) = response_body.errors {
@@ -0,0 +1,66 @@
+use backon::{ConstantBuilder, Retryable};
+use color_eyre::eyre::eyre;
+use graphql_client::{GraphQLQuery, Response};
+use tracing::{info, instrument};
+
+use super::request_builder::{find_err, RequestBuilderGraphql};
+use crate::state::RdrResult;
+use crate::transformations::ListRegion;
+
+pub async fn get_all_platform_regions(
+    request_builder_graphql: &RequestBuilderGraphql,
+) -> RdrResult<Vec<ListRegion>> {
+    let mut all_regions = vec![];
+    if let Some(response) = get_platform_regions(request_builder_graphql).await? {
+        all_regions.extend(response.platform.regions.iter().map(|region| ListRegion {
+            code: region.code.clone(),
+            name: region.name.clone(),
+            gateway_available: region.gateway_available,
+        }));
+    }
+    all_regions.sort_by(|a, b| a.code.cmp(&b.code));
+    info!("List of regions: {:#?}", all_regions);
+    Ok(all_regions)
+}
+
+/// Get Platform Regions
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_platform_regions_schema.graphql",
+    query_path = "src/fly_rust/queries/get_platform_regions.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetPlatformRegions;
+#[instrument(err)]
+pub async fn get_platform_regions(
+    request_builder_graphql: &RequestBuilderGraphql,
+) -> RdrResult<Option<get_platform_regions::ResponseData>> {
+    let variables = get_platform_regions::Variables {};
+    let request_body = GetPlatformRegions::build_query(variables);
+
+    let response = (|| async {
+        request_builder_graphql
+            .query()
+            .json(&request_body)
+            .send()
+            .await
+    })
+    .retry(ConstantBuilder::default())
+    .when(|e| find_err(e, "connection closed before message completed"))
+    .await?;
+
+    let bytes = response.bytes().await?;
+    let response_body: Response<get_platform_regions::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
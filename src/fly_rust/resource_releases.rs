@@ -21,13 +21,8 @@ pub async fn get_app_releases_machines(
 ) -> RdrResult<Option<get_app_releases_machines::ResponseData>> {
     let variables = get_app_releases_machines::Variables { app_name, limit };
     let request_body = GetAppReleasesMachines::build_query(variables);
-    let response = request_builder_graphql
-        .query()
-        .json(&request_body)
-        .send()
-        .await?;
+    let bytes = request_builder_graphql.batched_query(&request_body).await?;
 
-    let bytes = response.bytes().await?;
     let response_body: Response<get_app_releases_machines::ResponseData> =
         serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
     if let Some(errors) = response_body.errors {
@@ -107,3 +107,48 @@ pub async fn unset_secrets(
     }
     Ok(response_body.data)
 }
+
+/// Set Secrets
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/set_secrets_schema.graphql",
+    query_path = "src/fly_rust/queries/set_secrets.graphql",
+    response_derives = "Debug"
+)]
+pub struct SetSecrets;
+#[instrument(err, skip(secrets))]
+pub async fn set_secrets(
+    request_builder_graphql: &RequestBuilderGraphql,
+    app_name: String,
+    secrets: Vec<(String, String)>,
+) -> RdrResult<Option<set_secrets::ResponseData>> {
+    let variables = set_secrets::Variables {
+        input: set_secrets::SetSecretsInput {
+            app_id: app_name,
+            secrets: secrets
+                .into_iter()
+                .map(|(key, value)| set_secrets::SecretInput { key, value })
+                .collect(),
+        },
+    };
+    let request_body = SetSecrets::build_query(variables);
+    let response = request_builder_graphql
+        .query()
+        .json(&request_body)
+        .send()
+        .await?;
+    let bytes = response.bytes().await?;
+    let response_body: Response<set_secrets::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
@@ -0,0 +1,36 @@
+use color_eyre::eyre::eyre;
+use graphql_client::{GraphQLQuery, Response};
+use tracing::instrument;
+
+use super::request_builder::RequestBuilderGraphql;
+use crate::state::RdrResult;
+
+/// Get Viewer
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_viewer_schema.graphql",
+    query_path = "src/fly_rust/queries/get_viewer.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetViewer;
+#[instrument(err)]
+pub async fn get_viewer(
+    request_builder_graphql: &RequestBuilderGraphql,
+) -> RdrResult<Option<get_viewer::ResponseData>> {
+    let variables = get_viewer::Variables {};
+    let request_body = GetViewer::build_query(variables);
+    let bytes = request_builder_graphql.batched_query(&request_body).await?;
+    let response_body: Response<get_viewer::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
@@ -1,9 +1,168 @@
+use backon::{ConstantBuilder, Retryable};
 use color_eyre::eyre::eyre;
 use graphql_client::{GraphQLQuery, Response};
-use tracing::instrument;
+use tracing::{info, instrument};
 
-use super::request_builder::RequestBuilderGraphql;
+use super::request_builder::{find_err, RequestBuilderGraphql};
 use crate::state::RdrResult;
+use crate::transformations::ListWireGuardPeer;
+
+pub async fn get_all_organization_wire_guard_peers(
+    request_builder_graphql: &RequestBuilderGraphql,
+    org_slug: String,
+) -> RdrResult<Vec<ListWireGuardPeer>> {
+    let mut all_peers = vec![];
+    if let Some(response) =
+        get_organization_wire_guard_peers(request_builder_graphql, org_slug).await?
+    {
+        all_peers.extend(
+            response
+                .organization
+                .wire_guard_peers
+                .nodes
+                .iter()
+                .map(|peer| ListWireGuardPeer {
+                    name: peer.name.clone(),
+                    region: peer.region.clone(),
+                    peer_ip: peer.peerip.clone(),
+                    public_key: peer.pubkey.clone(),
+                }),
+        );
+    }
+    info!("List of WireGuard peers: {:#?}", all_peers);
+    Ok(all_peers)
+}
+
+/// Get Organization WireGuard Peers
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/get_organization_wire_guard_peers_schema.graphql",
+    query_path = "src/fly_rust/queries/get_organization_wire_guard_peers.graphql",
+    response_derives = "Debug"
+)]
+pub struct GetOrganizationWireGuardPeers;
+#[instrument(err)]
+pub async fn get_organization_wire_guard_peers(
+    request_builder_graphql: &RequestBuilderGraphql,
+    slug: String,
+) -> RdrResult<Option<get_organization_wire_guard_peers::ResponseData>> {
+    let variables = get_organization_wire_guard_peers::Variables { slug };
+    let request_body = GetOrganizationWireGuardPeers::build_query(variables);
+
+    let response = (|| async {
+        request_builder_graphql
+            .query()
+            .json(&request_body)
+            .send()
+            .await
+    })
+    .retry(ConstantBuilder::default())
+    .when(|e| find_err(e, "connection closed before message completed"))
+    .await?;
+
+    let bytes = response.bytes().await?;
+    let response_body: Response<get_organization_wire_guard_peers::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
+
+/// Add WireGuard Peer
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/add_wire_guard_peer_schema.graphql",
+    query_path = "src/fly_rust/queries/add_wire_guard_peer.graphql",
+    response_derives = "Debug"
+)]
+pub struct AddWireGuardPeer;
+#[instrument(err)]
+pub async fn add_wire_guard_peer(
+    request_builder_graphql: &RequestBuilderGraphql,
+    organization_id: String,
+    region: String,
+    name: String,
+    pubkey: String,
+) -> RdrResult<Option<add_wire_guard_peer::ResponseData>> {
+    let variables = add_wire_guard_peer::Variables {
+        input: add_wire_guard_peer::AddWireGuardPeerInput {
+            organization_id,
+            region,
+            name,
+            pubkey,
+        },
+    };
+    let request_body = AddWireGuardPeer::build_query(variables);
+    let response = request_builder_graphql
+        .query()
+        .json(&request_body)
+        .send()
+        .await?;
+    let bytes = response.bytes().await?;
+    let response_body: Response<add_wire_guard_peer::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
+
+/// Remove WireGuard Peer
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/fly_rust/queries/remove_wire_guard_peer_schema.graphql",
+    query_path = "src/fly_rust/queries/remove_wire_guard_peer.graphql",
+    response_derives = "Debug"
+)]
+pub struct RemoveWireGuardPeer;
+#[instrument(err)]
+pub async fn remove_wire_guard_peer(
+    request_builder_graphql: &RequestBuilderGraphql,
+    organization_id: String,
+    name: String,
+) -> RdrResult<Option<remove_wire_guard_peer::ResponseData>> {
+    let variables = remove_wire_guard_peer::Variables {
+        input: remove_wire_guard_peer::RemoveWireGuardPeerInput {
+            organization_id,
+            name,
+        },
+    };
+    let request_body = RemoveWireGuardPeer::build_query(variables);
+    let response = request_builder_graphql
+        .query()
+        .json(&request_body)
+        .send()
+        .await?;
+    let bytes = response.bytes().await?;
+    let response_body: Response<remove_wire_guard_peer::ResponseData> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    if let Some(errors) = response_body.errors {
+        return Err(eyre!(
+            "{}",
+            errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+    Ok(response_body.data)
+}
 
 #[derive(GraphQLQuery)]
 #[graphql(
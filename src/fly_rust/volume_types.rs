@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
 pub struct Volume {
@@ -29,7 +29,49 @@ impl Volume {
     }
 }
 
+#[derive(Deserialize, Debug)]
+pub struct VolumeSnapshot {
+    pub id: String,
+    pub size: i64,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug)]
 pub struct RemoveVolumeInput {
     pub id: String,
 }
+
+/// Forks keep the name of the source volume so the machines mounting it by name can move to the
+/// fork.
+#[derive(Debug)]
+pub struct ForkVolumeInput {
+    pub id: String,
+    pub name: String,
+    pub region: Option<String>,
+}
+
+/// A new empty volume, or one restored from the snapshot.
+#[derive(Debug)]
+pub struct CreateVolumeInput {
+    pub name: String,
+    pub size_gb: i32,
+    pub region: String,
+    pub encrypted: bool,
+    pub snapshot_id: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CreateVolumeRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_volume_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_gb: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<String>,
+}
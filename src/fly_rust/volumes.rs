@@ -5,7 +5,7 @@ use serde::de::DeserializeOwned;
 use tracing::{info, instrument};
 
 use super::request_builder::{find_err, RequestBuilderMachines};
-use super::volume_types::Volume;
+use super::volume_types::{CreateVolumeRequest, Volume, VolumeSnapshot};
 use crate::state::RdrResult;
 use crate::transformations::ListVolume;
 
@@ -50,6 +50,7 @@ pub async fn get_volumes(
     Ok(volumes)
 }
 
+#[instrument(err)]
 pub async fn delete_volume(
     request_builder_machines: &RequestBuilderMachines,
     app_name: &str,
@@ -65,3 +66,53 @@ pub async fn delete_volume(
         serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
     Ok(volume)
 }
+
+#[instrument(err)]
+pub async fn create_volume(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    request: &CreateVolumeRequest,
+) -> RdrResult<Volume> {
+    let response = request_builder_machines
+        .post(format!("/v1/apps/{app_name}/volumes"))
+        .json(request)
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+    let volume: Volume =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    Ok(volume)
+}
+
+/// Takes a snapshot on demand, the API doesn't return it so it's found by listing the snapshots.
+#[instrument(err)]
+pub async fn create_volume_snapshot(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    volume_id: &str,
+) -> RdrResult<()> {
+    request_builder_machines
+        .post(format!("/v1/apps/{app_name}/volumes/{volume_id}/snapshots"))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[instrument(err)]
+pub async fn list_volume_snapshots(
+    request_builder_machines: &RequestBuilderMachines,
+    app_name: &str,
+    volume_id: &str,
+) -> RdrResult<Vec<VolumeSnapshot>> {
+    let response = request_builder_machines
+        .get(format!("/v1/apps/{app_name}/volumes/{volume_id}/snapshots"))
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+    let snapshots: Vec<VolumeSnapshot> =
+        serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(&bytes))?;
+    Ok(snapshots)
+}
@@ -1,17 +1,24 @@
 use color_eyre::eyre::eyre;
 use crossterm::event::{Event as CrostermEvent, KeyCode, KeyEvent, KeyModifiers};
 use tui_input::backend::crossterm::EventHandler;
-use tui_input::Input;
+use tui_input::{Input, InputRequest};
 
-use crate::ops::logs::dump_file_path;
+use crate::config::Confirmation;
+use crate::keybindings::{self, Action, Context};
 use crate::ops::IoReqEvent;
+use crate::plugins;
 use crate::state::view::View;
 use crate::state::{
     InputState, MultiSelectMode, MultiSelectModeReason, PopupType, RdrResult, State,
 };
-use crate::transformations::{ListApp, ListOrganization};
+use crate::transformations::{
+    ListApp, ListDedicatedHost, ListExtension, ListMachine, ListOrganization,
+};
 use crate::widgets::log_viewer::TuiWidgetEvent;
 
+/// Lines scrolled by <PageUp/Down> in the scrollable popups
+const POPUP_SCROLL_PAGE: i32 = 10;
+
 pub async fn handle_key_events(key_event: KeyEvent, state: &mut State) -> RdrResult<()> {
     match key_event.code {
         KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
@@ -19,6 +26,7 @@ pub async fn handle_key_events(key_event: KeyEvent, state: &mut State) -> RdrRes
         }
         _ => {
             if !matches!(state.input_state, InputState::Hidden) {
+                //INFO: The keys of the text inputs are left as they are, they aren't actions
                 match key_event.code {
                     KeyCode::Enter => match &state.input_state {
                         InputState::Search { .. } => {
@@ -28,6 +36,10 @@ pub async fn handle_key_events(key_event: KeyEvent, state: &mut State) -> RdrRes
                             state.run_command().await?;
                             state.exit_input();
                         }
+                        InputState::KeybindingSearch { .. } => {
+                            state.popup = None;
+                            state.exit_input();
+                        }
                         InputState::Email { .. } => {
                             if state.should_process_popup() {
                                 let action = {
@@ -45,26 +57,202 @@ pub async fn handle_key_events(key_event: KeyEvent, state: &mut State) -> RdrRes
                                 if let Ok(action) = action {
                                     state.popup = None;
                                     state.input_state = InputState::Hidden;
-                                    if let Some(event) = action {
+                                    if let Some(event) = action
+                                        .and_then(|event| state.hold_for_typed_confirmation(event))
+                                    {
                                         state.dispatch(event).await;
                                     }
                                 }
                             }
                         }
+                        InputState::FilePath { .. }
+                            if state.should_process_popup()
+                                && matches!(state.get_popup_type(), PopupType::ExportViewPopup) =>
+                        {
+                            if let Ok(action) = state.process_export_view_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action {
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::Confirm { .. } if state.should_process_popup() => {
+                            //INFO: OK does nothing until the name is typed
+                            let confirmed = state.is_confirmation_typed()
+                                || !state
+                                    .should_take_action(&state.popup.as_ref().unwrap().actions);
+                            if confirmed {
+                                state.input_state = InputState::Hidden;
+                                if let Ok(action) = process_popup(state) {
+                                    run_popup_action(state, action).await?;
+                                }
+                            }
+                        }
+                        InputState::Secret { .. } if state.should_process_popup() => {
+                            if state.should_take_action(&state.popup.as_ref().unwrap().actions)
+                                && state.focus_secret_value()
+                            {
+                                return Ok(());
+                            }
+                            if let Ok(action) = state.process_set_secret_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action {
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::ShellCommand { .. } if state.should_process_popup() => {
+                            if let Ok(action) = state.process_exec_machine_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action {
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::Image { .. } if state.should_process_popup() => {
+                            if let Ok(action) = state.process_update_machines_image_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if action.is_some() {
+                                    state.exit_multi_select();
+                                }
+                                if let Some(event) = action
+                                    .and_then(|event| state.hold_for_typed_confirmation(event))
+                                {
+                                    if let IoReqEvent::UpdateMachinesImage {
+                                        machines, tag, ..
+                                    } = &event
+                                    {
+                                        state.open_view_machines_update_popup(machines, tag);
+                                    }
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::Name { .. } if state.should_process_popup() => {
+                            if let Ok(action) = state.process_rename_machine_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action
+                                    .and_then(|event| state.hold_for_typed_confirmation(event))
+                                {
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::Region { .. } if state.should_process_popup() => {
+                            let action = match state.get_popup_type() {
+                                PopupType::FocusLogsRegionPopup => {
+                                    state.process_focus_logs_region_popup()
+                                }
+                                _ => state.process_fork_volume_popup(),
+                            };
+                            if let Ok(action) = action {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action
+                                    .and_then(|event| state.hold_for_typed_confirmation(event))
+                                {
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::Metadata { .. } if state.should_process_popup() => {
+                            if let Ok(action) = state.process_set_machines_metadata_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action {
+                                    state.exit_multi_select();
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::FilePath { .. }
+                            if state.should_process_popup()
+                                && matches!(
+                                    state.get_popup_type(),
+                                    PopupType::CapturePacketsPopup | PopupType::TeeLogsPopup
+                                ) =>
+                        {
+                            let action = match state.get_popup_type() {
+                                PopupType::TeeLogsPopup => state.process_tee_logs_popup(),
+                                _ => state.process_capture_packets_popup(),
+                            };
+                            if let Ok(action) = action {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(event) = action {
+                                    state.dispatch(event).await;
+                                }
+                            }
+                        }
+                        InputState::FilePath { .. } if state.should_process_popup() => {
+                            if let Ok(file_path) = state.process_dump_logs_popup() {
+                                state.popup = None;
+                                state.input_state = InputState::Hidden;
+                                if let Some(file_path) = file_path {
+                                    state.dump_logs(file_path).await;
+                                }
+                            }
+                        }
                         _ => {}
                     },
                     KeyCode::Esc => match &mut state.input_state {
                         InputState::Email { input } => *input = Input::default(),
+                        InputState::FilePath { .. }
+                        | InputState::Metadata { .. }
+                        | InputState::Image { .. }
+                        | InputState::Name { .. }
+                        | InputState::Region { .. }
+                        | InputState::ShellCommand { .. }
+                        | InputState::Secret { .. }
+                        | InputState::Confirm { .. }
+                        | InputState::KeybindingSearch { .. } => {
+                            state.popup = None;
+                            state.exit_input();
+                        }
                         _ => {
                             if !state.resource_list.search_filter.is_empty() {
                                 state.resource_list.apply_search_filter("");
                             }
+                            state.logs_state.set_search(None);
                             state.exit_input();
                         }
                     },
+                    KeyCode::Char('r')
+                        if key_event.modifiers == KeyModifiers::CONTROL
+                            && matches!(&state.input_state, InputState::Search { .. })
+                            && state.get_current_view().is_logs() =>
+                    {
+                        state.toggle_log_search_regex();
+                    }
                     KeyCode::Tab if matches!(&state.input_state, InputState::Command { .. }) => {
                         state.complete_command();
                     }
+                    KeyCode::Tab if matches!(&state.input_state, InputState::FilePath { .. }) => {
+                        state.complete_dump_logs_file_path();
+                    }
+                    KeyCode::Tab
+                        if matches!(&state.input_state, InputState::Email { .. })
+                            && matches!(
+                                state.get_popup_type(),
+                                PopupType::DeleteOrganizationMembershipPopup
+                            ) =>
+                    {
+                        state.complete_member_email();
+                    }
+                    KeyCode::Tab if matches!(&state.input_state, InputState::Region { .. }) => {
+                        state.pick_region();
+                    }
+                    KeyCode::Up if state.update_region_picker() => {
+                        state.region_picker_previous();
+                    }
+                    KeyCode::Down if state.update_region_picker() => {
+                        state.region_picker_next();
+                    }
                     KeyCode::BackTab | KeyCode::Left | KeyCode::Up => {
                         if state.has_popup() {
                             state.popup_focus_previous();
@@ -80,96 +268,103 @@ pub async fn handle_key_events(key_event: KeyEvent, state: &mut State) -> RdrRes
                             input.handle_event(&CrostermEvent::Key(key_event));
                             state.apply_search_filter();
                         }
-                        InputState::Command { input, command: _ } => {
+                        InputState::Command { input, .. } => {
                             input.handle_event(&CrostermEvent::Key(key_event));
                             state.set_command();
                         }
-                        InputState::Email { input } => {
+                        InputState::Email { input }
+                        | InputState::FilePath { input }
+                        | InputState::Metadata { input }
+                        | InputState::Image { input }
+                        | InputState::Name { input }
+                        | InputState::Region { input }
+                        | InputState::ShellCommand { input }
+                        | InputState::Confirm { input, .. }
+                        | InputState::KeybindingSearch { input } => {
+                            input.handle_event(&CrostermEvent::Key(key_event));
+                        }
+                        InputState::Secret {
+                            key,
+                            value,
+                            value_focused,
+                        } => {
+                            let input = if *value_focused { value } else { key };
                             input.handle_event(&CrostermEvent::Key(key_event));
                         }
                         _ => {}
                     },
                 }
             } else if state.has_popup() {
+                //INFO: The form fields and the region picker take the keys before the actions
                 match key_event.code {
-                    KeyCode::Enter => {
-                        if state.should_process_popup() {
-                            let action = {
-                                let popup_type = &state.popup.as_ref().unwrap().popup_type;
-                                match popup_type {
-                                    PopupType::RestartResourcePopup => {
-                                        state.process_restart_resource_popup()
-                                    }
-                                    PopupType::StartMachinesPopup => {
-                                        state.process_start_machines_popup()
-                                    }
-                                    PopupType::SuspendMachinesPopup => {
-                                        state.process_suspend_machines_popup()
-                                    }
-                                    PopupType::StopMachinesPopup => {
-                                        state.process_stop_machines_popup()
-                                    }
-                                    PopupType::KillMachinePopup => {
-                                        state.process_kill_machine_popup()
-                                    }
-                                    PopupType::DestroyResourcePopup => {
-                                        state.process_destroy_resource_popup()
-                                    }
-                                    PopupType::CordonMachinesPopup => {
-                                        state.process_cordon_machines_popup()
-                                    }
-                                    PopupType::UncordonMachinesPopup => {
-                                        state.process_uncordon_machines_popup()
-                                    }
-                                    PopupType::InfoPopup
-                                    | PopupType::ErrorPopup
-                                    | PopupType::ViewOrganizationMembersPopup
-                                    | PopupType::ViewAppReleasesPopup
-                                    | PopupType::ViewAppServicesPopup
-                                    | PopupType::ViewCommandsPopup => Ok(None),
-                                    _ => Err(eyre!("noop")),
-                                }
-                            };
-                            if let Ok(action) = action {
-                                state.popup = None;
-                                if let Some(event) = action {
-                                    if matches!(
-                                        event,
-                                        IoReqEvent::RestartMachines { .. }
-                                            | IoReqEvent::StartMachines { .. }
-                                            | IoReqEvent::SuspendMachines { .. }
-                                            | IoReqEvent::StopMachines { .. }
-                                            | IoReqEvent::CordonMachines { .. }
-                                            | IoReqEvent::UncordonMachines { .. }
-                                            | IoReqEvent::UnsetSecrets { .. }
-                                    ) {
-                                        state.exit_multi_select();
-                                    }
-                                    state.dispatch(event).await;
-                                }
-                            }
-                        }
-                    }
-                    KeyCode::Esc => {
-                        state.close_popup();
+                    //INFO: Enter moves to the next field of the form, OK submits it
+                    KeyCode::Enter if state.focused_popup_field().is_some() => {
+                        state.pick_region();
+                        state.popup_focus_next();
                     }
-                    KeyCode::BackTab | KeyCode::Left | KeyCode::Up | KeyCode::Char('k') => {
-                        state.popup_focus_previous();
+                    KeyCode::Enter if state.focused_popup_number().is_some() => {
+                        state.popup_focus_next();
                     }
-                    KeyCode::Tab | KeyCode::Right | KeyCode::Down | KeyCode::Char('j') => {
+                    KeyCode::Tab if state.update_region_picker() => {
+                        state.pick_region();
                         state.popup_focus_next();
                     }
-                    KeyCode::Char(' ') => {
-                        state.toggle_force_checkbox();
+                    KeyCode::Up if state.update_region_picker() => {
+                        state.region_picker_previous();
+                    }
+                    KeyCode::Down if state.update_region_picker() => {
+                        state.region_picker_next();
+                    }
+                    KeyCode::Char(_)
+                    | KeyCode::Backspace
+                    | KeyCode::Delete
+                    | KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Home
+                    | KeyCode::End
+                        if state.focused_popup_field().is_some() =>
+                    {
+                        if let Some(field) = state.focused_popup_field() {
+                            field.handle_key(key_event);
+                        }
+                    }
+                    KeyCode::Char(_)
+                    | KeyCode::Backspace
+                    | KeyCode::Delete
+                    | KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Home
+                    | KeyCode::End
+                    | KeyCode::Up
+                    | KeyCode::Down
+                        if state.focused_popup_number().is_some() =>
+                    {
+                        if let Some(field) = state.focused_popup_number() {
+                            field.handle_key(key_event);
+                        }
+                    }
+                    _ => handle_popup_action(key_event, state).await?,
+                }
+            } else if state.is_copy_mode() {
+                //INFO: Esc always leaves, so remapped keys can't keep the mouse away
+                if key_event.code == KeyCode::Esc {
+                    state.exit_copy_mode();
+                    return Ok(());
+                }
+                match keybindings::action(Context::CopyMode, key_event) {
+                    Some(Action::CopyMode) => state.exit_copy_mode(),
+                    Some(Action::PreviousPage) => {
+                        state.logs_state.transition(TuiWidgetEvent::PrevPageKey)
+                    }
+                    Some(Action::NextPage) => {
+                        state.logs_state.transition(TuiWidgetEvent::NextPageKey)
                     }
                     _ => {}
                 }
             } else {
+                //INFO: The debugger is only shown in the debug builds, so its keys aren't actions
+                #[cfg(debug_assertions)]
                 match key_event.code {
-                    KeyCode::Char('a') if key_event.modifiers == KeyModifiers::CONTROL => {
-                        state.open_view_commands_popup()?;
-                    }
-                    KeyCode::Char(':') => state.enter_command_mode(),
                     KeyCode::Char('n') => state
                         .debugger_state
                         .transition(tui_logger::TuiWidgetEvent::PrevPageKey),
@@ -181,232 +376,600 @@ pub async fn handle_key_events(key_event: KeyEvent, state: &mut State) -> RdrRes
                         .transition(tui_logger::TuiWidgetEvent::EscapeKey),
                     _ => {}
                 }
-                match &state.get_current_view() {
-                    resource_list @ (View::Organizations { .. }
-                    | View::Apps { .. }
-                    | View::Machines { .. }
-                    | View::Volumes { .. }
-                    | View::Secrets { .. }) => {
-                        match (key_event.code, resource_list) {
-                            (KeyCode::Enter, view) => {
-                                if let MultiSelectMode::On(reason) = &state.multi_select_mode {
-                                    if !state.resource_list.multi_select_state.is_empty() {
-                                        match reason {
-                                            MultiSelectModeReason::RestartMachines => {
-                                                state.open_restart_resource_popup()?
-                                            }
-                                            MultiSelectModeReason::StartMachines => {
-                                                state.open_start_machines_popup()
-                                            }
-                                            MultiSelectModeReason::SuspendMachines => {
-                                                state.open_suspend_machines_popup()
-                                            }
-                                            MultiSelectModeReason::StopMachines => {
-                                                state.open_stop_machines_popup()
-                                            }
-                                            MultiSelectModeReason::CordonMachines => {
-                                                state.open_cordon_machines_popup()
-                                            }
-                                            MultiSelectModeReason::UncordonMachines => {
-                                                state.open_uncordon_machines_popup()
-                                            }
-                                            MultiSelectModeReason::UnsetSecrets => {
-                                                state.open_destroy_resource_popup()?;
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    match view {
-                                        View::Machines { .. } => {
-                                            state.navigate_to_machine_logs().await?;
-                                        }
-                                        View::Apps { .. } => {
-                                            state.navigate_to_machines().await?;
-                                        }
-                                        View::Organizations { .. } => {
-                                            state.navigate_to_apps().await?;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            (KeyCode::Char('d'), view)
-                                if key_event.modifiers == KeyModifiers::CONTROL =>
-                            {
-                                let should_open_destroy_popup = match view {
-                                    View::Secrets { .. } => false,
-                                    View::Organizations { filter } => filter.is_admin_only(),
-                                    _ => true,
-                                };
-                                if should_open_destroy_popup {
-                                    state.open_destroy_resource_popup()?;
-                                }
-                            }
-                            // Orgs
-                            (KeyCode::Char('A'), View::Organizations { .. }) => {
-                                state.toggle_org_admin_only().await?;
-                            }
-                            (KeyCode::Char('i'), View::Organizations { filter }) => {
-                                if filter.is_admin_only() {
-                                    state.open_create_organization_invite_popup()?;
-                                }
-                            }
-                            (KeyCode::Char('r'), View::Organizations { filter }) => {
-                                if filter.is_admin_only() {
-                                    state.open_delete_organization_membership_popup()?;
-                                }
-                            }
-                            (KeyCode::Char('m'), View::Organizations { .. }) => {
-                                let org: ListOrganization = state.get_selected_resource()?.into();
-                                state.clear_organization_members_list();
-                                state
-                                    .dispatch(IoReqEvent::ViewOrganizationMembers {
-                                        org_slug: org.slug,
-                                    })
-                                    .await;
-                                state.open_view_organization_members_popup()?;
-                            }
-                            // Apps
-                            (KeyCode::Char('o'), View::Apps { .. }) => {
-                                let app: ListApp = state.get_selected_resource()?.into();
-                                state
-                                    .dispatch(IoReqEvent::OpenApp { app_name: app.name })
-                                    .await;
-                            }
-                            (KeyCode::Char('r'), View::Apps { .. })
-                                if key_event.modifiers == KeyModifiers::CONTROL =>
-                            {
-                                state.open_restart_resource_popup()?;
-                            }
-                            (KeyCode::Char('r'), View::Apps { .. }) => {
-                                let app: ListApp = state.get_selected_resource()?.into();
-                                state.clear_app_releases_list();
-                                state
-                                    .dispatch(IoReqEvent::ViewAppReleases { app_name: app.name })
-                                    .await;
-                                state.open_view_app_releases_popup()?;
-                            }
-                            (KeyCode::Char('s'), View::Apps { .. }) => {
-                                let app: ListApp = state.get_selected_resource()?.into();
-                                state.clear_app_services_list();
-                                state
-                                    .dispatch(IoReqEvent::ViewAppServices { app_name: app.name })
-                                    .await;
-                                state.open_view_app_services_popup()?;
-                            }
-                            (KeyCode::Char('l'), View::Apps { .. }) => {
-                                state.navigate_to_app_logs().await?;
-                            }
-                            // Machines
-                            (KeyCode::Char('r'), View::Machines { .. }) => {
-                                state.start_restart_machines();
-                            }
-                            (KeyCode::Char('s'), View::Machines { .. }) => {
-                                state.start_start_machines();
-                            }
-                            (KeyCode::Char('u'), View::Machines { .. }) => {
-                                state.start_suspend_machines();
-                            }
-                            (KeyCode::Char('t'), View::Machines { .. }) => {
-                                state.start_stop_machines();
-                            }
-                            (KeyCode::Char('k'), View::Machines { .. })
-                                if key_event.modifiers == KeyModifiers::CONTROL =>
-                            {
-                                state.open_kill_machine_popup();
-                            }
-                            (KeyCode::Char('c'), View::Machines { .. }) => {
-                                state.start_cordon_machines();
-                            }
-                            (KeyCode::Char('C'), View::Machines { .. }) => {
-                                state.start_uncordon_machines();
-                            }
-                            (KeyCode::Char('l'), View::Machines { .. }) => {
-                                state.navigate_to_machine_logs().await?;
-                            }
-                            // Secrets
-                            (KeyCode::Char('u'), View::Secrets { .. }) => {
-                                state.start_unset_secrets();
-                            }
-                            // Common
-                            (KeyCode::Char('/'), _) => {
-                                state.enter_search_mode();
-                            }
-                            (KeyCode::Char(' '), _) => {
-                                if matches!(state.multi_select_mode, MultiSelectMode::On(..)) {
-                                    state.resource_list.toggle_multi_selection();
-                                }
-                            }
-                            (KeyCode::Esc, _) => {
-                                if !state.resource_list.search_filter.is_empty() {
-                                    state.resource_list.apply_search_filter("");
-                                } else if matches!(state.multi_select_mode, MultiSelectMode::On(..))
-                                {
-                                    state.exit_multi_select();
-                                } else {
-                                    state.navigate_back().await?;
-                                }
-                            }
-                            (
-                                KeyCode::BackTab | KeyCode::Left | KeyCode::Up | KeyCode::Char('k'),
-                                _,
-                            ) => {
-                                state.resource_list.previous(1);
-                            }
-                            (
-                                KeyCode::Tab | KeyCode::Right | KeyCode::Down | KeyCode::Char('j'),
-                                _,
-                            ) => {
-                                state.resource_list.next(1);
-                            }
-                            _ => {}
-                        }
-                    }
-                    View::AppLogs { opts, .. } => match key_event.code {
-                        KeyCode::Esc => state.navigate_back().await?,
-                        KeyCode::PageUp => state.logs_state.transition(TuiWidgetEvent::PrevPageKey),
-                        KeyCode::PageDown => {
-                            state.logs_state.transition(TuiWidgetEvent::NextPageKey)
+                handle_view_action(key_event, state).await?;
+                apply_confirmation(state).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_popup_action(key_event: KeyEvent, state: &mut State) -> RdrResult<()> {
+    let popup_type = state.get_popup_type();
+    let Some(action) = keybindings::action(Context::Popup(&popup_type), key_event) else {
+        return Ok(());
+    };
+    let previous_popup = std::mem::discriminant(&popup_type);
+    match (action, popup_type) {
+        //INFO: Enter activates the focused control, like Space does for the checkboxes
+        (Action::Confirm, _) if state.toggle_focused_checkbox() => {}
+        (Action::Confirm, _) if state.turn_wizard_page() => {}
+        (Action::Confirm, _) => {
+            if state.should_process_popup() {
+                if let Ok(action) = process_popup(state) {
+                    run_popup_action(state, action).await?;
+                }
+            }
+        }
+        (Action::NavigateBack, _) => state.close_popup(),
+        (Action::Refresh, _) => state.refresh().await?,
+        // Releases
+        (Action::Previous, PopupType::ViewAppReleasesPopup) => state.app_releases_previous(),
+        (Action::Next, PopupType::ViewAppReleasesPopup) => state.app_releases_next(),
+        (Action::Mark, PopupType::ViewAppReleasesPopup) => state.toggle_app_release_mark(),
+        (Action::Rollback, _) => state.open_rollback_release_popup()?,
+        // Cleanup
+        (Action::Previous, PopupType::AppCleanupPopup) => state.app_cleanup_previous(),
+        (Action::Next, PopupType::AppCleanupPopup) => state.app_cleanup_next(),
+        (Action::Mark, PopupType::AppCleanupPopup) => state.toggle_app_cleanup_mark(),
+        (Action::MarkAll, _) => state.toggle_app_cleanup_mark_all(),
+        // Scrollable
+        (
+            action @ (Action::Previous | Action::Next | Action::PreviousPage | Action::NextPage),
+            PopupType::ViewMachineDetailPopup
+            | PopupType::ViewMachineExecPopup
+            | PopupType::ViewLogLinePopup
+            | PopupType::ViewAppReleasesDiffPopup,
+        ) => state.scroll_popup(match action {
+            Action::Previous => -1,
+            Action::Next => 1,
+            Action::PreviousPage => -POPUP_SCROLL_PAGE,
+            _ => POPUP_SCROLL_PAGE,
+        }),
+        // Scale
+        (Action::Previous, PopupType::ScaleMachinePopup) => state.machine_scale_previous(),
+        (Action::Next, PopupType::ScaleMachinePopup) => state.machine_scale_next(),
+        // Services
+        (Action::Previous, PopupType::ViewAppServicesPopup) => state.app_services_previous(),
+        (Action::Next, PopupType::ViewAppServicesPopup) => state.app_services_next(),
+        (action @ (Action::EditService | Action::AddService), _) => {
+            state.open_edit_app_service_popup(action == Action::AddService)?
+        }
+        (Action::RemoveServicePort, _) => state.open_remove_app_service_port_popup()?,
+        // Autoscaling
+        (Action::Previous, PopupType::ViewAppAutoscalingPopup) => state.app_autoscaling_previous(),
+        (Action::Next, PopupType::ViewAppAutoscalingPopup) => state.app_autoscaling_next(),
+        (
+            action @ (Action::CycleAutostop
+            | Action::ToggleAutostart
+            | Action::IncreaseMinMachines
+            | Action::DecreaseMinMachines),
+            _,
+        ) => state.stage_app_autoscaling_change(action),
+        // Deploy token
+        (Action::CopyToken, _) => state.copy_deploy_token()?,
+        // Common
+        (Action::Previous | Action::FocusPrevious, _) => state.popup_focus_previous(),
+        (Action::Next | Action::FocusNext, _) => state.popup_focus_next(),
+        (Action::Mark, _) => {
+            state.toggle_focused_checkbox();
+        }
+        _ => {}
+    }
+    //INFO: A popup opened from another one, like the confirmation of the staged autoscaling, is
+    //confirmed at its configured level too
+    if state
+        .popup
+        .as_ref()
+        .is_some_and(|popup| std::mem::discriminant(&popup.popup_type) != previous_popup)
+    {
+        apply_confirmation(state).await?;
+    }
+    Ok(())
+}
+
+async fn handle_view_action(key_event: KeyEvent, state: &mut State) -> RdrResult<()> {
+    let view = state.get_current_view();
+    //INFO: The keys of a plugin's actions come from its script, they take over in its view
+    if let (View::Plugin { name }, KeyCode::Char(key)) = (&view, key_event.code) {
+        if !key_event.modifiers.contains(KeyModifiers::CONTROL)
+            && plugins::get(name).is_some_and(|plugin| plugin.action(key).is_some())
+        {
+            state
+                .dispatch(IoReqEvent::RunPluginAction {
+                    name: name.clone(),
+                    key,
+                    row: state.get_selected_resource()?,
+                })
+                .await;
+            return Ok(());
+        }
+    }
+    let Some(action) = keybindings::action(Context::View(&view), key_event) else {
+        return Ok(());
+    };
+    match (action, &view) {
+        (Action::ViewCommands, _) => state.open_view_commands_popup()?,
+        (Action::ViewKeybindings, _) => state.open_view_keybindings_popup(),
+        (Action::CommandMode, _) => state.enter_command_mode().await,
+        (Action::Search, _) => state.enter_search_mode(),
+        // Logs
+        (Action::NavigateBack, View::AppLogs { .. } | View::MachineLogs { .. }) => {
+            if state.logs_state.has_search() {
+                state.logs_state.set_search(None)
+            } else {
+                state.navigate_back().await?
+            }
+        }
+        (Action::Select, View::AppLogs { .. } | View::MachineLogs { .. }) => {
+            state.open_view_log_line_popup()
+        }
+        (Action::Previous, View::AppLogs { .. }) => {
+            state.logs_state.transition(TuiWidgetEvent::UpKey)
+        }
+        (Action::Next, View::AppLogs { .. }) => {
+            state.logs_state.transition(TuiWidgetEvent::DownKey)
+        }
+        (Action::DecreaseDisplayLevel, _) => state.logs_state.transition(TuiWidgetEvent::LeftKey),
+        (Action::IncreaseDisplayLevel, _) => state.logs_state.transition(TuiWidgetEvent::RightKey),
+        (Action::IncreaseFilterLevel, _) => state.logs_state.transition(TuiWidgetEvent::PlusKey),
+        (Action::DecreaseFilterLevel, _) => state.logs_state.transition(TuiWidgetEvent::MinusKey),
+        (Action::ToggleRegions, _) => state.logs_state.transition(TuiWidgetEvent::HideKey),
+        (Action::FocusRegion, _) => state.logs_state.transition(TuiWidgetEvent::FocusKey),
+        (Action::FocusRegionSearch, _) => {
+            state.load_regions().await;
+            state.open_focus_logs_region_popup();
+        }
+        (Action::CycleMachineFilter, _) => state.logs_state.transition(TuiWidgetEvent::InstanceKey),
+        (Action::PreviousPage, _) => state.logs_state.transition(TuiWidgetEvent::PrevPageKey),
+        (Action::NextPage, _) => state.logs_state.transition(TuiWidgetEvent::NextPageKey),
+        (Action::ResetScroll, _) => state.logs_state.transition(TuiWidgetEvent::EscapeKey),
+        (Action::ToggleRawJson, _) => state.logs_state.transition(TuiWidgetEvent::RawKey),
+        (Action::NextMatch, _) => state.logs_state.transition(TuiWidgetEvent::NextMatchKey),
+        (Action::PrevMatch, _) => state.logs_state.transition(TuiWidgetEvent::PrevMatchKey),
+        (Action::DumpLogs, View::AppLogs { opts, .. }) => {
+            state.open_dump_logs_popup(opts.app_name.clone());
+        }
+        (Action::DumpLogs, View::MachineLogs { opts, .. }) => {
+            state
+                .open_dump_logs_popup(opts.app_name.clone() + "_" + opts.vm_id.as_deref().unwrap());
+        }
+        (Action::TeeLogs, View::AppLogs { opts, .. }) => {
+            state.toggle_log_tee(opts.app_name.clone()).await;
+        }
+        (Action::TeeLogs, View::MachineLogs { opts, .. }) => {
+            state
+                .toggle_log_tee(opts.app_name.clone() + "_" + opts.vm_id.as_deref().unwrap())
+                .await;
+        }
+        (Action::LogStats, _) => state.open_view_log_stats_popup(),
+        (Action::CopyMode, _) => state.enter_copy_mode(),
+        (Action::SplitByLevel, _) => state.logs_split = !state.logs_split,
+        // Common
+        (Action::NavigateBack, _) => {
+            if !state.resource_list.search_filter.is_empty() {
+                state.resource_list.apply_search_filter("");
+            } else if matches!(state.multi_select_mode, MultiSelectMode::On(..)) {
+                state.exit_multi_select();
+            } else {
+                state.navigate_back().await?;
+            }
+        }
+        (Action::Select, view) => {
+            if let MultiSelectMode::On(reason) = &state.multi_select_mode {
+                if !state.resource_list.multi_select_state.is_empty() {
+                    match reason {
+                        MultiSelectModeReason::RestartMachines => {
+                            state.open_restart_resource_popup()?
                         }
-                        KeyCode::Up => state.logs_state.transition(TuiWidgetEvent::UpKey),
-                        KeyCode::Down => state.logs_state.transition(TuiWidgetEvent::DownKey),
-                        KeyCode::Left => state.logs_state.transition(TuiWidgetEvent::LeftKey),
-                        KeyCode::Right => state.logs_state.transition(TuiWidgetEvent::RightKey),
-                        KeyCode::Char('r') => {
-                            state.logs_state.transition(TuiWidgetEvent::EscapeKey)
+                        MultiSelectModeReason::StartMachines => state.open_start_machines_popup(),
+                        MultiSelectModeReason::SuspendMachines => {
+                            state.open_suspend_machines_popup()
                         }
-                        KeyCode::Char('+') => state.logs_state.transition(TuiWidgetEvent::PlusKey),
-                        KeyCode::Char('-') => state.logs_state.transition(TuiWidgetEvent::MinusKey),
-                        KeyCode::Char('t') => state.logs_state.transition(TuiWidgetEvent::HideKey),
-                        KeyCode::Char('f') => state.logs_state.transition(TuiWidgetEvent::FocusKey),
-                        KeyCode::Char('s') if key_event.modifiers == KeyModifiers::CONTROL => {
-                            let file_path = dump_file_path(opts.app_name.clone()).await?;
-                            state.dispatch(IoReqEvent::DumpLogs { file_path }).await;
+                        MultiSelectModeReason::StopMachines => state.open_stop_machines_popup(),
+                        MultiSelectModeReason::CordonMachines => state.open_cordon_machines_popup(),
+                        MultiSelectModeReason::UncordonMachines => {
+                            state.open_uncordon_machines_popup()
                         }
-                        _ => {}
-                    },
-                    View::MachineLogs { opts, .. } => match key_event.code {
-                        KeyCode::Esc => state.navigate_back().await?,
-                        KeyCode::PageUp => state.logs_state.transition(TuiWidgetEvent::PrevPageKey),
-                        KeyCode::PageDown => {
-                            state.logs_state.transition(TuiWidgetEvent::NextPageKey)
+                        MultiSelectModeReason::SetMachinesMetadata => {
+                            state.open_set_machines_metadata_popup()
                         }
-                        KeyCode::Char('r') => {
-                            state.logs_state.transition(TuiWidgetEvent::EscapeKey)
+                        MultiSelectModeReason::UpdateMachinesImage => {
+                            state.open_update_machines_image_popup()
                         }
-                        KeyCode::Char('s') if key_event.modifiers == KeyModifiers::CONTROL => {
-                            let file_path = dump_file_path(
-                                opts.app_name.clone() + "_" + &opts.vm_id.clone().unwrap(),
-                            )
-                            .await?;
-                            state.dispatch(IoReqEvent::DumpLogs { file_path }).await;
+                        MultiSelectModeReason::UnsetSecrets => {
+                            state.open_destroy_resource_popup()?;
                         }
-                        _ => {}
-                    },
+                    }
+                }
+            } else {
+                match view {
+                    View::Machines { .. } => {
+                        state.navigate_to_machine_logs().await?;
+                    }
+                    View::Apps { .. } => {
+                        state.navigate_to_machines().await?;
+                    }
+                    View::Organizations { .. } => {
+                        state.navigate_to_apps().await?;
+                    }
+                    View::DedicatedHosts { org_slug, .. } => {
+                        let host: ListDedicatedHost = state.get_selected_resource()?.into();
+                        state.clear_dedicated_host_machines_list();
+                        state
+                            .dispatch(IoReqEvent::ViewDedicatedHostMachines {
+                                org_slug: org_slug.clone(),
+                                host_id: host.id,
+                            })
+                            .await;
+                        state.open_view_dedicated_host_machines_popup()?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (Action::Destroy, view) => {
+            let should_open_destroy_popup = match view {
+                View::Secrets { .. }
+                | View::Certificates { .. }
+                | View::Extensions { .. }
+                | View::DedicatedHosts { .. }
+                | View::Processes { .. }
+                | View::Plugin { .. } => false,
+                View::Organizations { filter } => filter.is_admin_only(),
+                _ => true,
+            };
+            if should_open_destroy_popup {
+                state.open_destroy_resource_popup()?;
+                if let View::Apps { .. } = view {
+                    let app: ListApp = state.get_selected_resource()?.into();
+                    state
+                        .dispatch(IoReqEvent::PreviewAppDestroy { app_name: app.name })
+                        .await;
                 }
             }
         }
+        (Action::Export, _) => state.open_export_view_popup(),
+        (Action::Mark, _) => {
+            if matches!(state.multi_select_mode, MultiSelectMode::On(..)) {
+                state.resource_list.toggle_multi_selection();
+            }
+        }
+        (Action::Previous, _) => state.resource_list.previous(1),
+        (Action::Next, _) => state.resource_list.next(1),
+        // Orgs
+        (Action::ToggleAdminOnly, _) => state.toggle_org_admin_only().await?,
+        (Action::Refresh, _) => state.refresh().await?,
+        (Action::ListAllApps, _) => state.navigate_to_all_apps().await?,
+        (Action::Invite, View::Organizations { filter }) => {
+            if filter.is_admin_only() {
+                state.open_create_organization_invite_popup()?;
+            }
+        }
+        (Action::RemoveMember, View::Organizations { filter }) => {
+            if filter.is_admin_only() {
+                //INFO: The members are fetched for the completion of the email
+                let org: ListOrganization = state.get_selected_resource()?.into();
+                state.clear_organization_members_list();
+                state
+                    .dispatch(IoReqEvent::ViewOrganizationMembers { org_slug: org.slug })
+                    .await;
+                state.open_delete_organization_membership_popup()?;
+            }
+        }
+        (Action::ViewMembers, _) => {
+            let org: ListOrganization = state.get_selected_resource()?.into();
+            state.clear_organization_members_list();
+            state
+                .dispatch(IoReqEvent::ViewOrganizationMembers { org_slug: org.slug })
+                .await;
+            state.open_view_organization_members_popup()?;
+        }
+        // Apps
+        (Action::Open, View::Apps { .. }) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state
+                .dispatch(IoReqEvent::OpenApp { app_name: app.name })
+                .await;
+        }
+        (Action::Restart, View::Apps { .. }) => state.open_restart_resource_popup()?,
+        (Action::ViewReleases, _) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state.clear_app_releases_list();
+            state
+                .dispatch(IoReqEvent::ViewAppReleases { app_name: app.name })
+                .await;
+            state.open_view_app_releases_popup()?;
+        }
+        (Action::ViewServices, _) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state.clear_app_services_list();
+            state
+                .dispatch(IoReqEvent::ViewAppServices { app_name: app.name })
+                .await;
+            state.open_view_app_services_popup()?;
+        }
+        (Action::ViewEnv, _) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state.clear_app_env_list();
+            state
+                .dispatch(IoReqEvent::ViewAppEnv { app_name: app.name })
+                .await;
+            state.open_view_app_env_popup()?;
+        }
+        (Action::Cleanup, _) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state.clear_app_cleanup_list();
+            state
+                .dispatch(IoReqEvent::ViewAppCleanup { app_name: app.name })
+                .await;
+            state.open_app_cleanup_popup()?;
+        }
+        (Action::ViewAutoscaling, _) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state.clear_app_autoscaling_list();
+            state
+                .dispatch(IoReqEvent::ViewAppAutoscaling { app_name: app.name })
+                .await;
+            state.open_view_app_autoscaling_popup()?;
+        }
+        (Action::ViewPorts, _) => {
+            let app: ListApp = state.get_selected_resource()?.into();
+            state.clear_app_ports_list();
+            state
+                .dispatch(IoReqEvent::ViewAppPorts { app_name: app.name })
+                .await;
+            state.open_view_app_ports_popup()?;
+        }
+        (Action::Logs, View::Apps { .. }) => state.navigate_to_app_logs().await?,
+        (Action::CreateDeployToken, _) => state.open_create_deploy_token_popup()?,
+        // Machines
+        (Action::Restart, View::Machines { .. }) => state.start_restart_machines(),
+        (Action::Console, _) => state.request_console()?,
+        (Action::Start, _) => state.start_start_machines(),
+        (Action::Suspend, _) => state.start_suspend_machines(),
+        (Action::Stop, _) => state.start_stop_machines(),
+        (Action::Kill, _) => state.open_kill_machine_popup(),
+        (Action::Cordon, _) => state.start_cordon_machines(),
+        (Action::Uncordon, _) => state.start_uncordon_machines(),
+        (Action::Logs, View::Machines { .. }) => state.navigate_to_machine_logs().await?,
+        (Action::Exec, _) => state.open_exec_machine_popup()?,
+        (Action::Describe, _) => state.open_view_machine_detail_popup()?,
+        (Action::Scale, _) => state.open_scale_machine_popup()?,
+        (Action::SetMetadata, _) => state.start_set_machines_metadata(),
+        (Action::UpdateImage, _) => state.start_update_machines_image(),
+        (Action::Rename, _) => state.open_rename_machine_popup()?,
+        (Action::FilterByHost, _) => state.filter_machines_by_host()?,
+        (Action::Align, _) => state.open_align_machines_popup()?,
+        (Action::ViewProcesses, _) => state.navigate_to_processes().await?,
+        (Action::CapturePackets, _) => state.open_capture_packets_popup()?,
+        (Action::ViewChecks, View::Machines { app_name, .. }) => {
+            let machine: ListMachine = state.get_selected_resource()?.into();
+            state.clear_machine_checks_list();
+            state
+                .dispatch(IoReqEvent::ViewMachineChecks {
+                    app_name: app_name.clone(),
+                    machine_id: machine.id,
+                })
+                .await;
+            state.open_view_machine_checks_popup()?;
+        }
+        (Action::Pin, _) => state.toggle_pin().await?,
+        // Volumes
+        (Action::ToggleOrphaned, _) => state.toggle_orphaned_volumes_filter(),
+        (Action::Fork, _) => {
+            state.load_regions().await;
+            state.open_fork_volume_popup()?;
+        }
+        (Action::Snapshot, _) => state.open_snapshot_volume_popup()?,
+        (Action::Create, View::Volumes { .. }) => {
+            state.load_regions().await;
+            state.open_create_volume_popup();
+        }
+        (Action::Detach, _) => state.open_detach_volume_popup()?,
+        // Secrets
+        (Action::StageUnset, _) => state.start_unset_secrets(),
+        (Action::StageSet, _) => state.open_set_secret_popup()?,
+        (Action::DeploySecrets, _) => state.open_deploy_secrets_popup()?,
+        (Action::PendingSecrets, _) => state.open_pending_secrets_popup()?,
+        (Action::Unstage, _) => state.unstage_last_secret_change(),
+        // Extensions
+        (Action::Open, View::Extensions { .. }) => {
+            let extension: ListExtension = state.get_selected_resource()?.into();
+            state
+                .dispatch(IoReqEvent::OpenExtension {
+                    name: extension.name,
+                    provider: extension.provider,
+                })
+                .await;
+        }
+        // WireGuard peers
+        (Action::Create, View::WireGuardPeers { .. }) => {
+            state.load_regions().await;
+            state.open_create_wire_guard_peer_popup();
+        }
+        _ => {}
     }
+    Ok(())
+}
+
+fn process_popup(state: &mut State) -> RdrResult<Option<IoReqEvent>> {
+    match state.get_popup_type() {
+        PopupType::RestartResourcePopup => state.process_restart_resource_popup(),
+        PopupType::StartMachinesPopup => state.process_start_machines_popup(),
+        PopupType::SuspendMachinesPopup => state.process_suspend_machines_popup(),
+        PopupType::StopMachinesPopup => state.process_stop_machines_popup(),
+        PopupType::KillMachinePopup => state.process_kill_machine_popup(),
+        PopupType::AlignMachinesPopup => state.process_align_machines_popup(),
+        PopupType::SnapshotVolumePopup => state.process_snapshot_volume_popup(),
+        PopupType::CreateVolumePopup => state.process_create_volume_popup(),
+        PopupType::DetachVolumePopup => state.process_detach_volume_popup(),
+        PopupType::DeploySecretsPopup => state.process_deploy_secrets_popup(),
+        PopupType::PendingSecretsPopup => state.process_pending_secrets_popup(),
+        PopupType::DestroyResourcePopup => state.process_destroy_resource_popup(),
+        PopupType::CordonMachinesPopup => state.process_cordon_machines_popup(),
+        PopupType::UncordonMachinesPopup => state.process_uncordon_machines_popup(),
+        PopupType::OverwriteLogsDumpPopup => state.process_overwrite_logs_dump_popup(),
+        PopupType::ViewAppReleasesPopup => state.process_view_app_releases_popup(),
+        PopupType::RollbackReleasePopup => state.process_rollback_release_popup(),
+        PopupType::EditAppServicePopup => state.process_edit_app_service_popup(),
+        PopupType::UpdateAppServicesPopup => state.process_update_app_services_popup(),
+        PopupType::UpdateAppAutoscalingPopup => state.process_update_app_autoscaling_popup(),
+        PopupType::ConfirmOperationPopup => state.process_confirm_operation_popup(),
+        PopupType::CreateDeployTokenPopup => state.process_create_deploy_token_popup(),
+        PopupType::CreateWireGuardPeerPopup => state.process_create_wire_guard_peer_popup(),
+        PopupType::AppCleanupPopup => state.process_app_cleanup_popup(),
+        PopupType::ScaleMachinePopup => state.process_scale_machine_popup(),
+        PopupType::InfoPopup
+        | PopupType::ErrorPopup
+        | PopupType::ViewOrganizationMembersPopup
+        | PopupType::ViewAppReleasesDiffPopup
+        | PopupType::ViewMachinesUpdatePopup
+        | PopupType::ViewDeployTokenPopup
+        | PopupType::ViewAppServicesPopup
+        | PopupType::ViewAppEnvPopup
+        | PopupType::ViewAppPortsPopup
+        | PopupType::ViewAppAutoscalingPopup
+        | PopupType::ViewDedicatedHostMachinesPopup
+        | PopupType::ViewMachineChecksPopup
+        | PopupType::ViewMachineDetailPopup
+        | PopupType::ViewMachineExecPopup
+        | PopupType::ViewLogStatsPopup
+        | PopupType::ViewLogLinePopup
+        | PopupType::ViewCommandsPopup
+        | PopupType::ViewKeybindingsPopup => Ok(None),
+        _ => Err(eyre!("noop")),
+    }
+}
 
+async fn run_popup_action(state: &mut State, action: Option<IoReqEvent>) -> RdrResult<()> {
+    let popup_type = state.popup.as_ref().map(|popup| popup.popup_type.clone());
+    state.close_popup();
+    //INFO: The edited service and the staged autoscaling are confirmed with their diff before
+    //they're applied
+    match popup_type {
+        Some(PopupType::EditAppServicePopup) if state.has_app_service_edit() => {
+            return state.open_update_app_services_popup();
+        }
+        Some(PopupType::ViewAppAutoscalingPopup) if state.has_app_autoscaling_changes() => {
+            return state.open_update_app_autoscaling_popup();
+        }
+        _ => {}
+    }
+    let action = match popup_type {
+        Some(PopupType::ConfirmOperationPopup) => action,
+        _ => action.and_then(|event| state.hold_for_typed_confirmation(event)),
+    };
+    if let Some(event) = action {
+        if matches!(
+            event,
+            IoReqEvent::RestartMachines { .. }
+                | IoReqEvent::StartMachines { .. }
+                | IoReqEvent::SuspendMachines { .. }
+                | IoReqEvent::StopMachines { .. }
+                | IoReqEvent::CordonMachines { .. }
+                | IoReqEvent::UncordonMachines { .. }
+        ) {
+            state.exit_multi_select();
+        }
+        if let IoReqEvent::UpdateMachinesImage { machines, tag, .. } = &event {
+            state.open_view_machines_update_popup(machines, tag);
+        }
+        let is_releases_diff = matches!(event, IoReqEvent::DiffAppReleases { .. });
+        let is_services_update = matches!(event, IoReqEvent::UpdateAppServices { .. });
+        let is_autoscaling_update = matches!(event, IoReqEvent::UpdateAppAutoscaling { .. });
+        let rollback_image_ref = match &event {
+            IoReqEvent::RollbackRelease { image_ref, .. } => Some(image_ref.clone()),
+            _ => None,
+        };
+        state.dispatch(event).await;
+        if is_releases_diff {
+            state.open_view_app_releases_diff_popup()?;
+        }
+        //INFO: The services are reloaded once the machines are updated
+        if is_services_update {
+            state.open_view_app_services_popup()?;
+        }
+        if is_autoscaling_update {
+            state.open_view_app_autoscaling_popup()?;
+        }
+        //INFO: The machines are only known once the rollout starts, the rows are filled in then
+        if let Some(image_ref) = rollback_image_ref {
+            state.open_view_machines_update_popup(&[], &image_ref);
+        }
+    }
     Ok(())
 }
+
+/// Applies the confirmation level configured for the operation of the popup that was just opened.
+async fn apply_confirmation(state: &mut State) -> RdrResult<()> {
+    let Some((operation, name)) = state.confirmation() else {
+        return Ok(());
+    };
+    match state.confirmation_level(operation) {
+        Confirmation::None => {
+            state.popup_focus_ok();
+            if let Ok(action) = process_popup(state) {
+                run_popup_action(state, action).await?;
+            }
+        }
+        Confirmation::Popup => {}
+        Confirmation::Typed => state.start_typed_confirmation(name),
+    }
+    Ok(())
+}
+
+pub fn handle_paste_event(text: &str, state: &mut State) {
+    let insert = |input: &mut Input| {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            input.handle(InputRequest::InsertChar(c));
+        }
+    };
+    match &mut state.input_state {
+        InputState::Search { input } => {
+            insert(input);
+            state.apply_search_filter();
+        }
+        InputState::Command { input, .. } => {
+            insert(input);
+            state.set_command();
+        }
+        InputState::Email { input }
+        | InputState::FilePath { input }
+        | InputState::Metadata { input }
+        | InputState::Image { input }
+        | InputState::Name { input }
+        | InputState::Region { input }
+        | InputState::ShellCommand { input }
+        | InputState::Confirm { input, .. }
+        | InputState::KeybindingSearch { input } => insert(input),
+        //INFO: A secret value can span lines, e.g. a PEM key, so its newlines are kept
+        InputState::Secret {
+            value,
+            value_focused: true,
+            ..
+        } => {
+            for c in text
+                .replace("\r\n", "\n")
+                .replace('\r', "\n")
+                .chars()
+                .filter(|c| !c.is_control() || *c == '\n')
+            {
+                value.handle(InputRequest::InsertChar(c));
+            }
+        }
+        InputState::Secret { key, .. } => insert(key),
+        InputState::Hidden => {
+            if let Some(field) = state.focused_popup_field() {
+                field.paste(text);
+            } else if let Some(field) = state.focused_popup_number() {
+                field.paste(text);
+            } else if matches!(state.multi_select_mode, MultiSelectMode::On(..))
+                && !state.has_popup()
+            {
+                state.select_pasted_ids(text);
+            }
+        }
+    }
+}
@@ -0,0 +1,626 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use color_eyre::eyre::eyre;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::state::view::View;
+use crate::state::{PopupType, RdrResult};
+
+static KEYBINDINGS: OnceLock<Vec<Binding>> = OnceLock::new();
+
+/// Actions the keys are dispatched to, their keys can be changed with the `keybindings` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    NavigateBack,
+    Search,
+    CommandMode,
+    ViewCommands,
+    ViewKeybindings,
+    Select,
+    Confirm,
+    Previous,
+    Next,
+    FocusPrevious,
+    FocusNext,
+    PreviousPage,
+    NextPage,
+    Mark,
+    MarkAll,
+    Destroy,
+    Export,
+    Refresh,
+    ListAllApps,
+    ViewMembers,
+    ToggleAdminOnly,
+    Invite,
+    RemoveMember,
+    Open,
+    Logs,
+    ViewReleases,
+    ViewServices,
+    ViewEnv,
+    ViewPorts,
+    ViewAutoscaling,
+    Cleanup,
+    CreateDeployToken,
+    Restart,
+    Start,
+    Suspend,
+    Stop,
+    Kill,
+    Cordon,
+    Uncordon,
+    SetMetadata,
+    UpdateImage,
+    Rename,
+    Align,
+    Scale,
+    Console,
+    FilterByHost,
+    ViewChecks,
+    Describe,
+    Exec,
+    CapturePackets,
+    ViewProcesses,
+    Pin,
+    ToggleOrphaned,
+    Snapshot,
+    Fork,
+    Create,
+    Detach,
+    StageSet,
+    StageUnset,
+    DeploySecrets,
+    PendingSecrets,
+    Unstage,
+    ToggleRegions,
+    FocusRegion,
+    FocusRegionSearch,
+    CycleMachineFilter,
+    DecreaseDisplayLevel,
+    IncreaseDisplayLevel,
+    DecreaseFilterLevel,
+    IncreaseFilterLevel,
+    DumpLogs,
+    TeeLogs,
+    LogStats,
+    NextMatch,
+    PrevMatch,
+    ToggleRawJson,
+    ResetScroll,
+    CopyMode,
+    SplitByLevel,
+    Rollback,
+    EditService,
+    AddService,
+    RemoveServicePort,
+    CycleAutostop,
+    ToggleAutostart,
+    IncreaseMinMachines,
+    DecreaseMinMachines,
+    CopyToken,
+}
+
+/// Where a key is looked up, the current view, the open popup or the copy mode of the logs.
+#[derive(Debug, Clone, Copy)]
+pub enum Context<'a> {
+    View(&'a View),
+    Popup(&'a PopupType),
+    CopyMode,
+}
+
+/// Part of the app an action's key is matched in, nested in the one it's listed under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Global,
+    Lists,
+    Organizations,
+    Apps,
+    Machines,
+    Volumes,
+    Secrets,
+    Extensions,
+    WireGuardPeers,
+    Logs,
+    AppLogs,
+    Popups,
+    MembersPopup,
+    ReleasesPopup,
+    CleanupPopup,
+    ScrollPopup,
+    ScalePopup,
+    ServicesPopup,
+    AutoscalingPopup,
+    DeployTokenPopup,
+    CopyMode,
+}
+
+impl Scope {
+    fn parent(self) -> Option<Scope> {
+        match self {
+            Scope::Global | Scope::Popups | Scope::CopyMode => None,
+            Scope::Lists | Scope::Logs => Some(Scope::Global),
+            Scope::Organizations
+            | Scope::Apps
+            | Scope::Machines
+            | Scope::Volumes
+            | Scope::Secrets
+            | Scope::Extensions
+            | Scope::WireGuardPeers => Some(Scope::Lists),
+            Scope::AppLogs => Some(Scope::Logs),
+            Scope::MembersPopup
+            | Scope::ReleasesPopup
+            | Scope::CleanupPopup
+            | Scope::ScrollPopup
+            | Scope::ScalePopup
+            | Scope::ServicesPopup
+            | Scope::AutoscalingPopup
+            | Scope::DeployTokenPopup => Some(Scope::Popups),
+        }
+    }
+
+    /// Innermost scope of the context.
+    fn of(context: Context) -> Scope {
+        match context {
+            Context::View(view) => match view {
+                View::Organizations { .. } => Scope::Organizations,
+                View::Apps { .. } => Scope::Apps,
+                View::Machines { .. } => Scope::Machines,
+                View::Volumes { .. } => Scope::Volumes,
+                View::Secrets { .. } => Scope::Secrets,
+                View::Extensions { .. } => Scope::Extensions,
+                View::WireGuardPeers { .. } => Scope::WireGuardPeers,
+                View::AppLogs { .. } => Scope::AppLogs,
+                View::MachineLogs { .. } => Scope::Logs,
+                _ => Scope::Lists,
+            },
+            Context::Popup(popup_type) => match popup_type {
+                PopupType::ViewOrganizationMembersPopup => Scope::MembersPopup,
+                PopupType::ViewAppReleasesPopup => Scope::ReleasesPopup,
+                PopupType::AppCleanupPopup => Scope::CleanupPopup,
+                PopupType::ViewMachineDetailPopup
+                | PopupType::ViewMachineExecPopup
+                | PopupType::ViewLogLinePopup
+                | PopupType::ViewAppReleasesDiffPopup => Scope::ScrollPopup,
+                PopupType::ScaleMachinePopup => Scope::ScalePopup,
+                PopupType::ViewAppServicesPopup => Scope::ServicesPopup,
+                PopupType::ViewAppAutoscalingPopup => Scope::AutoscalingPopup,
+                PopupType::ViewDeployTokenPopup => Scope::DeployTokenPopup,
+                _ => Scope::Popups,
+            },
+            Context::CopyMode => Scope::CopyMode,
+        }
+    }
+
+    /// Whether it's the other scope or nested in it.
+    fn is_within(self, other: Scope) -> bool {
+        let mut scope = Some(self);
+        while let Some(current) = scope {
+            if current == other {
+                return true;
+            }
+            scope = current.parent();
+        }
+        false
+    }
+
+    fn overlaps(self, other: Scope) -> bool {
+        self.is_within(other) || other.is_within(self)
+    }
+}
+
+// INFO: An action can have different default keys per scope, like restart being Ctrl-r for the
+// apps and r for the machines, a remap replaces all of them. The text inputs, Ctrl-c, Esc of the
+// copy mode, the keys of the plugins' actions and the debugger of the debug builds aren't here.
+const DEFAULT_KEYS: &[(Action, Scope, &[&str])] = &[
+    (Action::NavigateBack, Scope::Global, &["esc"]),
+    (Action::Search, Scope::Global, &["/"]),
+    (Action::CommandMode, Scope::Global, &[":"]),
+    (Action::ViewCommands, Scope::Global, &["ctrl-a"]),
+    (Action::ViewKeybindings, Scope::Global, &["?"]),
+    (Action::Select, Scope::Global, &["enter"]),
+    (
+        Action::Previous,
+        Scope::Lists,
+        &["up", "k", "backtab", "left"],
+    ),
+    (Action::Next, Scope::Lists, &["down", "j", "tab", "right"]),
+    (Action::Mark, Scope::Lists, &["space"]),
+    (Action::Destroy, Scope::Lists, &["ctrl-d"]),
+    (Action::Export, Scope::Lists, &["ctrl-e"]),
+    (Action::Refresh, Scope::Organizations, &["R"]),
+    (Action::ListAllApps, Scope::Organizations, &["a"]),
+    (Action::ViewMembers, Scope::Organizations, &["m"]),
+    (Action::ToggleAdminOnly, Scope::Organizations, &["A"]),
+    (Action::Invite, Scope::Organizations, &["i"]),
+    (Action::RemoveMember, Scope::Organizations, &["r"]),
+    (Action::Open, Scope::Apps, &["o"]),
+    (Action::Logs, Scope::Apps, &["l"]),
+    (Action::ViewReleases, Scope::Apps, &["r"]),
+    (Action::ViewServices, Scope::Apps, &["s"]),
+    (Action::ViewEnv, Scope::Apps, &["e"]),
+    (Action::ViewPorts, Scope::Apps, &["P"]),
+    (Action::ViewAutoscaling, Scope::Apps, &["A"]),
+    (Action::Cleanup, Scope::Apps, &["C"]),
+    (Action::CreateDeployToken, Scope::Apps, &["T"]),
+    (Action::Restart, Scope::Apps, &["ctrl-r"]),
+    (Action::Logs, Scope::Machines, &["l"]),
+    (Action::Restart, Scope::Machines, &["r"]),
+    (Action::Start, Scope::Machines, &["s"]),
+    (Action::Suspend, Scope::Machines, &["u"]),
+    (Action::Stop, Scope::Machines, &["t"]),
+    (Action::Kill, Scope::Machines, &["ctrl-k"]),
+    (Action::Cordon, Scope::Machines, &["c"]),
+    (Action::Uncordon, Scope::Machines, &["C"]),
+    (Action::SetMetadata, Scope::Machines, &["M"]),
+    (Action::UpdateImage, Scope::Machines, &["I"]),
+    (Action::Rename, Scope::Machines, &["n"]),
+    (Action::Align, Scope::Machines, &["A"]),
+    (Action::Scale, Scope::Machines, &["S"]),
+    (Action::Console, Scope::Machines, &["ctrl-s"]),
+    (Action::FilterByHost, Scope::Machines, &["h"]),
+    (Action::ViewChecks, Scope::Machines, &["H"]),
+    (Action::Describe, Scope::Machines, &["d"]),
+    (Action::Exec, Scope::Machines, &["x"]),
+    (Action::CapturePackets, Scope::Machines, &["P"]),
+    (Action::ViewProcesses, Scope::Machines, &["T"]),
+    (Action::Pin, Scope::Machines, &["p"]),
+    (Action::ToggleOrphaned, Scope::Volumes, &["o"]),
+    (Action::Snapshot, Scope::Volumes, &["s"]),
+    (Action::Fork, Scope::Volumes, &["f"]),
+    (Action::Create, Scope::Volumes, &["c"]),
+    (Action::Detach, Scope::Volumes, &["D"]),
+    (Action::Pin, Scope::Volumes, &["p"]),
+    (Action::StageSet, Scope::Secrets, &["s", "n"]),
+    (Action::StageUnset, Scope::Secrets, &["u"]),
+    (Action::DeploySecrets, Scope::Secrets, &["d"]),
+    (Action::PendingSecrets, Scope::Secrets, &["c"]),
+    (Action::Unstage, Scope::Secrets, &["backspace"]),
+    (Action::Pin, Scope::Secrets, &["p"]),
+    (Action::Open, Scope::Extensions, &["o"]),
+    (Action::Create, Scope::WireGuardPeers, &["c"]),
+    (Action::PreviousPage, Scope::Logs, &["pageup"]),
+    (Action::NextPage, Scope::Logs, &["pagedown"]),
+    (Action::DumpLogs, Scope::Logs, &["ctrl-s"]),
+    (Action::TeeLogs, Scope::Logs, &["ctrl-t"]),
+    (Action::LogStats, Scope::Logs, &["S"]),
+    (Action::NextMatch, Scope::Logs, &["n"]),
+    (Action::PrevMatch, Scope::Logs, &["N"]),
+    (Action::ToggleRawJson, Scope::Logs, &["j"]),
+    (Action::ResetScroll, Scope::Logs, &["r"]),
+    (Action::CopyMode, Scope::Logs, &["y"]),
+    (Action::SplitByLevel, Scope::Logs, &["e"]),
+    (Action::Previous, Scope::AppLogs, &["up"]),
+    (Action::Next, Scope::AppLogs, &["down"]),
+    (Action::ToggleRegions, Scope::AppLogs, &["t"]),
+    (Action::FocusRegion, Scope::AppLogs, &["f"]),
+    (Action::FocusRegionSearch, Scope::AppLogs, &["F"]),
+    (Action::CycleMachineFilter, Scope::AppLogs, &["i"]),
+    (Action::DecreaseDisplayLevel, Scope::AppLogs, &["left"]),
+    (Action::IncreaseDisplayLevel, Scope::AppLogs, &["right"]),
+    (Action::DecreaseFilterLevel, Scope::AppLogs, &["-"]),
+    (Action::IncreaseFilterLevel, Scope::AppLogs, &["+"]),
+    (Action::NavigateBack, Scope::Popups, &["esc"]),
+    (Action::Confirm, Scope::Popups, &["enter"]),
+    (Action::Previous, Scope::Popups, &["up", "k"]),
+    (Action::Next, Scope::Popups, &["down", "j"]),
+    (Action::FocusPrevious, Scope::Popups, &["backtab", "left"]),
+    (Action::FocusNext, Scope::Popups, &["tab", "right"]),
+    (Action::Mark, Scope::Popups, &["space"]),
+    (Action::Refresh, Scope::MembersPopup, &["R"]),
+    (Action::Refresh, Scope::ReleasesPopup, &["R"]),
+    (Action::Rollback, Scope::ReleasesPopup, &["r"]),
+    (Action::MarkAll, Scope::CleanupPopup, &["a"]),
+    (Action::PreviousPage, Scope::ScrollPopup, &["pageup"]),
+    (Action::NextPage, Scope::ScrollPopup, &["pagedown"]),
+    (Action::Refresh, Scope::ServicesPopup, &["R"]),
+    (Action::EditService, Scope::ServicesPopup, &["e"]),
+    (Action::AddService, Scope::ServicesPopup, &["a"]),
+    (Action::RemoveServicePort, Scope::ServicesPopup, &["d"]),
+    (Action::Refresh, Scope::AutoscalingPopup, &["R"]),
+    (Action::CycleAutostop, Scope::AutoscalingPopup, &["a"]),
+    (Action::ToggleAutostart, Scope::AutoscalingPopup, &["s"]),
+    (Action::IncreaseMinMachines, Scope::AutoscalingPopup, &["+"]),
+    (Action::DecreaseMinMachines, Scope::AutoscalingPopup, &["-"]),
+    (Action::CopyToken, Scope::DeployTokenPopup, &["c"]),
+    (Action::CopyMode, Scope::CopyMode, &["y"]),
+    (Action::PreviousPage, Scope::CopyMode, &["pageup"]),
+    (Action::NextPage, Scope::CopyMode, &["pagedown"]),
+];
+
+/// Key of an action in a scope.
+#[derive(Debug, Clone, Copy)]
+struct Binding {
+    action: Action,
+    scope: Scope,
+    key: (KeyCode, KeyModifiers),
+}
+
+fn default_bindings() -> Vec<Binding> {
+    DEFAULT_KEYS
+        .iter()
+        .flat_map(|&(action, scope, keys)| {
+            keys.iter().map(move |key| Binding {
+                action,
+                scope,
+                key: parse_key(key).expect("default keys are valid"),
+            })
+        })
+        .collect()
+}
+
+/// Sets the keys of the actions once on launch, a key that can't be parsed or that's taken by
+/// another action where both apply is an error.
+pub fn set_keybindings(keybindings: &HashMap<Action, String>) -> RdrResult<()> {
+    let bindings = remap(default_bindings(), keybindings)?;
+    let _ = KEYBINDINGS.set(bindings);
+    Ok(())
+}
+
+/// Replaces the default keys of the remapped actions with their set key in all their scopes.
+fn remap(
+    mut bindings: Vec<Binding>,
+    keybindings: &HashMap<Action, String>,
+) -> RdrResult<Vec<Binding>> {
+    for (&action, spec) in keybindings {
+        let key =
+            parse_key(spec).ok_or_else(|| eyre!("Unknown key {:?} for {:?}", spec, action))?;
+        let mut scopes = bindings
+            .iter()
+            .filter(|binding| binding.action == action)
+            .map(|binding| binding.scope)
+            .collect::<Vec<_>>();
+        scopes.dedup();
+        bindings.retain(|binding| binding.action != action);
+        bindings.extend(
+            scopes
+                .into_iter()
+                .map(|scope| Binding { action, scope, key }),
+        );
+    }
+    for (i, binding) in bindings.iter().enumerate() {
+        if let Some(other) = bindings[i + 1..].iter().find(|other| {
+            other.key == binding.key
+                && other.action != binding.action
+                && other.scope.overlaps(binding.scope)
+        }) {
+            return Err(eyre!(
+                "{} is the key of both {:?} and {:?}",
+                format_key(binding.key),
+                binding.action,
+                other.action
+            ));
+        }
+    }
+    Ok(bindings)
+}
+
+fn bindings() -> &'static [Binding] {
+    KEYBINDINGS.get_or_init(default_bindings)
+}
+
+/// Action of the key in the context, if it has one.
+pub fn action(context: Context, key_event: KeyEvent) -> Option<Action> {
+    let scope = Scope::of(context);
+    let key = normalize(key_event);
+    bindings()
+        .iter()
+        .find(|binding| binding.key == key && scope.is_within(binding.scope))
+        .map(|binding| binding.action)
+}
+
+/// Label of the keys shown in the keymap, all the keys of a single action like `<s/n>` or the
+/// first ones of a few actions like `<↑/↓>`.
+pub fn label(context: Context, actions: &[Action]) -> String {
+    let scope = Scope::of(context);
+    let keys_of = |action: Action| {
+        bindings()
+            .iter()
+            .filter(move |binding| binding.action == action && scope.is_within(binding.scope))
+            .map(|binding| binding.key)
+    };
+    let keys = match actions {
+        [action] => keys_of(*action).collect::<Vec<_>>(),
+        _ => actions
+            .iter()
+            .filter_map(|action| keys_of(*action).next())
+            .collect(),
+    };
+    match keys[..] {
+        [key] => format_key(key),
+        _ => format!(
+            "<{}>",
+            keys.iter()
+                .map(|key| key_name(*key, true))
+                .collect::<Vec<_>>()
+                .join("/")
+        ),
+    }
+}
+
+/// Parses keys like `x`, `X`, `shift-x`, `ctrl-d`, `alt-enter`, `esc` or `pagedown`. Shift is
+/// carried by the case of the characters like crossterm does.
+fn parse_key(spec: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut shift = false;
+    let mut rest = spec.trim();
+    while rest.len() > 1 {
+        let Some((modifier, key)) = rest.split_once('-') else {
+            break;
+        };
+        match modifier.to_lowercase().as_str() {
+            "ctrl" => modifiers |= KeyModifiers::CONTROL,
+            "alt" => modifiers |= KeyModifiers::ALT,
+            "shift" => shift = true,
+            _ => return None,
+        }
+        rest = key;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) if modifiers.contains(KeyModifiers::CONTROL) => {
+            KeyCode::Char(c.to_ascii_lowercase())
+        }
+        (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_lowercase().as_str() {
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => return None,
+        },
+    };
+    Some((code, modifiers))
+}
+
+//INFO: Shift is only kept in the case of the characters, so Shift-Tab matches backtab
+fn normalize(key_event: KeyEvent) -> (KeyCode, KeyModifiers) {
+    let modifiers = key_event.modifiers - KeyModifiers::SHIFT;
+    match key_event.code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+            (KeyCode::Char(c.to_ascii_lowercase()), modifiers)
+        }
+        code => (code, modifiers),
+    }
+}
+
+/// Formats a key like the keymap does, e.g. `<Ctrl-d>` or `<Shift-c>`.
+fn format_key(key: (KeyCode, KeyModifiers)) -> String {
+    format!("<{}>", key_name(key, false))
+}
+
+/// Name of a key, the characters are kept as they are in a group of keys like `<n/N>`.
+fn key_name((code, modifiers): (KeyCode, KeyModifiers), grouped: bool) -> String {
+    let mut name = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl-");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt-");
+    }
+    match code {
+        KeyCode::Char(' ') => name.push_str("Space"),
+        KeyCode::Char(c) if c.is_ascii_uppercase() && !grouped => {
+            name.push_str(&format!("Shift-{}", c.to_ascii_lowercase()))
+        }
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::Up => name.push('↑'),
+        KeyCode::Down => name.push('↓'),
+        KeyCode::Left => name.push('←'),
+        KeyCode::Right => name.push('→'),
+        KeyCode::BackTab => name.push_str("Shift-Tab"),
+        KeyCode::PageUp => name.push_str("PageUp"),
+        KeyCode::PageDown => name.push_str("PageDown"),
+        code => name.push_str(&format!("{:?}", code)),
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_parsing() {
+        assert_eq!(
+            parse_key("ctrl-d"),
+            Some((KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("shift-c"),
+            Some((KeyCode::Char('C'), KeyModifiers::NONE))
+        );
+        assert_eq!(parse_key("C"), parse_key("shift-c"));
+        assert_eq!(
+            parse_key("-"),
+            Some((KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(parse_key("esc"), Some((KeyCode::Esc, KeyModifiers::NONE)));
+        assert_eq!(parse_key("meta-x"), None);
+        assert_eq!(parse_key("escape"), None);
+    }
+
+    #[test]
+    fn test_key_formatting() {
+        assert_eq!(format_key(parse_key("ctrl-d").unwrap()), "<Ctrl-d>");
+        assert_eq!(format_key(parse_key("R").unwrap()), "<Shift-r>");
+        assert_eq!(format_key(parse_key("/").unwrap()), "</>");
+        assert_eq!(format_key(parse_key("esc").unwrap()), "<Esc>");
+        assert_eq!(format_key(parse_key("space").unwrap()), "<Space>");
+    }
+
+    #[test]
+    fn test_key_lookup() {
+        let machines = View::Machines {
+            app_id: String::from("app-id"),
+            app_name: String::from("app"),
+        };
+        let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        let shift_tab = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        let n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(
+            action(Context::View(&machines), ctrl_d),
+            Some(Action::Destroy)
+        );
+        assert_eq!(
+            action(Context::View(&machines), shift_tab),
+            Some(Action::Previous)
+        );
+        assert_eq!(action(Context::View(&machines), n), Some(Action::Rename));
+        assert_eq!(action(Context::CopyMode, n), None);
+        assert_eq!(
+            action(Context::Popup(&PopupType::ViewAppReleasesPopup), n),
+            None
+        );
+    }
+
+    #[test]
+    fn test_remapping() {
+        assert!(remap(default_bindings(), &HashMap::new()).is_ok());
+
+        let bindings = remap(
+            default_bindings(),
+            &HashMap::from([(Action::Restart, String::from("R"))]),
+        )
+        .unwrap();
+        let restart_keys = bindings
+            .iter()
+            .filter(|binding| binding.action == Action::Restart)
+            .map(|binding| (binding.scope, format_key(binding.key)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            restart_keys,
+            [
+                (Scope::Apps, String::from("<Shift-r>")),
+                (Scope::Machines, String::from("<Shift-r>"))
+            ]
+        );
+
+        // s starts the machines, the destroy key of the lists would shadow it
+        let conflict = remap(
+            default_bindings(),
+            &HashMap::from([(Action::Destroy, String::from("s"))]),
+        );
+        assert!(conflict.is_err());
+        let unknown = remap(
+            default_bindings(),
+            &HashMap::from([(Action::Destroy, String::from("hyper-x"))]),
+        );
+        assert!(unknown.is_err());
+    }
+}
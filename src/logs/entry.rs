@@ -1,9 +1,27 @@
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::widgets::log_viewer::Level;
 
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity"];
+const MSG_KEYS: &[&str] = &["msg", "message"];
+//INFO: Dropped as the log line already has its own timestamp
+const TIME_KEYS: &[&str] = &["time", "ts", "timestamp"];
+
+/// Maps the level names of the common loggers, and the numeric levels of pino, to a `Level`.
+pub fn map_level(level: &str) -> Level {
+    match level.to_lowercase().as_str() {
+        "error" | "err" | "fatal" | "panic" | "critical" | "crit" | "alert" | "emerg" | "50"
+        | "60" => Level::Error,
+        "warn" | "warning" | "40" => Level::Warn,
+        "info" | "notice" | "30" => Level::Info,
+        "debug" | "20" => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LogEntry {
     pub level: String,
@@ -15,14 +33,47 @@ pub struct LogEntry {
 }
 impl LogEntry {
     pub fn map_level(&self) -> Level {
-        match self.level.as_str() {
-            "error" => Level::Error,
-            "warn" => Level::Warn,
-            "info" => Level::Info,
-            "debug" => Level::Debug,
-            "trace" => Level::Trace,
-            _ => Level::Trace,
+        map_level(&self.level)
+    }
+}
+
+/// Message of an app logging JSON objects, split into its level, message and the rest of the keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonMessage {
+    pub level: Option<String>,
+    pub msg: String,
+    /// Values other than strings are kept as JSON
+    pub fields: Vec<(String, String)>,
+}
+
+impl JsonMessage {
+    pub fn parse(message: &str) -> Option<Self> {
+        let message = message.trim();
+        if !message.starts_with('{') {
+            return None;
+        }
+        let Value::Object(object) = serde_json::from_str(message).ok()? else {
+            return None;
+        };
+        let mut parsed = JsonMessage {
+            level: None,
+            msg: String::new(),
+            fields: vec![],
+        };
+        for (key, value) in object {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            if parsed.level.is_none() && LEVEL_KEYS.contains(&key.as_str()) {
+                parsed.level = Some(value);
+            } else if parsed.msg.is_empty() && MSG_KEYS.contains(&key.as_str()) {
+                parsed.msg = value;
+            } else if !TIME_KEYS.contains(&key.as_str()) {
+                parsed.fields.push((key, value));
+            }
         }
+        Some(parsed)
     }
 }
 
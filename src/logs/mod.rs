@@ -15,6 +15,14 @@ pub trait LogStream {
     ) -> (BoxStream<'static, RdrResult<LogEntry>>, JoinHandle<()>);
 }
 
+/// How the logs are streamed, NATS falls back to polling while it reconnects.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LogTransport {
+    Nats,
+    Polling,
+    Reconnecting,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct LogOptions {
     pub app_name: String,
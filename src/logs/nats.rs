@@ -17,6 +17,7 @@ use crate::agent::{self};
 use crate::auth::read_access_token;
 use crate::fly_rust::request_builder::RequestBuilderGraphql;
 use crate::fly_rust::resource_apps::get_app_basic;
+use crate::ops::pause;
 use crate::state::RdrResult;
 
 #[derive(Clone, Debug)]
@@ -29,6 +30,9 @@ impl NatsLogStream {
         request_builder_graphql: &RequestBuilderGraphql,
         opts: &LogOptions,
     ) -> RdrResult<Self> {
+        if !agent::flyctl_available() {
+            return Err(eyre::eyre!(agent::FLYCTL_UNAVAILABLE_MESSAGE));
+        }
         let app_basic = get_app_basic(request_builder_graphql, opts.app_name.clone())
             .await?
             .ok_or_else(|| eyre::eyre!("App not found: {}", opts.app_name.to_string()))?;
@@ -110,40 +114,51 @@ impl LogStream for NatsLogStream {
     }
 }
 
+/// Forwards the logs of the subject until the subscription ends. The subscription is dropped
+/// while the polls are paused, so the logs sent in the meantime aren't received.
 async fn from_nats(
     nc: &async_nats_flyradar::Client,
     opts: &LogOptions,
     tx: mpsc::Sender<RdrResult<LogEntry>>,
 ) -> RdrResult<()> {
     let subject = opts.to_nats_subject();
-    tracing::info!("About to subscribe to: {}", subject);
-
-    let mut sub = nc.subscribe(subject).await?;
-    tracing::info!("Successfully subscribed to subject.");
-
-    while let Some(msg) = sub.next().await {
-        tracing::info!("Received NATS message");
-        let log: NatsLog = serde_json::from_slice(&msg.payload)?;
-
-        tx.send(Ok(LogEntry {
-            instance: log.fly.app.instance.clone(),
-            level: log.log.level,
-            message: log.message,
-            region: log.fly.region.clone(),
-            timestamp: log.timestamp,
-            meta: Meta {
-                instance: log.fly.app.instance,
-                region: log.fly.region,
-                event: Event {
-                    provider: log.event.provider,
+    loop {
+        pause::resumed().await;
+        tracing::info!("About to subscribe to: {}", subject);
+
+        let mut sub = nc.subscribe(subject.clone()).await?;
+        tracing::info!("Successfully subscribed to subject.");
+
+        loop {
+            let msg = tokio::select! {
+                msg = sub.next() => msg,
+                _ = pause::paused() => break,
+            };
+            let Some(msg) = msg else {
+                return Ok(());
+            };
+            tracing::info!("Received NATS message");
+            let log: NatsLog = serde_json::from_slice(&msg.payload)?;
+
+            tx.send(Ok(LogEntry {
+                instance: log.fly.app.instance.clone(),
+                level: log.log.level,
+                message: log.message,
+                region: log.fly.region.clone(),
+                timestamp: log.timestamp,
+                meta: Meta {
+                    instance: log.fly.app.instance,
+                    region: log.fly.region,
+                    event: Event {
+                        provider: log.event.provider,
+                    },
+                    http: None,
+                    error: None,
+                    url: None,
                 },
-                http: None,
-                error: None,
-                url: None,
-            },
-        }))
-        .await?;
+            }))
+            .await?;
+        }
+        tracing::info!("Unsubscribing while paused");
     }
-
-    Ok(())
 }
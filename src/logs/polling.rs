@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
 use color_eyre::eyre;
 use futures::stream::BoxStream;
 use reqwest::StatusCode;
@@ -12,11 +13,14 @@ use super::entry::LogEntry;
 use super::{LogOptions, LogStream};
 use crate::fly_rust::request_builder::RequestBuilderFly;
 use crate::fly_rust::resource_logs::get_app_logs;
+use crate::ops::pause;
 use crate::state::RdrResult;
 
 #[derive(Debug)]
 pub struct PollingStream {
     pub request_builder_fly: RequestBuilderFly,
+    // Where the polling goes on from, after the history fetched when the logs open
+    pub next_token: Option<String>,
 }
 
 impl LogStream for PollingStream {
@@ -27,9 +31,10 @@ impl LogStream for PollingStream {
         let (tx, rx) = mpsc::channel(100);
         let request_builder_fly_clone = self.request_builder_fly.clone();
         let opts_clone = opts.clone();
+        let next_token = self.next_token.clone();
         let poll_handle = task::spawn(async move {
             info!("Polling stream task started");
-            if let Err(e) = poll(&request_builder_fly_clone, &opts_clone, tx).await {
+            if let Err(e) = poll(&request_builder_fly_clone, &opts_clone, next_token, tx).await {
                 // Log error if needed
                 tracing::error!("Polling error: {}", e);
             }
@@ -46,20 +51,62 @@ impl LogStream for PollingStream {
 
 const MIN_WAIT: Duration = Duration::from_millis(64);
 const MAX_WAIT: Duration = Duration::from_millis(4096);
+/// Pages fetched at most for the history, the pages run from the oldest kept logs to the live ones
+const MAX_HISTORY_PAGES: usize = 20;
+
+fn entry_time(entry: &LogEntry) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&entry.timestamp)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Fetches the logs logged since `since` by paging up to the live ones. Returns them oldest
+/// first, with the token the polling goes on from.
+pub async fn fetch_history(
+    request_builder_fly: &RequestBuilderFly,
+    opts: &LogOptions,
+    since: DateTime<Utc>,
+) -> RdrResult<(Vec<LogEntry>, Option<String>)> {
+    let mut history = vec![];
+    let mut next_token = None;
+    for _ in 0..MAX_HISTORY_PAGES {
+        let (entries, token) = get_app_logs(
+            request_builder_fly,
+            &opts.app_name,
+            next_token.clone(),
+            opts.vm_id.clone(),
+            opts.region_code.clone(),
+        )
+        .await?;
+        if !token.is_empty() {
+            next_token = Some(token);
+        }
+        if entries.is_empty() {
+            break;
+        }
+        history.extend(
+            entries
+                .into_iter()
+                .filter(|entry| entry_time(entry).is_some_and(|time| time >= since)),
+        );
+    }
+    history.sort_by_key(entry_time);
+    Ok((history, next_token))
+}
 
 pub async fn poll(
     request_builder_fly: &RequestBuilderFly,
     opts: &LogOptions,
+    mut next_token: Option<String>,
     tx: tokio::sync::mpsc::Sender<RdrResult<LogEntry>>,
 ) -> RdrResult<()> {
-    let mut next_token = None;
-
     let retry_policy = ExponentialBuilder::default()
         .with_min_delay(MIN_WAIT)
         .with_max_delay(MAX_WAIT)
         .with_max_times(10);
 
     loop {
+        pause::resumed().await;
         info!("Polling logs...");
         let (new_token, was_empty) = (|| async {
             let (entries, token) = get_app_logs(
@@ -1,9 +1,13 @@
 use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use clap::{crate_authors, Command};
-use config::{FullConfig, TokenConfig};
+use clap::{crate_authors, value_parser, Arg, ArgAction, Command};
+use color_eyre::eyre::WrapErr;
+use config::{FullConfig, StartupView, TokenConfig};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ops::{IoReqEvent, IoRespEvent, Ops};
-use ratatui::backend::CrosstermBackend;
+use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::Terminal;
 use shadow_rs::shadow;
 use tracing::error;
@@ -11,8 +15,10 @@ use tracing::error;
 use tracing_subscriber::prelude::*;
 
 use crate::event::{Event, EventHandler};
-use crate::handler::handle_key_events;
-use crate::state::{RdrResult, State};
+use crate::handler::{handle_key_events, handle_paste_event};
+use crate::ops::machines::console::console_command;
+use crate::recorder::{Recorder, RecordingWriter};
+use crate::state::{PopupType, RdrResult, State};
 use crate::tui::Tui;
 
 shadow!(build);
@@ -24,8 +30,12 @@ pub mod config;
 pub mod event;
 pub mod fly_rust;
 pub mod handler;
+pub mod keybindings;
 pub mod logs;
+pub mod notification;
 pub mod ops;
+pub mod plugins;
+pub mod recorder;
 pub mod state;
 pub mod transformations;
 pub mod tui;
@@ -39,6 +49,7 @@ fn init_tracing() -> RdrResult<()> {
         // .with(tracing_subscriber::fmt::layer())
         // .with(tracing_subscriber::EnvFilter::new("hyper=debug"))
         .with(tui_logger::tracing_subscriber_layer())
+        .with(fly_rust::api_stats::ApiStatsLayer)
         .init();
     tui_logger::init_logger(tracing::log::LevelFilter::Trace).unwrap();
     Ok(())
@@ -48,14 +59,89 @@ fn init_tracing() -> RdrResult<()> {
 async fn main() -> RdrResult<()> {
     #[cfg(debug_assertions)]
     init_tracing()?;
-    Command::new(build::PROJECT_NAME)
+    let matches = Command::new(build::PROJECT_NAME)
         .about("Manage your Fly.io resources in style")
         .author(crate_authors!("\n"))
         .long_version(build::CLAP_LONG_VERSION)
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .value_name("PATH")
+                .value_parser(value_parser!(PathBuf))
+                .help("Record the session to an asciinema cast file"),
+        )
+        .arg(
+            Arg::new("low-bandwidth")
+                .long("low-bandwidth")
+                .action(ArgAction::SetTrue)
+                .help("Render with ASCII only, without the splash and the banner"),
+        )
+        .arg(
+            Arg::new("org")
+                .long("org")
+                .value_name("SLUG")
+                .help("Start in the apps of the organization"),
+        )
+        .arg(
+            Arg::new("app")
+                .long("app")
+                .value_name("NAME")
+                .help("Start in the machines of the app, looked up in --org if given"),
+        )
         .get_matches();
     color_eyre::install()?;
+    let rdr_config = config::read_rdr_config()
+        .await
+        .wrap_err("Could not read the flyradar config file flyradar.yml")?;
+    ui::set_low_bandwidth(rdr_config.low_bandwidth || matches.get_flag("low-bandwidth"));
+    transformations::set_formatting(rdr_config.formatting.clone());
+    ui::set_color_depth(rdr_config.color_depth);
+    ui::set_theme(&rdr_config.theme);
+    keybindings::set_keybindings(&rdr_config.keybindings)
+        .wrap_err("Could not set the keybindings of flyradar.yml")?;
+    if let Some(address) = rdr_config.metrics_address.clone() {
+        tokio::spawn(async move {
+            if let Err(err) = ops::metrics::serve(address).await {
+                error!("Serving metrics failed: {}", err);
+            }
+        });
+    }
+    //INFO: The flags take over the startup view of the config
+    let startup_view = match (
+        matches.get_one::<String>("org").cloned(),
+        matches.get_one::<String>("app").cloned(),
+    ) {
+        (org, Some(app)) => StartupView::Machines { org, app },
+        (Some(org), None) => StartupView::Apps { org: Some(org) },
+        (None, None) => rdr_config.startup_view.clone(),
+    };
+    let plugin_paths = rdr_config.plugins.clone();
+    let mut startup_errors =
+        tokio::task::spawn_blocking(move || plugins::load(&plugin_paths)).await?;
+    let unknown_confirmations = rdr_config.unknown_confirmations();
+    if !unknown_confirmations.is_empty() {
+        startup_errors.push(format!(
+            "Unknown operations in the confirmations of flyradar.yml: {}",
+            unknown_confirmations.join(", ")
+        ));
+    }
+
+    let recorder = match matches.get_one::<PathBuf>("record") {
+        Some(path) => {
+            let (width, height) = crossterm::terminal::size()?;
+            Some(Recorder::new(path, width, height)?)
+        }
+        None => None,
+    };
+
+    // Initialize the terminal user interface.
+    let backend = CrosstermBackend::new(RecordingWriter::new(io::stdout(), recorder.clone()));
+    let terminal = Terminal::new(backend)?;
+    let events = EventHandler::new(rdr_config.tick_rate, rdr_config.frame_rate);
+    let mut tui = Tui::new(terminal, events);
+    tui.init()?;
 
-    if let Ok(access_token) = auth::read_access_token().await {
+    if let Some(access_token) = wait_for_access_token(&mut tui).await? {
         let config = FullConfig {
             token_config: TokenConfig { access_token },
             wire_guard_state: None,
@@ -63,11 +149,18 @@ async fn main() -> RdrResult<()> {
 
         let (io_req_tx, mut io_req_rx) = tokio::sync::mpsc::channel::<IoReqEvent>(32);
         let (io_resp_tx, mut io_resp_rx) = tokio::sync::mpsc::channel::<IoRespEvent>(32);
+        let rdr_config = Arc::new(rdr_config);
         let mut state = State::default();
         let io_req_tx_clone = io_req_tx.clone();
-        state.init(io_req_tx);
+        state.init(io_req_tx, rdr_config.clone());
+        state.open_startup_view(startup_view).await?;
+        if !startup_errors.is_empty() {
+            state.open_popup(startup_errors.join("\n"), PopupType::ErrorPopup, None);
+        }
+        state.dispatch(IoReqEvent::Preflight).await;
+        state.dispatch(IoReqEvent::Watch).await;
         tokio::task::spawn(async move {
-            let ops = Ops::new(config, io_req_tx_clone, io_resp_tx);
+            let ops = Ops::new(config, rdr_config, io_req_tx_clone, io_resp_tx);
             while let Some(io_event) = io_req_rx.recv().await {
                 let mut ops_clone = ops.clone();
                 tokio::task::spawn(async move {
@@ -76,36 +169,130 @@ async fn main() -> RdrResult<()> {
             }
         });
 
-        // Initialize the terminal user interface.
-        let backend = CrosstermBackend::new(io::stdout());
-        let terminal = Terminal::new(backend)?;
-        let events = EventHandler::new(250);
-        let mut tui = Tui::new(terminal, events);
-        tui.init()?;
-
         // Start the main loop.
         while state.running {
-            // Render the user interface.
-            tui.draw(&mut state)?;
             tokio::select! {
                 Some(io_event) = io_resp_rx.recv() => {
                     state.handle_io_resp(io_event).await;
+                    state.mark_dirty();
                 }
                 event = tui.events.next() => match event? {
                     Event::Tick => state.tick().await,
+                    // Render the user interface, only when something changed since the last frame.
+                    Event::Render => {
+                        state.animate();
+                        if state.is_dirty() {
+                            tui.draw(&mut state)?;
+                            state.frame_drawn();
+                        }
+                    }
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Char('z'),
+                        modifiers: KeyModifiers::CONTROL,
+                        ..
+                    }) if cfg!(unix) => {
+                        state.pause_polling();
+                        tui.suspend()?;
+                        state.resume_polling().await;
+                        state.mark_dirty();
+                    }
                     Event::Key(key_event) => {
+                        if let Some(recorder) = recorder.as_ref().filter(|_| !state.is_typing_secret()) {
+                            recorder.input(&key_event);
+                        }
                         let res = handle_key_events(key_event, &mut state).await;
                         if res.is_err() {
                             error!("Handle key event err: {:#?}", res);
                         }
+                        tui.capture_mouse(!state.is_copy_mode())?;
+                        if let Some(request) = state.take_console_request() {
+                            state.pause_polling();
+                            match tui.attach(console_command(&request)).await {
+                                Ok(status) if !status.success() => state.open_popup(
+                                    format!("The console of {} exited with {}.", request.machine_id, status),
+                                    PopupType::ErrorPopup,
+                                    None,
+                                ),
+                                Ok(_) => {}
+                                Err(err) => {
+                                    state.open_popup(err.to_string(), PopupType::ErrorPopup, None)
+                                }
+                            }
+                            state.resume_polling().await;
+                        }
+                        state.mark_dirty();
                     }
                     Event::Mouse(_) => {}
-                    Event::Resize(_, _) => {}
+                    Event::Paste(text) => {
+                        handle_paste_event(&text, &mut state);
+                        state.mark_dirty();
+                    }
+                    Event::Focus(true) => state.resume_polling().await,
+                    Event::Focus(false) => state.pause_polling(),
+                    Event::Resize(width, height) => {
+                        if let Some(recorder) = &recorder {
+                            recorder.resize(width, height);
+                        }
+                        state.mark_dirty();
+                    }
                 }
             }
+            if let Some(text) = state.take_clipboard() {
+                tui.copy_to_clipboard(&text)?;
+            }
         }
-        // Exit the user interface.
-        tui.exit()?;
     }
+    // Exit the user interface.
+    tui.exit()?;
     Ok(())
 }
+
+/// Checks the access token before the session starts. Until it's usable, a screen explains why
+/// and offers to log in, retry or quit, `None` is returned on quit.
+async fn wait_for_access_token<B: Backend>(tui: &mut Tui<B>) -> RdrResult<Option<String>> {
+    let mut problem = match auth::check_access_token().await {
+        Ok(access_token) => return Ok(Some(access_token)),
+        Err(problem) => problem,
+    };
+    let mut status = None;
+    loop {
+        match tui.events.next().await? {
+            Event::Render | Event::Resize(..) => {
+                tui.draw_token_problem(&problem, status.as_deref())?
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('q') | KeyCode::Esc,
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }) => return Ok(None),
+            Event::Key(KeyEvent {
+                code: code @ (KeyCode::Char('l') | KeyCode::Char('r')),
+                ..
+            }) => {
+                if code == KeyCode::Char('l') {
+                    tui.draw_token_problem(
+                        &problem,
+                        Some("Complete the login in the browser flyctl opened for you..."),
+                    )?;
+                    if let Err(err) = auth::login().await {
+                        status = Some(err.to_string());
+                        continue;
+                    }
+                }
+                tui.draw_token_problem(&problem, Some("Checking the access token..."))?;
+                match auth::check_access_token().await {
+                    Ok(access_token) => return Ok(Some(access_token)),
+                    Err(new_problem) => {
+                        problem = new_problem;
+                        status = Some(String::from("Still can't start."));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use color_eyre::eyre::{
+    Context, {self},
+};
+use tokio::process::Command;
+
+use crate::state::RdrResult;
+
+/// Shows a desktop notification with the notifier of the platform.
+pub async fn notify(title: &str, body: &str) -> RdrResult<()> {
+    let mut command = notifier_command(title, body)?;
+    let status = command
+        .status()
+        .await
+        .wrap_err("failed to execute the notifier")?;
+    if !status.success() {
+        return Err(eyre::eyre!("notifier command failed"));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn notifier_command(title: &str, body: &str) -> RdrResult<Command> {
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!(
+        "display notification {:?} with title {:?}",
+        body, title
+    ));
+    Ok(command)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn notifier_command(title: &str, body: &str) -> RdrResult<Command> {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name", "flyradar", title, body]);
+    Ok(command)
+}
+
+#[cfg(not(unix))]
+fn notifier_command(_title: &str, _body: &str) -> RdrResult<Command> {
+    Err(eyre::eyre!(
+        "desktop notifications are not supported on this platform"
+    ))
+}
@@ -0,0 +1,204 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::eyre::eyre;
+use itertools::Itertools;
+use serde_json::json;
+
+use crate::fly_rust::machine_types::{Machine, MachineAutostop, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::{get_machine_config, update_machine};
+use crate::ops::lease::{acquire_leases, list_active_machines, ReleaseGuard};
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+/// Process group and region of the machines a change applies to.
+pub type ScalingGroupKey = (String, String);
+
+/// Settings staged for the services of a scaling group, applied together once confirmed.
+#[derive(Clone, Debug, Default)]
+pub struct AutoscalingChange {
+    pub autostop: Option<String>,
+    pub autostart: Option<bool>,
+    pub min_machines_running: Option<i32>,
+}
+
+impl AutoscalingChange {
+    /// Shows the staged settings in the row of the group.
+    pub fn apply_to_row(&self, row: &mut [String]) {
+        if let Some(autostop) = &self.autostop {
+            row[3] = autostop.clone();
+        }
+        if let Some(autostart) = self.autostart {
+            row[4] = String::from(if autostart { "on" } else { "off" });
+        }
+        if let Some(min_machines_running) = self.min_machines_running {
+            row[5] = min_machines_running.to_string();
+        }
+    }
+
+    pub fn diff(&self) -> Vec<String> {
+        let mut diff = vec![];
+        if let Some(autostop) = &self.autostop {
+            diff.push(format!("autostop {autostop}"));
+        }
+        if let Some(autostart) = self.autostart {
+            diff.push(format!(
+                "autostart {}",
+                if autostart { "on" } else { "off" }
+            ));
+        }
+        if let Some(min_machines_running) = self.min_machines_running {
+            diff.push(format!("min machines running {min_machines_running}"));
+        }
+        diff
+    }
+}
+
+#[derive(Default)]
+struct ScalingGroup {
+    machines: usize,
+    started: usize,
+    autostop: BTreeSet<&'static str>,
+    autostart: BTreeSet<bool>,
+    min_machines_running: BTreeSet<i32>,
+    metrics: BTreeSet<String>,
+}
+
+/// Autoscaling of the machines is configured on their services, grouped by process group and
+/// region since that's how the proxy starts and stops them.
+pub async fn autoscaling(ops: &Ops, app_name: String) -> RdrResult<()> {
+    let machines = list_active_machines(&ops.request_builder_machines, &app_name).await?;
+
+    let mut groups: BTreeMap<(String, String), ScalingGroup> = BTreeMap::new();
+    for machine in &machines {
+        let group = groups
+            .entry((machine.process_group(), machine.region.clone()))
+            .or_default();
+        group.machines += 1;
+        if machine.state == MACHINE_STATE_STARTED {
+            group.started += 1;
+        }
+        let Some(config) = &machine.config else {
+            continue;
+        };
+        for service in config.services.iter().flatten() {
+            group.autostop.insert(
+                match service.autostop.as_ref().unwrap_or(&MachineAutostop::Off) {
+                    MachineAutostop::Off => "off",
+                    MachineAutostop::Stop => "stop",
+                    MachineAutostop::Suspend => "suspend",
+                },
+            );
+            group
+                .autostart
+                .insert(service.autostart.unwrap_or_default());
+            group
+                .min_machines_running
+                .insert(service.min_machines_running.unwrap_or_default());
+        }
+        if let Some(metrics) = &config.metrics {
+            group
+                .metrics
+                .insert(format!(":{}{}", metrics.port, metrics.path));
+        }
+    }
+
+    let list = groups
+        .into_iter()
+        .map(|((process_group, region), group)| {
+            vec![
+                process_group,
+                region,
+                format!("{}/{}", group.started, group.machines),
+                group.autostop.iter().join(","),
+                group
+                    .autostart
+                    .iter()
+                    .map(|autostart| if *autostart { "on" } else { "off" })
+                    .join(","),
+                group.min_machines_running.iter().join(","),
+                group.metrics.iter().join(","),
+            ]
+        })
+        .collect();
+
+    ops.io_resp_tx
+        .send(IoRespEvent::AppAutoscaling { list })
+        .await?;
+
+    Ok(())
+}
+
+/// Applies the staged changes to every service of the machines in their process group and
+/// region, one update per machine, then reloads the autoscaling view.
+pub async fn update(
+    ops: &Ops,
+    app_name: String,
+    changes: BTreeMap<ScalingGroupKey, AutoscalingChange>,
+) -> RdrResult<()> {
+    let machines: Vec<Machine> = list_active_machines(&ops.request_builder_machines, &app_name)
+        .await?
+        .into_iter()
+        .filter(|machine| changes.contains_key(&(machine.process_group(), machine.region.clone())))
+        .collect();
+
+    let (leases, errors, release) = acquire_leases(ops, &app_name, machines).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    for lease in leases {
+        let (id, nonce, was_started, key) = {
+            let machine = lease.lock().unwrap();
+            (
+                machine.id.clone(),
+                machine.lease_nonce.clone(),
+                machine.state == MACHINE_STATE_STARTED,
+                (machine.process_group(), machine.region.clone()),
+            )
+        };
+        let (Some(nonce), Some(change)) = (nonce, changes.get(&key)) else {
+            continue;
+        };
+        let mut config = get_machine_config(&ops.request_builder_machines, &app_name, &id).await?;
+        let Some(services) = config["services"].as_array_mut() else {
+            continue;
+        };
+        for service in services {
+            if let Some(autostop) = &change.autostop {
+                service["autostop"] = json!(autostop);
+            }
+            if let Some(autostart) = change.autostart {
+                service["autostart"] = json!(autostart);
+            }
+            if let Some(min_machines_running) = change.min_machines_running {
+                service["min_machines_running"] = json!(min_machines_running);
+            }
+        }
+        //INFO: Stopped machines are only updated, they stay stopped
+        update_machine(
+            &ops.request_builder_machines,
+            &app_name,
+            &id,
+            None,
+            &config,
+            &nonce,
+            !was_started,
+        )
+        .await?;
+    }
+
+    autoscaling(ops, app_name).await
+}
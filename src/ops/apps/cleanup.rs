@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::machine_types::{Machine, RemoveMachineInput, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::list_machines;
+use crate::fly_rust::volume_types::{RemoveVolumeInput, Volume};
+use crate::fly_rust::volumes::{get_all_volumes, DESTROYED_VOLUME_STATES};
+use crate::ops::volumes::list::orphan_reason;
+use crate::ops::{machines, volumes, IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+pub const CLEANUP_KIND_MACHINE: &str = "machine";
+pub const CLEANUP_KIND_VOLUME: &str = "volume";
+
+/// Lists what's likely safe to remove from the app: machines stopped for longer than the
+/// configured number of days, leftover release command machines and orphaned volumes.
+pub async fn report(ops: &Ops, app_name: String) -> RdrResult<()> {
+    let stale_machine_days = ops.rdr_config.stale_machine_days;
+    let machines: Vec<Machine> =
+        list_machines::<Machine>(&ops.request_builder_machines, &app_name, false)
+            .await?
+            .into_iter()
+            .filter(|machine| machine.is_active())
+            .collect();
+    let volumes: Vec<Volume> = get_all_volumes::<Volume>(&ops.request_builder_machines, &app_name)
+        .await?
+        .into_iter()
+        .filter(|volume| !DESTROYED_VOLUME_STATES.contains(&volume.state.as_str()))
+        .collect();
+
+    let mut list = vec![];
+    for machine in &machines {
+        if machine.state == MACHINE_STATE_STARTED {
+            continue;
+        }
+        let reason = if machine.is_release_command_machine() {
+            String::from("Release command leftover")
+        } else {
+            let Ok(updated_at) = machine.updated_at.parse::<DateTime<Utc>>() else {
+                continue;
+            };
+            let days = (Utc::now() - updated_at).num_days();
+            if days < stale_machine_days {
+                continue;
+            }
+            format!("{} for {} days", machine.state, days)
+        };
+        list.push(vec![
+            CLEANUP_KIND_MACHINE.to_string(),
+            machine.id.clone(),
+            machine.region.clone(),
+            reason,
+        ]);
+    }
+
+    let machine_ids: HashSet<String> = machines.into_iter().map(|machine| machine.id).collect();
+    for volume in &volumes {
+        if let Some(reason) = orphan_reason(volume.attached_machine.as_deref(), &machine_ids) {
+            list.push(vec![
+                CLEANUP_KIND_VOLUME.to_string(),
+                volume.id.clone(),
+                volume.region.clone(),
+                format!("{} ({}GB)", reason, volume.size_gb),
+            ]);
+        }
+    }
+
+    ops.io_resp_tx
+        .send(IoRespEvent::AppCleanup { list })
+        .await?;
+
+    Ok(())
+}
+
+/// Destroys the machines first so the volumes they leave behind can be deleted, failures don't
+/// stop the rest and are reported at the end.
+pub async fn cleanup(
+    ops: &Ops,
+    app_name: String,
+    machine_ids: Vec<String>,
+    volume_ids: Vec<String>,
+) -> RdrResult<()> {
+    let _feedback_tx = ops.show_delayed_feedback(
+        format!("Cleaning up {}...", app_name),
+        Duration::from_secs(0),
+    );
+
+    let mut errors = vec![];
+    let mut removed = 0;
+    for id in machine_ids {
+        match machines::destroy::destroy(ops, &app_name, RemoveMachineInput { id, kill: false })
+            .await
+        {
+            Ok(()) => removed += 1,
+            Err(err) => errors.push(err),
+        }
+    }
+    for id in volume_ids {
+        match volumes::destroy::destroy(ops, &app_name, RemoveVolumeInput { id }).await {
+            Ok(()) => removed += 1,
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!("Removed {} resources of {}.", removed, app_name),
+        })
+        .await?;
+
+    Ok(())
+}
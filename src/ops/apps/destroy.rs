@@ -1,9 +1,78 @@
-use crate::fly_rust::resource_apps::delete_app;
+use itertools::Itertools;
+
+use crate::fly_rust::machine_types::{Machine, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::list_machines;
+use crate::fly_rust::resource_apps::{delete_app, get_app_ip_addresses};
+use crate::fly_rust::resource_certificates::get_all_app_certificates;
+use crate::fly_rust::volume_types::Volume;
+use crate::fly_rust::volumes::{get_all_volumes, DESTROYED_VOLUME_STATES};
 use crate::ops::Ops;
 use crate::state::RdrResult;
+use crate::transformations::{byte_unit, format_number};
 
 //TODO: Find the tigris statics bucket for the given app and org and delete the add-on.
 pub async fn destroy(ops: &Ops, app_name: String) -> RdrResult<()> {
     delete_app(&ops.request_builder_graphql, app_name).await?;
     Ok(())
 }
+
+/// Lists the machines, volumes, certificates and IPs destroyed along with the app.
+pub async fn preview(ops: &Ops, app_name: &str) -> RdrResult<String> {
+    let (machines, volumes, certificates, ip_addresses) = tokio::try_join!(
+        list_machines::<Machine>(&ops.request_builder_machines, app_name, false),
+        get_all_volumes::<Volume>(&ops.request_builder_machines, app_name),
+        get_all_app_certificates(&ops.request_builder_graphql, app_name.to_string()),
+        get_app_ip_addresses(&ops.request_builder_graphql, app_name.to_string()),
+    )?;
+    let volumes = volumes
+        .into_iter()
+        .filter(|volume| !DESTROYED_VOLUME_STATES.contains(&volume.state.as_str()))
+        .collect::<Vec<_>>();
+    let ip_addresses = ip_addresses
+        .map(|response| response.app.ip_addresses.nodes)
+        .unwrap_or_default();
+
+    let mut lines = vec![];
+    if !machines.is_empty() {
+        lines.push(format!(
+            "- {} machine(s), {} started",
+            machines.len(),
+            machines
+                .iter()
+                .filter(|machine| machine.state == MACHINE_STATE_STARTED)
+                .count()
+        ));
+    }
+    if !volumes.is_empty() {
+        lines.push(format!(
+            "- {} volume(s), {}{} of data",
+            volumes.len(),
+            format_number(volumes.iter().map(|volume| volume.size_gb).sum::<i32>()),
+            byte_unit(3)
+        ));
+    }
+    if !certificates.is_empty() {
+        lines.push(format!(
+            "- {} certificate(s): {}",
+            certificates.len(),
+            certificates
+                .iter()
+                .map(|certificate| &certificate.hostname)
+                .join(", ")
+        ));
+    }
+    if !ip_addresses.is_empty() {
+        lines.push(format!(
+            "- {} IP address(es): {}",
+            ip_addresses.len(),
+            ip_addresses
+                .iter()
+                .map(|ip| format!("{} ({})", ip.address, ip.type_))
+                .join(", ")
+        ));
+    }
+    if lines.is_empty() {
+        return Ok(String::from("Nothing else goes with it."));
+    }
+    Ok(format!("Going with it:\n{}", lines.join("\n")))
+}
@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::ops::lease::list_active_machines;
+use crate::ops::machines::drift::DRIFT_MARKER;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+/// Env vars of the app's machines by key. Secrets aren't part of the machine config so only the
+/// plain env vars are listed. The most common value is shown, the machines with another value or
+/// without the key are flagged.
+pub async fn env(ops: &Ops, app_name: String) -> RdrResult<()> {
+    let machines = list_active_machines(&ops.request_builder_machines, &app_name).await?;
+
+    let mut key_to_values: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for machine in &machines {
+        let Some(config) = machine.get_config() else {
+            continue;
+        };
+        for (key, value) in &config.env {
+            key_to_values
+                .entry(key.as_str())
+                .or_default()
+                .push((machine.id.as_str(), value.as_str()));
+        }
+    }
+
+    let app_env_list = key_to_values
+        .iter()
+        .map(|(key, values)| {
+            let counts = values.iter().map(|(_, value)| *value).counts();
+            let max_count = counts.values().max().copied().unwrap_or_default();
+            //INFO: Ties are broken by the value so the shown one is stable between polls
+            let common_value = counts
+                .iter()
+                .filter(|(_, count)| **count == max_count)
+                .map(|(value, _)| *value)
+                .min()
+                .unwrap_or_default();
+            let differing = values
+                .iter()
+                .filter(|(_, value)| *value != common_value)
+                .map(|(machine_id, value)| format!("{}={}", machine_id, value))
+                .chain(
+                    machines
+                        .iter()
+                        .filter(|machine| values.iter().all(|(id, _)| *id != machine.id))
+                        .map(|machine| format!("{} unset", machine.id)),
+                )
+                .sorted()
+                .collect::<Vec<_>>();
+            vec![
+                key.to_string(),
+                common_value.to_string(),
+                format!("{}/{}", values.len(), machines.len()),
+                if differing.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} {}", DRIFT_MARKER, differing.join(", "))
+                },
+            ]
+        })
+        .collect();
+    ops.io_resp_tx
+        .send(IoRespEvent::AppEnv { list: app_env_list })
+        .await?;
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::fly_rust::resource_apps::list_all;
 use crate::ops::{IoRespEvent, Ops};
+use crate::state::view::ALL_ORGS;
 use crate::state::RdrResult;
 use crate::transformations::ResourceList;
 
@@ -7,7 +8,7 @@ pub async fn list(ops: &Ops, seq_id: u64, org_slug: String) -> RdrResult<()> {
     let apps = list_all(&ops.request_builder_graphql).await?;
     let filtered_apps = apps
         .into_iter()
-        .filter(|app| app.org == org_slug)
+        .filter(|app| org_slug == ALL_ORGS || app.org == org_slug)
         .collect::<Vec<_>>();
 
     ops.io_resp_tx
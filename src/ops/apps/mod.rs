@@ -1,6 +1,11 @@
+pub mod autoscaling;
+pub mod cleanup;
 pub mod destroy;
+pub mod env;
 pub mod list;
 pub mod open;
+pub mod ports;
 pub mod releases;
 pub mod restart;
 pub mod services;
+pub mod tokens;
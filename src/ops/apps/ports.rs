@@ -0,0 +1,119 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use futures::future::try_join_all;
+use itertools::Itertools;
+
+use crate::fly_rust::machine_types::MACHINE_STATE_STARTED;
+use crate::fly_rust::machines::list_machine_processes;
+use crate::ops::lease::list_active_machines;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+#[derive(Default)]
+struct PortUsage {
+    listening_machines: BTreeSet<String>,
+    addresses: BTreeSet<String>,
+    declared_machines: BTreeSet<String>,
+    public_ports: BTreeSet<i32>,
+}
+
+/// Cross-references the sockets the processes of the started machines listen on with the
+/// internal ports of the services declared on them, mismatches are listed first.
+pub async fn ports(ops: &Ops, app_name: String) -> RdrResult<()> {
+    let machines = list_active_machines(&ops.request_builder_machines, &app_name)
+        .await?
+        .into_iter()
+        .filter(|machine| machine.state == MACHINE_STATE_STARTED)
+        .collect::<Vec<_>>();
+    let processes = try_join_all(machines.iter().map(|machine| {
+        list_machine_processes(&ops.request_builder_machines, &app_name, &machine.id)
+    }))
+    .await?;
+
+    let mut ports: BTreeMap<(i32, String), PortUsage> = BTreeMap::new();
+    for (machine, processes) in machines.iter().zip(processes) {
+        for service in machine
+            .config
+            .as_ref()
+            .and_then(|config| config.services.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            let usage = ports
+                .entry((service.internal_port, service.protocol.to_lowercase()))
+                .or_default();
+            usage.declared_machines.insert(machine.id.clone());
+            usage
+                .public_ports
+                .extend(service.ports.iter().flatten().filter_map(|port| port.port));
+        }
+        for socket in processes
+            .into_iter()
+            .flatten()
+            .flat_map(|process| process.listen_sockets.unwrap_or_default())
+        {
+            let Some((host, port)) = socket.address.rsplit_once(':') else {
+                continue;
+            };
+            let Ok(port) = port.parse::<i32>() else {
+                continue;
+            };
+            let proto = socket.proto.trim_end_matches('6').to_lowercase();
+            let usage = ports.entry((port, proto)).or_default();
+            usage.listening_machines.insert(machine.id.clone());
+            usage
+                .addresses
+                .insert(host.trim_matches(|c| c == '[' || c == ']').to_string());
+        }
+    }
+
+    let list = ports
+        .into_iter()
+        .map(|((port, proto), usage)| {
+            let status = port_status(&usage);
+            (
+                status.starts_with('⚠'),
+                vec![
+                    format!("{}/{}", port, proto),
+                    usage.addresses.iter().join(", "),
+                    format!("{}/{}", usage.listening_machines.len(), machines.len()),
+                    usage.public_ports.iter().join(", "),
+                    status,
+                ],
+            )
+        })
+        .sorted_by_key(|(is_mismatch, _)| !is_mismatch)
+        .map(|(_, row)| row)
+        .collect();
+
+    ops.io_resp_tx.send(IoRespEvent::AppPorts { list }).await?;
+
+    Ok(())
+}
+
+fn is_loopback(address: &str) -> bool {
+    address.starts_with("127.") || address == "::1" || address == "localhost"
+}
+
+fn port_status(usage: &PortUsage) -> String {
+    if usage.declared_machines.is_empty() {
+        return String::from("Internal");
+    }
+    let listening_declared = usage
+        .declared_machines
+        .intersection(&usage.listening_machines)
+        .count();
+    if listening_declared == 0 {
+        String::from("⚠ Nothing listening")
+    } else if usage.addresses.iter().all(|address| is_loopback(address)) {
+        String::from("⚠ Only on localhost")
+    } else if listening_declared < usage.declared_machines.len() {
+        format!(
+            "⚠ Listening on {}/{} machines",
+            listening_declared,
+            usage.declared_machines.len()
+        )
+    } else {
+        String::from("✓ Served")
+    }
+}
@@ -1,12 +1,34 @@
+use color_eyre::eyre::eyre;
+use similar::{ChangeTag, TextDiff};
+
+use crate::fly_rust::machine_types::{
+    MachineVersion, MACHINE_CONFIG_METADATA_KEY_FLY_RELEASE_VERSION, MACHINE_PROCESS_GROUP_APP,
+};
+use crate::fly_rust::machines::list_machine_versions;
 use crate::fly_rust::resource_releases::get_app_releases_machines;
+use crate::ops::lease::list_active_machines;
+use crate::ops::machines::image::update_image;
 use crate::ops::{IoRespEvent, Ops};
 use crate::state::RdrResult;
 use crate::transformations::format_time;
 
+pub const RELEASES_LIMIT: i64 = 25;
+const DIFF_CONTEXT_LINES: usize = 3;
+
 pub async fn releases(ops: &Ops, app_name: String, limit: i64) -> RdrResult<()> {
-    let response = get_app_releases_machines(&ops.request_builder_graphql, app_name, limit).await?;
+    if let Some(app_releases_list) = ops.cache.app_releases.get(&app_name) {
+        ops.io_resp_tx
+            .send(IoRespEvent::AppReleases {
+                list: app_releases_list,
+            })
+            .await?;
+        return Ok(());
+    }
+
+    let response =
+        get_app_releases_machines(&ops.request_builder_graphql, app_name.clone(), limit).await?;
     if let Some(response) = response {
-        let app_releases_list = response
+        let app_releases_list: Vec<Vec<String>> = response
             .app
             .releases
             .nodes
@@ -22,6 +44,9 @@ pub async fn releases(ops: &Ops, app_name: String, limit: i64) -> RdrResult<()>
                 ]
             })
             .collect();
+        ops.cache
+            .app_releases
+            .insert(app_name, app_releases_list.clone());
         ops.io_resp_tx
             .send(IoRespEvent::AppReleases {
                 list: app_releases_list,
@@ -31,3 +56,124 @@ pub async fn releases(ops: &Ops, app_name: String, limit: i64) -> RdrResult<()>
 
     Ok(())
 }
+
+/// Diffs the image refs of two releases and the configs they deployed. Configs are taken from the
+/// past versions of a machine of the app, as releases don't carry them.
+pub async fn diff(ops: &Ops, app_name: String, from: i64, to: i64) -> RdrResult<()> {
+    let (from, to) = (from.min(to), from.max(to));
+    let releases = get_app_releases_machines(
+        &ops.request_builder_graphql,
+        app_name.clone(),
+        RELEASES_LIMIT,
+    )
+    .await?
+    .map(|response| response.app.releases.nodes)
+    .unwrap_or_default();
+    let image_ref = |version: i64| {
+        releases
+            .iter()
+            .find(|release| release.version == version)
+            .map(|release| release.image_ref.clone())
+            .ok_or_else(|| eyre!("Release v{} is not found.", version))
+    };
+    let (from_image_ref, to_image_ref) = (image_ref(from)?, image_ref(to)?);
+
+    let mut list = vec![vec![String::from("@"), String::from("Image")]];
+    list.extend(diff_lines(&(from_image_ref + "\n"), &(to_image_ref + "\n")));
+
+    list.push(vec![String::from("@"), String::from("Config")]);
+    let versions = machine_versions(ops, &app_name).await?;
+    let config = |version: i64| {
+        versions
+            .iter()
+            .find(|machine_version| {
+                machine_version.user_config["metadata"]
+                    [MACHINE_CONFIG_METADATA_KEY_FLY_RELEASE_VERSION]
+                    .as_str()
+                    .and_then(|release_version| release_version.parse::<i64>().ok())
+                    == Some(version)
+            })
+            .and_then(|machine_version| {
+                serde_json::to_string_pretty(&machine_version.user_config).ok()
+            })
+    };
+    match (config(from), config(to)) {
+        (Some(from_config), Some(to_config)) => {
+            list.extend(diff_lines(&(from_config + "\n"), &(to_config + "\n")))
+        }
+        _ => list.push(vec![
+            String::from(" "),
+            String::from("The configs of these releases are no longer kept by the machines."),
+        ]),
+    }
+
+    ops.io_resp_tx
+        .send(IoRespEvent::AppReleasesDiff { list })
+        .await?;
+
+    Ok(())
+}
+
+/// Rolls the app back to the image of a past release, its machines are updated to the image one
+/// at a time like a rolling deploy.
+pub async fn rollback(ops: &Ops, app_name: &str, image_ref: &str) -> RdrResult<()> {
+    if image_ref.is_empty() {
+        return Err(eyre!("The release has no image to roll back to."));
+    }
+    let machines = list_active_machines(&ops.request_builder_machines, app_name)
+        .await?
+        .into_iter()
+        .map(|machine| machine.id)
+        .collect::<Vec<_>>();
+    if machines.is_empty() {
+        return Err(eyre!("{} has no machines to roll back.", app_name));
+    }
+    update_image(ops, app_name, machines, image_ref).await
+}
+
+async fn machine_versions(ops: &Ops, app_name: &str) -> RdrResult<Vec<MachineVersion>> {
+    let machines = list_active_machines(&ops.request_builder_machines, app_name).await?;
+    let machine = machines
+        .iter()
+        .find(|machine| machine.has_process_group(MACHINE_PROCESS_GROUP_APP))
+        .or(machines.first());
+    match machine {
+        Some(machine) => {
+            list_machine_versions(&ops.request_builder_machines, app_name, &machine.id).await
+        }
+        None => Ok(vec![]),
+    }
+}
+
+/// Changed lines with some context around them, as `[tag, line]` rows. Tags are `+`, `-` and ` `
+/// for the lines, `~` separates the hunks.
+fn diff_lines(from: &str, to: &str) -> Vec<Vec<String>> {
+    let diff = TextDiff::from_lines(from, to);
+    let hunks = diff.grouped_ops(DIFF_CONTEXT_LINES);
+    if hunks.is_empty() {
+        return vec![vec![String::from(" "), String::from("No changes.")]];
+    }
+    hunks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, hunk)| {
+            let separator = (i > 0).then(|| vec![String::from("~"), String::from("…")]);
+            separator.into_iter().chain(
+                hunk.iter()
+                    .flat_map(|op| diff.iter_changes(op))
+                    .map(|change| {
+                        let tag = match change.tag() {
+                            ChangeTag::Delete => "-",
+                            ChangeTag::Insert => "+",
+                            ChangeTag::Equal => " ",
+                        };
+                        vec![
+                            String::from(tag),
+                            change.to_string_lossy().trim_end().to_string(),
+                        ]
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
@@ -1,34 +1,189 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use color_eyre::eyre::OptionExt;
+use color_eyre::eyre::{eyre, OptionExt};
+use itertools::Itertools;
+use serde_json::json;
 
-use crate::ops::lease::list_active_machines;
+use crate::fly_rust::machine_types::{
+    Machine, MachineConfig, MachinePort, MachineService, MACHINE_STATE_STARTED,
+};
+use crate::fly_rust::machines::{get_machine, get_machine_config, update_machine};
+use crate::ops::lease::{acquire_leases, list_active_machines, ReleaseGuard};
+use crate::ops::wait::{wait_for_health_checks, wait_for_start_or_stop};
 use crate::ops::{IoRespEvent, Ops};
-use crate::state::RdrResult;
+use crate::state::{PopupType, RdrResult};
+
+const UPDATE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A public port of a service.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServicePort {
+    pub port: i32,
+    pub handlers: Vec<String>,
+    pub force_https: bool,
+}
+
+impl ServicePort {
+    fn describe(&self, protocol: &str) -> String {
+        format!(
+            "{} {} [{}] force https: {}",
+            protocol.to_uppercase(),
+            self.port,
+            self.handlers.join(",").to_uppercase(),
+            self.force_https
+        )
+    }
+}
+
+/// An edit of the service with the protocol and internal port, applied to every machine that has
+/// it.
+#[derive(Clone, Debug)]
+pub struct ServiceEdit {
+    pub protocol: String,
+    pub internal_port: i32,
+    pub new_internal_port: i32,
+    /// The port being edited, None when one is added
+    pub port: Option<ServicePort>,
+    /// None when the port is removed
+    pub new_port: Option<ServicePort>,
+}
+
+impl ServiceEdit {
+    /// Reads the service and the port from a row of the services list.
+    pub fn from_row(row: &[String]) -> RdrResult<Self> {
+        let (port, internal_port) = row[1]
+            .split_once(" => ")
+            .ok_or_eyre("No ports are found.")?;
+        let port = ServicePort {
+            port: port.parse()?,
+            handlers: row[2]
+                .trim_matches(['[', ']'])
+                .split(',')
+                .filter(|handler| !handler.is_empty())
+                .map(|handler| handler.to_lowercase())
+                .collect(),
+            force_https: row[3].eq_ignore_ascii_case("true"),
+        };
+        let internal_port = internal_port.parse()?;
+        Ok(Self {
+            protocol: row[0].to_lowercase(),
+            internal_port,
+            new_internal_port: internal_port,
+            port: Some(port.clone()),
+            new_port: Some(port),
+        })
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.internal_port == self.new_internal_port && self.port == self.new_port
+    }
+
+    /// Lines removed and added by the edit, prefixed with - and +.
+    pub fn diff(&self) -> Vec<String> {
+        let mut diff = vec![];
+        if self.internal_port != self.new_internal_port {
+            diff.push(format!("- internal port {}", self.internal_port));
+            diff.push(format!("+ internal port {}", self.new_internal_port));
+        }
+        if self.port != self.new_port {
+            if let Some(port) = &self.port {
+                diff.push(format!("- {}", port.describe(&self.protocol)));
+            }
+            if let Some(port) = &self.new_port {
+                diff.push(format!("+ {}", port.describe(&self.protocol)));
+            }
+        }
+        diff
+    }
+
+    fn matches(&self, service: &MachineService) -> bool {
+        service.protocol == self.protocol
+            && service.internal_port == self.internal_port
+            && self.port.as_ref().map_or(true, |port| {
+                service
+                    .ports
+                    .iter()
+                    .flatten()
+                    .any(|service_port| service_port.port == Some(port.port))
+            })
+    }
+
+    fn apply(&self, services: &mut Vec<serde_json::Value>) {
+        for service in services.iter_mut().filter(|service| {
+            service["protocol"] == json!(self.protocol)
+                && service["internal_port"] == json!(self.internal_port)
+        }) {
+            service["internal_port"] = json!(self.new_internal_port);
+            if !service["ports"].is_array() {
+                service["ports"] = json!([]);
+            }
+            let ports = service["ports"].as_array_mut().unwrap();
+            let position = self
+                .port
+                .as_ref()
+                .and_then(|port| ports.iter().position(|p| p["port"] == json!(port.port)));
+            match (position, &self.new_port) {
+                (Some(position), Some(new_port)) => {
+                    let port = &mut ports[position];
+                    port["port"] = json!(new_port.port);
+                    port["handlers"] = json!(new_port.handlers);
+                    port["force_https"] = json!(new_port.force_https);
+                }
+                (Some(position), None) => {
+                    ports.remove(position);
+                }
+                (None, Some(new_port)) if self.port.is_none() => ports.push(json!({
+                    "port": new_port.port,
+                    "handlers": new_port.handlers,
+                    "force_https": new_port.force_https,
+                })),
+                _ => {}
+            }
+        }
+        //INFO: A service without ports isn't reachable, it's removed along with its last port
+        services.retain(|service| {
+            service["ports"]
+                .as_array()
+                .map_or(true, |ports| !ports.is_empty())
+        });
+    }
+}
 
 pub async fn services(ops: &Ops, app_name: String) -> RdrResult<()> {
     let mut services: HashSet<String> = HashSet::new();
     let mut service_to_process_group: HashMap<String, Vec<String>> = HashMap::new();
     let mut service_to_region: HashMap<String, Vec<String>> = HashMap::new();
     let mut service_to_machines: HashMap<String, i32> = HashMap::new();
+    let mut service_to_warnings: HashMap<String, BTreeSet<&'static str>> = HashMap::new();
 
     let machines = list_active_machines(&ops.request_builder_machines, &app_name).await?;
 
-    for (machine, service, port) in machines
+    for (machine, config, service, port) in machines
         .iter()
         .filter_map(|machine| machine.config.as_ref().map(|config| (machine, config)))
         .filter_map(|(machine, config)| {
-            config.services.as_ref().map(|services| (machine, services))
+            config
+                .services
+                .as_ref()
+                .map(|services| (machine, config, services))
         })
-        .flat_map(|(machine, services)| services.iter().map(move |service| (machine, service)))
-        .filter_map(|(machine, service)| {
+        .flat_map(|(machine, config, services)| {
+            services
+                .iter()
+                .map(move |service| (machine, config, service))
+        })
+        .filter_map(|(machine, config, service)| {
             service
                 .ports
                 .as_ref()
-                .map(|ports| (machine, service, ports))
+                .map(|ports| (machine, config, service, ports))
         })
-        .flat_map(|(machine, service, ports)| {
-            ports.iter().map(move |port| (machine, service, port))
+        .flat_map(|(machine, config, service, ports)| {
+            ports
+                .iter()
+                .map(move |port| (machine, config, service, port))
         })
     {
         let protocol = &service.protocol;
@@ -51,6 +206,11 @@ pub async fn services(ops: &Ops, app_name: String) -> RdrResult<()> {
         let key = get_service_key(protocol, ports, &https, &handlers);
         services.insert(key.clone());
 
+        service_to_warnings
+            .entry(key.clone())
+            .or_default()
+            .extend(service_warnings(config, service, port));
+
         service_to_process_group
             .entry(key.clone())
             .or_default()
@@ -62,8 +222,10 @@ pub async fn services(ops: &Ops, app_name: String) -> RdrResult<()> {
         *service_to_machines.entry(key).or_insert(0) += 1;
     }
 
+    //INFO: Sorted so the cursor stays on the same service across reloads
     let app_services_list = services
         .iter()
+        .sorted()
         .map(|service| {
             let components: Vec<&str> = service.split('-').collect();
             vec![
@@ -91,6 +253,16 @@ pub async fn services(ops: &Ops, app_name: String) -> RdrResult<()> {
                     .get(service)
                     .map(|count| count.to_string())
                     .unwrap_or_default(),
+                service_to_warnings
+                    .get(service)
+                    .filter(|warnings| !warnings.is_empty())
+                    .map(|warnings| {
+                        format!(
+                            "⚠ {}",
+                            warnings.iter().copied().collect::<Vec<_>>().join(", ")
+                        )
+                    })
+                    .unwrap_or_default(),
             ]
         })
         .collect();
@@ -103,6 +275,187 @@ pub async fn services(ops: &Ops, app_name: String) -> RdrResult<()> {
     Ok(())
 }
 
+/// Applies the edit to the machines that have the service one by one, each started one passing
+/// its health checks before the next one is updated, then reloads the services view.
+pub async fn update(ops: &Ops, app_name: String, edit: ServiceEdit) -> RdrResult<()> {
+    let machines: Vec<Machine> = list_active_machines(&ops.request_builder_machines, &app_name)
+        .await?
+        .into_iter()
+        .filter(|machine| {
+            machine
+                .config
+                .as_ref()
+                .and_then(|config| config.services.as_ref())
+                .is_some_and(|services| services.iter().any(|service| edit.matches(service)))
+        })
+        .collect();
+    if machines.is_empty() {
+        return Err(eyre!("No machines have the service."));
+    }
+
+    let (leases, errors, release) = acquire_leases(ops, &app_name, machines).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    let total = leases.len();
+    let mut updated = vec![];
+    let mut skipped = vec![];
+    for (i, lease) in leases.into_iter().enumerate() {
+        let (id, nonce, was_started) = {
+            let machine = lease.lock().unwrap();
+            (
+                machine.id.clone(),
+                machine.lease_nonce.clone(),
+                machine.state == MACHINE_STATE_STARTED,
+            )
+        };
+        let Some(nonce) = nonce else {
+            skipped.push(format!("{} (no lease)", id));
+            continue;
+        };
+        let mut config = get_machine_config(&ops.request_builder_machines, &app_name, &id).await?;
+        let Some(services) = config["services"].as_array_mut() else {
+            skipped.push(format!("{} (no services)", id));
+            continue;
+        };
+        edit.apply(services);
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!(
+                    "Updating the services of {} ({}/{}): updating {}...",
+                    app_name,
+                    i + 1,
+                    total,
+                    id
+                ),
+            })
+            .await?;
+        //INFO: Stopped machines are only updated, they stay stopped
+        update_machine(
+            &ops.request_builder_machines,
+            &app_name,
+            &id,
+            None,
+            &config,
+            &nonce,
+            !was_started,
+        )
+        .await?;
+        if !was_started {
+            updated.push(id);
+            continue;
+        }
+        // The new version is waited for, not the one the lease was taken on
+        let machine = get_machine(&ops.request_builder_machines, &app_name, &id).await?;
+        wait_for_start_or_stop(
+            &ops.request_builder_machines,
+            &app_name,
+            Arc::new(Mutex::new(machine)),
+            "start",
+            UPDATE_TIMEOUT,
+        )
+        .await?;
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!(
+                    "Updating the services of {} ({}/{}): waiting for the health checks of {}...",
+                    app_name,
+                    i + 1,
+                    total,
+                    id
+                ),
+            })
+            .await?;
+        wait_for_health_checks(
+            &ops.request_builder_machines,
+            &app_name,
+            &id,
+            UPDATE_TIMEOUT,
+        )
+        .await?;
+        updated.push(id);
+    }
+
+    if updated.is_empty() {
+        return Err(eyre!(
+            "No machines were updated, skipped {}.",
+            skipped.join(", ")
+        ));
+    }
+    let mut message = format!(
+        "Updated the services of {} on {}.",
+        app_name,
+        updated.join(", ")
+    );
+    if !skipped.is_empty() {
+        message.push_str(&format!("\nSkipped {}.", skipped.join(", ")));
+    }
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message,
+        })
+        .await?;
+
+    services(ops, app_name).await
+}
+
+/// Lint-style checks of a service port, these are valid configs that are most likely mistakes.
+fn service_warnings(
+    config: &MachineConfig,
+    service: &MachineService,
+    port: &MachinePort,
+) -> Vec<&'static str> {
+    let mut warnings = vec![];
+
+    let has_service_checks = service
+        .checks
+        .as_ref()
+        .is_some_and(|checks| !checks.is_empty());
+    let has_machine_checks = config.checks.as_ref().is_some_and(|checks| {
+        checks
+            .values()
+            .any(|check| check.port == Some(service.internal_port))
+    });
+    if !has_service_checks && !has_machine_checks {
+        warnings.push("No health checks");
+    }
+
+    let has_http_handler = port
+        .handlers
+        .as_ref()
+        .is_some_and(|handlers| handlers.iter().any(|handler| handler == "http"));
+    if port.force_https.unwrap_or_default() && !has_http_handler {
+        warnings.push("Force Https without an http handler");
+    }
+
+    if let Some(concurrency) = &service.concurrency {
+        if concurrency.hard_limit == 0 || concurrency.soft_limit == 0 {
+            warnings.push("Concurrency limit of 0");
+        } else if concurrency.soft_limit > concurrency.hard_limit {
+            warnings.push("Soft limit above the hard limit");
+        }
+    }
+
+    warnings
+}
+
 fn get_service_key(protocol: &str, ports: String, forcehttps: &str, handlers: &str) -> String {
     format!("{}-{}-{}-{}", protocol, ports, forcehttps, handlers)
 }
@@ -112,3 +465,126 @@ fn make_ascii_titlecase(s: &mut str) {
         r.make_ascii_uppercase();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row() -> Vec<String> {
+        ["tcp", "443 => 8080", "[TLS,HTTP]", "true"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    fn tcp_services() -> Vec<serde_json::Value> {
+        vec![json!({
+            "protocol": "tcp",
+            "internal_port": 8080,
+            "ports": [
+                { "port": 80, "handlers": ["http"], "force_https": true },
+                { "port": 443, "handlers": ["tls", "http"], "force_https": false },
+            ],
+        })]
+    }
+
+    #[test]
+    fn test_edit_from_row() {
+        let edit = ServiceEdit::from_row(&row()).unwrap();
+        assert_eq!(edit.protocol, "tcp");
+        assert_eq!(edit.internal_port, 8080);
+        assert_eq!(
+            edit.port,
+            Some(ServicePort {
+                port: 443,
+                handlers: vec![String::from("tls"), String::from("http")],
+                force_https: true,
+            })
+        );
+        assert!(edit.is_noop());
+        assert!(ServiceEdit::from_row(&["tcp", "", "[]", "false"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn test_edit_matching() {
+        let edit = ServiceEdit::from_row(&row()).unwrap();
+        let service: MachineService = serde_json::from_value(tcp_services().remove(0)).unwrap();
+        assert!(edit.matches(&service));
+        let other_port = ServiceEdit {
+            port: Some(ServicePort {
+                port: 8443,
+                handlers: vec![],
+                force_https: false,
+            }),
+            ..edit.clone()
+        };
+        assert!(!other_port.matches(&service));
+        let added_port = ServiceEdit {
+            port: None,
+            ..edit.clone()
+        };
+        assert!(added_port.matches(&service));
+        let other_service = ServiceEdit {
+            internal_port: 9090,
+            ..edit
+        };
+        assert!(!other_service.matches(&service));
+    }
+
+    #[test]
+    fn test_edit_applying() {
+        let edit = ServiceEdit::from_row(&row()).unwrap();
+
+        let mut services = tcp_services();
+        ServiceEdit {
+            new_internal_port: 9090,
+            new_port: Some(ServicePort {
+                port: 8443,
+                handlers: vec![String::from("tls")],
+                force_https: false,
+            }),
+            ..edit.clone()
+        }
+        .apply(&mut services);
+        assert_eq!(services[0]["internal_port"], json!(9090));
+        assert_eq!(
+            services[0]["ports"][1],
+            json!({ "port": 8443, "handlers": ["tls"], "force_https": false })
+        );
+
+        let mut services = tcp_services();
+        ServiceEdit {
+            port: None,
+            new_port: Some(ServicePort {
+                port: 8443,
+                handlers: vec![],
+                force_https: false,
+            }),
+            ..edit.clone()
+        }
+        .apply(&mut services);
+        assert_eq!(services[0]["ports"].as_array().unwrap().len(), 3);
+
+        let mut services = tcp_services();
+        ServiceEdit {
+            new_port: None,
+            ..edit.clone()
+        }
+        .apply(&mut services);
+        assert_eq!(
+            services[0]["ports"],
+            json!([{ "port": 80, "handlers": ["http"], "force_https": true }])
+        );
+    }
+
+    #[test]
+    fn test_removing_the_last_port_drops_the_service() {
+        let mut services = tcp_services();
+        services[0]["ports"].as_array_mut().unwrap().remove(0);
+        ServiceEdit {
+            new_port: None,
+            ..ServiceEdit::from_row(&row()).unwrap()
+        }
+        .apply(&mut services);
+        assert!(services.is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use color_eyre::eyre::{eyre, OptionExt};
+
+use crate::fly_rust::resource_apps::{create_deploy_token, get_app_basic};
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+/// Mints a token that can only deploy the app, like `fly tokens create deploy`. The token is
+/// sent to be shown once, it can't be read back from the API.
+pub async fn create_deploy(
+    ops: &Ops,
+    app_name: &str,
+    name: &str,
+    expiry_days: u32,
+) -> RdrResult<()> {
+    let app = get_app_basic(&ops.request_builder_graphql, app_name.to_string())
+        .await?
+        .ok_or_eyre("App not found.")?
+        .appbasic;
+    let response = create_deploy_token(
+        &ops.request_builder_graphql,
+        app.organization.id,
+        app.id,
+        name.to_string(),
+        format!("{}h", expiry_days * 24),
+    )
+    .await?;
+    let token = response
+        .and_then(|response| response.create_limited_access_token)
+        .and_then(|payload| payload.limited_access_token.token_header)
+        .ok_or_else(|| eyre!("No token was returned for {}.", app_name))?;
+    ops.io_resp_tx
+        .send(IoRespEvent::DeployToken {
+            app_name: app_name.to_string(),
+            token,
+        })
+        .await?;
+    Ok(())
+}
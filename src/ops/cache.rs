@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::fly_rust::machine_types::Machine;
+use crate::fly_rust::resource_organizations::OrganizationFilter;
+
+const ORGANIZATIONS_TTL: Duration = Duration::from_secs(30);
+const ORGANIZATION_MEMBERS_TTL: Duration = Duration::from_secs(60);
+const APP_RELEASES_TTL: Duration = Duration::from_secs(30);
+const IMAGE_DATES_TTL: Duration = Duration::from_secs(300);
+const DEDICATED_MACHINES_TTL: Duration = Duration::from_secs(30);
+
+/// Keeps responses around for a while so reopening a view doesn't refetch the same data.
+#[derive(Clone, Debug)]
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<K, (Instant, V)>>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        TtlCache {
+            ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((inserted_at, value)) if inserted_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+    pub fn insert(&self, key: K, value: V) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now(), value));
+    }
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Responses of the slow-changing resources
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    pub organizations: TtlCache<OrganizationFilter, Vec<Vec<String>>>,
+    pub organization_members: TtlCache<String, Vec<Vec<String>>>,
+    pub app_releases: TtlCache<String, Vec<Vec<String>>>,
+    /// When the images of an app were first released, by image ref and keyed by app name.
+    pub image_dates: TtlCache<String, HashMap<String, String>>,
+    /// Machines on the dedicated hosts with their app names, keyed by org slug.
+    pub dedicated_machines: TtlCache<String, Vec<(String, Machine)>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        ResponseCache {
+            organizations: TtlCache::new(ORGANIZATIONS_TTL),
+            organization_members: TtlCache::new(ORGANIZATION_MEMBERS_TTL),
+            app_releases: TtlCache::new(APP_RELEASES_TTL),
+            image_dates: TtlCache::new(IMAGE_DATES_TTL),
+            dedicated_machines: TtlCache::new(DEDICATED_MACHINES_TTL),
+        }
+    }
+}
+
+impl ResponseCache {
+    pub fn clear(&self) {
+        self.organizations.clear();
+        self.organization_members.clear();
+        self.app_releases.clear();
+        self.image_dates.clear();
+        self.dedicated_machines.clear();
+    }
+}
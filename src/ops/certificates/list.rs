@@ -0,0 +1,18 @@
+use crate::fly_rust::resource_certificates::get_all_app_certificates;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+use crate::transformations::ResourceList;
+
+pub async fn list(ops: &Ops, seq_id: u64, app: &str) -> RdrResult<()> {
+    let certificates =
+        get_all_app_certificates(&ops.request_builder_graphql, app.to_string()).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Certificates {
+            seq_id,
+            list: certificates.transform(),
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::path::{PathBuf, MAIN_SEPARATOR};
+
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::capture::captured_calls;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+/// Default path suggested in the export popup
+pub fn export_file_path(resource_info: String) -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    format!("~{MAIN_SEPARATOR}flyradar-{resource_info}-{timestamp}.md")
+}
+
+/// Default path of the exported API calls
+pub fn api_capture_file_path() -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    format!("~{MAIN_SEPARATOR}flyradar-capture-{timestamp}.log")
+}
+
+/// Renders the rows as a Markdown table, only the columns with a header are kept.
+pub fn to_markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let escape = |cell: &str| cell.replace('|', "\\|").replace('\n', " ");
+    let mut table = format!(
+        "| {} |\n|{}|\n",
+        headers.join(" | "),
+        vec!["---"; headers.len()].join("|")
+    );
+    for row in rows {
+        let cells = row
+            .iter()
+            .take(headers.len())
+            .map(|cell| escape(cell))
+            .collect::<Vec<_>>();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table
+}
+
+/// Writes the table to the file, or copies it to the clipboard when there is no file.
+pub async fn export(ops: &Ops, table: String, file_path: Option<PathBuf>) -> RdrResult<()> {
+    let message = match file_path {
+        Some(file_path) => {
+            tokio::fs::write(&file_path, table).await?;
+            format!("Exported the table to {}.", file_path.to_string_lossy())
+        }
+        None => {
+            ops.io_resp_tx
+                .send(IoRespEvent::CopyToClipboard { text: table })
+                .await?;
+            String::from("Copied the table to the clipboard.")
+        }
+    };
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message,
+        })
+        .await?;
+    Ok(())
+}
+
+/// Writes the captured API calls to the file, one per line.
+pub async fn export_capture(ops: &Ops, file_path: PathBuf) -> RdrResult<()> {
+    let calls = captured_calls();
+    if calls.is_empty() {
+        return Err(eyre!(
+            "No API calls were captured, start capturing them with :capture on."
+        ));
+    }
+    let lines = calls
+        .iter()
+        .map(|call| call.to_line() + "\n")
+        .collect::<String>();
+    tokio::fs::write(&file_path, lines).await?;
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Exported {} captured API calls to {}.",
+                calls.len(),
+                file_path.to_string_lossy()
+            ),
+        })
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use crate::fly_rust::resource_extensions::get_all_organization_extensions;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+use crate::transformations::ResourceList;
+
+pub async fn list(ops: &Ops, seq_id: u64, org_slug: String) -> RdrResult<()> {
+    let extensions =
+        get_all_organization_extensions(&ops.request_builder_graphql, org_slug).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Extensions {
+            seq_id,
+            list: extensions.transform(),
+        })
+        .await?;
+
+    Ok(())
+}
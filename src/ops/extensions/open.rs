@@ -0,0 +1,17 @@
+use color_eyre::eyre::eyre;
+use reqwest::Url;
+
+use crate::fly_rust::resource_extensions::get_add_on;
+use crate::ops::Ops;
+use crate::state::RdrResult;
+
+pub async fn open(ops: &Ops, name: String, provider: String) -> RdrResult<()> {
+    let response = get_add_on(&ops.request_builder_graphql, name.clone(), provider).await?;
+    let sso_link = response
+        .and_then(|response| response.add_on)
+        .and_then(|add_on| add_on.sso_link)
+        .ok_or_else(|| eyre!("No dashboard is available for the extension: {}", name))?;
+    let url = Url::parse(&sso_link)?;
+    webbrowser::open(url.as_str()).map_err(|_err| eyre!("Could not open the dashboard."))?;
+    Ok(())
+}
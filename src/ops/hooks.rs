@@ -0,0 +1,356 @@
+use std::process::Stdio;
+
+use color_eyre::eyre::eyre;
+use itertools::Itertools;
+use tokio::process::Command;
+use tracing::info;
+
+use crate::config::{Hook, HookStage};
+use crate::ops::IoReqEvent;
+use crate::state::RdrResult;
+
+/// Operation an event performs and the resources it touches, passed to the hooks as env vars.
+pub struct HookContext {
+    pub operation: &'static str,
+    pub vars: Vec<(&'static str, String)>,
+}
+
+impl HookContext {
+    /// Only the operations changing resources have hooks.
+    pub fn from_event(event: &IoReqEvent) -> Option<Self> {
+        let (operation, vars) = match event {
+            IoReqEvent::DestroyOrganization { org_id, .. } => {
+                ("destroy_organization", vec![("ORG_ID", org_id.clone())])
+            }
+            IoReqEvent::CreateOrganizationInvite { org_id, email } => (
+                "invite_member",
+                vec![("ORG_ID", org_id.clone()), ("EMAIL", email.clone())],
+            ),
+            IoReqEvent::DeleteOrganizationMembership { org_slug, email } => (
+                "remove_member",
+                vec![("ORG_SLUG", org_slug.clone()), ("EMAIL", email.clone())],
+            ),
+            IoReqEvent::RestartApp {
+                app_name, org_slug, ..
+            } => (
+                "restart_app",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("ORG_SLUG", org_slug.clone()),
+                ],
+            ),
+            IoReqEvent::DestroyApp {
+                app_name, org_slug, ..
+            } => (
+                "destroy_app",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("ORG_SLUG", org_slug.clone()),
+                ],
+            ),
+            IoReqEvent::RestartMachines {
+                app_name, machines, ..
+            } => ("restart_machines", machines_vars(app_name, machines)),
+            IoReqEvent::StartMachines {
+                app_name, machines, ..
+            } => ("start_machines", machines_vars(app_name, machines)),
+            IoReqEvent::StopMachines {
+                app_name, machines, ..
+            } => ("stop_machines", machines_vars(app_name, machines)),
+            IoReqEvent::SuspendMachines {
+                app_name, machines, ..
+            } => ("suspend_machines", machines_vars(app_name, machines)),
+            IoReqEvent::CordonMachines {
+                app_name, machines, ..
+            } => ("cordon_machines", machines_vars(app_name, machines)),
+            IoReqEvent::UncordonMachines {
+                app_name, machines, ..
+            } => ("uncordon_machines", machines_vars(app_name, machines)),
+            IoReqEvent::SetMachinesMetadata {
+                app_name,
+                machines,
+                key,
+                value,
+                ..
+            } => {
+                let mut vars = machines_vars(app_name, machines);
+                vars.push(("METADATA_KEY", key.clone()));
+                vars.push(("METADATA_VALUE", value.clone()));
+                ("set_machines_metadata", vars)
+            }
+            IoReqEvent::UpdateMachinesImage {
+                app_name,
+                machines,
+                tag,
+                ..
+            } => {
+                let mut vars = machines_vars(app_name, machines);
+                vars.push(("IMAGE", tag.clone()));
+                ("update_machines_image", vars)
+            }
+            IoReqEvent::RollbackRelease {
+                app_name,
+                version,
+                image_ref,
+            } => (
+                "rollback_release",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("RELEASE_VERSION", version.to_string()),
+                    ("IMAGE", image_ref.clone()),
+                ],
+            ),
+            IoReqEvent::CreateDeployToken { app_name, name, .. } => (
+                "create_deploy_token",
+                vec![("APP_NAME", app_name.clone()), ("NAME", name.clone())],
+            ),
+            IoReqEvent::RenameMachine {
+                app_name,
+                machine_id,
+                name,
+                ..
+            } => (
+                "rename_machine",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("MACHINE_ID", machine_id.clone()),
+                    ("NAME", name.clone()),
+                ],
+            ),
+            IoReqEvent::AlignMachines {
+                app_name,
+                machine_id,
+            } => (
+                "align_machines",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("MACHINE_ID", machine_id.clone()),
+                ],
+            ),
+            IoReqEvent::ExecMachine {
+                app_name,
+                machine_id,
+                command,
+            } => (
+                "exec_machine",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("MACHINE_ID", machine_id.clone()),
+                    ("COMMAND", command.clone()),
+                ],
+            ),
+            IoReqEvent::ScaleMachine {
+                app_name,
+                machine_id,
+                preset,
+                ..
+            } => (
+                "scale_machine",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("MACHINE_ID", machine_id.clone()),
+                    ("VM_SIZE", preset.size()),
+                    ("VM_MEMORY_MB", preset.memory_mb.to_string()),
+                ],
+            ),
+            IoReqEvent::KillMachine {
+                app_name, params, ..
+            } => (
+                "kill_machine",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("MACHINE_ID", params.id.clone()),
+                ],
+            ),
+            IoReqEvent::DestroyMachine {
+                app_name, params, ..
+            } => (
+                "destroy_machine",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("MACHINE_ID", params.id.clone()),
+                ],
+            ),
+            IoReqEvent::CreateWireGuardPeer {
+                org_slug,
+                name,
+                region,
+                ..
+            } => (
+                "create_wireguard_peer",
+                vec![
+                    ("ORG_SLUG", org_slug.clone()),
+                    ("PEER_NAME", name.clone()),
+                    ("REGION", region.clone()),
+                ],
+            ),
+            IoReqEvent::RemoveWireGuardPeer { org_slug, name, .. } => (
+                "remove_wireguard_peer",
+                vec![("ORG_SLUG", org_slug.clone()), ("PEER_NAME", name.clone())],
+            ),
+            IoReqEvent::DestroyVolume {
+                app_name, params, ..
+            } => (
+                "destroy_volume",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("VOLUME_ID", params.id.clone()),
+                ],
+            ),
+            IoReqEvent::ForkVolume {
+                app_name, params, ..
+            } => (
+                "fork_volume",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("VOLUME_ID", params.id.clone()),
+                    ("REGION", params.region.clone().unwrap_or_default()),
+                ],
+            ),
+            IoReqEvent::CreateVolume {
+                app_name, params, ..
+            } => (
+                "create_volume",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("VOLUME_NAME", params.name.clone()),
+                    ("REGION", params.region.clone()),
+                    ("SIZE_GB", params.size_gb.to_string()),
+                ],
+            ),
+            IoReqEvent::DetachVolume {
+                app_name,
+                volume_id,
+                machine_id,
+                ..
+            } => (
+                "detach_volume",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("VOLUME_ID", volume_id.clone()),
+                    ("MACHINE_ID", machine_id.clone()),
+                ],
+            ),
+            IoReqEvent::SnapshotVolume {
+                app_name,
+                volume_id,
+            } => (
+                "snapshot_volume",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("VOLUME_ID", volume_id.clone()),
+                ],
+            ),
+            IoReqEvent::DeploySecrets {
+                app_name, changes, ..
+            } => (
+                "deploy_secrets",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    (
+                        "SECRETS",
+                        changes.iter().map(|change| change.key()).join(","),
+                    ),
+                ],
+            ),
+            IoReqEvent::CleanupApp {
+                app_name,
+                machines,
+                volumes,
+            } => {
+                let mut vars = machines_vars(app_name, machines);
+                vars.push(("VOLUME_IDS", volumes.join(",")));
+                ("cleanup_app", vars)
+            }
+            IoReqEvent::UpdateAppServices { app_name, edit } => (
+                "update_services",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    ("PROTOCOL", edit.protocol.clone()),
+                    ("INTERNAL_PORT", edit.internal_port.to_string()),
+                ],
+            ),
+            IoReqEvent::UpdateAppAutoscaling { app_name, changes } => (
+                "update_autoscaling",
+                vec![
+                    ("APP_NAME", app_name.clone()),
+                    (
+                        "PROCESS_GROUPS",
+                        changes
+                            .keys()
+                            .map(|(process_group, _)| process_group)
+                            .join(","),
+                    ),
+                    (
+                        "REGIONS",
+                        changes.keys().map(|(_, region)| region).join(","),
+                    ),
+                ],
+            ),
+            _ => return None,
+        };
+        Some(Self { operation, vars })
+    }
+}
+
+fn machines_vars(app_name: &str, machines: &[String]) -> Vec<(&'static str, String)> {
+    vec![
+        ("APP_NAME", app_name.to_string()),
+        ("MACHINE_IDS", machines.join(",")),
+    ]
+}
+
+/// Runs the configured hooks of the operation one by one, stopping at the first failing one.
+/// A failing `before` hook cancels the operation.
+pub async fn run_hooks(hooks: &[Hook], context: &HookContext, stage: HookStage) -> RdrResult<()> {
+    for hook in hooks
+        .iter()
+        .filter(|hook| hook.when == stage && (hook.on == context.operation || hook.on == "*"))
+    {
+        info!("Running the {:?} hook of {}", stage, context.operation);
+        //INFO: The output is captured so it doesn't draw over the tui
+        let output = shell(&hook.run)
+            .envs(
+                context
+                    .vars
+                    .iter()
+                    .map(|(key, value)| (format!("FLYRADAR_{key}"), value)),
+            )
+            .env("FLYRADAR_OPERATION", context.operation)
+            .env("FLYRADAR_HOOK", format!("{:?}", stage).to_lowercase())
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "The {} hook of {} failed ({}):\n{}",
+                format!("{:?}", stage).to_lowercase(),
+                context.operation,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .rev()
+                    .take(5)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .join("\n")
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn shell(script: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(script);
+    command
+}
+
+#[cfg(windows)]
+fn shell(script: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").arg(script);
+    command
+}
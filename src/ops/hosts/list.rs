@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use futures::future::try_join_all;
+use itertools::Itertools;
+
+use crate::fly_rust::machine_types::{Machine, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::list_machines;
+use crate::fly_rust::resource_apps::list_all;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+use crate::transformations::{ListDedicatedHost, ResourceList};
+
+/// Lists the machines of the org that run on a dedicated host along with their app names. They're
+/// cached for a while since it takes a request per app of the org.
+pub async fn list_dedicated_machines(
+    ops: &Ops,
+    org_slug: &str,
+) -> RdrResult<Vec<(String, Machine)>> {
+    if let Some(machines) = ops.cache.dedicated_machines.get(&org_slug.to_string()) {
+        return Ok(machines);
+    }
+    let apps = list_all(&ops.request_builder_graphql)
+        .await?
+        .into_iter()
+        .filter(|app| app.org == org_slug)
+        .collect::<Vec<_>>();
+
+    let machines_per_app = try_join_all(apps.iter().map(|app| async {
+        let machines =
+            list_machines::<Machine>(&ops.request_builder_machines, &app.name, false).await?;
+        RdrResult::Ok(
+            machines
+                .into_iter()
+                .filter(|machine| machine.host_dedication_id().is_some())
+                .map(|machine| (app.name.clone(), machine))
+                .collect::<Vec<_>>(),
+        )
+    }))
+    .await?;
+
+    let machines = machines_per_app.into_iter().flatten().collect::<Vec<_>>();
+    ops.cache
+        .dedicated_machines
+        .insert(org_slug.to_string(), machines.clone());
+    Ok(machines)
+}
+
+//INFO: The API doesn't expose the capacity of a dedicated host, so the CPUs and the memory are
+// what the org's machines on it reserve rather than how much of the host is used
+pub async fn list(ops: &Ops, seq_id: u64, org_slug: String) -> RdrResult<()> {
+    let machines = list_dedicated_machines(ops, &org_slug).await?;
+
+    let mut hosts: BTreeMap<String, Vec<(String, Machine)>> = BTreeMap::new();
+    for (app_name, machine) in machines {
+        let host_id = machine.host_dedication_id().unwrap_or_default();
+        hosts.entry(host_id).or_default().push((app_name, machine));
+    }
+
+    let hosts = hosts
+        .into_iter()
+        .map(|(id, machines)| {
+            let mut host = ListDedicatedHost {
+                id,
+                regions: machines
+                    .iter()
+                    .map(|(_, machine)| machine.region.clone())
+                    .unique()
+                    .join(", "),
+                apps: machines
+                    .iter()
+                    .map(|(app_name, _)| app_name)
+                    .unique()
+                    .count(),
+                machines: machines.len(),
+                started_machines: 0,
+                cpus: 0,
+                started_cpus: 0,
+                memory_mb: 0,
+                started_memory_mb: 0,
+            };
+            for (_, machine) in &machines {
+                let (cpus, memory_mb) = machine
+                    .get_config()
+                    .and_then(|c| c.guest.as_ref())
+                    .map(|guest| (guest.cpus, guest.memory_mb))
+                    .unwrap_or_default();
+                host.cpus += cpus;
+                host.memory_mb += memory_mb;
+                if machine.state == MACHINE_STATE_STARTED {
+                    host.started_machines += 1;
+                    host.started_cpus += cpus;
+                    host.started_memory_mb += memory_mb;
+                }
+            }
+            host
+        })
+        .collect::<Vec<_>>();
+
+    ops.io_resp_tx
+        .send(IoRespEvent::DedicatedHosts {
+            seq_id,
+            list: hosts.transform(),
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use super::list::list_dedicated_machines;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+pub async fn machines(ops: &Ops, org_slug: String, host_id: String) -> RdrResult<()> {
+    let machines = list_dedicated_machines(ops, &org_slug).await?;
+
+    let list = machines
+        .into_iter()
+        .filter(|(_, machine)| machine.host_dedication_id().as_deref() == Some(host_id.as_str()))
+        .map(|(app_name, machine)| {
+            let (cpus, memory_mb) = machine
+                .get_config()
+                .and_then(|c| c.guest.as_ref())
+                .map(|guest| (guest.cpus, guest.memory_mb))
+                .unwrap_or_default();
+            vec![
+                app_name,
+                machine.id,
+                machine.name,
+                machine.state,
+                machine.region,
+                cpus.to_string(),
+                format!("{}MB", memory_mb),
+            ]
+        })
+        .collect();
+
+    ops.io_resp_tx
+        .send(IoRespEvent::DedicatedHostMachines { list })
+        .await?;
+
+    Ok(())
+}
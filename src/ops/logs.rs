@@ -1,27 +1,156 @@
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
 
-use futures::stream::select_all;
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{TimeDelta, Utc};
+use directories::UserDirs;
+use futures::stream::{select_all, BoxStream};
+use futures::StreamExt;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio::time::{sleep, Duration};
-use tokio_stream::StreamExt;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{error, info};
 
+use crate::config::LogDumpFormat;
+use crate::logs::entry::LogEntry;
 use crate::logs::nats::NatsLogStream;
-use crate::logs::polling::PollingStream;
-use crate::logs::{LogOptions, LogStream};
-use crate::ops::Ops;
+use crate::logs::polling::{fetch_history, PollingStream};
+use crate::logs::{LogOptions, LogStream, LogTransport};
+use crate::ops::{IoRespEvent, Ops};
 use crate::state::RdrResult;
 use crate::widgets::log_viewer::{cleanup_logger, init_logger, Drain, LevelFilter};
 
-pub async fn dump_file_path(resource_info: String) -> RdrResult<PathBuf> {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    let filename = format!("{}_{}.log", resource_info, timestamp);
-    Ok(tokio::fs::canonicalize(".").await?.join(filename))
+const NDJSON_EXTENSIONS: &[&str] = &["ndjson", "jsonl", "json"];
+const NATS_RECONNECT_MIN_DELAY: Duration = Duration::from_secs(1);
+const NATS_RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+const NATS_RECONNECT_MAX_TIMES: usize = 6;
+
+/// Default path suggested in the dump logs popup
+pub fn dump_file_path(resource_info: String, format: LogDumpFormat) -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let extension = match format {
+        LogDumpFormat::Text => "log",
+        LogDumpFormat::Ndjson => NDJSON_EXTENSIONS[0],
+    };
+    format!("~{MAIN_SEPARATOR}flyradar-logs-{resource_info}-{timestamp}.{extension}")
+}
+
+/// Format of the dump, NDJSON for the JSON extensions and text otherwise
+pub fn dump_format(path: &Path) -> LogDumpFormat {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if NDJSON_EXTENSIONS.contains(&extension) => LogDumpFormat::Ndjson,
+        _ => LogDumpFormat::Text,
+    }
+}
+
+/// Expands the leading `~` of a path to the home directory
+pub fn expand_file_path(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), UserDirs::new()) {
+        (Some(rest), Some(user_dirs)) if rest.is_empty() || rest.starts_with(MAIN_SEPARATOR) => {
+            user_dirs
+                .home_dir()
+                .join(rest.trim_start_matches(MAIN_SEPARATOR))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Completes the last segment of a path to the longest prefix shared by the matching entries of
+/// its directory, directories are completed with a trailing separator
+pub fn complete_file_path(path: &str) -> Option<String> {
+    let (dir, prefix) = match path.rfind(MAIN_SEPARATOR) {
+        Some(index) => path.split_at(index + 1),
+        None => ("", path),
+    };
+    let entries =
+        std::fs::read_dir(expand_file_path(if dir.is_empty() { "." } else { dir })).ok()?;
+    let candidates: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let mut name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) {
+                return None;
+            }
+            if entry.path().is_dir() {
+                name.push(MAIN_SEPARATOR);
+            }
+            Some(name)
+        })
+        .collect();
+    let completion = common_prefix(&candidates)?;
+    Some(format!("{dir}{completion}"))
+}
+
+/// Longest prefix shared by all the candidates, `None` without candidates.
+pub fn common_prefix(candidates: &[String]) -> Option<String> {
+    Some(
+        candidates
+            .iter()
+            .skip(1)
+            .fold(candidates.first()?.clone(), |common, candidate| {
+                common
+                    .chars()
+                    .zip(candidate.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a)
+                    .collect()
+            }),
+    )
+}
+
+/// Opens the configured log dump file of the app in append mode, if there is one
+async fn open_log_dump(ops: &Ops, app_name: &str) -> Option<BufWriter<File>> {
+    let path = expand_file_path(ops.rdr_config.log_dump.get(app_name)?);
+    match open_append(&path).await {
+        Ok(writer) => Some(writer),
+        Err(err) => {
+            error!(
+                "Could not open the log dump file {}: {}",
+                path.to_string_lossy(),
+                err
+            );
+            None
+        }
+    }
+}
+
+pub async fn open_append(path: &Path) -> std::io::Result<BufWriter<File>> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    Ok(BufWriter::new(file))
+}
+
+async fn append_line(writer: &mut BufWriter<File>, line: &str) -> std::io::Result<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await
+}
+
+/// Line of a record in the tee file, in the format its extension implies
+fn format_log_tee_line(app_name: &str, path: &Path, record: &LogEntry) -> String {
+    match dump_format(path) {
+        LogDumpFormat::Text => format_log_dump_line(app_name, record),
+        LogDumpFormat::Ndjson => match serde_json::to_string(record) {
+            Ok(line) => line + "\n",
+            Err(_) => format_log_dump_line(app_name, record),
+        },
+    }
+}
+
+fn format_log_dump_line(app_name: &str, record: &LogEntry) -> String {
+    format!(
+        "{} {}[{}] {} [{}] {}\n",
+        record.timestamp,
+        app_name,
+        record.instance,
+        record.region,
+        record.level,
+        String::from_utf8_lossy(&strip_ansi_escapes::strip(record.message.as_bytes()))
+    )
 }
 
 pub struct LogsResources {
@@ -29,6 +158,125 @@ pub struct LogsResources {
     pub cancellation_token_nats: CancellationToken,
     pub polling_handle: Option<JoinHandle<()>>,
     pub nats: Option<NatsLogStream>,
+    // File the streamed logs are appended to while it is set, toggled from the logs view
+    pub tee: Option<PathBuf>,
+}
+
+/// Turns the tee off after a failure, so the logs view stops showing it
+async fn stop_log_tee(ops: &Ops) {
+    ops.logs_resources.lock().unwrap().tee = None;
+    let _ = ops
+        .io_resp_tx
+        .send(IoRespEvent::LogTee { file_path: None })
+        .await;
+}
+
+/// The logs of the last minutes shown before the streamed ones, with the token to poll on
+/// from. Empty when turned off or when fetching them failed.
+async fn load_history(
+    ops: &Ops,
+    opts: &LogOptions,
+    minutes: i64,
+) -> (Vec<LogEntry>, Option<String>) {
+    if minutes <= 0 {
+        return (vec![], None);
+    }
+    let since = Utc::now() - TimeDelta::minutes(minutes);
+    match fetch_history(&ops.request_builder_fly, opts, since).await {
+        Ok(history) => history,
+        Err(err) => {
+            info!("Could not fetch the log history: {}", err);
+            (vec![], None)
+        }
+    }
+}
+
+/// Polls the logs until the NATS stream takes over, the handle is kept to stop it then.
+fn start_polling(
+    ops: &Ops,
+    opts: &LogOptions,
+    next_token: Option<String>,
+) -> BoxStream<'static, Option<RdrResult<LogEntry>>> {
+    let polling_stream = PollingStream {
+        request_builder_fly: ops.request_builder_fly.clone(),
+        next_token,
+    };
+    let (stream, polling_handle) = polling_stream.stream(opts);
+    ops.logs_resources.lock().unwrap().polling_handle = Some(polling_handle);
+    stream.map(Some).boxed()
+}
+
+/// Streams over NATS and stops the polling once both had the time to overlap. The stream ends
+/// with `None` when the subscription drops.
+fn start_nats(
+    ops: &Ops,
+    opts: &LogOptions,
+    nats: NatsLogStream,
+) -> BoxStream<'static, Option<RdrResult<LogEntry>>> {
+    let logs_resources_clone = ops.logs_resources.clone();
+    tokio::spawn(async move {
+        // Wait 2 seconds before cancelling polling
+        sleep(Duration::from_secs(2)).await;
+        // Abort the polling task
+        if let Some(handle) = logs_resources_clone.lock().unwrap().polling_handle.take() {
+            info!("aborting the polling task");
+            handle.abort();
+        }
+    });
+    let (stream, _handle) = nats.stream(opts);
+    ops.logs_resources.lock().unwrap().nats = Some(nats);
+    stream
+        .map(Some)
+        .chain(futures::stream::once(async { None }))
+        .boxed()
+}
+
+async fn set_transport(ops: &Ops, transport: LogTransport) {
+    let _ = ops
+        .io_resp_tx
+        .send(IoRespEvent::LogTransport { transport })
+        .await;
+}
+
+/// Connects to NATS again with a backoff, the logs are polled meanwhile. Gives up and stays on
+/// polling after the last attempt.
+fn reconnect_nats(
+    ops: &Ops,
+    opts: &LogOptions,
+    cancellation_token_nats: CancellationToken,
+    reconnect_tx: mpsc::Sender<NatsLogStream>,
+) {
+    let request_builder_graphql = ops.request_builder_graphql.clone();
+    let io_resp_tx = ops.io_resp_tx.clone();
+    let opts = opts.clone();
+    tokio::spawn(async move {
+        let retry_policy = ExponentialBuilder::default()
+            .with_min_delay(NATS_RECONNECT_MIN_DELAY)
+            .with_max_delay(NATS_RECONNECT_MAX_DELAY)
+            .with_max_times(NATS_RECONNECT_MAX_TIMES);
+        let connect = (|| NatsLogStream::new(&request_builder_graphql, &opts))
+            .retry(retry_policy)
+            .notify(|err, delay| {
+                info!("Reconnecting to NATS in {:?} after: {}", delay, err);
+            });
+        tokio::select! {
+            result = connect => match result {
+                Ok(nats) => {
+                    let _ = reconnect_tx.send(nats).await;
+                }
+                Err(err) => {
+                    info!("Could not reconnect to NATS: {}", err);
+                    info!("Continuing with polling only...");
+                    let _ = io_resp_tx
+                        .send(IoRespEvent::LogTransport {
+                            transport: LogTransport::Polling,
+                        })
+                        .await;
+                }
+            },
+            _ = cancellation_token_nats.cancelled() => {}
+        }
+    });
 }
 
 pub async fn logs(
@@ -41,23 +289,21 @@ pub async fn logs(
     if opts.no_tail {
         let polling_stream = PollingStream {
             request_builder_fly: ops.request_builder_fly.clone(),
+            next_token: None,
         };
         let (stream, polling_handle) = polling_stream.stream(opts);
-        streams.push(stream);
+        streams.push(stream.map(Some).boxed());
         polling_handle.abort();
     } else {
-        // Start polling stream
-        let polling_stream = PollingStream {
-            request_builder_fly: ops.request_builder_fly.clone(),
+        //INFO: Fetched before the stream starts, so the history comes before the live logs and
+        // the polling goes on from where it ends
+        let history_minutes = ops.rdr_config.log_history_minutes;
+        let (history, next_token) = tokio::select! {
+            history = load_history(ops, opts, history_minutes) => history,
+            _ = cancellation_token_nats.cancelled() => return Ok(()),
         };
-        let (polling_stream, polling_handle) = polling_stream.stream(opts);
-
-        {
-            let mut logs_resources_guard = ops.logs_resources.lock().unwrap();
-            logs_resources_guard.polling_handle = Some(polling_handle);
-        }
-
-        streams.push(polling_stream);
+        // Start polling stream
+        streams.push(start_polling(ops, opts, next_token));
 
         let nats_connect_fut = NatsLogStream::new(&ops.request_builder_graphql, opts);
         tokio::select! {
@@ -65,26 +311,14 @@ pub async fn logs(
             nats_connect_result = nats_connect_fut => {
             match nats_connect_result {
                 Ok(nats) => {
-                    let logs_resources_clone = ops.logs_resources.clone();
                     // Successfully connected to NATS
-                    tokio::spawn(async move {
-                        // Wait 2 seconds before cancelling polling
-                        sleep(Duration::from_secs(2)).await;
-                        // Abort the polling task
-                        if let Some(handle) = logs_resources_clone.lock().unwrap().polling_handle.take()
-                        {
-                            info!("aborting the polling task");
-                            handle.abort();
-                        }
-                    });
-                    let (stream, _handle) = nats.stream(opts);
-                    streams.push(stream);
-                    let mut logs_resources_guard = ops.logs_resources.lock().unwrap();
-                    logs_resources_guard.nats = Some(nats);
+                    streams.push(start_nats(ops, opts, nats));
+                    set_transport(ops, LogTransport::Nats).await;
                 }
                 Err(e) => {
                     info!("Could not connect to NATS: {}", e);
                     info!("Continuing with polling only...");
+                    set_transport(ops, LogTransport::Polling).await;
                 }}
             }
             _ = cancellation_token_nats.cancelled() => {
@@ -95,11 +329,82 @@ pub async fn logs(
 
         let drain = Drain::new();
         init_logger(LevelFilter::Trace)?;
+        for record in &history {
+            drain.log(record);
+        }
         // Combine all active streams
         let mut combined = select_all(streams);
-        while let Some(entry) = combined.next().await {
+        let (reconnect_tx, mut reconnect_rx) = mpsc::channel(1);
+        //INFO: Written as the logs arrive, independent of the in-memory buffer of the viewer
+        let mut log_dump = open_log_dump(ops, &opts.app_name).await;
+        let mut log_tee: Option<(PathBuf, BufWriter<File>)> = None;
+        loop {
+            let entry = tokio::select! {
+                entry = combined.next() => entry,
+                Some(nats) = reconnect_rx.recv() => {
+                    info!("Reconnected to NATS");
+                    combined.push(start_nats(ops, opts, nats));
+                    set_transport(ops, LogTransport::Nats).await;
+                    continue;
+                }
+            };
+            let Some(entry) = entry else {
+                break;
+            };
+            //INFO: The NATS subscription dropped, e.g. after a sleep or a tunnel hiccup
+            let Some(entry) = entry else {
+                // Drained on leaving the logs
+                if cancellation_token_nats.is_cancelled() {
+                    break;
+                }
+                info!("NATS stream dropped, falling back to polling");
+                ops.logs_resources.lock().unwrap().nats = None;
+                combined.push(start_polling(ops, opts, None));
+                set_transport(ops, LogTransport::Reconnecting).await;
+                reconnect_nats(
+                    ops,
+                    opts,
+                    cancellation_token_nats.clone(),
+                    reconnect_tx.clone(),
+                );
+                continue;
+            };
             let record = entry?;
             drain.log(&record);
+            if let Some(writer) = log_dump.as_mut() {
+                let line = format_log_dump_line(&opts.app_name, &record);
+                if let Err(err) = append_line(writer, &line).await {
+                    error!("Appending to the log dump file failed: {}", err);
+                    log_dump = None;
+                }
+            }
+            //INFO: The tee is toggled while the stream runs, so its path is checked on every record
+            let tee_path = ops.logs_resources.lock().unwrap().tee.clone();
+            if log_tee.as_ref().map(|(path, _)| path) != tee_path.as_ref() {
+                log_tee = match tee_path {
+                    Some(path) => match open_append(&path).await {
+                        Ok(writer) => Some((path, writer)),
+                        Err(err) => {
+                            error!(
+                                "Could not open the log tee file {}: {}",
+                                path.to_string_lossy(),
+                                err
+                            );
+                            stop_log_tee(ops).await;
+                            None
+                        }
+                    },
+                    None => None,
+                };
+            }
+            if let Some((path, writer)) = log_tee.as_mut() {
+                let line = format_log_tee_line(&opts.app_name, path, &record);
+                if let Err(err) = append_line(writer, &line).await {
+                    error!("Appending to the log tee file failed: {}", err);
+                    stop_log_tee(ops).await;
+                    log_tee = None;
+                }
+            }
             // if logs_tx.send(entry).await.is_err() {
             //     info!("Receiver dropped, exiting stream loop");
             //     break;
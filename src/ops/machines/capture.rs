@@ -0,0 +1,79 @@
+use std::path::{PathBuf, MAIN_SEPARATOR};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::machine_types::MachineExecRequest;
+use crate::fly_rust::machines::exec_machine;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+const CAPTURE_SECONDS: i32 = 10;
+const CAPTURE_PACKETS: u64 = 1000;
+const CAPTURE_FILE: &str = "/tmp/flyradar-capture.pcap";
+const TCPDUMP_NOT_FOUND: i32 = 127;
+
+pub fn capture_file_path(machine_id: &str) -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    format!("~{MAIN_SEPARATOR}flyradar-capture-{machine_id}-{timestamp}.pcap")
+}
+
+/// Captures the packets of a machine with tcpdump, bounded by time and packet count. The pcap is
+/// read back base64 encoded from the output of the exec, so it doesn't need a tunnel.
+pub async fn capture(
+    ops: &Ops,
+    app_name: String,
+    machine_id: String,
+    file_path: PathBuf,
+) -> RdrResult<()> {
+    let script = format!(
+        "command -v tcpdump >/dev/null || exit {TCPDUMP_NOT_FOUND}; \
+         timeout {CAPTURE_SECONDS} tcpdump -i any -c {CAPTURE_PACKETS} -w {CAPTURE_FILE} >/dev/null 2>&1; \
+         base64 {CAPTURE_FILE}; status=$?; rm -f {CAPTURE_FILE}; exit $status"
+    );
+    let request = MachineExecRequest {
+        cmd: format!("sh -c '{script}'"),
+        timeout: CAPTURE_SECONDS * 2,
+    };
+    let response = exec_machine(
+        &ops.request_builder_machines,
+        &app_name,
+        &machine_id,
+        &request,
+    )
+    .await?;
+    match response.exit_code {
+        0 => {}
+        TCPDUMP_NOT_FOUND => {
+            return Err(eyre!(
+                "tcpdump is not installed on machine {}. Add it to the image to capture packets.",
+                machine_id
+            ))
+        }
+        code => {
+            return Err(eyre!(
+                "Packet capture failed with exit code {}: {}",
+                code,
+                response.stderr.trim()
+            ))
+        }
+    }
+
+    let pcap = STANDARD.decode(response.stdout.split_whitespace().collect::<String>())?;
+    tokio::fs::write(&file_path, &pcap).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Captured {} bytes of packets from machine {} to {}.",
+                pcap.len(),
+                machine_id,
+                file_path.to_string_lossy()
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
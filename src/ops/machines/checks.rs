@@ -0,0 +1,52 @@
+use chrono_humanize::HumanTime;
+
+use crate::fly_rust::machine_types::ConsulCheckStatus;
+use crate::fly_rust::machines::get_machine;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+pub async fn checks(ops: &Ops, app_name: String, machine_id: String) -> RdrResult<()> {
+    let machine = get_machine(&ops.request_builder_machines, &app_name, &machine_id).await?;
+    let mut checks = machine.checks.unwrap_or_default();
+
+    if checks.is_empty() {
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!("Machine {} has no health checks.", machine_id),
+            })
+            .await?;
+        return Ok(());
+    }
+
+    // Failing checks first
+    checks.sort_by_key(|check| match check.status {
+        ConsulCheckStatus::Critical => 0,
+        ConsulCheckStatus::Warning => 1,
+        ConsulCheckStatus::Passing => 2,
+    });
+    let list = checks
+        .into_iter()
+        .map(|check| {
+            vec![
+                check.name,
+                match check.status {
+                    ConsulCheckStatus::Critical => String::from("critical"),
+                    ConsulCheckStatus::Warning => String::from("warning"),
+                    ConsulCheckStatus::Passing => String::from("passing"),
+                },
+                check
+                    .updated_at
+                    .map(|updated_at| HumanTime::from(updated_at).to_string())
+                    .unwrap_or_default(),
+                check.output,
+            ]
+        })
+        .collect();
+
+    ops.io_resp_tx
+        .send(IoRespEvent::MachineChecks { list })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use tokio::process::Command;
+
+use crate::agent::flyctl;
+
+/// Machine to open the console of once the terminal is handed over.
+#[derive(Clone, Debug)]
+pub struct ConsoleRequest {
+    pub app_name: String,
+    pub machine_id: String,
+}
+
+/// Opens a shell through flyctl, which connects over the WireGuard tunnel of its agent and signs
+/// in to hallpass with a short-lived SSH certificate.
+pub fn console_command(request: &ConsoleRequest) -> Command {
+    let mut command = Command::new(flyctl());
+    command
+        .args([
+            "ssh",
+            "console",
+            "--app",
+            &request.app_name,
+            "--machine",
+            &request.machine_id,
+        ])
+        .env("FLY_NO_UPDATE_CHECK", "1");
+    command
+}
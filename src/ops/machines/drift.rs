@@ -0,0 +1,166 @@
+use std::collections::{BTreeMap, HashMap};
+
+use color_eyre::eyre::{eyre, OptionExt};
+use itertools::Itertools;
+
+use crate::fly_rust::machine_types::{Machine, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::{get_machine_config, update_machine};
+use crate::ops::lease::{acquire_leases, list_active_machines, ReleaseGuard};
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+/// Marks the machines whose config drifted from the rest of their process group.
+pub const DRIFT_MARKER: &str = "⚠";
+
+fn image_and_env(machine: &Machine) -> (String, BTreeMap<&str, &str>) {
+    let env = machine
+        .get_config()
+        .map(|config| {
+            config
+                .env
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    (machine.full_image_ref(), env)
+}
+
+/// Compares the image and env of the machines against the most common ones of their process
+/// group, returns what drifted by machine id.
+pub fn drift(machines: &[Machine]) -> HashMap<String, String> {
+    let mut drift = HashMap::new();
+    for (_, group) in &machines
+        .iter()
+        .sorted_by(|a, b| {
+            a.process_group()
+                .cmp(&b.process_group())
+                .then(a.id.cmp(&b.id))
+        })
+        .chunk_by(|machine| machine.process_group())
+    {
+        let group = group.collect::<Vec<_>>();
+        if group.len() < 2 {
+            continue;
+        }
+        let configs = group
+            .iter()
+            .map(|machine| image_and_env(machine))
+            .collect::<Vec<_>>();
+        let counts = configs.iter().counts();
+        let max_count = counts.values().max().copied().unwrap_or_default();
+        //INFO: Ties are broken by the machine ids, the config of the lowest id wins
+        let Some((common_image, common_env)) =
+            configs.iter().find(|config| counts[config] == max_count)
+        else {
+            continue;
+        };
+        for (machine, (image, env)) in group.iter().zip(configs.iter()) {
+            let drifted = [
+                (image != common_image).then_some("image"),
+                (env != common_env).then_some("env"),
+            ]
+            .into_iter()
+            .flatten()
+            .join(", ");
+            if !drifted.is_empty() {
+                drift.insert(machine.id.clone(), format!("{} {}", DRIFT_MARKER, drifted));
+            }
+        }
+    }
+    drift
+}
+
+/// Updates the image and env of the machines in the process group of the source machine to the
+/// source machine's.
+pub async fn align(ops: &Ops, app_name: &str, machine_id: &str) -> RdrResult<()> {
+    let machines = list_active_machines(&ops.request_builder_machines, app_name).await?;
+    let source = machines
+        .iter()
+        .find(|machine| machine.id == machine_id)
+        .ok_or_eyre("Machine not found.")?;
+    let source_config = image_and_env(source);
+    let process_group = source.process_group();
+    let targets = machines
+        .iter()
+        .filter(|machine| {
+            machine.process_group() == process_group && image_and_env(machine) != source_config
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if targets.is_empty() {
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!(
+                    "The machines of the {} process group already match {}.",
+                    process_group, machine_id
+                ),
+            })
+            .await?;
+        return Ok(());
+    }
+    let config = get_machine_config(&ops.request_builder_machines, app_name, machine_id).await?;
+
+    let (leases, errors, release) = acquire_leases(ops, app_name, targets).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    let mut aligned = vec![];
+    for lease in leases {
+        let (id, nonce, was_started) = {
+            let machine = lease.lock().unwrap();
+            (
+                machine.id.clone(),
+                machine.lease_nonce.clone(),
+                machine.state == MACHINE_STATE_STARTED,
+            )
+        };
+        let Some(nonce) = nonce else {
+            continue;
+        };
+        let mut target_config =
+            get_machine_config(&ops.request_builder_machines, app_name, &id).await?;
+        target_config["image"] = config["image"].clone();
+        target_config["env"] = config["env"].clone();
+        //INFO: Stopped machines are only updated, they stay stopped
+        update_machine(
+            &ops.request_builder_machines,
+            app_name,
+            &id,
+            None,
+            &target_config,
+            &nonce,
+            !was_started,
+        )
+        .await?;
+        aligned.push(id);
+    }
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Aligned the image and env of {} to {}.",
+                aligned.join(", "),
+                machine_id
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
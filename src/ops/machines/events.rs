@@ -0,0 +1,29 @@
+use chrono::{DateTime, Local};
+use itertools::Itertools;
+
+use crate::fly_rust::machine_types::Machine;
+
+const MAX_MACHINE_EVENTS: usize = 10;
+
+/// Restarts and OOM kills of the machines, newest first. Machines only hold their recent events so
+/// this covers the recent history.
+pub fn recent_events(machines: &[Machine]) -> Vec<String> {
+    machines
+        .iter()
+        .flat_map(|machine| {
+            machine
+                .events
+                .iter()
+                .flatten()
+                .filter_map(move |event| Some((event.timestamp, &machine.id, event.describe()?)))
+        })
+        .sorted_by(|a, b| b.0.cmp(&a.0))
+        .take(MAX_MACHINE_EVENTS)
+        .map(|(timestamp, machine_id, description)| {
+            let time = DateTime::from_timestamp_millis(timestamp)
+                .map(|time| time.with_timezone(&Local).format("%H:%M:%S").to_string())
+                .unwrap_or_default();
+            format!("{} {} {}", time, machine_id, description)
+        })
+        .collect()
+}
@@ -0,0 +1,48 @@
+use crate::fly_rust::machine_types::MachineExecRequest;
+use crate::fly_rust::machines::exec_machine;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+const EXEC_TIMEOUT_SECONDS: i32 = 30;
+pub const EXEC_STDOUT: &str = "stdout";
+pub const EXEC_STDERR: &str = "stderr";
+pub const EXEC_EXIT_CODE: &str = "exit";
+
+/// Runs the command on the machine and sends its output line by line, each line tagged with the
+/// stream it's from, followed by the exit code.
+pub async fn exec(ops: &Ops, app_name: &str, machine_id: &str, command: String) -> RdrResult<()> {
+    let request = MachineExecRequest {
+        cmd: command,
+        timeout: EXEC_TIMEOUT_SECONDS,
+    };
+    let response = exec_machine(
+        &ops.request_builder_machines,
+        app_name,
+        machine_id,
+        &request,
+    )
+    .await?;
+
+    let tagged = |stream: &str, output: &str| {
+        output
+            .lines()
+            .map(|line| vec![stream.to_string(), line.to_string()])
+            .collect::<Vec<_>>()
+    };
+    let mut list = tagged(EXEC_STDOUT, &response.stdout);
+    list.extend(tagged(EXEC_STDERR, &response.stderr));
+    list.push(vec![
+        EXEC_EXIT_CODE.to_string(),
+        response.exit_code.to_string(),
+    ]);
+
+    ops.io_resp_tx
+        .send(IoRespEvent::MachineExec {
+            machine_id: machine_id.to_string(),
+            command: request.cmd,
+            list,
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use color_eyre::eyre::eyre;
+use futures::stream::{
+    StreamExt, {self},
+};
+use reqwest::StatusCode;
+
+use crate::fly_rust::machines::get_machine;
+use crate::fly_rust::resource_apps::list_all;
+use crate::fly_rust::resource_organizations::get_detailed_organization_by_slug;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+const MAX_CONCURRENT_LOOKUPS: usize = 20;
+
+/// Machine ids are unique across apps but the machines api is scoped to an app, so every app of
+/// the viewer is asked for the machine. Only a not found answer counts as a miss, the machine
+/// isn't reported missing when an app couldn't be asked.
+pub async fn find(ops: &Ops, machine_id: String) -> RdrResult<()> {
+    let apps = list_all(&ops.request_builder_graphql).await?;
+    let mut lookups = stream::iter(apps)
+        .map(|app| {
+            let machine_id = &machine_id;
+            async move {
+                match get_machine(&ops.request_builder_machines, &app.name, machine_id).await {
+                    Ok(_) => Ok(Some(app)),
+                    Err(err)
+                        if err
+                            .downcast_ref::<reqwest::Error>()
+                            .and_then(|err| err.status())
+                            == Some(StatusCode::NOT_FOUND) =>
+                    {
+                        Ok(None)
+                    }
+                    Err(err) => Err(err.wrap_err(format!("Asking {} failed", app.name))),
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_LOOKUPS);
+    let mut found = None;
+    let mut first_error = None;
+    while let Some(lookup) = lookups.next().await {
+        match lookup {
+            Ok(Some(app)) => {
+                found = Some(app);
+                break;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    //INFO: The lookups still running are cancelled
+    drop(lookups);
+    let app = match (found, first_error) {
+        (Some(app), _) => app,
+        (None, Some(err)) => {
+            return Err(err.wrap_err(format!("Could not look for the machine {}", machine_id)))
+        }
+        (None, None) => return Err(eyre!("Machine not found: {}", machine_id)),
+    };
+    let org = get_detailed_organization_by_slug(&ops.request_builder_graphql, app.org.clone())
+        .await?
+        .ok_or_else(|| eyre!("Organization not found: {}", app.org))?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::JumpToMachine {
+            org_id: org.organizationdetails.id,
+            org_slug: org.organizationdetails.slug,
+            app_id: app.id,
+            app_name: app.name,
+            machine_id,
+        })
+        .await?;
+
+    Ok(())
+}
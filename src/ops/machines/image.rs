@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+use tracing::debug;
+
+use crate::fly_rust::machine_types::{Machine, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::{get_machine, get_machine_config, update_machine};
+use crate::fly_rust::resource_releases::get_app_releases_machines;
+use crate::ops::apps::releases::RELEASES_LIMIT;
+use crate::ops::lease::{acquire_leases, ReleaseGuard};
+use crate::ops::select_many_machines::select_many_machines;
+use crate::ops::wait::{wait_for_health_checks, wait_for_start_or_stop};
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+use crate::transformations::format_age;
+
+pub const UPDATE_PENDING: &str = "pending";
+pub const UPDATE_DONE: &str = "✓ updated";
+pub const UPDATE_FAILED: &str = "✗ failed";
+pub const UPDATE_SKIPPED: &str = "skipped";
+const UPDATE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Marks the images older than the configured number of days.
+pub const OLD_IMAGE_MARKER: &str = "⚠";
+/// Build date label of the OCI image spec, set by most builders.
+const IMAGE_CREATED_LABEL: &str = "org.opencontainers.image.created";
+
+/// The image with its tag replaced, a full image ref is taken as is.
+pub fn with_image_tag(image: &str, tag: &str) -> String {
+    if tag.contains('/') || tag.contains(':') || tag.contains('@') {
+        return tag.to_string();
+    }
+    let image = image.split_once('@').map_or(image, |(image, _)| image);
+    let repository = match image.rsplit_once(':') {
+        //INFO: A colon before the last slash is the port of the registry
+        Some((repository, current_tag)) if !current_tag.contains('/') => repository,
+        _ => image,
+    };
+    format!("{}:{}", repository, tag)
+}
+
+/// When each image of the app was first released, keyed by the image ref without its digest. The
+/// releases being unavailable only leaves the ages to the image labels.
+pub async fn image_dates(ops: &Ops, app_name: &str) -> HashMap<String, String> {
+    if let Some(image_dates) = ops.cache.image_dates.get(&app_name.to_string()) {
+        return image_dates;
+    }
+    let releases = match get_app_releases_machines(
+        &ops.request_builder_graphql,
+        app_name.to_string(),
+        RELEASES_LIMIT,
+    )
+    .await
+    {
+        Ok(response) => response
+            .map(|response| response.app.releases.nodes)
+            .unwrap_or_default(),
+        Err(err) => {
+            debug!("Listing the releases of {} failed: {:#?}", app_name, err);
+            return HashMap::new();
+        }
+    };
+    let mut image_dates: HashMap<String, String> = HashMap::new();
+    for release in releases {
+        let image = without_digest(&release.image_ref).to_string();
+        //INFO: Redeploying an image makes a new release, the age is from the first one
+        let is_earlier = image_dates
+            .get(&image)
+            .map_or(true, |created_at| release.created_at < *created_at);
+        if is_earlier {
+            image_dates.insert(image, release.created_at);
+        }
+    }
+    ops.cache
+        .image_dates
+        .insert(app_name.to_string(), image_dates.clone());
+    image_dates
+}
+
+fn without_digest(image: &str) -> &str {
+    image.split_once('@').map_or(image, |(image, _)| image)
+}
+
+/// Age of the machine's image, from its build date label or else the release that introduced it,
+/// marked when it's older than the given days.
+pub fn image_age(
+    machine: &Machine,
+    image_dates: &HashMap<String, String>,
+    max_age_days: i64,
+) -> String {
+    let created_at = machine
+        .image_ref
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(IMAGE_CREATED_LABEL))
+        .or_else(|| image_dates.get(without_digest(&machine.full_image_ref())));
+    let Some(created_at) = created_at else {
+        return String::new();
+    };
+    let Ok(time) = DateTime::parse_from_rfc3339(created_at) else {
+        return String::new();
+    };
+    let age = format_age(created_at);
+    if Utc::now().signed_duration_since(time).num_days() > max_age_days {
+        format!("{} {}", OLD_IMAGE_MARKER, age)
+    } else {
+        age
+    }
+}
+
+/// Progress of the rollout, a status per machine.
+struct Rollout<'a> {
+    ops: &'a Ops,
+    rows: Vec<Vec<String>>,
+}
+
+impl Rollout<'_> {
+    async fn set(&mut self, i: usize, status: String) -> RdrResult<()> {
+        self.rows[i][1] = status;
+        self.ops
+            .io_resp_tx
+            .send(IoRespEvent::MachinesUpdateProgress {
+                list: self.rows.clone(),
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Updates the machines to the image one at a time. The next machine is only updated once the
+/// previous one is started with its health checks passing, the rollout stops at the first
+/// failure.
+pub async fn update_image(
+    ops: &Ops,
+    app_name: &str,
+    machines: Vec<String>,
+    tag: &str,
+) -> RdrResult<()> {
+    let machines = select_many_machines(ops, app_name, machines).await?;
+    let (leases, errors, release) = acquire_leases(ops, app_name, machines).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    let mut rollout = Rollout {
+        ops,
+        rows: leases
+            .iter()
+            .map(|lease| {
+                vec![
+                    lease.lock().unwrap().id.clone(),
+                    String::from(UPDATE_PENDING),
+                ]
+            })
+            .collect(),
+    };
+    for (i, lease) in leases.iter().enumerate() {
+        if let Err(err) = update_one(ops, app_name, lease, tag, &mut rollout, i).await {
+            rollout
+                .set(i, format!("{}: {}", UPDATE_FAILED, err))
+                .await?;
+            for j in i + 1..leases.len() {
+                rollout.set(j, String::from(UPDATE_SKIPPED)).await?;
+            }
+            break;
+        }
+        rollout.set(i, String::from(UPDATE_DONE)).await?;
+    }
+
+    Ok(())
+}
+
+async fn update_one(
+    ops: &Ops,
+    app_name: &str,
+    lease: &Arc<Mutex<Machine>>,
+    tag: &str,
+    rollout: &mut Rollout<'_>,
+    i: usize,
+) -> RdrResult<()> {
+    let (id, nonce, was_started) = {
+        let machine = lease.lock().unwrap();
+        (
+            machine.id.clone(),
+            machine.lease_nonce.clone().unwrap_or_default(),
+            machine.state == MACHINE_STATE_STARTED,
+        )
+    };
+    let mut config = get_machine_config(&ops.request_builder_machines, app_name, &id).await?;
+    let image = with_image_tag(config["image"].as_str().unwrap_or_default(), tag);
+    config["image"] = image.clone().into();
+    rollout.set(i, format!("updating to {}", image)).await?;
+
+    //INFO: Stopped machines are only updated, they stay stopped
+    update_machine(
+        &ops.request_builder_machines,
+        app_name,
+        &id,
+        None,
+        &config,
+        &nonce,
+        !was_started,
+    )
+    .await?;
+    if !was_started {
+        return Ok(());
+    }
+    // The new version is waited for, not the one the lease was taken on
+    let updated = get_machine(&ops.request_builder_machines, app_name, &id).await?;
+    wait_for_start_or_stop(
+        &ops.request_builder_machines,
+        app_name,
+        Arc::new(Mutex::new(updated)),
+        "start",
+        UPDATE_TIMEOUT,
+    )
+    .await?;
+    rollout
+        .set(i, String::from("waiting for the health checks"))
+        .await?;
+    wait_for_health_checks(&ops.request_builder_machines, app_name, &id, UPDATE_TIMEOUT).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_tag_replacing() {
+        assert_eq!(
+            with_image_tag("registry.fly.io:5000/app:old", "new"),
+            "registry.fly.io:5000/app:new"
+        );
+        assert_eq!(
+            with_image_tag("registry.fly.io:5000/app", "new"),
+            "registry.fly.io:5000/app:new"
+        );
+        assert_eq!(
+            with_image_tag(
+                "registry.fly.io/app@sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                "new"
+            ),
+            "registry.fly.io/app:new"
+        );
+        assert_eq!(
+            with_image_tag("registry.fly.io/app:old@sha256:0123456789abcdef", "new"),
+            "registry.fly.io/app:new"
+        );
+        assert_eq!(
+            with_image_tag("registry.fly.io/app:old", "registry.fly.io/other:v2"),
+            "registry.fly.io/other:v2"
+        );
+        assert_eq!(
+            with_image_tag("registry.fly.io/app:old", "app@sha256:0123456789abcdef"),
+            "app@sha256:0123456789abcdef"
+        );
+    }
+}
@@ -1,19 +1,67 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::fly_rust::machine_types::Machine;
 use crate::fly_rust::machines::list_machines;
-use crate::ops::{IoRespEvent, Ops};
+use crate::ops::machines::{drift, events, image};
+use crate::ops::{metrics, IoRespEvent, Ops};
 use crate::state::RdrResult;
 use crate::transformations::{ListMachine, ResourceList};
 
 pub async fn list(ops: &Ops, seq_id: u64, app: &str) -> RdrResult<()> {
-    let machines = list_machines::<ListMachine>(
+    let details = list_machines::<serde_json::Value>(
         &ops.request_builder_machines,
         app,
         //INFO: When summary is set to true, server doesn't send states like "stopping"
         false,
     )
     .await?;
+    let machines = details
+        .iter()
+        .map(|machine| serde_json::from_value::<Machine>(machine.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    metrics::observe_machines(app, &machines);
+    let mut drift = drift::drift(&machines);
+    let image_max_age_days = ops
+        .rdr_config
+        .apps
+        .get(app)
+        .and_then(|app| app.image_max_age_days)
+        .unwrap_or(ops.rdr_config.image_max_age_days);
+    let image_dates = image::image_dates(ops, app).await;
 
     // Sort by id
-    let mut sorted_machines = machines;
+    let mut sorted_machines = machines
+        .iter()
+        .map(|machine| ListMachine {
+            id: machine.id.clone(),
+            name: machine.name.clone(),
+            state: machine.state.clone(),
+            region: machine.region.clone(),
+            updated_at: machine.updated_at.clone(),
+            host_dedication_id: machine.host_dedication_id().unwrap_or_default(),
+            restart_count: machine.restart_count(),
+            crash_looping: machine.is_crash_looping(),
+            drift: drift.remove(&machine.id).unwrap_or_default(),
+            created_at: machine.created_at.clone(),
+            image_age: image::image_age(machine, &image_dates, image_max_age_days),
+            image_ref: machine.full_image_ref(),
+            private_ip: machine.private_ip.clone(),
+            process_group: machine.process_group(),
+            metadata: machine
+                .get_config()
+                .and_then(|config| config.metadata.as_ref())
+                .map(|metadata| {
+                    metadata
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key, value))
+                        .sorted()
+                        .join(" ")
+                })
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
     sorted_machines.sort_by(|m1, m2| m1.id.cmp(&m2.id));
 
     ops.io_resp_tx
@@ -22,6 +70,22 @@ pub async fn list(ops: &Ops, seq_id: u64, app: &str) -> RdrResult<()> {
             list: sorted_machines.transform(),
         })
         .await?;
+    ops.io_resp_tx
+        .send(IoRespEvent::MachineEvents {
+            seq_id,
+            list: events::recent_events(&machines),
+        })
+        .await?;
+    ops.io_resp_tx
+        .send(IoRespEvent::MachineDetails {
+            seq_id,
+            list: machines
+                .iter()
+                .map(|machine| machine.id.clone())
+                .zip(details)
+                .collect::<HashMap<_, _>>(),
+        })
+        .await?;
 
     Ok(())
 }
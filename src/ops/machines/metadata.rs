@@ -0,0 +1,61 @@
+use color_eyre::eyre::eyre;
+use futures::future::join_all;
+
+use crate::fly_rust::machines::{delete_machine_metadata, set_machine_metadata};
+use crate::ops::Ops;
+use crate::state::RdrResult;
+
+/// Sets the key on every machine at once, an empty value removes it. Metadata updates don't
+/// restart the machines so no lease is taken.
+pub async fn set_metadata(
+    ops: &Ops,
+    app_name: &str,
+    machines: Vec<String>,
+    key: &str,
+    value: &str,
+) -> RdrResult<()> {
+    if key.is_empty() {
+        return Err(eyre!("Metadata key can't be empty, use key=value."));
+    }
+    let results = join_all(machines.iter().map(|machine_id| async move {
+        if value.is_empty() {
+            delete_machine_metadata(&ops.request_builder_machines, app_name, machine_id, key).await
+        } else {
+            set_machine_metadata(
+                &ops.request_builder_machines,
+                app_name,
+                machine_id,
+                key,
+                value,
+            )
+            .await
+        }
+        .map_err(|e| {
+            eyre!(
+                "Failed to update the metadata of machine {}: {}",
+                machine_id,
+                e
+            )
+        })
+    }))
+    .await;
+
+    let errors = results
+        .into_iter()
+        .filter_map(|result| result.err())
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    Ok(())
+}
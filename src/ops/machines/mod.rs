@@ -1,8 +1,20 @@
+pub mod capture;
+pub mod checks;
+pub mod console;
 pub mod cordon;
 pub mod destroy;
+pub mod drift;
+pub mod events;
+pub mod exec;
+pub mod find;
+pub mod image;
 pub mod kill;
 pub mod list;
+pub mod metadata;
+pub mod processes;
+pub mod rename;
 pub mod restart;
+pub mod scale;
 pub mod start;
 pub mod stop;
 pub mod suspend;
@@ -0,0 +1,38 @@
+use itertools::Itertools;
+
+use crate::fly_rust::machines::list_machine_processes;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+use crate::transformations::{format_bytes, ListProcess, ResourceList};
+
+/// Lists the processes of a machine, the busiest ones first like top does.
+pub async fn list(ops: &Ops, seq_id: u64, app_name: String, machine_id: String) -> RdrResult<()> {
+    let processes =
+        list_machine_processes(&ops.request_builder_machines, &app_name, &machine_id).await?;
+    let processes = processes
+        .unwrap_or_default()
+        .into_iter()
+        .sorted_by(|a, b| b.cpu.cmp(&a.cpu).then(a.pid.cmp(&b.pid)))
+        .map(|process| ListProcess {
+            pid: process.pid,
+            cpu: process.cpu,
+            rss: format_bytes(process.rss),
+            command: process.command,
+            listen_sockets: process
+                .listen_sockets
+                .unwrap_or_default()
+                .iter()
+                .map(|socket| format!("{}/{}", socket.address, socket.proto))
+                .join(", "),
+        })
+        .collect::<Vec<_>>();
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Processes {
+            seq_id,
+            list: processes.transform(),
+        })
+        .await?;
+
+    Ok(())
+}
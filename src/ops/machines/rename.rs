@@ -0,0 +1,46 @@
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::machine_types::MACHINE_STATE_STARTED;
+use crate::fly_rust::machines::{get_machine, get_machine_config, update_machine};
+use crate::ops::lease::{acquire_leases, ReleaseGuard};
+use crate::ops::Ops;
+use crate::state::RdrResult;
+
+/// Renames the machine. The name goes through a machine update, so a started machine is
+/// restarted with its current config while the rest stay as they are.
+pub async fn rename(ops: &Ops, app_name: &str, machine_id: &str, name: &str) -> RdrResult<()> {
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(eyre!("Machine name can't be empty or contain spaces."));
+    }
+    let machine = get_machine(&ops.request_builder_machines, app_name, machine_id).await?;
+    if machine.name == name {
+        return Ok(());
+    }
+    let skip_launch = machine.state != MACHINE_STATE_STARTED;
+    let (leases, errors, release) = acquire_leases(ops, app_name, vec![machine]).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if let Some(err) = errors.first() {
+        return Err(eyre!("{}", err));
+    }
+    let nonce = leases
+        .first()
+        .and_then(|lease| lease.lock().unwrap().lease_nonce.clone())
+        .ok_or_else(|| eyre!("Could not acquire a lease on {}.", machine_id))?;
+
+    let config = get_machine_config(&ops.request_builder_machines, app_name, machine_id).await?;
+    update_machine(
+        &ops.request_builder_machines,
+        app_name,
+        machine_id,
+        Some(name),
+        &config,
+        &nonce,
+        skip_launch,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::machine_types::{MachineGuest, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::{get_machine, get_machine_config, update_machine};
+use crate::ops::lease::{acquire_leases, ReleaseGuard};
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+use crate::transformations::{byte_unit, format_number};
+
+const CPU_KIND_SHARED: &str = "shared";
+const CPU_KIND_PERFORMANCE: &str = "performance";
+//INFO: Monthly prices of a CPU with the memory it comes with and of the memory on top of it, as
+// published for the cheapest regions. Prices vary by region so the costs are only estimates.
+const SHARED_CPU_MONTHLY: f64 = 1.94;
+const SHARED_CPU_MEMORY_MB: i32 = 256;
+const PERFORMANCE_CPU_MONTHLY: f64 = 31.0;
+const PERFORMANCE_CPU_MEMORY_MB: i32 = 2048;
+const ADDITIONAL_MEMORY_GB_MONTHLY: f64 = 5.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VmPreset {
+    pub cpu_kind: &'static str,
+    pub cpus: i32,
+    pub memory_mb: i32,
+}
+
+impl VmPreset {
+    pub fn size(&self) -> String {
+        format!("{}-cpu-{}x", self.cpu_kind, self.cpus)
+    }
+}
+
+/// Sizes `fly scale vm` offers, each with the memory it comes with and a few steps above it.
+pub fn presets() -> Vec<VmPreset> {
+    let shared = [1, 2, 4, 8].into_iter().flat_map(|cpus| {
+        [1, 2, 4, 8].into_iter().map(move |factor| VmPreset {
+            cpu_kind: CPU_KIND_SHARED,
+            cpus,
+            memory_mb: cpus * SHARED_CPU_MEMORY_MB * factor,
+        })
+    });
+    let performance = [1, 2, 4, 8, 16].into_iter().flat_map(|cpus| {
+        [1, 2, 4].into_iter().map(move |factor| VmPreset {
+            cpu_kind: CPU_KIND_PERFORMANCE,
+            cpus,
+            memory_mb: cpus * PERFORMANCE_CPU_MEMORY_MB * factor,
+        })
+    });
+    shared.chain(performance).collect()
+}
+
+/// Estimated monthly cost of a machine running all month with the given guest.
+pub fn monthly_cost(cpu_kind: &str, cpus: i32, memory_mb: i32) -> f64 {
+    let (cpu_monthly, included_memory_mb) = if cpu_kind == CPU_KIND_PERFORMANCE {
+        (PERFORMANCE_CPU_MONTHLY, PERFORMANCE_CPU_MEMORY_MB)
+    } else {
+        (SHARED_CPU_MONTHLY, SHARED_CPU_MEMORY_MB)
+    };
+    let additional_memory_mb = (memory_mb - cpus * included_memory_mb).max(0);
+    cpus as f64 * cpu_monthly + additional_memory_mb as f64 / 1024.0 * ADDITIONAL_MEMORY_GB_MONTHLY
+}
+
+/// Rows of the presets with their cost and the difference to the current guest.
+pub fn preset_rows(guest: &MachineGuest, presets: &[VmPreset]) -> Vec<Vec<String>> {
+    let current_cost = monthly_cost(&guest.cpu_kind, guest.cpus, guest.memory_mb);
+    presets
+        .iter()
+        .map(|preset| {
+            let cost = monthly_cost(preset.cpu_kind, preset.cpus, preset.memory_mb);
+            let is_current = preset.cpu_kind == guest.cpu_kind
+                && preset.cpus == guest.cpus
+                && preset.memory_mb == guest.memory_mb;
+            vec![
+                preset.size(),
+                preset.cpus.to_string(),
+                format_memory(preset.memory_mb),
+                format!("${:.2}", cost),
+                if is_current {
+                    String::from("current")
+                } else if cost >= current_cost {
+                    format!("+${:.2}", cost - current_cost)
+                } else {
+                    format!("-${:.2}", current_cost - cost)
+                },
+            ]
+        })
+        .collect()
+}
+
+fn format_memory(memory_mb: i32) -> String {
+    if memory_mb >= 1024 {
+        format!("{}{}", format_number(memory_mb / 1024), byte_unit(3))
+    } else {
+        format!("{}{}", format_number(memory_mb), byte_unit(2))
+    }
+}
+
+/// Updates the guest of the machine to the preset, which restarts it if it's started. A stopped
+/// machine stays stopped.
+pub async fn scale(ops: &Ops, app_name: &str, machine_id: &str, preset: VmPreset) -> RdrResult<()> {
+    let machine = get_machine(&ops.request_builder_machines, app_name, machine_id).await?;
+    let was_started = machine.state == MACHINE_STATE_STARTED;
+    let (leases, errors, release) = acquire_leases(ops, app_name, vec![machine]).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+    if let Some(err) = errors.first() {
+        return Err(eyre!(
+            "Failed to acquire the lease of {}: {}",
+            machine_id,
+            err
+        ));
+    }
+    let nonce = leases
+        .first()
+        .and_then(|lease| lease.lock().unwrap().lease_nonce.clone())
+        .ok_or_else(|| eyre!("Failed to acquire the lease of {}.", machine_id))?;
+
+    let mut config =
+        get_machine_config(&ops.request_builder_machines, app_name, machine_id).await?;
+    config["guest"]["cpu_kind"] = preset.cpu_kind.into();
+    config["guest"]["cpus"] = preset.cpus.into();
+    config["guest"]["memory_mb"] = preset.memory_mb.into();
+    update_machine(
+        &ops.request_builder_machines,
+        app_name,
+        machine_id,
+        None,
+        &config,
+        &nonce,
+        !was_started,
+    )
+    .await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Scaled {} to {} with {}.",
+                machine_id,
+                preset.size(),
+                format_memory(preset.memory_mb)
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
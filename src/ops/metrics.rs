@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::fly_rust::machine_types::{ConsulCheckStatus, Machine};
+use crate::ops::watches::WATCH_INTERVAL;
+use crate::state::RdrResult;
+
+/// An app that's neither open nor watched anymore stops being exported after missing a few polls
+const STALE_AFTER: Duration = WATCH_INTERVAL.saturating_mul(3);
+
+lazy_static! {
+    static ref OBSERVED: Mutex<Observed> = Mutex::new(Observed::default());
+}
+
+/// Counts keyed by app, then by the label of the gauge, as last seen by the polls.
+#[derive(Default)]
+struct Observed {
+    machines: BTreeMap<String, Seen<BTreeMap<String, usize>>>,
+    checks: BTreeMap<String, Seen<BTreeMap<String, usize>>>,
+    volumes: BTreeMap<String, Seen<BTreeMap<String, usize>>>,
+    orphaned_volumes: BTreeMap<String, Seen<usize>>,
+}
+
+impl Observed {
+    fn evict_stale(&mut self) {
+        self.machines.retain(|_, seen| !seen.is_stale());
+        self.checks.retain(|_, seen| !seen.is_stale());
+        self.volumes.retain(|_, seen| !seen.is_stale());
+        self.orphaned_volumes.retain(|_, seen| !seen.is_stale());
+    }
+}
+
+struct Seen<T> {
+    value: T,
+    at: Instant,
+}
+
+impl<T> Seen<T> {
+    fn now(value: T) -> Self {
+        Seen {
+            value,
+            at: Instant::now(),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.at.elapsed() > STALE_AFTER
+    }
+}
+
+fn count_by<'a>(labels: impl Iterator<Item = &'a str>) -> BTreeMap<String, usize> {
+    labels.fold(BTreeMap::new(), |mut counts, label| {
+        *counts.entry(label.to_string()).or_default() += 1;
+        counts
+    })
+}
+
+pub fn observe_machines(app_name: &str, machines: &[Machine]) {
+    let checks = machines
+        .iter()
+        .flat_map(|machine| machine.checks.iter().flatten())
+        .map(|check| match check.status {
+            ConsulCheckStatus::Critical => "critical",
+            ConsulCheckStatus::Warning => "warning",
+            ConsulCheckStatus::Passing => "passing",
+        });
+    let mut observed = OBSERVED.lock();
+    observed.machines.insert(
+        app_name.to_string(),
+        Seen::now(count_by(
+            machines.iter().map(|machine| machine.state.as_str()),
+        )),
+    );
+    observed
+        .checks
+        .insert(app_name.to_string(), Seen::now(count_by(checks)));
+}
+
+pub fn observe_volumes<'a>(app_name: &str, states: impl Iterator<Item = &'a str>, orphaned: usize) {
+    let mut observed = OBSERVED.lock();
+    observed
+        .volumes
+        .insert(app_name.to_string(), Seen::now(count_by(states)));
+    observed
+        .orphaned_volumes
+        .insert(app_name.to_string(), Seen::now(orphaned));
+}
+
+/// Prometheus text exposition of the observed state, the apps that weren't polled lately are left
+/// out rather than exported with their last state.
+fn render() -> String {
+    let mut observed = OBSERVED.lock();
+    observed.evict_stale();
+    let mut output = String::new();
+    let mut write_gauge =
+        |name: &str,
+         help: &str,
+         label: &str,
+         counts: &BTreeMap<String, Seen<BTreeMap<String, usize>>>| {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} gauge");
+            for (app_name, counts) in counts {
+                for (value, count) in &counts.value {
+                    let _ = writeln!(
+                        output,
+                        "{name}{{app=\"{}\",{label}=\"{}\"}} {count}",
+                        escape(app_name),
+                        escape(value)
+                    );
+                }
+            }
+        };
+    write_gauge(
+        "flyradar_machines",
+        "Machines of the app by state.",
+        "state",
+        &observed.machines,
+    );
+    write_gauge(
+        "flyradar_machine_checks",
+        "Health checks of the app's machines by status.",
+        "status",
+        &observed.checks,
+    );
+    write_gauge(
+        "flyradar_volumes",
+        "Volumes of the app by state.",
+        "state",
+        &observed.volumes,
+    );
+    let _ = writeln!(
+        output,
+        "# HELP flyradar_orphaned_volumes Volumes of the app that are unattached or whose machine is gone."
+    );
+    let _ = writeln!(output, "# TYPE flyradar_orphaned_volumes gauge");
+    for (app_name, count) in &observed.orphaned_volumes {
+        let _ = writeln!(
+            output,
+            "flyradar_orphaned_volumes{{app=\"{}\"}} {}",
+            escape(app_name),
+            count.value
+        );
+    }
+    output
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves the observed state on `/metrics`. Only the open views and the watches are polled, so
+/// the gauges cover the apps visited in the session and the watched ones.
+pub async fn serve(address: String) -> RdrResult<()> {
+    let listener = TcpListener::bind(&address).await?;
+    info!("Serving metrics on http://{}/metrics", address);
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let response = match stream.read(&mut buf).await {
+                Ok(n) if buf[..n].starts_with(b"GET /metrics ") => {
+                    let body = render();
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                }
+                Ok(_) => String::from(
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                ),
+                Err(err) => {
+                    error!("Reading the metrics request failed: {}", err);
+                    return;
+                }
+            };
+            if let Err(err) = stream.write_all(response.as_bytes()).await {
+                error!("Writing the metrics response failed: {}", err);
+            }
+        });
+    }
+}
@@ -1,39 +1,74 @@
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use apps::autoscaling::{AutoscalingChange, ScalingGroupKey};
 use apps::restart::AppRestartParams;
+use apps::services::ServiceEdit;
+use cache::ResponseCache;
+use hooks::{run_hooks, HookContext};
 use logs::LogsResources;
 use machines::kill::KillMachineInput;
+use machines::scale::VmPreset;
+use parking_lot::RwLock;
 use reqwest::Client;
+use secrets::SecretChange;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
+use tracing::error;
 
-use crate::config::{FullConfig, DEFAULT_API_BASE_URL, DEFAULT_FLAPS_BASE_URL};
+use crate::auth::{self, rotate_access_token};
+use crate::config::{
+    FullConfig, HookStage, RdrConfig, DEFAULT_API_BASE_URL, DEFAULT_FLAPS_BASE_URL,
+};
 use crate::fly_rust::machine_types::{RemoveMachineInput, RestartMachineInput, StopMachineInput};
 use crate::fly_rust::request_builder::{
-    RequestBuilderFly, RequestBuilderGraphql, RequestBuilderMachines, {self},
+    RequestBuilderFly, RequestBuilderGraphql, RequestBuilderMachines, SharedAccessToken, {self},
 };
 use crate::fly_rust::resource_organizations::OrganizationFilter;
-use crate::fly_rust::volume_types::RemoveVolumeInput;
-use crate::logs::LogOptions;
+use crate::fly_rust::volume_types::{CreateVolumeInput, ForkVolumeInput, RemoveVolumeInput};
+use crate::logs::{LogOptions, LogTransport};
 use crate::state::PopupType;
+use crate::transformations::ListRegion;
 use crate::widgets::log_viewer::dump_logs;
 
 pub mod apps;
+mod cache;
+pub mod certificates;
+pub mod export;
+pub mod extensions;
+pub mod hooks;
+pub mod hosts;
 mod lease;
 pub mod logs;
 pub mod machines;
+pub mod metrics;
 pub mod organizations;
+pub mod pause;
+pub mod peers;
+pub mod plugins;
+pub mod preflight;
+pub mod regions;
 pub mod secrets;
 pub mod select_many_machines;
 pub mod volumes;
 mod wait;
+pub mod watches;
 
 #[derive(Debug)]
 pub enum IoReqEvent {
+    Preflight,
+    Watch,
+    /// Drops the cached responses before refetching, in the same task so the refetch can't be
+    /// answered from the cache.
+    ClearCache {
+        refetch: Option<Box<IoReqEvent>>,
+    },
+    Login,
+    Logout,
     ListOrganizations {
         seq_id: u64,
         filter: OrganizationFilter,
@@ -51,6 +86,17 @@ pub enum IoReqEvent {
         org_slug: String,
         email: String,
     },
+    SwitchOrganization {
+        org_slug: String,
+        list_apps: bool,
+    },
+    ListOrganizationSlugs {
+        filter: OrganizationFilter,
+    },
+    ListRegions,
+    JumpToMachine {
+        machine_id: String,
+    },
     ListApps {
         seq_id: u64,
         org_slug: String,
@@ -64,9 +110,97 @@ pub enum IoReqEvent {
     ViewAppReleases {
         app_name: String,
     },
+    DiffAppReleases {
+        app_name: String,
+        from: i64,
+        to: i64,
+    },
+    RollbackRelease {
+        app_name: String,
+        version: i64,
+        image_ref: String,
+    },
+    CreateDeployToken {
+        app_name: String,
+        name: String,
+        expiry_days: u32,
+    },
     ViewAppServices {
         app_name: String,
     },
+    UpdateAppServices {
+        app_name: String,
+        edit: ServiceEdit,
+    },
+    ViewAppEnv {
+        app_name: String,
+    },
+    ViewAppPorts {
+        app_name: String,
+    },
+    ViewAppAutoscaling {
+        app_name: String,
+    },
+    ViewAppCleanup {
+        app_name: String,
+    },
+    CleanupApp {
+        app_name: String,
+        machines: Vec<String>,
+        volumes: Vec<String>,
+    },
+    UpdateAppAutoscaling {
+        app_name: String,
+        changes: BTreeMap<ScalingGroupKey, AutoscalingChange>,
+    },
+    ListExtensions {
+        seq_id: u64,
+        org_slug: String,
+    },
+    OpenExtension {
+        name: String,
+        provider: String,
+    },
+    ListDedicatedHosts {
+        seq_id: u64,
+        org_slug: String,
+    },
+    ListWireGuardPeers {
+        seq_id: u64,
+        org_slug: String,
+    },
+    CreateWireGuardPeer {
+        seq_id: u64,
+        org_id: String,
+        org_slug: String,
+        name: String,
+        region: String,
+        public_key: String,
+    },
+    RemoveWireGuardPeer {
+        seq_id: u64,
+        org_id: String,
+        org_slug: String,
+        name: String,
+    },
+    ListProcesses {
+        seq_id: u64,
+        app_name: String,
+        machine_id: String,
+    },
+    ViewDedicatedHostMachines {
+        org_slug: String,
+        host_id: String,
+    },
+    ListPlugin {
+        seq_id: u64,
+        name: String,
+    },
+    RunPluginAction {
+        name: String,
+        key: char,
+        row: Vec<String>,
+    },
     RestartApp {
         seq_id: u64,
         app_name: String,
@@ -78,10 +212,22 @@ pub enum IoReqEvent {
         app_name: String,
         org_slug: String,
     },
+    PreviewAppDestroy {
+        app_name: String,
+    },
     ListMachines {
         seq_id: u64,
         app_name: String,
     },
+    ViewMachineChecks {
+        app_name: String,
+        machine_id: String,
+    },
+    CapturePackets {
+        app_name: String,
+        machine_id: String,
+        file_path: PathBuf,
+    },
     RestartMachines {
         seq_id: u64,
         app_name: String,
@@ -104,6 +250,27 @@ pub enum IoReqEvent {
         app_name: String,
         params: KillMachineInput,
     },
+    AlignMachines {
+        app_name: String,
+        machine_id: String,
+    },
+    RenameMachine {
+        seq_id: u64,
+        app_name: String,
+        machine_id: String,
+        name: String,
+    },
+    ExecMachine {
+        app_name: String,
+        machine_id: String,
+        command: String,
+    },
+    ScaleMachine {
+        seq_id: u64,
+        app_name: String,
+        machine_id: String,
+        preset: VmPreset,
+    },
     SuspendMachines {
         seq_id: u64,
         app_name: String,
@@ -119,6 +286,19 @@ pub enum IoReqEvent {
         app_name: String,
         machines: Vec<String>,
     },
+    SetMachinesMetadata {
+        seq_id: u64,
+        app_name: String,
+        machines: Vec<String>,
+        key: String,
+        value: String,
+    },
+    UpdateMachinesImage {
+        seq_id: u64,
+        app_name: String,
+        machines: Vec<String>,
+        tag: String,
+    },
     UncordonMachines {
         seq_id: u64,
         app_name: String,
@@ -130,6 +310,16 @@ pub enum IoReqEvent {
     DumpLogs {
         file_path: PathBuf,
     },
+    ExportView {
+        table: String,
+        file_path: Option<PathBuf>,
+    },
+    ExportCapture {
+        file_path: PathBuf,
+    },
+    TeeLogs {
+        file_path: Option<PathBuf>,
+    },
     StopLogs,
     ListVolumes {
         seq_id: u64,
@@ -140,14 +330,39 @@ pub enum IoReqEvent {
         app_name: String,
         params: RemoveVolumeInput,
     },
+    SnapshotVolume {
+        app_name: String,
+        volume_id: String,
+    },
+    ForkVolume {
+        seq_id: u64,
+        app_name: String,
+        params: ForkVolumeInput,
+    },
+    CreateVolume {
+        seq_id: u64,
+        app_name: String,
+        params: CreateVolumeInput,
+    },
+    DetachVolume {
+        seq_id: u64,
+        app_name: String,
+        volume_id: String,
+        machine_id: String,
+    },
     ListSecrets {
         seq_id: u64,
         app_name: String,
     },
-    UnsetSecrets {
+    DeploySecrets {
+        seq_id: u64,
+        app_name: String,
+        /// Staged in flyradar, applied before the machines are updated
+        changes: Vec<SecretChange>,
+    },
+    ListCertificates {
         seq_id: u64,
         app_name: String,
-        keys: Vec<String>,
     },
 }
 
@@ -165,6 +380,16 @@ pub enum IoRespEvent {
         seq_id: u64,
         list: Vec<Vec<String>>,
     },
+    /// Recent events of the listed machines, sent after each list of the machines.
+    MachineEvents {
+        seq_id: u64,
+        list: Vec<String>,
+    },
+    /// Full objects of the listed machines by id, as the API returned them.
+    MachineDetails {
+        seq_id: u64,
+        list: HashMap<String, serde_json::Value>,
+    },
     Volumes {
         seq_id: u64,
         list: Vec<Vec<String>>,
@@ -173,19 +398,128 @@ pub enum IoRespEvent {
         seq_id: u64,
         list: Vec<Vec<String>>,
     },
+    Certificates {
+        seq_id: u64,
+        list: Vec<Vec<String>>,
+    },
+    Extensions {
+        seq_id: u64,
+        list: Vec<Vec<String>>,
+    },
+    DedicatedHosts {
+        seq_id: u64,
+        list: Vec<Vec<String>>,
+    },
+    WireGuardPeers {
+        seq_id: u64,
+        list: Vec<Vec<String>>,
+    },
+    Processes {
+        seq_id: u64,
+        list: Vec<Vec<String>>,
+    },
+    Plugin {
+        seq_id: u64,
+        name: String,
+        list: Vec<Vec<String>>,
+    },
+    Alerts {
+        list: Vec<String>,
+    },
+    /// The credentials are swapped after a login or logout.
+    SessionChanged {
+        logged_in: bool,
+    },
     OrganizationMembers {
         list: Vec<Vec<String>>,
     },
     AppReleases {
         list: Vec<Vec<String>>,
     },
+    AppReleasesDiff {
+        list: Vec<Vec<String>>,
+    },
     AppServices {
         list: Vec<Vec<String>>,
     },
+    AppEnv {
+        list: Vec<Vec<String>>,
+    },
+    AppPorts {
+        list: Vec<Vec<String>>,
+    },
+    AppAutoscaling {
+        list: Vec<Vec<String>>,
+    },
+    AppCleanup {
+        list: Vec<Vec<String>>,
+    },
+    DedicatedHostMachines {
+        list: Vec<Vec<String>>,
+    },
+    MachineChecks {
+        list: Vec<Vec<String>>,
+    },
+    /// Status of each machine of a rolling image update.
+    MachinesUpdateProgress {
+        list: Vec<Vec<String>>,
+    },
+    /// What goes with the app if it's destroyed, shown in the destroy popup.
+    AppDestroyPreview {
+        app_name: String,
+        preview: String,
+    },
+    /// A token that was just created, it can't be fetched again.
+    DeployToken {
+        app_name: String,
+        token: String,
+    },
+    /// Output lines of a command ran on a machine, tagged with their stream.
+    MachineExec {
+        machine_id: String,
+        command: String,
+        list: Vec<Vec<String>>,
+    },
+    SwitchOrganization {
+        org_id: String,
+        org_slug: String,
+        list_apps: bool,
+    },
+    OrganizationSlugs {
+        slugs: Vec<String>,
+    },
+    Regions {
+        regions: Vec<ListRegion>,
+    },
+    LogTee {
+        file_path: Option<PathBuf>,
+    },
+    SecretsDeployed {
+        app_name: String,
+        changes: Vec<SecretChange>,
+    },
+    LogTransport {
+        transport: LogTransport,
+    },
+    JumpToMachine {
+        org_id: String,
+        org_slug: String,
+        app_id: String,
+        app_name: String,
+        machine_id: String,
+    },
+    Viewer {
+        email: String,
+        scoped_org: Option<String>,
+    },
     SetPopup {
         popup_type: PopupType,
         message: String,
     },
+    /// Written to the terminal by the main loop, between the frames
+    CopyToClipboard {
+        text: String,
+    },
 }
 
 #[derive(Clone)]
@@ -193,14 +527,18 @@ pub struct Ops {
     pub request_builder_machines: RequestBuilderMachines,
     pub request_builder_graphql: RequestBuilderGraphql,
     request_builder_fly: RequestBuilderFly,
+    access_token: SharedAccessToken,
+    rdr_config: Arc<RdrConfig>,
     io_req_tx: Sender<IoReqEvent>,
     io_resp_tx: Sender<IoRespEvent>,
     logs_resources: Arc<Mutex<LogsResources>>,
+    cache: ResponseCache,
 }
 
 impl Ops {
     pub fn new(
         config: FullConfig,
+        rdr_config: Arc<RdrConfig>,
         io_req_tx: Sender<IoReqEvent>,
         io_resp_tx: Sender<IoRespEvent>,
     ) -> Self {
@@ -209,33 +547,49 @@ impl Ops {
             .pool_idle_timeout(Duration::from_secs(40))
             .build()
             .unwrap();
+        let access_token: SharedAccessToken =
+            Arc::new(RwLock::new(config.token_config.access_token));
+        tokio::spawn(rotate_access_token(access_token.clone()));
         Ops {
             request_builder_machines: request_builder::RequestBuilderMachines::new(
                 http_client.clone(),
                 DEFAULT_FLAPS_BASE_URL.to_string(),
-                config.token_config.access_token.clone(),
+                access_token.clone(),
             ),
             request_builder_graphql: request_builder::RequestBuilderGraphql::new(
                 http_client.clone(),
                 format!("{DEFAULT_API_BASE_URL}/graphql"),
-                config.token_config.access_token.clone(),
+                access_token.clone(),
             ),
             // Used only for polling vm logs
             request_builder_fly: request_builder::RequestBuilderFly::new(
                 http_client,
                 format!("{DEFAULT_API_BASE_URL}/api"),
-                config.token_config.access_token,
+                access_token.clone(),
             ),
+            access_token,
+            rdr_config,
             io_req_tx,
             io_resp_tx,
             logs_resources: Arc::new(Mutex::new(LogsResources {
                 cancellation_token_nats: CancellationToken::new(),
                 polling_handle: None,
                 nats: None,
+                tee: None,
             })),
+            cache: ResponseCache::default(),
         }
     }
 
+    /// Swaps the credentials of the ops and all their clones, like the token rotation does, so
+    /// the running tasks (e.g. the watches) carry on as the new user. What's cached or streamed for
+    /// the previous user is dropped.
+    async fn set_access_token(&mut self, access_token: String) {
+        *self.access_token.write() = access_token;
+        self.cache.clear();
+        self.cleanup_logs_resources().await;
+    }
+
     async fn cleanup_logs_resources(&mut self) {
         let (polling_handle, nats) = {
             let mut resources = self.logs_resources.lock().unwrap();
@@ -251,7 +605,110 @@ impl Ops {
     }
 
     pub async fn handle_io_req(&mut self, io_event: IoReqEvent) {
+        let hook_context = HookContext::from_event(&io_event);
+        if let Some(hook_context) = &hook_context {
+            if let Err(err) =
+                run_hooks(&self.rdr_config.hooks, hook_context, HookStage::Before).await
+            {
+                let _ = self
+                    .io_resp_tx
+                    .send(IoRespEvent::SetPopup {
+                        popup_type: PopupType::ErrorPopup,
+                        message: err.to_string(),
+                    })
+                    .await;
+                return;
+            }
+        }
+        self.handle_event(io_event).await;
+        //INFO: The operations report their own errors, so the after hooks run once the
+        //operation is attempted regardless of how it went.
+        if let Some(hook_context) = &hook_context {
+            if let Err(err) =
+                run_hooks(&self.rdr_config.hooks, hook_context, HookStage::After).await
+            {
+                let _ = self
+                    .io_resp_tx
+                    .send(IoRespEvent::SetPopup {
+                        popup_type: PopupType::ErrorPopup,
+                        message: err.to_string(),
+                    })
+                    .await;
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, io_event: IoReqEvent) {
         match io_event {
+            IoReqEvent::Preflight => {
+                if let Err(err) = preflight::preflight(self).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::Watch => {
+                if let Err(err) = watches::watch(self).await {
+                    error!("Watching failed: {}", err);
+                }
+            }
+            IoReqEvent::ClearCache { refetch } => {
+                self.cache.clear();
+                if let Some(refetch) = refetch {
+                    Box::pin(self.handle_event(*refetch)).await;
+                }
+            }
+            IoReqEvent::Login => {
+                let _ = self
+                    .io_resp_tx
+                    .send(IoRespEvent::SetPopup {
+                        popup_type: PopupType::InfoPopup,
+                        message: String::from(
+                            "Complete the login in the browser flyctl opened for you...",
+                        ),
+                    })
+                    .await;
+                match auth::login().await {
+                    Ok(access_token) => {
+                        self.set_access_token(access_token).await;
+                        let _ = self
+                            .io_resp_tx
+                            .send(IoRespEvent::SessionChanged { logged_in: true })
+                            .await;
+                    }
+                    Err(err) => {
+                        let _ = self
+                            .io_resp_tx
+                            .send(IoRespEvent::SetPopup {
+                                popup_type: PopupType::ErrorPopup,
+                                message: err.to_string(),
+                            })
+                            .await;
+                    }
+                }
+            }
+            IoReqEvent::Logout => match auth::logout().await {
+                Ok(()) => {
+                    self.set_access_token(String::new()).await;
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SessionChanged { logged_in: false })
+                        .await;
+                }
+                Err(err) => {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            },
             IoReqEvent::ListOrganizations { seq_id, filter } => {
                 if let Err(err) = organizations::list::list(self, seq_id, filter).await {
                     let _ = self
@@ -268,7 +725,347 @@ impl Ops {
                 filter,
                 org_id,
             } => {
-                if let Err(err) = organizations::delete::delete(self, org_id).await {
+                if let Err(err) = organizations::delete::delete(self, org_id).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                } else {
+                    self.cache.organizations.clear();
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListOrganizations {
+                            seq_id: seq_id + 1,
+                            filter,
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::CreateOrganizationInvite { org_id, email } => {
+                if let Err(err) = organizations::invite::invite(self, org_id, email).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::DeleteOrganizationMembership {
+                org_slug: org_id,
+                email,
+            } => {
+                if let Err(err) = organizations::remove::remove(self, org_id, email).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::JumpToMachine { machine_id } => {
+                if let Err(err) = machines::find::find(self, machine_id).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::SwitchOrganization {
+                org_slug,
+                list_apps,
+            } => {
+                if let Err(err) = organizations::switch::switch(self, org_slug, list_apps).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ListOrganizationSlugs { filter } => {
+                //INFO: Completing is best effort, a failure shouldn't interrupt typing the command
+                if let Err(err) = organizations::list::slugs(self, filter).await {
+                    error!("Listing the organization slugs failed: {}", err);
+                }
+            }
+            IoReqEvent::ListRegions => {
+                //INFO: The region can still be typed without the picker
+                if let Err(err) = regions::list(self).await {
+                    error!("Listing the regions failed: {}", err);
+                }
+            }
+            IoReqEvent::ListApps { seq_id, org_slug } => {
+                if let Err(err) = apps::list::list(self, seq_id, org_slug).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::OpenApp { app_name } => {
+                if let Err(err) = apps::open::open(self, app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewOrganizationMembers { org_slug } => {
+                if let Err(err) = organizations::members::members(self, org_slug).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewAppReleases { app_name } => {
+                if let Err(err) =
+                    apps::releases::releases(self, app_name, apps::releases::RELEASES_LIMIT).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::DiffAppReleases { app_name, from, to } => {
+                if let Err(err) = apps::releases::diff(self, app_name, from, to).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::RollbackRelease {
+                app_name,
+                image_ref,
+                ..
+            } => {
+                if let Err(err) = apps::releases::rollback(self, &app_name, &image_ref).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::CreateDeployToken {
+                app_name,
+                name,
+                expiry_days,
+            } => {
+                if let Err(err) =
+                    apps::tokens::create_deploy(self, &app_name, &name, expiry_days).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewAppServices { app_name } => {
+                if let Err(err) = apps::services::services(self, app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewAppEnv { app_name } => {
+                if let Err(err) = apps::env::env(self, app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewAppAutoscaling { app_name } => {
+                if let Err(err) = apps::autoscaling::autoscaling(self, app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::UpdateAppAutoscaling { app_name, changes } => {
+                if let Err(err) = apps::autoscaling::update(self, app_name, changes).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::UpdateAppServices { app_name, edit } => {
+                if let Err(err) = apps::services::update(self, app_name, edit).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewAppCleanup { app_name } => {
+                if let Err(err) = apps::cleanup::report(self, app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::CleanupApp {
+                app_name,
+                machines,
+                volumes,
+            } => {
+                if let Err(err) = apps::cleanup::cleanup(self, app_name, machines, volumes).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ViewAppPorts { app_name } => {
+                if let Err(err) = apps::ports::ports(self, app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ListExtensions { seq_id, org_slug } => {
+                if let Err(err) = extensions::list::list(self, seq_id, org_slug).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::OpenExtension { name, provider } => {
+                if let Err(err) = extensions::open::open(self, name, provider).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ListDedicatedHosts { seq_id, org_slug } => {
+                if let Err(err) = hosts::list::list(self, seq_id, org_slug).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ListWireGuardPeers { seq_id, org_slug } => {
+                if let Err(err) = peers::list::list(self, seq_id, org_slug).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::CreateWireGuardPeer {
+                seq_id,
+                org_id,
+                org_slug,
+                name,
+                region,
+                public_key,
+            } => {
+                if let Err(err) =
+                    peers::create::create(self, &org_id, &name, &region, &public_key).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                } else {
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListWireGuardPeers {
+                            seq_id: seq_id + 1,
+                            org_slug,
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::RemoveWireGuardPeer {
+                seq_id,
+                org_id,
+                org_slug,
+                name,
+            } => {
+                if let Err(err) = peers::remove::remove(self, &org_id, &name).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -279,29 +1076,21 @@ impl Ops {
                 } else {
                     let _ = self
                         .io_req_tx
-                        .send(IoReqEvent::ListOrganizations {
+                        .send(IoReqEvent::ListWireGuardPeers {
                             seq_id: seq_id + 1,
-                            filter,
-                        })
-                        .await;
-                }
-            }
-            IoReqEvent::CreateOrganizationInvite { org_id, email } => {
-                if let Err(err) = organizations::invite::invite(self, org_id, email).await {
-                    let _ = self
-                        .io_resp_tx
-                        .send(IoRespEvent::SetPopup {
-                            popup_type: PopupType::ErrorPopup,
-                            message: err.to_string(),
+                            org_slug,
                         })
                         .await;
                 }
             }
-            IoReqEvent::DeleteOrganizationMembership {
-                org_slug: org_id,
-                email,
+            IoReqEvent::ListProcesses {
+                seq_id,
+                app_name,
+                machine_id,
             } => {
-                if let Err(err) = organizations::remove::remove(self, org_id, email).await {
+                if let Err(err) =
+                    machines::processes::list(self, seq_id, app_name, machine_id).await
+                {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -311,8 +1100,11 @@ impl Ops {
                         .await;
                 }
             }
-            IoReqEvent::ListApps { seq_id, org_slug } => {
-                if let Err(err) = apps::list::list(self, seq_id, org_slug).await {
+            IoReqEvent::ViewMachineChecks {
+                app_name,
+                machine_id,
+            } => {
+                if let Err(err) = machines::checks::checks(self, app_name, machine_id).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -322,8 +1114,14 @@ impl Ops {
                         .await;
                 }
             }
-            IoReqEvent::OpenApp { app_name } => {
-                if let Err(err) = apps::open::open(self, app_name).await {
+            IoReqEvent::CapturePackets {
+                app_name,
+                machine_id,
+                file_path,
+            } => {
+                if let Err(err) =
+                    machines::capture::capture(self, app_name, machine_id, file_path).await
+                {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -333,8 +1131,8 @@ impl Ops {
                         .await;
                 }
             }
-            IoReqEvent::ViewOrganizationMembers { org_slug } => {
-                if let Err(err) = organizations::members::members(self, org_slug).await {
+            IoReqEvent::ViewDedicatedHostMachines { org_slug, host_id } => {
+                if let Err(err) = hosts::machines::machines(self, org_slug, host_id).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -344,8 +1142,8 @@ impl Ops {
                         .await;
                 }
             }
-            IoReqEvent::ViewAppReleases { app_name } => {
-                if let Err(err) = apps::releases::releases(self, app_name, 25).await {
+            IoReqEvent::ListPlugin { seq_id, name } => {
+                if let Err(err) = plugins::list(self, seq_id, name).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -355,8 +1153,8 @@ impl Ops {
                         .await;
                 }
             }
-            IoReqEvent::ViewAppServices { app_name } => {
-                if let Err(err) = apps::services::services(self, app_name).await {
+            IoReqEvent::RunPluginAction { name, key, row } => {
+                if let Err(err) = plugins::run_action(self, name, key, row).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -413,6 +1211,18 @@ impl Ops {
                         .await;
                 }
             }
+            IoReqEvent::PreviewAppDestroy { app_name } => {
+                //INFO: Errors are shown in the destroy popup, an error popup would replace it
+                let preview = apps::destroy::preview(self, &app_name)
+                    .await
+                    .unwrap_or_else(|err| {
+                        format!("Could not check what goes with the app: {}", err)
+                    });
+                let _ = self
+                    .io_resp_tx
+                    .send(IoRespEvent::AppDestroyPreview { app_name, preview })
+                    .await;
+            }
             IoReqEvent::ListMachines { seq_id, app_name } => {
                 if let Err(err) = machines::list::list(self, seq_id, &app_name).await {
                     let _ = self
@@ -497,6 +1307,46 @@ impl Ops {
                         .await;
                 }
             }
+            IoReqEvent::AlignMachines {
+                app_name,
+                machine_id,
+            } => {
+                if let Err(err) = machines::drift::align(self, &app_name, &machine_id).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::RenameMachine {
+                seq_id,
+                app_name,
+                machine_id,
+                name,
+            } => {
+                if let Err(err) =
+                    machines::rename::rename(self, &app_name, &machine_id, &name).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                } else {
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListMachines {
+                            seq_id: seq_id + 1,
+                            app_name,
+                        })
+                        .await;
+                }
+            }
             IoReqEvent::KillMachine {
                 seq_id,
                 app_name,
@@ -527,6 +1377,47 @@ impl Ops {
                         .await;
                 }
             }
+            IoReqEvent::ExecMachine {
+                app_name,
+                machine_id,
+                command,
+            } => {
+                if let Err(err) = machines::exec::exec(self, &app_name, &machine_id, command).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ScaleMachine {
+                seq_id,
+                app_name,
+                machine_id,
+                preset,
+            } => {
+                if let Err(err) = machines::scale::scale(self, &app_name, &machine_id, preset).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                } else {
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListMachines {
+                            seq_id: seq_id + 1,
+                            app_name,
+                        })
+                        .await;
+                }
+            }
             IoReqEvent::SuspendMachines {
                 seq_id,
                 app_name,
@@ -573,6 +1464,68 @@ impl Ops {
                         .await;
                 }
             }
+            IoReqEvent::SetMachinesMetadata {
+                seq_id,
+                app_name,
+                machines,
+                key,
+                value,
+            } => {
+                if let Err(err) =
+                    machines::metadata::set_metadata(self, &app_name, machines, &key, &value).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                } else {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::InfoPopup,
+                            message: format!(
+                                "Successfully updated the metadata of the selected machines for {}.",
+                                app_name
+                            ),
+                        })
+                        .await;
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListMachines {
+                            seq_id: seq_id + 1,
+                            app_name,
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::UpdateMachinesImage {
+                seq_id,
+                app_name,
+                machines,
+                tag,
+            } => {
+                if let Err(err) =
+                    machines::image::update_image(self, &app_name, machines, &tag).await
+                {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+                let _ = self
+                    .io_req_tx
+                    .send(IoReqEvent::ListMachines {
+                        seq_id: seq_id + 1,
+                        app_name,
+                    })
+                    .await;
+            }
             IoReqEvent::CordonMachines {
                 seq_id,
                 app_name,
@@ -657,7 +1610,7 @@ impl Ops {
                 }
             }
             IoReqEvent::DumpLogs { file_path } => {
-                if let Err(err) = dump_logs(&file_path).await {
+                if let Err(err) = dump_logs(&file_path, logs::dump_format(&file_path)).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -678,12 +1631,58 @@ impl Ops {
                         .await;
                 }
             }
+            IoReqEvent::ExportView { table, file_path } => {
+                if let Err(err) = export::export(self, table, file_path).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ExportCapture { file_path } => {
+                if let Err(err) = export::export_capture(self, file_path).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::TeeLogs { file_path } => {
+                //INFO: Opened here first so a bad path fails in the popup, not in the stream loop
+                if let Some(path) = &file_path {
+                    if let Err(err) = logs::open_append(path).await {
+                        let _ = self
+                            .io_resp_tx
+                            .send(IoRespEvent::SetPopup {
+                                popup_type: PopupType::ErrorPopup,
+                                message: format!(
+                                    "Could not open {}: {}",
+                                    path.to_string_lossy(),
+                                    err
+                                ),
+                            })
+                            .await;
+                        return;
+                    }
+                }
+                self.logs_resources.lock().unwrap().tee = file_path.clone();
+                let _ = self
+                    .io_resp_tx
+                    .send(IoRespEvent::LogTee { file_path })
+                    .await;
+            }
             IoReqEvent::StopLogs => {
-                self.logs_resources
-                    .lock()
-                    .unwrap()
-                    .cancellation_token_nats
-                    .cancel();
+                {
+                    let mut resources = self.logs_resources.lock().unwrap();
+                    resources.cancellation_token_nats.cancel();
+                    resources.tee = None;
+                }
                 self.cleanup_logs_resources().await;
             }
             IoReqEvent::ListVolumes { seq_id, app_name } => {
@@ -720,8 +1719,26 @@ impl Ops {
                         .await;
                 }
             }
-            IoReqEvent::ListSecrets { seq_id, app_name } => {
-                if let Err(err) = secrets::list::list(self, seq_id, &app_name).await {
+            IoReqEvent::SnapshotVolume {
+                app_name,
+                volume_id,
+            } => {
+                if let Err(err) = volumes::snapshot::snapshot(self, &app_name, &volume_id).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ForkVolume {
+                seq_id,
+                app_name,
+                params,
+            } => {
+                if let Err(err) = volumes::fork::fork(self, &app_name, params).await {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -729,14 +1746,48 @@ impl Ops {
                             message: err.to_string(),
                         })
                         .await;
+                } else {
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListVolumes {
+                            seq_id: seq_id + 1,
+                            app_name,
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::CreateVolume {
+                seq_id,
+                app_name,
+                params,
+            } => {
+                if let Err(err) = volumes::create::create(self, &app_name, params).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                } else {
+                    let _ = self
+                        .io_req_tx
+                        .send(IoReqEvent::ListVolumes {
+                            seq_id: seq_id + 1,
+                            app_name,
+                        })
+                        .await;
                 }
             }
-            IoReqEvent::UnsetSecrets {
+            IoReqEvent::DetachVolume {
                 seq_id,
                 app_name,
-                keys,
+                volume_id,
+                machine_id,
             } => {
-                if let Err(err) = secrets::unset::unset(self, &app_name, keys).await {
+                if let Err(err) =
+                    volumes::detach::detach(self, &app_name, &volume_id, &machine_id).await
+                {
                     let _ = self
                         .io_resp_tx
                         .send(IoRespEvent::SetPopup {
@@ -747,13 +1798,50 @@ impl Ops {
                 } else {
                     let _ = self
                         .io_req_tx
-                        .send(IoReqEvent::ListSecrets {
+                        .send(IoReqEvent::ListVolumes {
                             seq_id: seq_id + 1,
                             app_name,
                         })
                         .await;
                 }
             }
+            IoReqEvent::ListSecrets { seq_id, app_name } => {
+                if let Err(err) = secrets::list::list(self, seq_id, &app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::DeploySecrets {
+                seq_id,
+                app_name,
+                changes,
+            } => {
+                if let Err(err) = secrets::deploy::deploy(self, seq_id, &app_name, changes).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
+            IoReqEvent::ListCertificates { seq_id, app_name } => {
+                if let Err(err) = certificates::list::list(self, seq_id, &app_name).await {
+                    let _ = self
+                        .io_resp_tx
+                        .send(IoRespEvent::SetPopup {
+                            popup_type: PopupType::ErrorPopup,
+                            message: err.to_string(),
+                        })
+                        .await;
+                }
+            }
         }
     }
 
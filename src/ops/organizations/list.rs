@@ -1,16 +1,42 @@
 use crate::fly_rust::resource_organizations::{get_all_organizations, OrganizationFilter};
 use crate::ops::{IoRespEvent, Ops};
 use crate::state::RdrResult;
-use crate::transformations::ResourceList;
+use crate::transformations::{ListOrganization, ResourceList};
+
+async fn cached_list(ops: &Ops, filter: OrganizationFilter) -> RdrResult<Vec<Vec<String>>> {
+    let list = match ops.cache.organizations.get(&filter) {
+        Some(list) => list,
+        None => {
+            let list = get_all_organizations(&ops.request_builder_graphql, filter.clone())
+                .await?
+                .transform();
+            ops.cache.organizations.insert(filter, list.clone());
+            list
+        }
+    };
+    Ok(list)
+}
 
 pub async fn list(ops: &Ops, seq_id: u64, filter: OrganizationFilter) -> RdrResult<()> {
-    let organizations = get_all_organizations(&ops.request_builder_graphql, filter).await?;
+    let list = cached_list(ops, filter).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Organizations { seq_id, list })
+        .await?;
+
+    Ok(())
+}
+
+/// Sends the slugs of the orgs to complete the commands with, sharing the cache of the list.
+pub async fn slugs(ops: &Ops, filter: OrganizationFilter) -> RdrResult<()> {
+    let slugs = cached_list(ops, filter)
+        .await?
+        .into_iter()
+        .map(|row| ListOrganization::from(row).slug)
+        .collect();
 
     ops.io_resp_tx
-        .send(IoRespEvent::Organizations {
-            seq_id,
-            list: organizations.transform(),
-        })
+        .send(IoRespEvent::OrganizationSlugs { slugs })
         .await?;
 
     Ok(())
@@ -3,7 +3,15 @@ use crate::ops::{IoRespEvent, Ops};
 use crate::state::RdrResult;
 
 pub async fn members(ops: &Ops, org_slug: String) -> RdrResult<()> {
-    let org = get_detailed_organization_by_slug(&ops.request_builder_graphql, org_slug).await?;
+    if let Some(members) = ops.cache.organization_members.get(&org_slug) {
+        ops.io_resp_tx
+            .send(IoRespEvent::OrganizationMembers { list: members })
+            .await?;
+        return Ok(());
+    }
+
+    let org =
+        get_detailed_organization_by_slug(&ops.request_builder_graphql, org_slug.clone()).await?;
 
     if let Some(org) = org {
         let members = org
@@ -13,6 +21,9 @@ pub async fn members(ops: &Ops, org_slug: String) -> RdrResult<()> {
             .into_iter()
             .map(|edge| vec![edge.node.name, edge.node.email, edge.role])
             .collect::<Vec<Vec<String>>>();
+        ops.cache
+            .organization_members
+            .insert(org_slug, members.clone());
         ops.io_resp_tx
             .send(IoRespEvent::OrganizationMembers { list: members })
             .await?;
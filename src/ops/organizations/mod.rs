@@ -3,3 +3,4 @@ pub mod invite;
 pub mod list;
 pub mod members;
 pub mod remove;
+pub mod switch;
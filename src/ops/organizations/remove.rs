@@ -22,6 +22,7 @@ pub async fn remove(ops: &Ops, org_slug: String, email: String) -> RdrResult<()>
             Some(user_id) => {
                 delete_organization_membership(&ops.request_builder_graphql, org_id, user_id)
                     .await?;
+                ops.cache.organization_members.clear();
                 ops.io_resp_tx
                     .send(IoRespEvent::SetPopup {
                         popup_type: PopupType::InfoPopup,
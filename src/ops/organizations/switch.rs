@@ -0,0 +1,21 @@
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::resource_organizations::get_detailed_organization_by_slug;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+pub async fn switch(ops: &Ops, org_slug: String, list_apps: bool) -> RdrResult<()> {
+    let org = get_detailed_organization_by_slug(&ops.request_builder_graphql, org_slug.clone())
+        .await?
+        .ok_or_else(|| eyre!("Organization not found: {}", org_slug))?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SwitchOrganization {
+            org_id: org.organizationdetails.id,
+            org_slug: org.organizationdetails.slug,
+            list_apps,
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use lazy_static::lazy_static;
+use tokio::sync::watch;
+
+lazy_static! {
+    static ref PAUSED: watch::Sender<bool> = watch::Sender::new(false);
+}
+
+/// Pauses the polls hitting the API, while the terminal is unfocused or flyradar is suspended.
+pub fn set_paused(paused: bool) {
+    PAUSED.send_replace(paused);
+}
+
+pub fn is_paused() -> bool {
+    *PAUSED.borrow()
+}
+
+/// Waits until the polls are resumed, returns right away if they aren't paused.
+pub async fn resumed() {
+    let _ = PAUSED.subscribe().wait_for(|paused| !paused).await;
+}
+
+/// Waits until the polls are paused, returns right away if they are.
+pub async fn paused() {
+    let _ = PAUSED.subscribe().wait_for(|paused| *paused).await;
+}
@@ -0,0 +1,54 @@
+use std::net::Ipv6Addr;
+
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::resource_wireguard::add_wire_guard_peer;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+const WIREGUARD_PORT: u16 = 51820;
+
+/// Network of the org the peer can reach and its DNS server, both derived from the /48 prefix of
+/// the peer's IP like flyctl does.
+fn org_network(peer_ip: &str) -> RdrResult<(Ipv6Addr, Ipv6Addr)> {
+    let segments = peer_ip.parse::<Ipv6Addr>()?.segments();
+    let network = Ipv6Addr::new(segments[0], segments[1], segments[2], 0, 0, 0, 0, 0);
+    let dns = Ipv6Addr::new(segments[0], segments[1], segments[2], 0, 0, 0, 0, 3);
+    Ok((network, dns))
+}
+
+/// Adds a peer with the given public key to the org, like `fly wireguard create`. The private key
+/// never leaves the user's machine so the returned config has a placeholder for it.
+pub async fn create(
+    ops: &Ops,
+    org_id: &str,
+    name: &str,
+    region: &str,
+    public_key: &str,
+) -> RdrResult<()> {
+    let peer = add_wire_guard_peer(
+        &ops.request_builder_graphql,
+        org_id.to_string(),
+        region.to_string(),
+        name.to_string(),
+        public_key.to_string(),
+    )
+    .await?
+    .ok_or_else(|| eyre!("No peer was returned for {}.", name))?
+    .add_wire_guard_peer;
+    let (network, dns) = org_network(&peer.peerip)?;
+    let config = format!(
+        "[Interface]\nPrivateKey = <private key of {}>\nAddress = {}/120\nDNS = {}\n\n[Peer]\nPublicKey = {}\nAllowedIPs = {}/48\nEndpoint = {}:{}\nPersistentKeepalive = 15",
+        public_key, peer.peerip, dns, peer.pubkey, network, peer.endpointip, WIREGUARD_PORT
+    );
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Added the peer {} in {}. Its WireGuard config:\n\n{}",
+                name, region, config
+            ),
+        })
+        .await?;
+    Ok(())
+}
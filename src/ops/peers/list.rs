@@ -0,0 +1,18 @@
+use crate::fly_rust::resource_wireguard::get_all_organization_wire_guard_peers;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+use crate::transformations::ResourceList;
+
+pub async fn list(ops: &Ops, seq_id: u64, org_slug: String) -> RdrResult<()> {
+    let peers =
+        get_all_organization_wire_guard_peers(&ops.request_builder_graphql, org_slug).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::WireGuardPeers {
+            seq_id,
+            list: peers.transform(),
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::fly_rust::resource_wireguard::remove_wire_guard_peer;
+use crate::ops::Ops;
+use crate::state::RdrResult;
+
+pub async fn remove(ops: &Ops, org_id: &str, name: &str) -> RdrResult<()> {
+    remove_wire_guard_peer(
+        &ops.request_builder_graphql,
+        org_id.to_string(),
+        name.to_string(),
+    )
+    .await?;
+    Ok(())
+}
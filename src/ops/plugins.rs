@@ -0,0 +1,35 @@
+use color_eyre::eyre::OptionExt;
+
+use crate::ops::{IoRespEvent, Ops};
+use crate::plugins::{self, Plugin};
+use crate::state::{PopupType, RdrResult};
+
+fn get_plugin(name: &str) -> RdrResult<&'static dyn Plugin> {
+    plugins::get(name).ok_or_eyre("Plugin not found.")
+}
+
+pub async fn list(ops: &Ops, seq_id: u64, name: String) -> RdrResult<()> {
+    let plugin = get_plugin(&name)?;
+    let list = tokio::task::spawn_blocking(move || plugin.list()).await??;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Plugin { seq_id, name, list })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn run_action(ops: &Ops, name: String, key: char, row: Vec<String>) -> RdrResult<()> {
+    let plugin = get_plugin(&name)?;
+    let action = plugin.action(key).ok_or_eyre("Action not found.")?;
+    let message = tokio::task::spawn_blocking(move || plugin.run_action(action, &row)).await??;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message,
+        })
+        .await?;
+
+    Ok(())
+}
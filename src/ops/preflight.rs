@@ -0,0 +1,29 @@
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::resource_viewer::get_viewer;
+use crate::fly_rust::resource_viewer::get_viewer::GetViewerViewerOn;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+/// Resolves who the access token belongs to. Tokens that aren't issued to a user (e.g. org
+/// tokens) only see their own org, which is reported back as the scoped org.
+pub async fn preflight(ops: &Ops) -> RdrResult<()> {
+    let viewer = get_viewer(&ops.request_builder_graphql)
+        .await?
+        .ok_or_else(|| eyre!("Could not resolve the identity of the access token."))?;
+
+    let is_user_token = matches!(viewer.viewer.on, GetViewerViewerOn::User);
+    let scoped_org = match viewer.organizations.nodes.as_slice() {
+        [org] if !is_user_token => Some(org.slug.clone()),
+        _ => None,
+    };
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Viewer {
+            email: viewer.viewer.email,
+            scoped_org,
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::fly_rust::resource_regions::get_all_platform_regions;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+/// Sends the regions of the platform to pick from, they only change with new regions so they're
+/// listed once per session.
+pub async fn list(ops: &Ops) -> RdrResult<()> {
+    let regions = get_all_platform_regions(&ops.request_builder_graphql).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::Regions { regions })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::machine_types::MACHINE_STATE_STARTED;
+use crate::fly_rust::machines::{get_machine, get_machine_config, update_machine};
+use crate::ops::lease::{acquire_leases, list_active_machines, ReleaseGuard};
+use crate::ops::secrets::{set, split_changes, unset, SecretChange};
+use crate::ops::wait::{wait_for_health_checks, wait_for_start_or_stop};
+use crate::ops::{IoReqEvent, IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+const DEPLOY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Applies the staged secrets the way `fly secrets deploy` does for the machines apps, the
+/// machines are updated one by one with their own config so they pick up the latest secrets, each
+/// started one passing its health checks before the next one is updated. The changes pending in
+/// flyradar are staged on Fly.io first.
+pub async fn deploy(
+    ops: &Ops,
+    seq_id: u64,
+    app_name: &str,
+    changes: Vec<SecretChange>,
+) -> RdrResult<()> {
+    if !changes.is_empty() {
+        let (secrets, keys) = split_changes(changes.clone());
+        if !secrets.is_empty() {
+            set::set(ops, app_name, secrets).await?;
+        }
+        if !keys.is_empty() {
+            unset::unset(ops, app_name, keys).await?;
+        }
+        ops.io_resp_tx
+            .send(IoRespEvent::SecretsDeployed {
+                app_name: app_name.to_string(),
+                changes,
+            })
+            .await?;
+        ops.io_req_tx
+            .send(IoReqEvent::ListSecrets {
+                seq_id: seq_id + 1,
+                app_name: app_name.to_string(),
+            })
+            .await?;
+    }
+
+    let machines = list_active_machines(&ops.request_builder_machines, app_name).await?;
+    if machines.is_empty() {
+        return Err(eyre!(
+            "{} has no machines to deploy the secrets to.",
+            app_name
+        ));
+    }
+
+    let (leases, errors, release) = acquire_leases(ops, app_name, machines).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "{} errors occurred:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("{}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    let total = leases.len();
+    let mut deployed = vec![];
+    for (i, lease) in leases.into_iter().enumerate() {
+        let (id, nonce, was_started) = {
+            let machine = lease.lock().unwrap();
+            (
+                machine.id.clone(),
+                machine.lease_nonce.clone(),
+                machine.state == MACHINE_STATE_STARTED,
+            )
+        };
+        let Some(nonce) = nonce else {
+            continue;
+        };
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!(
+                    "Deploying the secrets of {} ({}/{}): updating {}...",
+                    app_name,
+                    i + 1,
+                    total,
+                    id
+                ),
+            })
+            .await?;
+        let config = get_machine_config(&ops.request_builder_machines, app_name, &id).await?;
+        //INFO: Stopped machines are only updated, they stay stopped
+        update_machine(
+            &ops.request_builder_machines,
+            app_name,
+            &id,
+            None,
+            &config,
+            &nonce,
+            !was_started,
+        )
+        .await?;
+        if !was_started {
+            deployed.push(id);
+            continue;
+        }
+        // The new version is waited for, not the one the lease was taken on
+        let updated = get_machine(&ops.request_builder_machines, app_name, &id).await?;
+        wait_for_start_or_stop(
+            &ops.request_builder_machines,
+            app_name,
+            Arc::new(Mutex::new(updated)),
+            "start",
+            DEPLOY_TIMEOUT,
+        )
+        .await?;
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!(
+                    "Deploying the secrets of {} ({}/{}): waiting for the health checks of {}...",
+                    app_name,
+                    i + 1,
+                    total,
+                    id
+                ),
+            })
+            .await?;
+        wait_for_health_checks(&ops.request_builder_machines, app_name, &id, DEPLOY_TIMEOUT)
+            .await?;
+        deployed.push(id);
+    }
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Deployed the secrets of {} to {}.",
+                app_name,
+                deployed.join(", ")
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
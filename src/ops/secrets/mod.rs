@@ -1,2 +1,40 @@
+pub mod deploy;
 pub mod list;
+pub mod set;
 pub mod unset;
+
+/// A secret change staged in flyradar, it reaches Fly.io only when the secrets are deployed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretChange {
+    Set { key: String, value: String },
+    Unset { key: String },
+}
+
+impl SecretChange {
+    pub fn key(&self) -> &str {
+        match self {
+            SecretChange::Set { key, .. } | SecretChange::Unset { key } => key,
+        }
+    }
+
+    /// How the change is listed, without the value.
+    pub fn label(&self) -> String {
+        match self {
+            SecretChange::Set { key, .. } => format!("Set {}", key),
+            SecretChange::Unset { key } => format!("Unset {}", key),
+        }
+    }
+}
+
+/// Splits the changes into the secrets to set and the keys to unset.
+pub fn split_changes(changes: Vec<SecretChange>) -> (Vec<(String, String)>, Vec<String>) {
+    let mut secrets = vec![];
+    let mut keys = vec![];
+    for change in changes {
+        match change {
+            SecretChange::Set { key, value } => secrets.push((key, value)),
+            SecretChange::Unset { key } => keys.push(key),
+        }
+    }
+    (secrets, keys)
+}
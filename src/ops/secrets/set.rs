@@ -0,0 +1,8 @@
+use crate::fly_rust::resource_secrets::set_secrets;
+use crate::ops::Ops;
+use crate::state::RdrResult;
+
+pub async fn set(ops: &Ops, app_name: &str, secrets: Vec<(String, String)>) -> RdrResult<()> {
+    set_secrets(&ops.request_builder_graphql, app_name.to_string(), secrets).await?;
+    Ok(())
+}
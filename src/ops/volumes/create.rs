@@ -0,0 +1,28 @@
+use crate::fly_rust::volume_types::{CreateVolumeInput, CreateVolumeRequest};
+use crate::fly_rust::volumes::create_volume;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+pub async fn create(ops: &Ops, app_name: &str, params: CreateVolumeInput) -> RdrResult<()> {
+    let request = CreateVolumeRequest {
+        name: params.name,
+        region: Some(params.region),
+        source_volume_id: None,
+        size_gb: Some(params.size_gb),
+        encrypted: Some(params.encrypted),
+        snapshot_id: params.snapshot_id,
+    };
+    let volume = create_volume(&ops.request_builder_machines, app_name, &request).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Created the volume {} ({}GB) in {}.",
+                volume.id, volume.size_gb, volume.region
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
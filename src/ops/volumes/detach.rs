@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use color_eyre::eyre::eyre;
+
+use crate::fly_rust::machine_types::{StopMachineInput, MACHINE_STATE_STARTED};
+use crate::fly_rust::machines::{get_machine, get_machine_config, stop_machine, update_machine};
+use crate::ops::lease::{acquire_leases, ReleaseGuard};
+use crate::ops::wait::wait_for_start_or_stop;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+/// Stops the machine the volume is attached to and removes the volume from its mounts. The
+/// machine is left stopped, it'd fail to start without the volume if the app relies on it.
+pub async fn detach(ops: &Ops, app_name: &str, volume_id: &str, machine_id: &str) -> RdrResult<()> {
+    let machine = get_machine(&ops.request_builder_machines, app_name, machine_id).await?;
+    let was_started = machine.state == MACHINE_STATE_STARTED;
+    let (leases, errors, release) = acquire_leases(ops, app_name, vec![machine]).await;
+    let _release_guard = ReleaseGuard {
+        release: Some(release),
+    };
+
+    if let Some(err) = errors.first() {
+        return Err(eyre!("{}", err));
+    }
+    let Some(lease) = leases.into_iter().next() else {
+        return Err(eyre!("Could not acquire a lease on {}.", machine_id));
+    };
+    let nonce = lease
+        .lock()
+        .unwrap()
+        .lease_nonce
+        .clone()
+        .ok_or_else(|| eyre!("Could not acquire a lease on {}.", machine_id))?;
+
+    if was_started {
+        ops.io_resp_tx
+            .send(IoRespEvent::SetPopup {
+                popup_type: PopupType::InfoPopup,
+                message: format!("Stopping {} to detach {}...", machine_id, volume_id),
+            })
+            .await?;
+        let params = StopMachineInput {
+            id: machine_id.to_string(),
+            ..Default::default()
+        };
+        stop_machine(&ops.request_builder_machines, app_name, &params, &nonce).await?;
+        wait_for_start_or_stop(
+            &ops.request_builder_machines,
+            app_name,
+            lease.clone(),
+            "stop",
+            Duration::from_secs(300),
+        )
+        .await?;
+    }
+
+    let mut config =
+        get_machine_config(&ops.request_builder_machines, app_name, machine_id).await?;
+    if let Some(mounts) = config["mounts"].as_array_mut() {
+        mounts.retain(|mount| mount["volume"] != volume_id);
+    }
+    update_machine(
+        &ops.request_builder_machines,
+        app_name,
+        machine_id,
+        None,
+        &config,
+        &nonce,
+        true,
+    )
+    .await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Detached {} from {}. The machine is left stopped, start it once it has a volume to mount or it no longer needs one.",
+                volume_id, machine_id
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
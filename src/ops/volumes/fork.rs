@@ -0,0 +1,28 @@
+use crate::fly_rust::volume_types::{CreateVolumeRequest, ForkVolumeInput};
+use crate::fly_rust::volumes::create_volume;
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+
+pub async fn fork(ops: &Ops, app_name: &str, params: ForkVolumeInput) -> RdrResult<()> {
+    let request = CreateVolumeRequest {
+        name: params.name,
+        region: params.region,
+        source_volume_id: Some(params.id.clone()),
+        size_gb: None,
+        encrypted: None,
+        snapshot_id: None,
+    };
+    let volume = create_volume(&ops.request_builder_machines, app_name, &request).await?;
+
+    ops.io_resp_tx
+        .send(IoRespEvent::SetPopup {
+            popup_type: PopupType::InfoPopup,
+            message: format!(
+                "Forked {} into {} in {}. The data is copied in the background, the fork can be attached once it's created.",
+                params.id, volume.id, volume.region
+            ),
+        })
+        .await?;
+
+    Ok(())
+}
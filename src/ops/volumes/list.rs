@@ -1,13 +1,54 @@
+use std::collections::HashSet;
+
+use crate::fly_rust::machine_types::Machine;
+use crate::fly_rust::machines::list_machines;
 use crate::fly_rust::volumes::get_volumes;
-use crate::ops::{IoRespEvent, Ops};
+use crate::ops::{metrics, IoRespEvent, Ops};
 use crate::state::RdrResult;
 use crate::transformations::ResourceList;
 
+/// Marks the orphaned volumes so they can be filtered on.
+pub const ORPHANED_VOLUME_MARKER: &str = "⚠";
+
+/// Volumes that aren't attached or whose machine is gone, these are billed but unused.
+pub fn orphan_reason(
+    attached_machine_id: Option<&str>,
+    machine_ids: &HashSet<String>,
+) -> Option<&'static str> {
+    match attached_machine_id.filter(|machine_id| !machine_id.is_empty()) {
+        Some(machine_id) if !machine_ids.contains(machine_id) => Some("Attached machine is gone"),
+        Some(_) => None,
+        None => Some("Not attached"),
+    }
+}
+
 pub async fn list(ops: &Ops, seq_id: u64, app: &str) -> RdrResult<()> {
-    let mut volumes = get_volumes(&ops.request_builder_machines, app).await?;
+    let (mut volumes, machines) = tokio::try_join!(
+        get_volumes(&ops.request_builder_machines, app),
+        list_machines::<Machine>(&ops.request_builder_machines, app, true),
+    )?;
     // Sort by id
     volumes.sort_by(|m1, m2| m1.id.cmp(&m2.id));
 
+    let machine_ids: HashSet<String> = machines
+        .into_iter()
+        .filter(|machine| machine.is_active())
+        .map(|machine| machine.id)
+        .collect();
+    for volume in &mut volumes {
+        if let Some(reason) = orphan_reason(volume.attached_machine_id.as_deref(), &machine_ids) {
+            volume.orphaned = format!("{} {}", ORPHANED_VOLUME_MARKER, reason);
+        }
+    }
+    metrics::observe_volumes(
+        app,
+        volumes.iter().map(|volume| volume.state.as_str()),
+        volumes
+            .iter()
+            .filter(|volume| !volume.orphaned.is_empty())
+            .count(),
+    );
+
     ops.io_resp_tx
         .send(IoRespEvent::Volumes {
             seq_id,
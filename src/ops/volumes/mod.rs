@@ -1,2 +1,6 @@
+pub mod create;
 pub mod destroy;
+pub mod detach;
+pub mod fork;
 pub mod list;
+pub mod snapshot;
@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use color_eyre::eyre::eyre;
+use tokio::time::{sleep, Instant};
+
+use crate::fly_rust::volumes::{create_volume_snapshot, list_volume_snapshots};
+use crate::ops::{IoRespEvent, Ops};
+use crate::state::{PopupType, RdrResult};
+use crate::transformations::format_bytes;
+
+const SNAPSHOT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Takes a snapshot of the volume and follows it until it's created, the status is shown as it
+/// changes.
+pub async fn snapshot(ops: &Ops, app_name: &str, volume_id: &str) -> RdrResult<()> {
+    let existing = list_volume_snapshots(&ops.request_builder_machines, app_name, volume_id)
+        .await?
+        .into_iter()
+        .map(|snapshot| snapshot.id)
+        .collect::<HashSet<_>>();
+    let _feedback_tx = ops.show_delayed_feedback(
+        format!("Taking a snapshot of {}...", volume_id),
+        Duration::from_secs(0),
+    );
+    create_volume_snapshot(&ops.request_builder_machines, app_name, volume_id).await?;
+
+    let started_at = Instant::now();
+    let mut last_status = None;
+    while started_at.elapsed() < SNAPSHOT_TIMEOUT {
+        sleep(SNAPSHOT_POLL_INTERVAL).await;
+        let snapshot = list_volume_snapshots(&ops.request_builder_machines, app_name, volume_id)
+            .await?
+            .into_iter()
+            .filter(|snapshot| !existing.contains(&snapshot.id))
+            .max_by_key(|snapshot| snapshot.created_at);
+        let Some(snapshot) = snapshot else {
+            continue;
+        };
+        if snapshot.status == "created" {
+            ops.io_resp_tx
+                .send(IoRespEvent::SetPopup {
+                    popup_type: PopupType::InfoPopup,
+                    message: format!(
+                        "Snapshot {} of {} is created ({}).",
+                        snapshot.id,
+                        volume_id,
+                        format_bytes(snapshot.size.max(0) as u64)
+                    ),
+                })
+                .await?;
+            return Ok(());
+        }
+        if last_status.as_ref() != Some(&snapshot.status) {
+            ops.io_resp_tx
+                .send(IoRespEvent::SetPopup {
+                    popup_type: PopupType::InfoPopup,
+                    message: format!(
+                        "Snapshot {} of {} is {}...",
+                        snapshot.id, volume_id, snapshot.status
+                    ),
+                })
+                .await?;
+            last_status = Some(snapshot.status);
+        }
+    }
+    Err(eyre!(
+        "The snapshot of {} is still in progress, check back later.",
+        volume_id
+    ))
+}
@@ -7,8 +7,8 @@ use color_eyre::eyre::{bail, eyre};
 use tokio::time::timeout;
 use tracing::info;
 
-use crate::fly_rust::machine_types::{Machine, MachineRestartPolicy};
-use crate::fly_rust::machines::wait;
+use crate::fly_rust::machine_types::{ConsulCheckStatus, Machine, MachineRestartPolicy};
+use crate::fly_rust::machines::{get_machine, wait};
 use crate::fly_rust::request_builder::RequestBuilderMachines;
 use crate::state::RdrResult;
 
@@ -122,3 +122,37 @@ impl WaitTimeoutError {
         &self.desired_state
     }
 }
+
+const HEALTH_CHECKS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Waits until every health check of the machine passes, machines without checks pass right
+/// away.
+pub async fn wait_for_health_checks(
+    request_builder: &RequestBuilderMachines,
+    app_name: &str,
+    machine_id: &str,
+    timeout_duration: Duration,
+) -> RdrResult<()> {
+    let passing = || async {
+        loop {
+            let machine = get_machine(request_builder, app_name, machine_id).await?;
+            if machine
+                .checks
+                .iter()
+                .flatten()
+                .all(|check| matches!(check.status, ConsulCheckStatus::Passing))
+            {
+                return RdrResult::Ok(());
+            }
+            tokio::time::sleep(HEALTH_CHECKS_POLL_INTERVAL).await;
+        }
+    };
+    match timeout(timeout_duration, passing()).await {
+        Ok(result) => result,
+        Err(_elapsed) => Err(eyre!(
+            "The health checks of {} didn't pass in {:?}",
+            machine_id,
+            timeout_duration
+        )),
+    }
+}
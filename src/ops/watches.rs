@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use tracing::error;
+
+use crate::config::{Watch, WatchCondition};
+use crate::fly_rust::machine_types::Machine;
+use crate::fly_rust::machines::list_machines;
+use crate::fly_rust::resource_logs::get_app_logs;
+use crate::notification::notify;
+use crate::ops::{metrics, pause, IoRespEvent, Ops};
+use crate::state::RdrResult;
+
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(30);
+const ERROR_LOG_WINDOW: Duration = Duration::from_secs(60);
+//INFO: Bounds the catching up after a burst of logs
+const MAX_LOG_PAGES: usize = 10;
+
+/// Error logs seen so far, the token picks up where the previous check left off.
+#[derive(Default)]
+struct ErrorLogs {
+    next_token: Option<String>,
+    timestamps: VecDeque<DateTime<Utc>>,
+}
+
+/// Checks the watches for the whole session and sends the alerts raised on every check.
+pub async fn watch(ops: &Ops) -> RdrResult<()> {
+    let mut raised: HashSet<String> = HashSet::new();
+    let mut error_logs: HashMap<String, ErrorLogs> = HashMap::new();
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        interval.tick().await;
+        pause::resumed().await;
+        let mut alerts = vec![];
+        let mut still_raised = HashSet::new();
+        for watch in &ops.rdr_config.watches {
+            let key = format!("{}:{:?}", watch.app, watch.condition);
+            match check(ops, watch, &mut error_logs).await {
+                Ok(Some(alert)) => {
+                    //INFO: Notifies once per raised alert, it's forgotten once the condition clears
+                    if watch.notify && !raised.contains(&key) {
+                        let body = alert.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = notify("Watch alert", &body).await {
+                                error!("Could not show the desktop notification: {}", err);
+                            }
+                        });
+                    }
+                    still_raised.insert(key);
+                    alerts.push(alert);
+                }
+                Ok(None) => {}
+                Err(err) => error!("Checking the watch of {} failed: {}", watch.app, err),
+            }
+        }
+        raised = still_raised;
+        ops.io_resp_tx
+            .send(IoRespEvent::Alerts { list: alerts })
+            .await?;
+    }
+}
+
+async fn check(
+    ops: &Ops,
+    watch: &Watch,
+    error_logs: &mut HashMap<String, ErrorLogs>,
+) -> RdrResult<Option<String>> {
+    match &watch.condition {
+        WatchCondition::MachineState { state } => {
+            let machines = list_machines::<Machine>(
+                &ops.request_builder_machines,
+                &watch.app,
+                //INFO: When summary is set to true, server doesn't send states like "stopping"
+                false,
+            )
+            .await?;
+            metrics::observe_machines(&watch.app, &machines);
+            let ids = machines
+                .iter()
+                .filter(|machine| machine.state == *state)
+                .map(|machine| machine.id.as_str())
+                .sorted()
+                .collect::<Vec<_>>();
+            Ok((!ids.is_empty()).then(|| {
+                format!(
+                    "{}: {} {} {}",
+                    watch.app,
+                    ids.join(", "),
+                    if ids.len() == 1 { "is" } else { "are" },
+                    state
+                )
+            }))
+        }
+        WatchCondition::ErrorLogRate { above } => {
+            let logs = error_logs.entry(watch.app.clone()).or_default();
+            for _ in 0..MAX_LOG_PAGES {
+                let (entries, next_token) = get_app_logs(
+                    &ops.request_builder_fly,
+                    &watch.app,
+                    logs.next_token.clone(),
+                    None,
+                    None,
+                )
+                .await?;
+                if !next_token.is_empty() {
+                    logs.next_token = Some(next_token);
+                }
+                if entries.is_empty() {
+                    break;
+                }
+                logs.timestamps.extend(
+                    entries
+                        .iter()
+                        .filter(|entry| entry.level == "error")
+                        .filter_map(|entry| entry.timestamp.parse::<DateTime<Utc>>().ok()),
+                );
+            }
+            let window_start = Utc::now() - ERROR_LOG_WINDOW;
+            logs.timestamps
+                .retain(|timestamp| *timestamp >= window_start);
+            let count = logs.timestamps.len();
+            Ok((count > *above).then(|| {
+                format!(
+                    "{}: {} error logs in the last minute (above {})",
+                    watch.app, count, above
+                )
+            }))
+        }
+    }
+}
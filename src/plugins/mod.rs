@@ -0,0 +1,94 @@
+use std::sync::OnceLock;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::keybindings::{self, Context};
+use crate::state::view::View;
+use crate::state::RdrResult;
+
+pub mod script;
+
+/// Keys of the debugger, only shown in the debug builds.
+const DEBUGGER_KEYS: [char; 3] = ['n', 'm', 'b'];
+
+static PLUGINS: OnceLock<Vec<Box<dyn Plugin>>> = OnceLock::new();
+
+#[derive(Clone, Debug)]
+pub struct PluginAction {
+    pub key: char,
+    pub description: String,
+    /// Key as it's shown in the keymap
+    pub keybinding: String,
+}
+
+impl PluginAction {
+    pub fn new(key: char, description: String) -> Self {
+        PluginAction {
+            key,
+            description,
+            keybinding: format!("<{}>", key),
+        }
+    }
+}
+
+/// A custom resource view. The rows are listed like the built-in views, the first column being
+/// the id of the row, and the actions run against the selected row.
+///
+/// The methods block, they are called off the async runtime.
+pub trait Plugin: Send + Sync {
+    /// Name of the view, also the command opening it
+    fn name(&self) -> &str;
+    fn headers(&self) -> &[String];
+    fn actions(&self) -> &[PluginAction];
+    fn list(&self) -> RdrResult<Vec<Vec<String>>>;
+    /// Runs the action against the row, returns the message shown to the user.
+    fn run_action(&self, action: &PluginAction, row: &[String]) -> RdrResult<String>;
+
+    fn action(&self, key: char) -> Option<&PluginAction> {
+        self.actions().iter().find(|action| action.key == key)
+    }
+}
+
+/// Loads the plugins once on startup. A plugin failing to load is skipped, the errors are returned
+/// to be shown.
+pub fn load(paths: &[String]) -> Vec<String> {
+    let mut errors = vec![];
+    PLUGINS.get_or_init(|| {
+        let mut plugins: Vec<Box<dyn Plugin>> = vec![];
+        for path in paths {
+            match script::RhaiPlugin::load(path) {
+                Ok(plugin) if get_from(&plugins, plugin.name()).is_some() => {
+                    errors.push(format!("Skipping the plugin {}, its name is taken.", path));
+                }
+                Ok(plugin) => plugins.push(Box::new(plugin)),
+                Err(err) => errors.push(format!("Loading the plugin {} failed: {}", path, err)),
+            }
+        }
+        plugins
+    });
+    errors
+}
+
+pub fn get(name: &str) -> Option<&'static dyn Plugin> {
+    get_from(PLUGINS.get()?, name)
+}
+
+fn get_from<'a>(plugins: &'a [Box<dyn Plugin>], name: &str) -> Option<&'a dyn Plugin> {
+    plugins
+        .iter()
+        .find(|plugin| plugin.name() == name)
+        .map(|plugin| plugin.as_ref())
+}
+
+/// Whether a built-in action of the plugin views, remapped or not, is on the key.
+fn is_reserved_key(key: char) -> bool {
+    let view = View::Plugin {
+        name: String::new(),
+    };
+    keybindings::action(
+        Context::View(&view),
+        KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE),
+    )
+    .is_some()
+        || (cfg!(debug_assertions) && DEBUGGER_KEYS.contains(&key))
+}
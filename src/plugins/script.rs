@@ -0,0 +1,142 @@
+use color_eyre::eyre::eyre;
+use reqwest::Client;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use tokio::runtime::Handle;
+
+use super::{is_reserved_key, Plugin, PluginAction};
+use crate::command::Command;
+use crate::ops::logs::expand_file_path;
+use crate::state::RdrResult;
+
+type ScriptResult<T> = Result<T, Box<rhai::EvalAltResult>>;
+
+/// A plugin written in Rhai (https://rhai.rs). The script defines:
+///
+/// - `fn name()`, the name of the view
+/// - `fn headers()`, an array of the column names
+/// - `fn list()`, an array of rows, each row being an array of cells
+/// - `fn actions()` (optional), a map of keys to action descriptions, e.g. `#{ "r": "Rollback" }`
+/// - `fn run_action(key, row)` (optional), returns the message shown after the action ran
+///
+/// On top of the standard library, scripts can call `http_get(url, headers)`,
+/// `http_post(url, body, headers)`, `env(name)`, `from_json(text)` and `to_json(value)`.
+pub struct RhaiPlugin {
+    engine: Engine,
+    ast: AST,
+    name: String,
+    headers: Vec<String>,
+    actions: Vec<PluginAction>,
+}
+
+impl RhaiPlugin {
+    pub fn load(path: &str) -> RdrResult<Self> {
+        let engine = engine(Client::new());
+        let ast = engine.compile_file(expand_file_path(path))?;
+
+        let name = engine.call_fn::<String>(&mut Scope::new(), &ast, "name", ())?;
+        if name.parse::<Command>().is_ok() {
+            return Err(eyre!("{} is the name of a built-in view.", name));
+        }
+        let headers = engine
+            .call_fn::<Array>(&mut Scope::new(), &ast, "headers", ())?
+            .into_iter()
+            .map(|header| header.to_string())
+            .collect::<Vec<_>>();
+        let actions = if ast.iter_functions().any(|f| f.name == "actions") {
+            engine
+                .call_fn::<Map>(&mut Scope::new(), &ast, "actions", ())?
+                .into_iter()
+                .map(|(key, description)| {
+                    let mut chars = key.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(key), None) if !is_reserved_key(key) => {
+                            Ok(PluginAction::new(key, description.to_string()))
+                        }
+                        _ => Err(eyre!("{} can't be the key of an action.", key)),
+                    }
+                })
+                .collect::<RdrResult<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+
+        Ok(RhaiPlugin {
+            engine,
+            ast,
+            name,
+            headers,
+            actions,
+        })
+    }
+}
+
+impl Plugin for RhaiPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn headers(&self) -> &[String] {
+        &self.headers
+    }
+    fn actions(&self) -> &[PluginAction] {
+        &self.actions
+    }
+    fn list(&self) -> RdrResult<Vec<Vec<String>>> {
+        self.engine
+            .call_fn::<Array>(&mut Scope::new(), &self.ast, "list", ())?
+            .into_iter()
+            .map(|row| {
+                let row = row
+                    .into_array()
+                    .map_err(|type_name| eyre!("Expected a row array, got {}.", type_name))?;
+                Ok(row.into_iter().map(|cell| cell.to_string()).collect())
+            })
+            .collect()
+    }
+    fn run_action(&self, action: &PluginAction, row: &[String]) -> RdrResult<String> {
+        let row = row.iter().cloned().map(Dynamic::from).collect::<Array>();
+        let message = self.engine.call_fn::<Dynamic>(
+            &mut Scope::new(),
+            &self.ast,
+            "run_action",
+            (action.key.to_string(), row),
+        )?;
+        Ok(if message.is_unit() {
+            format!("{} done.", action.description)
+        } else {
+            message.to_string()
+        })
+    }
+}
+
+fn engine(client: Client) -> Engine {
+    let mut engine = Engine::new();
+
+    let get_client = client.clone();
+    engine.register_fn("http_get", move |url: &str, headers: Map| {
+        send(get_client.get(url), headers)
+    });
+    engine.register_fn("http_post", move |url: &str, body: &str, headers: Map| {
+        send(client.post(url).body(body.to_string()), headers)
+    });
+    engine.register_fn("env", |name: &str| std::env::var(name).unwrap_or_default());
+    engine.register_fn("from_json", |text: &str| -> ScriptResult<Dynamic> {
+        let value =
+            serde_json::from_str::<serde_json::Value>(text).map_err(|err| err.to_string())?;
+        rhai::serde::to_dynamic(value)
+    });
+    engine.register_fn("to_json", |value: Dynamic| -> ScriptResult<String> {
+        Ok(serde_json::to_string(&value).map_err(|err| err.to_string())?)
+    });
+
+    engine
+}
+
+/// Scripts run on a blocking thread so the request is driven by the runtime's handle.
+fn send(request: reqwest::RequestBuilder, headers: Map) -> ScriptResult<String> {
+    let request = headers.into_iter().fold(request, |request, (name, value)| {
+        request.header(name.as_str(), value.to_string())
+    });
+    Handle::current()
+        .block_on(async { request.send().await?.error_for_status()?.text().await })
+        .map_err(|err| err.to_string().into())
+}
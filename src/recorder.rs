@@ -0,0 +1,137 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::json;
+
+use crate::state::RdrResult;
+
+struct Cast {
+    file: BufWriter<File>,
+    started_at: Instant,
+}
+
+impl Cast {
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let event = json!([self.started_at.elapsed().as_secs_f64(), code, data]);
+        writeln!(self.file, "{}", event)?;
+        self.file.flush()
+    }
+}
+
+/// Records the session into an asciinema v2 cast file.
+///
+/// Every drawn frame is an output event and every key press is an input event, so the cast can
+/// be played back with `asciinema play`.
+#[derive(Clone)]
+pub struct Recorder(Arc<Mutex<Cast>>);
+
+impl Recorder {
+    /// Creates the cast file and writes its header.
+    pub fn new(path: &Path, width: u16, height: u16) -> RdrResult<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            "env": {
+                "TERM": std::env::var("TERM").unwrap_or_default(),
+                "SHELL": std::env::var("SHELL").unwrap_or_default(),
+            },
+        });
+        writeln!(file, "{}", header)?;
+        Ok(Self(Arc::new(Mutex::new(Cast {
+            file,
+            started_at: Instant::now(),
+        }))))
+    }
+
+    pub fn input(&self, key_event: &KeyEvent) {
+        if let Some(data) = key_event_to_input(key_event) {
+            let _ = self.0.lock().unwrap().event("i", &data);
+        }
+    }
+
+    pub fn resize(&self, width: u16, height: u16) {
+        let _ = self
+            .0
+            .lock()
+            .unwrap()
+            .event("r", &format!("{}x{}", width, height));
+    }
+
+    fn output(&self, data: &str) -> io::Result<()> {
+        self.0.lock().unwrap().event("o", data)
+    }
+}
+
+//INFO: Inputs are recorded as the bytes a terminal would send for them, keys without a common
+//encoding are skipped.
+fn key_event_to_input(key_event: &KeyEvent) -> Option<String> {
+    let input = match key_event.code {
+        KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            let c = c.to_ascii_lowercase();
+            if !c.is_ascii_lowercase() {
+                return None;
+            }
+            ((c as u8 - b'a' + 1) as char).to_string()
+        }
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => String::from("\r"),
+        KeyCode::Esc => String::from("\x1b"),
+        KeyCode::Tab => String::from("\t"),
+        KeyCode::BackTab => String::from("\x1b[Z"),
+        KeyCode::Backspace => String::from("\x7f"),
+        KeyCode::Up => String::from("\x1b[A"),
+        KeyCode::Down => String::from("\x1b[B"),
+        KeyCode::Right => String::from("\x1b[C"),
+        KeyCode::Left => String::from("\x1b[D"),
+        KeyCode::PageUp => String::from("\x1b[5~"),
+        KeyCode::PageDown => String::from("\x1b[6~"),
+        _ => return None,
+    };
+    Some(input)
+}
+
+/// Writer of the terminal backend, copies what's drawn to the recorder when there is one.
+pub struct RecordingWriter<W: Write> {
+    inner: W,
+    recorder: Option<Recorder>,
+    // A frame is written in many small writes, it's recorded as a single event on flush
+    frame: Vec<u8>,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    pub fn new(inner: W, recorder: Option<Recorder>) -> Self {
+        Self {
+            inner,
+            recorder,
+            frame: vec![],
+        }
+    }
+}
+
+impl<W: Write> Write for RecordingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if self.recorder.is_some() {
+            self.frame.extend_from_slice(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        if let Some(recorder) = &self.recorder {
+            if !self.frame.is_empty() {
+                recorder.output(&String::from_utf8_lossy(&self.frame))?;
+                self.frame.clear();
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,37 +1,102 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use color_eyre::eyre::OptionExt;
+use color_eyre::eyre::{eyre, OptionExt};
 use dashmap::{DashMap, DashSet};
 use focusable::FocusContainer;
 use itertools::Itertools;
+use regex::RegexBuilder;
 use strum::IntoEnumIterator;
 use tokio::sync::mpsc::{self, Sender};
 use tracing::{error, log};
 use tui_input::Input;
-use view::View;
+use view::{View, ALL_ORGS};
 
-use crate::command::{match_command, Command};
-use crate::fly_rust::machine_types::{RemoveMachineInput, RestartMachineInput, StopMachineInput};
+use crate::agent::flyctl_available;
+use crate::command::{complete_arg, match_command, Command, CommandLine};
+use crate::config::{read_rdr_pins, toggle_rdr_pin, Confirmation, RdrConfig, StartupView};
+use crate::fly_rust::capture::{is_capture_enabled, set_capture_enabled};
+use crate::fly_rust::machine_types::{
+    MachineGuest, RemoveMachineInput, RestartMachineInput, StopMachineInput, MACHINE_STATE_STARTED,
+};
 use crate::fly_rust::resource_organizations::OrganizationFilter;
-use crate::fly_rust::volume_types::RemoveVolumeInput;
-use crate::logs::LogOptions;
+use crate::fly_rust::volume_types::{CreateVolumeInput, ForkVolumeInput, RemoveVolumeInput};
+use crate::keybindings::{self, Action, Context};
+use crate::logs::{LogOptions, LogTransport};
+use crate::notification::notify;
+use crate::ops::apps::autoscaling::{AutoscalingChange, ScalingGroupKey};
+use crate::ops::apps::cleanup::{CLEANUP_KIND_MACHINE, CLEANUP_KIND_VOLUME};
 use crate::ops::apps::restart::AppRestartParams;
+use crate::ops::apps::services::{ServiceEdit, ServicePort};
+use crate::ops::export::{api_capture_file_path, export_file_path, to_markdown_table};
+use crate::ops::logs::{common_prefix, complete_file_path, dump_file_path, expand_file_path};
+use crate::ops::machines::capture::capture_file_path;
+use crate::ops::machines::console::ConsoleRequest;
+use crate::ops::machines::image::UPDATE_PENDING;
 use crate::ops::machines::kill::KillMachineInput;
-use crate::ops::{IoReqEvent, IoRespEvent};
-use crate::transformations::{ListApp, ListMachine, ListOrganization, ListVolume};
+use crate::ops::machines::scale;
+use crate::ops::secrets::SecretChange;
+use crate::ops::volumes::list::ORPHANED_VOLUME_MARKER;
+use crate::ops::{pause, IoReqEvent, IoRespEvent};
+use crate::plugins;
+use crate::transformations::{
+    ListApp, ListDedicatedHost, ListMachine, ListOrganization, ListVolume, ListWireGuardPeer,
+};
+use crate::ui::is_low_bandwidth;
 use crate::widgets::focusable_check_box::CheckBox;
+use crate::widgets::focusable_input::InputField;
+use crate::widgets::focusable_number::NumberField;
 use crate::widgets::focusable_text::TextBox;
 use crate::widgets::focusable_widget::FocusableWidget;
 use crate::widgets::form::Form;
-use crate::widgets::log_viewer::{LevelFilter, TuiWidgetState};
+use crate::widgets::log_viewer::{
+    log_stats, total_log_events, LevelFilter, LogStats, TuiWidgetEvent, TuiWidgetState,
+};
+use crate::widgets::region_picker::RegionPicker;
 use crate::widgets::selectable_list::SelectableList;
+use crate::widgets::wizard::{Wizard, WizardStep};
 
 pub mod view;
 
 pub type RdrResult<T> = color_eyre::eyre::Result<T>;
 
+const TOP_ERROR_MESSAGES: usize = 10;
+//INFO: Keeps the time-based cells like the ages fresh while nothing else changes
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_secs(2);
+const DEPLOY_TOKEN_NAME: &str = "flyradar deploy token";
+const DEPLOY_TOKEN_EXPIRY_DAYS: u32 = 365;
+const DEPLOY_TOKEN_MAX_EXPIRY_DAYS: u32 = 3650;
+//INFO: The largest volume the Machines API creates
+const VOLUME_MAX_SIZE_GB: u32 = 500;
+const DEPLOY_TOKEN_COPIED: &str = "Copied it to the clipboard";
+
+fn deploy_token_copy_hint() -> String {
+    let key = keybindings::label(
+        Context::Popup(&PopupType::ViewDeployTokenPopup),
+        &[Action::CopyToken],
+    );
+    format!("Press {} to copy it", key)
+}
+
+fn staged_secrets_warning(view: &View) -> String {
+    let key = |action| keybindings::label(Context::View(view), &[action]);
+    format!(
+        "\n\nWarning! This will be kept as a pending change until the secrets are deployed with {}, it can be un-staged until then with {} or {}.",
+        key(Action::DeploySecrets),
+        key(Action::PendingSecrets),
+        key(Action::Unstage)
+    )
+}
+
+/// Replaced with what goes with the app once it's fetched, names the app so that a late preview
+/// doesn't land on the popup of another app.
+fn destroy_app_preview_placeholder(app_name: &str) -> String {
+    format!("\n\nChecking what goes with {}...", app_name)
+}
+
 #[derive(Debug, Clone)]
 pub enum PopupType {
     ErrorPopup,
@@ -42,19 +107,61 @@ pub enum PopupType {
     DeleteOrganizationMembershipPopup,
     ViewOrganizationMembersPopup,
     ViewAppReleasesPopup,
+    ViewAppReleasesDiffPopup,
+    RollbackReleasePopup,
+    CreateDeployTokenPopup,
+    ViewDeployTokenPopup,
+    CreateWireGuardPeerPopup,
     ViewAppServicesPopup,
+    EditAppServicePopup,
+    UpdateAppServicesPopup,
+    ViewAppEnvPopup,
+    ViewAppPortsPopup,
+    ViewAppAutoscalingPopup,
+    UpdateAppAutoscalingPopup,
+    ConfirmOperationPopup,
+    AppCleanupPopup,
+    ViewDedicatedHostMachinesPopup,
+    ViewMachineChecksPopup,
+    ViewMachineDetailPopup,
+    ViewLogStatsPopup,
+    ViewLogLinePopup,
     ViewCommandsPopup,
+    ViewKeybindingsPopup,
     StartMachinesPopup,
     StopMachinesPopup,
     KillMachinePopup,
+    AlignMachinesPopup,
+    RenameMachinePopup,
+    ScaleMachinePopup,
+    ExecMachinePopup,
+    ViewMachineExecPopup,
+    SnapshotVolumePopup,
+    ForkVolumePopup,
+    FocusLogsRegionPopup,
+    CreateVolumePopup,
+    DetachVolumePopup,
     SuspendMachinesPopup,
     CordonMachinesPopup,
+    SetMachinesMetadataPopup,
+    UpdateMachinesImagePopup,
+    ViewMachinesUpdatePopup,
+    SetSecretPopup,
+    DeploySecretsPopup,
+    PendingSecretsPopup,
     UncordonMachinesPopup,
+    DumpLogsPopup,
+    TeeLogsPopup,
+    CapturePacketsPopup,
+    ExportViewPopup,
+    OverwriteLogsDumpPopup,
 }
 pub struct RdrPopup {
     pub popup_type: PopupType,
     pub message: String,
     pub actions: Form,
+    // Set for the forms split into pages, `actions` holds the current page
+    pub wizard: Option<Wizard>,
 }
 impl RdrPopup {
     pub fn new(popup_type: PopupType, message: String) -> Self {
@@ -68,6 +175,30 @@ impl RdrPopup {
                 TextBox::new("Cancel").boxed(),
                 TextBox::new("OK").boxed(),
             ]),
+            PopupType::CreateDeployTokenPopup => Form::from_iter([
+                InputField::new("Name", DEPLOY_TOKEN_NAME).boxed(),
+                NumberField::new("Expiry (days)", 1, DEPLOY_TOKEN_MAX_EXPIRY_DAYS)
+                    .with_value(DEPLOY_TOKEN_EXPIRY_DAYS)
+                    .with_step(30)
+                    .boxed(),
+                TextBox::new("Cancel").boxed(),
+                TextBox::new("OK").boxed(),
+            ]),
+            PopupType::CreateWireGuardPeerPopup => Form::from_iter([
+                InputField::new("Name", "").boxed(),
+                InputField::new("Region", "").boxed(),
+                InputField::new("Public key", "").boxed(),
+                TextBox::new("Cancel").boxed(),
+                TextBox::new("OK").boxed(),
+            ]),
+            PopupType::EditAppServicePopup => Form::from_iter([
+                NumberField::new("Internal port", 1, u16::MAX.into()).boxed(),
+                NumberField::new("Port", 1, u16::MAX.into()).boxed(),
+                InputField::new("Handlers", "").boxed(),
+                CheckBox::new("Force https", false).boxed(),
+                TextBox::new("Cancel").boxed(),
+                TextBox::new("OK").boxed(),
+            ]),
             PopupType::DestroyResourcePopup
             | PopupType::CreateOrganizationInvitePopup
             | PopupType::DeleteOrganizationMembershipPopup
@@ -75,16 +206,53 @@ impl RdrPopup {
             | PopupType::SuspendMachinesPopup
             | PopupType::StopMachinesPopup
             | PopupType::KillMachinePopup
+            | PopupType::AlignMachinesPopup
+            | PopupType::RenameMachinePopup
+            | PopupType::RollbackReleasePopup
+            | PopupType::UpdateAppServicesPopup
+            | PopupType::UpdateAppAutoscalingPopup
+            | PopupType::ConfirmOperationPopup
+            | PopupType::SnapshotVolumePopup
             | PopupType::CordonMachinesPopup
-            | PopupType::UncordonMachinesPopup => {
+            | PopupType::SetMachinesMetadataPopup
+            | PopupType::UpdateMachinesImagePopup
+            | PopupType::SetSecretPopup
+            | PopupType::DeploySecretsPopup
+            | PopupType::PendingSecretsPopup
+            | PopupType::ForkVolumePopup
+            | PopupType::FocusLogsRegionPopup
+            | PopupType::DetachVolumePopup
+            | PopupType::UncordonMachinesPopup
+            | PopupType::DumpLogsPopup
+            | PopupType::TeeLogsPopup
+            | PopupType::CreateVolumePopup
+            | PopupType::CapturePacketsPopup
+            | PopupType::ExportViewPopup
+            | PopupType::AppCleanupPopup
+            | PopupType::ScaleMachinePopup
+            | PopupType::ExecMachinePopup
+            | PopupType::OverwriteLogsDumpPopup => {
                 Form::from_iter([TextBox::new("Cancel").boxed(), TextBox::new("OK").boxed()])
             }
             PopupType::InfoPopup
             | PopupType::ErrorPopup
             | PopupType::ViewOrganizationMembersPopup
             | PopupType::ViewAppReleasesPopup
+            | PopupType::ViewAppReleasesDiffPopup
             | PopupType::ViewAppServicesPopup
-            | PopupType::ViewCommandsPopup => Form::from_iter([TextBox::new("Dismiss").boxed()]),
+            | PopupType::ViewAppEnvPopup
+            | PopupType::ViewAppPortsPopup
+            | PopupType::ViewAppAutoscalingPopup
+            | PopupType::ViewDedicatedHostMachinesPopup
+            | PopupType::ViewMachineChecksPopup
+            | PopupType::ViewMachinesUpdatePopup
+            | PopupType::ViewDeployTokenPopup
+            | PopupType::ViewMachineDetailPopup
+            | PopupType::ViewMachineExecPopup
+            | PopupType::ViewLogStatsPopup
+            | PopupType::ViewLogLinePopup
+            | PopupType::ViewCommandsPopup
+            | PopupType::ViewKeybindingsPopup => Form::from_iter([TextBox::new("Dismiss").boxed()]),
         });
 
         actions.reset_focus();
@@ -93,16 +261,74 @@ impl RdrPopup {
             popup_type,
             message,
             actions,
+            wizard: None,
         }
     }
+
+    pub fn with_wizard(popup_type: PopupType, mut wizard: Wizard) -> Self {
+        let (message, actions) = wizard.page();
+        Self {
+            wizard: Some(wizard),
+            ..Self::with_actions(popup_type, message, Some(actions))
+        }
+    }
+
+    /// The widgets of the form, and of the other pages of the wizard.
+    pub fn fields(&self) -> impl Iterator<Item = &Box<dyn FocusableWidget>> {
+        self.actions
+            .children
+            .iter()
+            .chain(self.wizard.iter().flat_map(|wizard| wizard.fields()))
+    }
 }
 
 #[derive(Debug)]
 pub enum InputState {
     Hidden,
-    Command { input: Input, command: String },
-    Search { input: Input },
-    Email { input: Input },
+    Command {
+        input: Input,
+        command: String,
+        /// Completed lines the Tab cycles through, dropped on typing
+        completions: Vec<String>,
+        completion_index: usize,
+    },
+    Search {
+        input: Input,
+    },
+    Email {
+        input: Input,
+    },
+    FilePath {
+        input: Input,
+    },
+    Metadata {
+        input: Input,
+    },
+    Image {
+        input: Input,
+    },
+    Name {
+        input: Input,
+    },
+    Region {
+        input: Input,
+    },
+    ShellCommand {
+        input: Input,
+    },
+    /// The key is entered first, Enter moves on to the value.
+    Secret {
+        key: Input,
+        value: Input,
+        value_focused: bool,
+    },
+    Confirm {
+        input: Input,
+        name: String,
+    },
+    KeybindingSearch {
+        input: Input,
+    },
 }
 
 pub enum MultiSelectModeReason {
@@ -111,6 +337,8 @@ pub enum MultiSelectModeReason {
     SuspendMachines,
     StopMachines,
     CordonMachines,
+    SetMachinesMetadata,
+    UpdateMachinesImage,
     UncordonMachines,
     UnsetSecrets,
 }
@@ -126,10 +354,24 @@ pub enum ResourceType {
     Machines,
     Volumes,
     Secrets,
+    Certificates,
+    Extensions,
+    DedicatedHosts,
+    WireGuardPeers,
+    Processes,
+    Plugin,
+}
+
+/// Identity of the access token, resolved on startup.
+pub struct Viewer {
+    pub email: String,
+    pub scoped_org: Option<String>,
 }
 
 pub struct State {
     pub running: bool,
+    // flyradar's settings, read once on launch
+    pub rdr_config: Arc<RdrConfig>,
     pub debugger_state: tui_logger::TuiWidgetState,
     pub splash_shown: Arc<AtomicBool>,
     pub view_history: Vec<View>,
@@ -140,11 +382,74 @@ pub struct State {
     pub resource_list: SelectableList,
     pub organization_members_list: Vec<Vec<String>>,
     pub app_releases_list: Vec<Vec<String>>,
+    pub app_releases_cursor: usize,
+    pub app_releases_marked: Vec<String>,
+    pub app_releases_diff_list: Vec<Vec<String>>,
+    // Only kept while its popup is open
+    pub deploy_token: Option<String>,
+    // Completes the `[org-slug]` arguments of the commands, refreshed on entering the command mode
+    pub org_slugs: Vec<String>,
+    pub region_picker: RegionPicker,
     pub app_services_list: Vec<Vec<String>>,
+    pub app_services_cursor: usize,
+    // Staged from the edit popup until its diff is confirmed
+    app_service_edit: Option<ServiceEdit>,
+    pub app_env_list: Vec<Vec<String>>,
+    pub app_ports_list: Vec<Vec<String>>,
+    pub app_autoscaling_list: Vec<Vec<String>>,
+    pub app_autoscaling_cursor: usize,
+    // Staged with the keys of the autoscaling popup until they're confirmed together
+    pub app_autoscaling_changes: BTreeMap<ScalingGroupKey, AutoscalingChange>,
+    pub app_cleanup_list: Vec<Vec<String>>,
+    pub app_cleanup_cursor: usize,
+    pub app_cleanup_marked: HashSet<String>,
+    pub dedicated_host_machines_list: Vec<Vec<String>>,
+    pub machine_checks_list: Vec<Vec<String>>,
+    pub machines_update_list: Vec<Vec<String>>,
+    pub machine_scale_list: Vec<Vec<String>>,
+    pub machine_scale_cursor: usize,
+    dump_logs_file_path: Option<PathBuf>,
+    // File the streamed logs are teed to, as confirmed by the ops
+    pub log_tee: Option<PathBuf>,
+    // Shown in the status bar while the logs stream
+    pub log_transport: Option<LogTransport>,
+    // Secret changes staged per app, sent to Fly.io with the deploy
+    pending_secrets: HashMap<String, Vec<SecretChange>>,
+    // Org whose apps open once the organizations are listed on launch
+    startup_org: Option<String>,
+    startup_app: Option<String>,
+    crash_looping_machines: HashSet<String>,
+    pub log_stats: LogStats,
+    pub alerts: Vec<String>,
+    pub machine_events: Vec<String>,
+    machine_details: HashMap<String, serde_json::Value>,
+    pub machine_detail: Vec<String>,
+    pub log_line_detail: Vec<String>,
+    pub machine_exec_list: Vec<Vec<String>>,
+    pub popup_scroll: u16,
     pub logs_state: TuiWidgetState,
+    // Searches the logs by regex instead of plain text
+    pub log_search_regex: bool,
+    pub log_search_invalid: bool,
+    // Set while the mouse is left to the terminal to select the log lines, true if the logs were
+    // following the new lines before
+    copy_mode: Option<bool>,
+    // Shows the errors and the warnings in a pane above all the logs
+    pub logs_split: bool,
     pub input_state: InputState,
     pub multi_select_mode: MultiSelectMode,
     pub popup: Option<RdrPopup>,
+    pub viewer: Option<Viewer>,
+    logged_out: bool,
+    console_request: Option<ConsoleRequest>,
+    // Copied by the main loop once the key or response is handled
+    clipboard: Option<String>,
+    // Filled in with a popup of its own, held back until its name is typed
+    awaiting_confirmation: Option<IoReqEvent>,
+    dirty: bool,
+    last_drawn: Instant,
+    drawn_log_events: usize,
+    drawn_splash: bool,
 }
 
 impl Default for State {
@@ -155,6 +460,7 @@ impl Default for State {
         }
         Self {
             running: true,
+            rdr_config: Arc::new(RdrConfig::default()),
             debugger_state: tui_logger::TuiWidgetState::new()
                 .set_default_display_level(log::LevelFilter::Info),
             splash_shown: Arc::new(AtomicBool::new(false)),
@@ -168,22 +474,76 @@ impl Default for State {
             resource_list: SelectableList::default(),
             organization_members_list: vec![],
             app_releases_list: vec![],
+            app_releases_cursor: 0,
+            app_releases_marked: vec![],
+            app_releases_diff_list: vec![],
+            deploy_token: None,
+            org_slugs: vec![],
+            region_picker: RegionPicker::default(),
             app_services_list: vec![],
+            app_services_cursor: 0,
+            app_service_edit: None,
+            app_env_list: vec![],
+            app_ports_list: vec![],
+            app_autoscaling_list: vec![],
+            app_autoscaling_cursor: 0,
+            app_autoscaling_changes: BTreeMap::new(),
+            app_cleanup_list: vec![],
+            app_cleanup_cursor: 0,
+            app_cleanup_marked: HashSet::new(),
+            dedicated_host_machines_list: vec![],
+            machine_checks_list: vec![],
+            machines_update_list: vec![],
+            machine_scale_list: vec![],
+            machine_scale_cursor: 0,
+            dump_logs_file_path: None,
+            log_tee: None,
+            log_transport: None,
+            crash_looping_machines: HashSet::new(),
+            log_stats: LogStats::default(),
+            alerts: vec![],
+            machine_events: vec![],
+            machine_details: HashMap::new(),
+            pending_secrets: HashMap::new(),
+            startup_org: None,
+            startup_app: None,
+            machine_detail: vec![],
+            log_line_detail: vec![],
+            machine_exec_list: vec![],
+            popup_scroll: 0,
             logs_state: TuiWidgetState::new().set_default_display_level(LevelFilter::Trace),
+            log_search_regex: false,
+            log_search_invalid: false,
+            copy_mode: None,
+            logs_split: false,
             input_state: InputState::Hidden,
             multi_select_mode: MultiSelectMode::Off,
             popup: None,
+            viewer: None,
+            logged_out: false,
+            console_request: None,
+            clipboard: None,
+            awaiting_confirmation: None,
+            dirty: true,
+            last_drawn: Instant::now(),
+            drawn_log_events: 0,
+            drawn_splash: false,
         }
     }
 }
 
 impl State {
-    pub fn init(&mut self, io_req_tx: Sender<IoReqEvent>) {
-        let splash_shown = Arc::clone(&self.splash_shown);
-        tokio::spawn(async move {
-            tokio::time::sleep(Duration::from_millis(500)).await;
-            splash_shown.store(true, Ordering::SeqCst);
-        });
+    pub fn init(&mut self, io_req_tx: Sender<IoReqEvent>, rdr_config: Arc<RdrConfig>) {
+        self.rdr_config = rdr_config;
+        if is_low_bandwidth() {
+            self.splash_shown.store(true, Ordering::SeqCst);
+        } else {
+            let splash_shown = Arc::clone(&self.splash_shown);
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                splash_shown.store(true, Ordering::SeqCst);
+            });
+        }
 
         let mut current_view = self.get_current_view();
         let (current_view_tx, mut current_view_rx) = mpsc::channel::<View>(8);
@@ -196,6 +556,9 @@ impl State {
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
+                        if pause::is_paused() {
+                            continue;
+                        }
                         match current_view {
                             View::Organizations { ref filter } => {
                                 if let Some(io_tx) = io_tx_clone.as_ref() {
@@ -237,6 +600,55 @@ impl State {
                                     }).await;
                                 }
                             }
+                            View::Certificates { ref app_name, .. } => {
+                                if let Some(io_tx) = io_tx_clone.as_ref() {
+                                    let _ = io_tx.send(IoReqEvent::ListCertificates{
+                                        seq_id: *seq_ids_clone.get(&ResourceType::Certificates).unwrap() + 1,
+                                        app_name: app_name.clone()
+                                    }).await;
+                                }
+                            }
+                            View::Extensions { ref org_slug, .. } => {
+                                if let Some(io_tx) = io_tx_clone.as_ref() {
+                                    let _ = io_tx.send(IoReqEvent::ListExtensions{
+                                        seq_id: *seq_ids_clone.get(&ResourceType::Extensions).unwrap() + 1,
+                                        org_slug: org_slug.clone()
+                                    }).await;
+                                }
+                            }
+                            View::DedicatedHosts { ref org_slug, .. } => {
+                                if let Some(io_tx) = io_tx_clone.as_ref() {
+                                    let _ = io_tx.send(IoReqEvent::ListDedicatedHosts{
+                                        seq_id: *seq_ids_clone.get(&ResourceType::DedicatedHosts).unwrap() + 1,
+                                        org_slug: org_slug.clone()
+                                    }).await;
+                                }
+                            }
+                            View::WireGuardPeers { ref org_slug, .. } => {
+                                if let Some(io_tx) = io_tx_clone.as_ref() {
+                                    let _ = io_tx.send(IoReqEvent::ListWireGuardPeers{
+                                        seq_id: *seq_ids_clone.get(&ResourceType::WireGuardPeers).unwrap() + 1,
+                                        org_slug: org_slug.clone()
+                                    }).await;
+                                }
+                            }
+                            View::Processes { ref app_name, ref machine_id, .. } => {
+                                if let Some(io_tx) = io_tx_clone.as_ref() {
+                                    let _ = io_tx.send(IoReqEvent::ListProcesses{
+                                        seq_id: *seq_ids_clone.get(&ResourceType::Processes).unwrap() + 1,
+                                        app_name: app_name.clone(),
+                                        machine_id: machine_id.clone()
+                                    }).await;
+                                }
+                            }
+                            View::Plugin { ref name } => {
+                                if let Some(io_tx) = io_tx_clone.as_ref() {
+                                    let _ = io_tx.send(IoReqEvent::ListPlugin{
+                                        seq_id: *seq_ids_clone.get(&ResourceType::Plugin).unwrap() + 1,
+                                        name: name.clone()
+                                    }).await;
+                                }
+                            }
                             _ => {}
                         };
                     }
@@ -249,8 +661,49 @@ impl State {
         });
     }
 
-    /// Handles the tick event of the terminal.
-    pub async fn tick(&mut self) {}
+    pub fn pause_polling(&self) {
+        pause::set_paused(true);
+    }
+    /// Polls the current view right away instead of waiting for the next tick.
+    pub async fn resume_polling(&self) {
+        if self.logged_out {
+            return;
+        }
+        pause::set_paused(false);
+        if let Some(tx) = &self.current_view_tx {
+            let _ = tx.send(self.get_current_view()).await;
+        }
+    }
+
+    /// Handles the tick event of the terminal. Marks the frame dirty when new logs arrived since
+    /// the last draw, which don't go through the events.
+    pub async fn tick(&mut self) {
+        //INFO: The ages of the machines are recomputed on every draw
+        if total_log_events() != self.drawn_log_events
+            || self.last_drawn.elapsed() >= IDLE_REDRAW_INTERVAL
+            || matches!(self.get_current_view(), View::Machines { .. })
+        {
+            self.dirty = true;
+        }
+    }
+    /// Runs on every frame, independent of the tick rate, so the splash goes away on time.
+    pub fn animate(&mut self) {
+        if self.splash_shown.load(Ordering::SeqCst) != self.drawn_splash {
+            self.dirty = true;
+        }
+    }
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+    pub fn frame_drawn(&mut self) {
+        self.dirty = false;
+        self.last_drawn = Instant::now();
+        self.drawn_log_events = total_log_events();
+        self.drawn_splash = self.splash_shown.load(Ordering::SeqCst);
+    }
 
     pub fn quit(&mut self) {
         self.running = false;
@@ -277,6 +730,12 @@ impl State {
                     && seq_id > self.get_seq_id(ResourceType::Organizations) =>
             {
                 self.set_seq_id(ResourceType::Organizations, seq_id);
+                if let Some(org_slug) = self.startup_org.take() {
+                    match self.navigate_to_startup_org(&list, org_slug).await {
+                        Ok(()) => return,
+                        Err(err) => self.open_popup(err.to_string(), PopupType::ErrorPopup, None),
+                    }
+                }
                 self.resource_list
                     .set_items(list, self.prev_selected_id.take());
             }
@@ -285,6 +744,12 @@ impl State {
                     && seq_id > self.get_seq_id(ResourceType::Apps) =>
             {
                 self.set_seq_id(ResourceType::Apps, seq_id);
+                if let Some(app_name) = self.startup_app.take() {
+                    match self.navigate_to_startup_app(&list, app_name).await {
+                        Ok(()) => return,
+                        Err(err) => self.open_popup(err.to_string(), PopupType::ErrorPopup, None),
+                    }
+                }
                 self.resource_list
                     .set_items(list, self.prev_selected_id.take());
             }
@@ -293,9 +758,24 @@ impl State {
                     && seq_id > self.get_seq_id(ResourceType::Machines) =>
             {
                 self.set_seq_id(ResourceType::Machines, seq_id);
+                if let View::Machines { app_name, .. } = &current_view {
+                    self.notify_crash_loops(app_name, &list);
+                }
                 self.resource_list
                     .set_items(list, self.prev_selected_id.take());
             }
+            IoRespEvent::MachineEvents { seq_id, list }
+                if matches!(current_view, View::Machines { .. })
+                    && seq_id >= self.get_seq_id(ResourceType::Machines) =>
+            {
+                self.machine_events = list;
+            }
+            IoRespEvent::MachineDetails { seq_id, list }
+                if matches!(current_view, View::Machines { .. })
+                    && seq_id >= self.get_seq_id(ResourceType::Machines) =>
+            {
+                self.machine_details = list;
+            }
             IoRespEvent::Volumes { seq_id, list }
                 if matches!(current_view, View::Volumes { .. })
                     && seq_id > self.get_seq_id(ResourceType::Volumes) =>
@@ -312,21 +792,224 @@ impl State {
                 self.resource_list
                     .set_items(list, self.prev_selected_id.take());
             }
+            IoRespEvent::Certificates { seq_id, list }
+                if matches!(current_view, View::Certificates { .. })
+                    && seq_id > self.get_seq_id(ResourceType::Certificates) =>
+            {
+                self.set_seq_id(ResourceType::Certificates, seq_id);
+                self.resource_list
+                    .set_items(list, self.prev_selected_id.take());
+            }
+            IoRespEvent::Extensions { seq_id, list }
+                if matches!(current_view, View::Extensions { .. })
+                    && seq_id > self.get_seq_id(ResourceType::Extensions) =>
+            {
+                self.set_seq_id(ResourceType::Extensions, seq_id);
+                self.resource_list
+                    .set_items(list, self.prev_selected_id.take());
+            }
+            IoRespEvent::DedicatedHosts { seq_id, list }
+                if matches!(current_view, View::DedicatedHosts { .. })
+                    && seq_id > self.get_seq_id(ResourceType::DedicatedHosts) =>
+            {
+                self.set_seq_id(ResourceType::DedicatedHosts, seq_id);
+                self.resource_list
+                    .set_items(list, self.prev_selected_id.take());
+            }
+            IoRespEvent::WireGuardPeers { seq_id, list }
+                if matches!(current_view, View::WireGuardPeers { .. })
+                    && seq_id > self.get_seq_id(ResourceType::WireGuardPeers) =>
+            {
+                self.set_seq_id(ResourceType::WireGuardPeers, seq_id);
+                self.resource_list
+                    .set_items(list, self.prev_selected_id.take());
+            }
+            IoRespEvent::Processes { seq_id, list }
+                if matches!(current_view, View::Processes { .. })
+                    && seq_id > self.get_seq_id(ResourceType::Processes) =>
+            {
+                self.set_seq_id(ResourceType::Processes, seq_id);
+                self.resource_list
+                    .set_items(list, self.prev_selected_id.take());
+            }
+            IoRespEvent::Plugin { seq_id, name, list }
+                if matches!(&current_view, View::Plugin { name: current_name } if *current_name == name)
+                    && seq_id > self.get_seq_id(ResourceType::Plugin) =>
+            {
+                self.set_seq_id(ResourceType::Plugin, seq_id);
+                self.resource_list
+                    .set_items(list, self.prev_selected_id.take());
+            }
+            IoRespEvent::Alerts { list } => {
+                self.alerts = list;
+            }
             IoRespEvent::OrganizationMembers { list } => {
                 self.organization_members_list = list;
             }
             IoRespEvent::AppReleases { list } => {
                 self.app_releases_list = list;
             }
+            IoRespEvent::AppReleasesDiff { list } => {
+                self.app_releases_diff_list = list;
+            }
             IoRespEvent::AppServices { list } => {
+                self.app_services_cursor =
+                    self.app_services_cursor.min(list.len().saturating_sub(1));
                 self.app_services_list = list;
             }
+            IoRespEvent::AppEnv { list } => {
+                self.app_env_list = list;
+            }
+            IoRespEvent::AppPorts { list } => {
+                self.app_ports_list = list;
+            }
+            IoRespEvent::AppCleanup { list } => {
+                self.app_cleanup_list = list;
+            }
+            IoRespEvent::AppAutoscaling { mut list } => {
+                self.app_autoscaling_cursor = self
+                    .app_autoscaling_cursor
+                    .min(list.len().saturating_sub(1));
+                //INFO: A reload keeps showing the changes that are still staged
+                for row in &mut list {
+                    if let Some(change) = self
+                        .app_autoscaling_changes
+                        .get(&(row[0].clone(), row[1].clone()))
+                    {
+                        change.apply_to_row(row);
+                    }
+                }
+                self.app_autoscaling_list = list;
+            }
+            IoRespEvent::DedicatedHostMachines { list } => {
+                self.dedicated_host_machines_list = list;
+            }
+            IoRespEvent::MachineChecks { list } => {
+                self.machine_checks_list = list;
+            }
+            IoRespEvent::MachinesUpdateProgress { list } => {
+                self.machines_update_list = list;
+            }
+            IoRespEvent::OrganizationSlugs { slugs } => {
+                self.org_slugs = slugs;
+            }
+            IoRespEvent::Regions { regions } => {
+                self.region_picker.regions = regions;
+            }
+            IoRespEvent::SecretsDeployed { app_name, changes } => {
+                if let Some(pending) = self.pending_secrets.get_mut(&app_name) {
+                    pending.retain(|change| !changes.contains(change));
+                    if pending.is_empty() {
+                        self.pending_secrets.remove(&app_name);
+                    }
+                }
+            }
+            IoRespEvent::LogTee { file_path } => {
+                self.log_tee = file_path;
+            }
+            IoRespEvent::LogTransport { transport } => {
+                if self.get_current_view().is_logs() {
+                    self.log_transport = Some(transport);
+                }
+            }
+            IoRespEvent::DeployToken { app_name, token } => {
+                let message = format!(
+                    "Deploy token of {}, it's only shown this once. {}: {}",
+                    app_name,
+                    deploy_token_copy_hint(),
+                    token
+                );
+                self.open_popup(message, PopupType::ViewDeployTokenPopup, None);
+                self.deploy_token = Some(token);
+            }
+            IoRespEvent::AppDestroyPreview { app_name, preview } => {
+                if let Some(popup) = self.popup.as_mut().filter(|popup| {
+                    matches!(popup.popup_type, PopupType::DestroyResourcePopup)
+                        && popup
+                            .message
+                            .contains(&destroy_app_preview_placeholder(&app_name))
+                }) {
+                    popup.message = popup.message.replace(
+                        &destroy_app_preview_placeholder(&app_name),
+                        &format!("\n\n{}", preview),
+                    );
+                }
+            }
+            IoRespEvent::MachineExec {
+                machine_id,
+                command,
+                list,
+            } => {
+                self.machine_exec_list = list;
+                self.popup_scroll = 0;
+                self.open_popup(
+                    format!(
+                        "$ {} on {} (<↑/↓>, <PageUp/Down> to scroll)",
+                        command, machine_id
+                    ),
+                    PopupType::ViewMachineExecPopup,
+                    None,
+                );
+            }
+            IoRespEvent::Viewer { email, scoped_org } => {
+                if let (Some(slug), View::Organizations { filter }) = (&scoped_org, &current_view) {
+                    let new_view = View::Organizations {
+                        filter: filter.clone().slug(Some(slug.clone())),
+                    };
+                    let new_view_clone = new_view.clone();
+                    if let Err(err) = self
+                        .set_current_view(&new_view, |view_history| {
+                            view_history.pop();
+                            view_history.push(new_view_clone);
+                        })
+                        .await
+                    {
+                        self.open_popup(err.to_string(), PopupType::ErrorPopup, None);
+                    }
+                } else if let Some(slug) = &scoped_org {
+                    for view in self.view_history.iter_mut() {
+                        if let View::Organizations { filter } = view {
+                            *filter = filter.clone().slug(Some(slug.clone()));
+                        }
+                    }
+                }
+                self.viewer = Some(Viewer { email, scoped_org });
+            }
+            IoRespEvent::SessionChanged { logged_in } => {
+                if let Err(err) = self.reset_session(logged_in).await {
+                    self.open_popup(err.to_string(), PopupType::ErrorPopup, None);
+                }
+            }
+            IoRespEvent::SwitchOrganization {
+                org_id,
+                org_slug,
+                list_apps,
+            } => {
+                if let Err(err) = self.switch_organization(org_id, org_slug, list_apps).await {
+                    self.open_popup(err.to_string(), PopupType::ErrorPopup, None);
+                }
+            }
+            IoRespEvent::JumpToMachine {
+                org_id,
+                org_slug,
+                app_id,
+                app_name,
+                machine_id,
+            } => {
+                if let Err(err) = self
+                    .jump_to_machine(org_id, org_slug, app_id, app_name, machine_id)
+                    .await
+                {
+                    self.open_popup(err.to_string(), PopupType::ErrorPopup, None);
+                }
+            }
             IoRespEvent::SetPopup {
                 popup_type,
                 message,
             } => {
                 self.popup = Some(RdrPopup::new(popup_type, message));
             }
+            IoRespEvent::CopyToClipboard { text } => self.clipboard = Some(text),
             _ => {}
         }
     }
@@ -347,20 +1030,24 @@ impl State {
             })
             .unwrap_or_default()
     }
+    //INFO: The aggregated Apps view isn't an org
     pub fn get_current_org(&self) -> Option<(String, String)> {
-        self.view_history.iter().rev().find_map(|view| {
-            if let View::Apps { org_id, org_slug } = view {
+        self.view_history.iter().rev().find_map(|view| match view {
+            View::Apps { org_id, org_slug } if org_slug != ALL_ORGS => {
                 Some((org_id.clone(), org_slug.clone()))
-            } else {
-                None
             }
+            _ => None,
         })
     }
     pub fn get_current_app(&self) -> Option<(String, String)> {
         self.view_history.iter().rev().find_map(|view| match view {
             View::Machines { app_id, app_name } => Some((app_id.clone(), app_name.clone())),
             View::Secrets { app_id, app_name } => Some((app_id.clone(), app_name.clone())),
+            View::Certificates { app_id, app_name } => Some((app_id.clone(), app_name.clone())),
             View::Volumes { app_id, app_name } => Some((app_id.clone(), app_name.clone())),
+            View::Processes {
+                app_id, app_name, ..
+            } => Some((app_id.clone(), app_name.clone())),
             View::AppLogs { app_id, opts } => Some((app_id.clone(), opts.app_name.clone())),
             _ => None,
         })
@@ -393,14 +1080,8 @@ impl State {
         } else {
             OrganizationFilter::default()
         };
-        let new_view = if filter.is_admin_only() {
-            View::Organizations {
-                filter: OrganizationFilter::default(),
-            }
-        } else {
-            View::Organizations {
-                filter: OrganizationFilter::admin_only(),
-            }
+        let new_view = View::Organizations {
+            filter: filter.clone().admin(!filter.is_admin_only()),
         };
         let new_view_clone = new_view.clone();
         self.set_current_view(&new_view, |view_history| {
@@ -422,7 +1103,8 @@ impl State {
                 View::AppLogs { app_id, .. }
                 | View::Machines { app_id, .. }
                 | View::Volumes { app_id, .. }
-                | View::Secrets { app_id, .. } => {
+                | View::Secrets { app_id, .. }
+                | View::Certificates { app_id, .. } => {
                     self.prev_selected_id = Some(app_id);
                 }
                 View::MachineLogs { opts, .. } => {
@@ -453,6 +1135,77 @@ impl State {
         .await?;
         Ok(())
     }
+    /// Opens the configured view on launch, on top of the Organizations view. The apps of an org
+    /// open once the orgs are listed, its id is only known then.
+    pub async fn open_startup_view(&mut self, startup_view: StartupView) -> RdrResult<()> {
+        let org = match startup_view {
+            StartupView::Organizations => return Ok(()),
+            StartupView::Apps { org } => org,
+            StartupView::Machines { org, app } => {
+                self.startup_app = Some(app);
+                org
+            }
+        };
+        match org {
+            Some(org_slug) => {
+                self.startup_org = Some(org_slug);
+                Ok(())
+            }
+            None => self.navigate_to_all_apps().await,
+        }
+    }
+    async fn navigate_to_startup_org(
+        &mut self,
+        orgs: &[Vec<String>],
+        org_slug: String,
+    ) -> RdrResult<()> {
+        let org = orgs
+            .iter()
+            .map(|row| ListOrganization::from(row.clone()))
+            .find(|org| org.slug == org_slug)
+            .ok_or_else(|| eyre!("The startup organization {} was not found.", org_slug))?;
+        let new_view = View::Apps {
+            org_id: org.id,
+            org_slug: org.slug,
+        };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            view_history.push(new_view_clone);
+        })
+        .await
+    }
+    async fn navigate_to_startup_app(
+        &mut self,
+        apps: &[Vec<String>],
+        app_name: String,
+    ) -> RdrResult<()> {
+        let app = apps
+            .iter()
+            .map(|row| ListApp::from(row.clone()))
+            .find(|app| app.name == app_name)
+            .ok_or_else(|| eyre!("The startup app {} was not found.", app_name))?;
+        let new_view = View::Machines {
+            app_id: app.id,
+            app_name: app.name,
+        };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            view_history.push(new_view_clone);
+        })
+        .await
+    }
+    pub async fn navigate_to_all_apps(&mut self) -> RdrResult<()> {
+        let new_view = View::Apps {
+            org_id: String::new(),
+            org_slug: String::from(ALL_ORGS),
+        };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            view_history.push(new_view_clone);
+        })
+        .await?;
+        Ok(())
+    }
     pub async fn navigate_to_machines(&mut self) -> RdrResult<()> {
         let app: ListApp = self.get_selected_resource()?.into();
         let new_view = View::Machines {
@@ -502,6 +1255,123 @@ impl State {
         .await?;
         Ok(())
     }
+    pub async fn navigate_to_processes(&mut self) -> RdrResult<()> {
+        let (app_id, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let new_view = View::Processes {
+            app_id,
+            app_name,
+            machine_id: machine.id,
+        };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            view_history.push(new_view_clone);
+        })
+        .await?;
+        Ok(())
+    }
+    /// Starts over from the organizations after the user changed. Nothing is polled while logged
+    /// out.
+    async fn reset_session(&mut self, logged_in: bool) -> RdrResult<()> {
+        self.logged_out = !logged_in;
+        self.viewer = None;
+        let new_view = View::Organizations {
+            filter: OrganizationFilter::default(),
+        };
+        let new_view_clone = new_view.clone();
+        if logged_in {
+            self.resume_polling().await;
+        } else {
+            self.pause_polling();
+        }
+        self.set_current_view(&new_view, move |view_history| {
+            *view_history = vec![new_view_clone];
+        })
+        .await?;
+        if logged_in {
+            self.dispatch(IoReqEvent::Preflight).await;
+            self.open_popup(String::from("Logged in."), PopupType::InfoPopup, None);
+        } else {
+            self.open_popup(
+                String::from("Logged out. Run :login to log in again."),
+                PopupType::InfoPopup,
+                None,
+            );
+        }
+        Ok(())
+    }
+    /// Rebuilds the history as if the machine was navigated to from its org, selecting it.
+    pub async fn jump_to_machine(
+        &mut self,
+        org_id: String,
+        org_slug: String,
+        app_id: String,
+        app_name: String,
+        machine_id: String,
+    ) -> RdrResult<()> {
+        let organizations_view = View::Organizations {
+            filter: self.get_current_org_filter(),
+        };
+        let apps_view = View::Apps { org_id, org_slug };
+        let new_view = View::Machines { app_id, app_name };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            *view_history = vec![organizations_view, apps_view, new_view_clone];
+        })
+        .await?;
+        self.prev_selected_id = Some(machine_id);
+        Ok(())
+    }
+    /// Plugin views replace each other instead of stacking up in the history.
+    pub async fn navigate_to_plugin(&mut self, name: String) -> RdrResult<()> {
+        let new_view = View::Plugin { name };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            if matches!(view_history.last(), Some(View::Plugin { .. })) {
+                view_history.pop();
+            }
+            view_history.push(new_view_clone);
+        })
+        .await?;
+        Ok(())
+    }
+    /// Swaps the org of the org-scoped views in place, keeping the current search filter.
+    /// App-scoped views can't outlive their org so they fall back to the new org's apps, as do all
+    /// views with `list_apps`.
+    pub async fn switch_organization(
+        &mut self,
+        org_id: String,
+        org_slug: String,
+        list_apps: bool,
+    ) -> RdrResult<()> {
+        let search_filter = self.resource_list.search_filter.clone();
+        let apps_view = View::Apps {
+            org_id: org_id.clone(),
+            org_slug: org_slug.clone(),
+        };
+        let new_view = match self.get_current_view() {
+            View::Extensions { .. } if !list_apps => View::Extensions { org_id, org_slug },
+            View::DedicatedHosts { .. } if !list_apps => View::DedicatedHosts { org_id, org_slug },
+            View::WireGuardPeers { .. } if !list_apps => View::WireGuardPeers { org_id, org_slug },
+            _ => apps_view.clone(),
+        };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            if let Some(index) = view_history
+                .iter()
+                .position(|view| matches!(view, View::Apps { .. }))
+            {
+                view_history.truncate(index);
+            }
+            view_history.push(apps_view);
+            if !matches!(new_view_clone, View::Apps { .. }) {
+                view_history.push(new_view_clone);
+            }
+        })
+        .await?;
+        self.resource_list.apply_search_filter(&search_filter);
+        Ok(())
+    }
     async fn navigate_via_command(&mut self, command: Command) -> RdrResult<()> {
         let can_navigate = match command {
             Command::Organizations => {
@@ -509,8 +1379,11 @@ impl State {
                 Ok(View::Organizations { filter })
             }
             Command::Apps => self
-                .get_current_org()
-                .map(|(org_id, org_slug)| View::Apps { org_id, org_slug })
+                .view_history
+                .iter()
+                .rev()
+                .find(|view| matches!(view, View::Apps { .. }))
+                .cloned()
                 .ok_or("Select an organization first."),
             Command::Machines => self
                 .get_current_app()
@@ -524,12 +1397,42 @@ impl State {
                 .get_current_app()
                 .map(|(app_id, app_name)| View::Secrets { app_id, app_name })
                 .ok_or("Select an app first."),
-            Command::Quit => {
-                self.quit();
-                return Ok(());
-            }
-        };
-        // Check if navigation is allowed
+            Command::Certificates => self
+                .get_current_app()
+                .map(|(app_id, app_name)| View::Certificates { app_id, app_name })
+                .ok_or("Select an app first."),
+            Command::Extensions => self
+                .get_current_org()
+                .map(|(org_id, org_slug)| View::Extensions { org_id, org_slug })
+                .ok_or("Select an organization first."),
+            Command::Hosts => self
+                .get_current_org()
+                .map(|(org_id, org_slug)| View::DedicatedHosts { org_id, org_slug })
+                .ok_or("Select an organization first."),
+            Command::Peers => self
+                .get_current_org()
+                .map(|(org_id, org_slug)| View::WireGuardPeers { org_id, org_slug })
+                .ok_or("Select an organization first."),
+            Command::Logs => {
+                return self.navigate_to_app_logs_via_command(None, false).await;
+            }
+            Command::Capture => {
+                return self.run_capture_command(None).await;
+            }
+            Command::Login => {
+                self.dispatch(IoReqEvent::Login).await;
+                return Ok(());
+            }
+            Command::Logout => {
+                self.dispatch(IoReqEvent::Logout).await;
+                return Ok(());
+            }
+            Command::Quit => {
+                self.quit();
+                return Ok(());
+            }
+        };
+        // Check if navigation is allowed
         match can_navigate {
             Ok(new_view) => {
                 let new_view_clone = new_view.clone();
@@ -544,7 +1447,13 @@ impl State {
                             view_history.pop();
                         }
                     }
-                    View::Machines { .. } | View::Volumes { .. } | View::Secrets { .. } => {
+                    View::Machines { .. }
+                    | View::Volumes { .. }
+                    | View::Secrets { .. }
+                    | View::Certificates { .. }
+                    | View::Extensions { .. }
+                    | View::DedicatedHosts { .. }
+                    | View::WireGuardPeers { .. } => {
                         while !matches!(view_history.last(), Some(View::Apps { .. })) {
                             view_history.pop();
                         }
@@ -563,25 +1472,173 @@ impl State {
         }
     }
 
+    /// Streams the logs of the app selected in the apps view, or of the current app.
+    async fn navigate_to_app_logs_via_command(
+        &mut self,
+        region_code: Option<String>,
+        no_tail: bool,
+    ) -> RdrResult<()> {
+        let app = match self.get_current_view() {
+            View::Apps { .. } => self.get_selected_resource().ok().map(|row| {
+                let app: ListApp = row.into();
+                (app.id, app.name)
+            }),
+            _ => self.get_current_app(),
+        };
+        let Some((app_id, app_name)) = app else {
+            self.open_popup(
+                String::from("Select an app first."),
+                PopupType::ErrorPopup,
+                None,
+            );
+            return Ok(());
+        };
+        let new_view = View::AppLogs {
+            app_id,
+            opts: LogOptions {
+                app_name,
+                vm_id: None,
+                region_code,
+                no_tail,
+            },
+        };
+        let new_view_clone = new_view.clone();
+        self.set_current_view(&new_view, move |view_history| {
+            while view_history
+                .last()
+                .is_some_and(|view| !matches!(view, View::Apps { .. }))
+            {
+                view_history.pop();
+            }
+            view_history.push(new_view_clone);
+        })
+        .await?;
+        Ok(())
+    }
+
+    //INFO: Notifies once per machine entering a crash loop, the machine is forgotten once it recovers
+    fn notify_crash_loops(&mut self, app_name: &str, list: &[Vec<String>]) {
+        let crash_looping: HashSet<String> = list
+            .iter()
+            .map(|row| ListMachine::from(row.clone()))
+            .filter(|machine| machine.crash_looping)
+            .map(|machine| machine.id)
+            .collect();
+        let new_crash_loops = crash_looping
+            .difference(&self.crash_looping_machines)
+            .cloned()
+            .sorted()
+            .collect::<Vec<_>>();
+        self.crash_looping_machines = crash_looping;
+        if new_crash_loops.is_empty() {
+            return;
+        }
+        let body = format!(
+            "{} of {} {} restarting repeatedly.",
+            new_crash_loops.join(", "),
+            app_name,
+            if new_crash_loops.len() == 1 {
+                "is"
+            } else {
+                "are"
+            }
+        );
+        if !self.rdr_config.crash_loop_notifications {
+            return;
+        }
+        tokio::spawn(async move {
+            if let Err(err) = notify("Crash loop detected", &body).await {
+                error!("Could not show the desktop notification: {}", err);
+            }
+        });
+    }
+
+    pub async fn toggle_pin(&mut self) -> RdrResult<()> {
+        let (_, app_name) = self.get_current_app().ok_or_eyre("Select an app first.")?;
+        let id = self.get_selected_resource()?[0].clone();
+        match toggle_rdr_pin(&app_name, &id).await {
+            Ok(pinned) => self.resource_list.set_pinned(pinned),
+            Err(err) => self.open_popup(err.to_string(), PopupType::ErrorPopup, None),
+        }
+        Ok(())
+    }
+
     pub fn exit_input(&mut self) {
         self.input_state = InputState::Hidden
     }
     // Command handling
-    pub fn enter_command_mode(&mut self) {
+    pub async fn enter_command_mode(&mut self) {
         self.reset_search_filter();
         self.input_state = InputState::Command {
             input: Input::default(),
             command: String::default(),
+            completions: vec![],
+            completion_index: 0,
         };
+        self.dispatch(IoReqEvent::ListOrganizationSlugs {
+            filter: self.get_current_org_filter().admin(false),
+        })
+        .await;
+    }
+    fn arg_candidates(&self, placeholder: &str) -> &[String] {
+        match placeholder {
+            "[org-slug]" => &self.org_slugs,
+            _ => &[],
+        }
     }
     pub fn set_command(&mut self) {
-        if let InputState::Command { input, command } = &mut self.input_state {
-            *command = String::from(match_command(input.value()));
+        let InputState::Command { input, .. } = &self.input_state else {
+            return;
+        };
+        //INFO: The first candidate of the argument is hinted, the command name otherwise
+        let hint = complete_arg(input.value(), |placeholder| {
+            self.arg_candidates(placeholder)
+        })
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| String::from(match_command(input.value())));
+        if let InputState::Command {
+            command,
+            completions,
+            ..
+        } = &mut self.input_state
+        {
+            *command = hint;
+            completions.clear();
         }
     }
+    /// Completes the command name, or cycles through the candidates of its argument.
     pub fn complete_command(&mut self) {
-        if let InputState::Command { input, command } = &mut self.input_state {
-            *command = String::from(match_command(input.value()));
+        let InputState::Command {
+            input, completions, ..
+        } = &self.input_state
+        else {
+            return;
+        };
+        let new_completions = if completions.is_empty() {
+            Some(complete_arg(input.value(), |placeholder| {
+                self.arg_candidates(placeholder)
+            }))
+        } else {
+            None
+        };
+        if let InputState::Command {
+            input,
+            command,
+            completions,
+            completion_index,
+        } = &mut self.input_state
+        {
+            if let Some(new_completions) = new_completions {
+                *completions = new_completions;
+                *completion_index = 0;
+            } else {
+                *completion_index = (*completion_index + 1) % completions.len();
+            }
+            *command = completions
+                .get(*completion_index)
+                .cloned()
+                .unwrap_or_else(|| String::from(match_command(input.value())));
             *input = Input::new(command.clone());
         }
     }
@@ -591,11 +1648,14 @@ impl State {
         update_history: impl FnOnce(&mut Vec<View>),
     ) -> RdrResult<()> {
         match new_view {
-            View::AppLogs { ref opts, .. } => {
-                self.dispatch(IoReqEvent::StreamLogs { opts: opts.clone() })
-                    .await;
-            }
-            View::MachineLogs { ref opts, .. } => {
+            View::AppLogs { ref opts, .. } | View::MachineLogs { ref opts, .. } => {
+                let level = self
+                    .rdr_config
+                    .apps
+                    .get(&opts.app_name)
+                    .and_then(|app| app.logs.level)
+                    .unwrap_or(LevelFilter::Trace);
+                self.logs_state = TuiWidgetState::new().set_default_display_level(level);
                 self.dispatch(IoReqEvent::StreamLogs { opts: opts.clone() })
                     .await;
             }
@@ -603,9 +1663,19 @@ impl State {
                 self.exit_multi_select();
                 self.reset_search_filter();
                 self.resource_list.reset();
+                self.machine_events.clear();
+                self.machine_details.clear();
+                if let View::Machines { app_name, .. }
+                | View::Volumes { app_name, .. }
+                | View::Secrets { app_name, .. } = new_view
+                {
+                    self.resource_list.pinned = read_rdr_pins(app_name).await;
+                }
                 // Cleanup the possible allocated logs resources while leaving logs screen
                 self.logs_state =
                     TuiWidgetState::new().set_default_display_level(LevelFilter::Trace);
+                self.log_tee = None;
+                self.log_transport = None;
                 self.dispatch(IoReqEvent::StopLogs).await;
             }
         };
@@ -617,8 +1687,45 @@ impl State {
     }
     pub async fn run_command(&mut self) -> RdrResult<()> {
         if let InputState::Command { input, .. } = &self.input_state {
-            match input.value().parse::<Command>() {
-                Ok(command) => self.navigate_via_command(command).await?,
+            let value = input.value().trim().to_string();
+            let name = value.split_whitespace().next().unwrap_or_default();
+            if name.parse::<Command>().is_err() && plugins::get(name).is_some() {
+                return self.navigate_to_plugin(name.to_string()).await;
+            }
+            match value.parse::<CommandLine>() {
+                Ok(line) => match (line.command, line.arg(0)) {
+                    // `:org <slug>` switches the org without leaving the current view
+                    (Command::Organizations, Some(org_slug)) => {
+                        self.dispatch(IoReqEvent::SwitchOrganization {
+                            org_slug: org_slug.to_string(),
+                            list_apps: false,
+                        })
+                        .await
+                    }
+                    (Command::Apps, Some(org_slug)) => {
+                        self.dispatch(IoReqEvent::SwitchOrganization {
+                            org_slug: org_slug.to_string(),
+                            list_apps: true,
+                        })
+                        .await
+                    }
+                    // `:machine <id>` finds the app of the machine wherever it is
+                    (Command::Machines, Some(machine_id)) => {
+                        self.dispatch(IoReqEvent::JumpToMachine {
+                            machine_id: machine_id.to_string(),
+                        })
+                        .await
+                    }
+                    (Command::Capture, action) => self.run_capture_command(action).await?,
+                    (Command::Logs, _) => {
+                        self.navigate_to_app_logs_via_command(
+                            line.flag_value("--region").map(String::from),
+                            line.has_flag("--no-tail"),
+                        )
+                        .await?
+                    }
+                    (command, _) => self.navigate_via_command(command).await?,
+                },
                 Err(err) => self.open_popup(err.to_string(), PopupType::ErrorPopup, None),
             }
         }
@@ -626,6 +1733,30 @@ impl State {
         Ok(())
     }
 
+    /// Turns the capture of the API calls on or off, toggles it without an action.
+    async fn run_capture_command(&mut self, action: Option<&str>) -> RdrResult<()> {
+        let enabled = match action {
+            None => !is_capture_enabled(),
+            Some("on") => true,
+            Some("off") => false,
+            Some("export") => {
+                self.dispatch(IoReqEvent::ExportCapture {
+                    file_path: expand_file_path(&api_capture_file_path()),
+                })
+                .await;
+                return Ok(());
+            }
+            Some(action) => return Err(eyre!("Unknown capture action: {}", action)),
+        };
+        set_capture_enabled(enabled);
+        let message = if enabled {
+            "Capturing the API calls, without their headers and bodies. Run :capture export to save them to a file."
+        } else {
+            "Stopped capturing the API calls, the captured ones are kept until you quit."
+        };
+        self.open_popup(message.to_string(), PopupType::InfoPopup, None);
+        Ok(())
+    }
     pub fn enter_search_mode(&mut self) {
         self.reset_search_filter();
         self.input_state = InputState::Search {
@@ -638,12 +1769,41 @@ impl State {
         self.input_state = InputState::Hidden
     }
     pub fn apply_search_filter(&mut self) {
-        if let InputState::Search { input } = &mut self.input_state {
-            self.resource_list.apply_search_filter(input.value());
+        if let InputState::Search { input } = &self.input_state {
+            let pattern = input.value().to_string();
+            if self.get_current_view().is_logs() {
+                self.apply_log_search(&pattern);
+            } else {
+                self.resource_list.apply_search_filter(&pattern);
+            }
         }
     }
     fn reset_search_filter(&mut self) {
         self.resource_list.apply_search_filter("");
+        self.logs_state.set_search(None);
+    }
+    /// Highlights the pattern in the logs, smart case: case insensitive unless it has uppercase
+    /// letters.
+    fn apply_log_search(&mut self, pattern: &str) {
+        let pattern = if self.log_search_regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        let search = (!pattern.is_empty())
+            .then(|| {
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(!pattern.chars().any(char::is_uppercase))
+                    .build()
+                    .ok()
+            })
+            .flatten();
+        self.log_search_invalid = !pattern.is_empty() && search.is_none();
+        self.logs_state.set_search(search);
+    }
+    pub fn toggle_log_search_regex(&mut self) {
+        self.log_search_regex ^= true;
+        self.apply_search_filter();
     }
     // Multiselect handling
     pub fn start_restart_machines(&mut self) {
@@ -661,25 +1821,241 @@ impl State {
     pub fn start_cordon_machines(&mut self) {
         self.multi_select_mode = MultiSelectMode::On(MultiSelectModeReason::CordonMachines);
     }
+    pub fn start_set_machines_metadata(&mut self) {
+        self.multi_select_mode = MultiSelectMode::On(MultiSelectModeReason::SetMachinesMetadata);
+    }
+    pub fn start_update_machines_image(&mut self) {
+        self.multi_select_mode = MultiSelectMode::On(MultiSelectModeReason::UpdateMachinesImage);
+    }
     pub fn start_uncordon_machines(&mut self) {
         self.multi_select_mode = MultiSelectMode::On(MultiSelectModeReason::UncordonMachines);
     }
     pub fn start_unset_secrets(&mut self) {
         self.multi_select_mode = MultiSelectMode::On(MultiSelectModeReason::UnsetSecrets);
     }
+    pub fn open_set_secret_popup(&mut self) -> RdrResult<()> {
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        let mut message = format!(
+            "Stage set a secret of {}, enter the key and then the value. Setting an existing key updates it.",
+            app_name
+        );
+        message.push_str(&staged_secrets_warning(&self.get_current_view()));
+        self.input_state = InputState::Secret {
+            key: Input::default(),
+            value: Input::default(),
+            value_focused: false,
+        };
+        self.open_popup(message, PopupType::SetSecretPopup, None);
+        self.popup_focus_ok();
+        Ok(())
+    }
+    pub fn open_deploy_secrets_popup(&mut self) -> RdrResult<()> {
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        let mut message = format!(
+            "Are you sure to deploy the staged secrets of {}? Its machines will be updated one by one to pick them up.",
+            app_name
+        );
+        let pending = self.pending_secret_changes();
+        if !pending.is_empty() {
+            message.push_str("\n\nThe pending changes are applied first:");
+            for change in pending {
+                message.push_str(&format!("\n{}", change.label()));
+            }
+        }
+        self.open_popup(message, PopupType::DeploySecretsPopup, None);
+        Ok(())
+    }
+    pub fn process_deploy_secrets_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            Ok(None)
+        } else {
+            let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+            Ok(Some(IoReqEvent::DeploySecrets {
+                seq_id: self.get_seq_id(ResourceType::Secrets),
+                changes: self.pending_secret_changes().to_vec(),
+                app_name,
+            }))
+        }
+    }
+    /// The secret changes of the current app waiting for the deploy, in the order they were
+    /// staged.
+    pub fn pending_secret_changes(&self) -> &[SecretChange] {
+        self.get_current_app()
+            .and_then(|(_, app_name)| self.pending_secrets.get(&app_name))
+            .map_or(&[], |changes| changes.as_slice())
+    }
+    /// A change replaces the pending one of the same key.
+    fn stage_secret_change(&mut self, app_name: String, change: SecretChange) {
+        let pending = self.pending_secrets.entry(app_name).or_default();
+        pending.retain(|pending| pending.key() != change.key());
+        pending.push(change);
+    }
+    /// Drops the latest pending change of the current app.
+    pub fn unstage_last_secret_change(&mut self) {
+        let Some((_, app_name)) = self.get_current_app() else {
+            return;
+        };
+        if let Some(pending) = self.pending_secrets.get_mut(&app_name) {
+            pending.pop();
+            if pending.is_empty() {
+                self.pending_secrets.remove(&app_name);
+            }
+        }
+    }
+    pub fn open_pending_secrets_popup(&mut self) -> RdrResult<()> {
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        let pending = self.pending_secret_changes();
+        if pending.is_empty() {
+            self.open_popup(
+                format!("{} has no pending secret changes.", app_name),
+                PopupType::InfoPopup,
+                None,
+            );
+            return Ok(());
+        }
+        let mut actions: Vec<Box<dyn FocusableWidget>> = pending
+            .iter()
+            .map(|change| CheckBox::new(&change.label(), true).boxed())
+            .collect();
+        actions.push(TextBox::new("Cancel").boxed());
+        actions.push(TextBox::new("OK").boxed());
+        let message = format!(
+            "The secret changes of {} waiting for the deploy. Uncheck the ones to un-stage.",
+            app_name
+        );
+        self.open_popup(
+            message,
+            PopupType::PendingSecretsPopup,
+            Some(Form::new(actions)),
+        );
+        Ok(())
+    }
+    /// Keeps the checked changes, the popup lists them in the order they are pending.
+    pub fn process_pending_secrets_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        let popup = self.popup.as_ref().unwrap();
+        if !self.should_take_action(&popup.actions) {
+            return Ok(None);
+        }
+        let checked = popup
+            .actions
+            .children
+            .iter()
+            .filter_map(|child| child.as_any().downcast_ref::<CheckBox>())
+            .map(CheckBox::is_check)
+            .collect::<Vec<_>>();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        if let Some(pending) = self.pending_secrets.get_mut(&app_name) {
+            let mut checked = checked.into_iter();
+            pending.retain(|_| checked.next().unwrap_or(true));
+            if pending.is_empty() {
+                self.pending_secrets.remove(&app_name);
+            }
+        }
+        Ok(None)
+    }
+    /// Moves on to the value once a key is entered.
+    pub fn focus_secret_value(&mut self) -> bool {
+        match &mut self.input_state {
+            InputState::Secret {
+                key, value_focused, ..
+            } if !*value_focused && !key.value().trim().is_empty() => {
+                *value_focused = true;
+                true
+            }
+            _ => false,
+        }
+    }
+    pub fn process_set_secret_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::Secret { key, value, .. } = &self.input_state else {
+            return Ok(None);
+        };
+        let key = key.value().trim();
+        if key.is_empty() {
+            return Err(eyre!("The key of the secret is empty."));
+        }
+        let change = SecretChange::Set {
+            key: key.to_string(),
+            value: value.value().to_string(),
+        };
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        self.stage_secret_change(app_name, change);
+        Ok(None)
+    }
+    /// Selects the rows whose ids are in the pasted text, one id per line.
+    pub fn select_pasted_ids(&mut self, text: &str) {
+        let (found, not_found): (Vec<_>, Vec<_>) = text
+            .lines()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .partition(|id| self.resource_list.items.iter().any(|row| row[0] == *id));
+        for id in &found {
+            self.resource_list.multi_select_state.insert(id.to_string());
+        }
+        if !not_found.is_empty() {
+            self.open_popup(
+                format!(
+                    "Selected {} of the pasted ids, not found: {}",
+                    found.len(),
+                    not_found.join(", ")
+                ),
+                PopupType::InfoPopup,
+                None,
+            );
+        }
+    }
     pub fn exit_multi_select(&mut self) {
         self.multi_select_mode = MultiSelectMode::Off;
         self.resource_list.multi_select_state = DashSet::new();
     }
     // Popup handling
     pub fn open_popup(&mut self, message: String, popup_type: PopupType, actions: Option<Form>) {
+        if !matches!(popup_type, PopupType::ViewDeployTokenPopup) {
+            self.deploy_token = None;
+        }
         self.popup = Some(RdrPopup::with_actions(popup_type, message, actions));
     }
+    pub fn open_wizard_popup(&mut self, popup_type: PopupType, wizard: Wizard) {
+        self.deploy_token = None;
+        self.popup = Some(RdrPopup::with_wizard(popup_type, wizard));
+    }
+    /// Goes to the next or the previous page of the wizard if Next or Back is focused, returns
+    /// whether it did.
+    pub fn turn_wizard_page(&mut self) -> bool {
+        let Some(popup) = self.popup.as_mut() else {
+            return false;
+        };
+        let forward = match popup
+            .actions
+            .children
+            .iter()
+            .find(|child| child.is_focused())
+            .and_then(|child| child.as_any().downcast_ref::<TextBox>())
+            .map(|textbox| textbox.content.as_str())
+        {
+            Some("Next") => true,
+            Some("Back") => false,
+            _ => return false,
+        };
+        let Some(wizard) = popup.wizard.as_mut() else {
+            return false;
+        };
+        let page = std::mem::replace(&mut popup.actions, Form::new(vec![]));
+        let (message, mut actions) = wizard.turn(page, forward);
+        actions.reset_focus();
+        actions.focus_first();
+        popup.message = message;
+        popup.actions = actions;
+        true
+    }
     pub fn has_popup(&self) -> bool {
         self.popup.is_some()
     }
     pub fn close_popup(&mut self) {
-        self.popup = None
+        self.popup = None;
+        self.deploy_token = None;
     }
     //INFO:Can be called only if has_popup() passes
     pub fn get_popup_type(&self) -> PopupType {
@@ -687,13 +2063,211 @@ impl State {
     }
     pub fn popup_focus_previous(&mut self) {
         if let Some(popup) = self.popup.as_mut() {
-            popup.actions.focus_previous();
+            popup.actions.cycle_focus(false);
         }
     }
     pub fn popup_focus_next(&mut self) {
         if let Some(popup) = self.popup.as_mut() {
-            popup.actions.focus_next();
+            popup.actions.cycle_focus(true);
+        }
+    }
+    pub fn popup_focus_ok(&mut self) {
+        if let Some(popup) = self.popup.as_mut() {
+            //INFO: OK is the last action of the confirmation popups
+            popup.actions.reset_focus();
+            popup.actions.focus_last();
+        }
+    }
+    /// Operation of the open confirmation popup and the name to type to confirm it, the name of
+    /// the resource or the app for the selected machines and secrets.
+    pub fn confirmation(&self) -> Option<(&'static str, String)> {
+        let popup = self.popup.as_ref()?;
+        if !matches!(self.input_state, InputState::Hidden) {
+            return None;
+        }
+        let selected = self.resource_list.selected().cloned();
+        match (&popup.popup_type, self.get_current_view()) {
+            (PopupType::DestroyResourcePopup, View::Organizations { .. }) => {
+                let org: ListOrganization = selected?.into();
+                Some(("destroy_organization", org.slug))
+            }
+            (PopupType::DestroyResourcePopup, View::Apps { .. }) => {
+                let app: ListApp = selected?.into();
+                Some(("destroy_app", app.name))
+            }
+            (PopupType::RestartResourcePopup, View::Apps { .. }) => {
+                let app: ListApp = selected?.into();
+                Some(("restart_app", app.name))
+            }
+            (PopupType::DestroyResourcePopup, View::Machines { .. }) => {
+                let machine: ListMachine = selected?.into();
+                //INFO: Can be confirmed more strictly than the rest of the machines
+                if self.is_last_started_machine(&machine) {
+                    Some(("destroy_last_machine", machine.id))
+                } else {
+                    Some(("destroy_machine", machine.id))
+                }
+            }
+            (PopupType::KillMachinePopup, View::Machines { .. }) => {
+                let machine: ListMachine = selected?.into();
+                Some(("kill_machine", machine.id))
+            }
+            (PopupType::DestroyResourcePopup, View::Volumes { .. }) => {
+                let volume: ListVolume = selected?.into();
+                Some(("destroy_volume", volume.id))
+            }
+            (PopupType::DestroyResourcePopup, View::Secrets { app_name, .. }) => {
+                Some(("unset_secrets", app_name))
+            }
+            (PopupType::RestartResourcePopup, View::Machines { app_name, .. }) => {
+                Some(("restart_machines", app_name))
+            }
+            (PopupType::StartMachinesPopup, View::Machines { app_name, .. }) => {
+                Some(("start_machines", app_name))
+            }
+            (PopupType::SuspendMachinesPopup, View::Machines { app_name, .. }) => {
+                Some(("suspend_machines", app_name))
+            }
+            (PopupType::StopMachinesPopup, View::Machines { app_name, .. }) => {
+                Some(("stop_machines", app_name))
+            }
+            (PopupType::CordonMachinesPopup, View::Machines { app_name, .. }) => {
+                Some(("cordon_machines", app_name))
+            }
+            (PopupType::UncordonMachinesPopup, View::Machines { app_name, .. }) => {
+                Some(("uncordon_machines", app_name))
+            }
+            (PopupType::AlignMachinesPopup, View::Machines { app_name, .. }) => {
+                Some(("align_machines", app_name))
+            }
+            (PopupType::ExecMachinePopup, View::Machines { app_name, .. }) => {
+                Some(("exec_machine", app_name))
+            }
+            (PopupType::ScaleMachinePopup, View::Machines { app_name, .. }) => {
+                Some(("scale_machine", app_name))
+            }
+            (PopupType::DeploySecretsPopup, View::Secrets { app_name, .. }) => {
+                Some(("deploy_secrets", app_name))
+            }
+            (PopupType::SnapshotVolumePopup, View::Volumes { .. }) => {
+                let volume: ListVolume = selected?.into();
+                Some(("snapshot_volume", volume.id))
+            }
+            (PopupType::DetachVolumePopup, View::Volumes { .. }) => {
+                let volume: ListVolume = selected?.into();
+                Some(("detach_volume", volume.id))
+            }
+            (PopupType::UpdateAppAutoscalingPopup, View::Apps { .. }) => {
+                let app: ListApp = selected?.into();
+                Some(("update_autoscaling", app.name))
+            }
+            (PopupType::UpdateAppServicesPopup, View::Apps { .. }) => {
+                let app: ListApp = selected?.into();
+                Some(("update_services", app.name))
+            }
+            (PopupType::RollbackReleasePopup, View::Apps { .. }) => {
+                let app: ListApp = selected?.into();
+                Some(("rollback_release", app.name))
+            }
+            (PopupType::DestroyResourcePopup, View::WireGuardPeers { .. }) => {
+                let peer: ListWireGuardPeer = selected?.into();
+                Some(("remove_wireguard_peer", peer.name))
+            }
+            _ => None,
+        }
+    }
+    pub fn confirmation_level(&self, operation: &str) -> Confirmation {
+        self.rdr_config
+            .confirmations
+            .get(operation)
+            .copied()
+            .unwrap_or_default()
+    }
+    /// Operations filled in with a popup of their own, which already asks for them, so only the
+    /// typed confirmation adds a step. Returns the event back when it can run right away.
+    pub fn hold_for_typed_confirmation(&mut self, event: IoReqEvent) -> Option<IoReqEvent> {
+        let (operation, name, message) = match &event {
+            IoReqEvent::DeleteOrganizationMembership { org_slug, email } => (
+                "remove_member",
+                email.clone(),
+                format!("Are you sure to remove {} from {}?", email, org_slug),
+            ),
+            IoReqEvent::CreateDeployToken { app_name, .. } => (
+                "create_deploy_token",
+                app_name.clone(),
+                format!("Are you sure to create a deploy token for {}?", app_name),
+            ),
+            IoReqEvent::CleanupApp {
+                app_name,
+                machines,
+                volumes,
+            } => (
+                "cleanup_app",
+                app_name.clone(),
+                format!(
+                    "Are you sure to destroy {} machines and {} volumes of {}?",
+                    machines.len(),
+                    volumes.len(),
+                    app_name
+                ),
+            ),
+            IoReqEvent::RenameMachine {
+                machine_id, name, ..
+            } => (
+                "rename_machine",
+                machine_id.clone(),
+                format!("Are you sure to rename {} to {}?", machine_id, name),
+            ),
+            IoReqEvent::UpdateMachinesImage {
+                app_name,
+                machines,
+                tag,
+                ..
+            } => (
+                "update_machines_image",
+                app_name.clone(),
+                format!(
+                    "Are you sure to update {} machines of {} to {}?",
+                    machines.len(),
+                    app_name,
+                    tag
+                ),
+            ),
+            IoReqEvent::ForkVolume { params, .. } => (
+                "fork_volume",
+                params.id.clone(),
+                format!("Are you sure to fork {} into {}?", params.id, params.name),
+            ),
+            _ => return Some(event),
+        };
+        if self.confirmation_level(operation) != Confirmation::Typed {
+            return Some(event);
+        }
+        self.awaiting_confirmation = Some(event);
+        self.open_popup(message, PopupType::ConfirmOperationPopup, None);
+        self.start_typed_confirmation(name);
+        None
+    }
+    pub fn process_confirm_operation_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        let event = self.awaiting_confirmation.take();
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        Ok(event)
+    }
+    pub fn start_typed_confirmation(&mut self, name: String) {
+        if let Some(popup) = self.popup.as_mut() {
+            popup
+                .message
+                .push_str(&format!("\n\nType {} to confirm.", name));
         }
+        self.input_state = InputState::Confirm {
+            input: Input::default(),
+            name,
+        };
+    }
+    pub fn is_confirmation_typed(&self) -> bool {
+        matches!(&self.input_state, InputState::Confirm { input, name } if input.value() == name)
     }
     pub fn should_take_action(&self, actions: &Form) -> bool {
         actions
@@ -703,35 +2277,54 @@ impl State {
             .and_then(|focused_action| focused_action.as_any().downcast_ref::<TextBox>())
             .is_some_and(|textbox| textbox.content == "OK")
     }
-    pub fn toggle_force_checkbox(&mut self) {
-        let current_view = self.get_current_view();
-        if let Some(popup) = self.popup.as_mut() {
-            match popup.popup_type {
-                PopupType::RestartResourcePopup => {
-                    let checkbox = popup.actions.children[0].as_mut();
-                    if checkbox.is_focused() {
-                        checkbox
-                            .as_any_mut()
-                            .downcast_mut::<CheckBox>()
-                            .unwrap()
-                            .toggle();
-                    }
-                }
-                PopupType::DestroyResourcePopup
-                    if matches!(current_view, View::Machines { .. }) =>
-                {
-                    let checkbox = popup.actions.children[0].as_mut();
-                    if checkbox.is_focused() {
-                        checkbox
-                            .as_any_mut()
-                            .downcast_mut::<CheckBox>()
-                            .unwrap()
-                            .toggle();
-                    }
-                }
-                _ => {}
-            }
-        }
+    /// Toggles the checkbox of the popup's form if it's focused, returns whether it did.
+    pub fn toggle_focused_checkbox(&mut self) -> bool {
+        let Some(checkbox) = self.popup.as_mut().and_then(|popup| {
+            popup
+                .actions
+                .children
+                .iter_mut()
+                .find(|child| child.is_focused())
+                .and_then(|child| child.as_any_mut().downcast_mut::<CheckBox>())
+        }) else {
+            return false;
+        };
+        checkbox.toggle();
+        true
+    }
+    /// Field of the popup's form that is being typed in.
+    pub fn focused_popup_field(&mut self) -> Option<&mut InputField> {
+        self.popup
+            .as_mut()?
+            .actions
+            .children
+            .iter_mut()
+            .find(|child| child.is_focused())
+            .and_then(|child| child.as_any_mut().downcast_mut::<InputField>())
+    }
+    /// Number field of the popup's form that is being typed in or spun.
+    pub fn focused_popup_number(&mut self) -> Option<&mut NumberField> {
+        self.popup
+            .as_mut()?
+            .actions
+            .children
+            .iter_mut()
+            .find(|child| child.is_focused())
+            .and_then(|child| child.as_any_mut().downcast_mut::<NumberField>())
+    }
+    /// Value of a number field of the popup's form, an error if it's out of its bounds.
+    fn popup_number(&self, label: &str) -> RdrResult<u32> {
+        self.popup
+            .as_ref()
+            .and_then(|popup| {
+                popup
+                    .fields()
+                    .filter_map(|child| child.as_any().downcast_ref::<NumberField>())
+                    .find(|field| field.label == label)
+            })
+            .ok_or_else(|| eyre!("No {} field.", label))?
+            .value()
+            .map_err(|err| eyre!(err))
     }
     //INFO:Can be called only if has_popup() passes
     pub fn should_process_popup(&self) -> bool {
@@ -761,11 +2354,19 @@ impl State {
             }
             View::Apps { .. } => {
                 let app: ListApp = selected_resource.into();
-                message = format!("{} app: {}?", message, app.name);
+                message = format!(
+                    "{} app: {}?{}",
+                    message,
+                    app.name,
+                    destroy_app_preview_placeholder(&app.name)
+                );
             }
-            View::Machines { .. } => {
+            View::Machines { ref app_name, .. } => {
                 let machine: ListMachine = selected_resource.into();
                 message = format!("{} machine: {}?", message, machine.id);
+                if self.is_last_started_machine(&machine) {
+                    message.push_str(&format!("\n\nWarning! This is the only started machine of {}. Destroying it leaves the app with nothing to serve its requests until a new machine is deployed, and it is not reversible.", app_name));
+                }
                 self.open_popup(
                     message,
                     PopupType::DestroyResourcePopup,
@@ -809,14 +2410,31 @@ impl State {
                     "Are you sure to stage unset the selected secrets: {}?",
                     keys,
                 );
-                message.push_str("\n\nWarning! This will be staged but won't affect VMs. Run \"fly secrets deploy\" for this app to apply the changes.");
+                message.push_str(&staged_secrets_warning(&self.get_current_view()));
+            }
+            View::WireGuardPeers { .. } => {
+                let peer: ListWireGuardPeer = selected_resource.into();
+                message = format!(
+                    "Are you sure to remove the WireGuard peer: {}? The tunnels using it will stop working.",
+                    peer.name
+                );
             }
             _ => {}
         }
         self.open_popup(message, PopupType::DestroyResourcePopup, None);
         Ok(())
     }
-    pub fn process_destroy_resource_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+    fn is_last_started_machine(&self, machine: &ListMachine) -> bool {
+        machine.state == "started"
+            && self
+                .resource_list
+                .items
+                .iter()
+                .filter(|item| ListMachine::from((*item).clone()).state == "started")
+                .count()
+                == 1
+    }
+    pub fn process_destroy_resource_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
         if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
             return Ok(None);
         }
@@ -870,11 +2488,21 @@ impl State {
                     .multi_select_state
                     .clone()
                     .into_iter()
-                    .collect();
-                Ok(Some(IoReqEvent::UnsetSecrets {
-                    seq_id: self.get_seq_id(ResourceType::Secrets),
-                    app_name,
-                    keys,
+                    .sorted()
+                    .collect::<Vec<_>>();
+                for key in keys {
+                    self.stage_secret_change(app_name.clone(), SecretChange::Unset { key });
+                }
+                self.exit_multi_select();
+                Ok(None)
+            }
+            View::WireGuardPeers { org_id, org_slug } => {
+                let peer: ListWireGuardPeer = self.get_selected_resource()?.into();
+                Ok(Some(IoReqEvent::RemoveWireGuardPeer {
+                    seq_id: self.get_seq_id(ResourceType::WireGuardPeers),
+                    org_id,
+                    org_slug,
+                    name: peer.name,
                 }))
             }
             _ => Ok(None),
@@ -977,7 +2605,7 @@ impl State {
     pub fn open_delete_organization_membership_popup(&mut self) -> RdrResult<()> {
         let org: ListOrganization = self.get_selected_resource()?.into();
         let message = format!(
-            "Remove a user from this organization {}. User must have accepted a previous invitation to join.",
+            "Remove a user from this organization {}. User must have accepted a previous invitation to join. Press Tab to complete the email from the members.",
             org.name
         );
         self.input_state = InputState::Email {
@@ -986,6 +2614,22 @@ impl State {
         self.open_popup(message, PopupType::DeleteOrganizationMembershipPopup, None);
         Ok(())
     }
+    /// Completes the email to the longest prefix shared by the matching members, ignoring the case.
+    pub fn complete_member_email(&mut self) {
+        if let InputState::Email { input } = &mut self.input_state {
+            let value = input.value().to_lowercase();
+            let candidates = self
+                .organization_members_list
+                .iter()
+                .filter_map(|member| member.get(1))
+                .filter(|email| email.to_lowercase().starts_with(&value))
+                .cloned()
+                .collect::<Vec<_>>();
+            if let Some(email) = common_prefix(&candidates) {
+                *input = Input::new(email);
+            }
+        }
+    }
     pub fn process_delete_organization_membership_popup(&self) -> RdrResult<Option<IoReqEvent>> {
         if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
             Ok(None)
@@ -1002,7 +2646,232 @@ impl State {
             }))
         }
     }
-    pub fn open_view_organization_members_popup(&mut self) -> RdrResult<()> {
+    pub fn open_dump_logs_popup(&mut self, resource_info: String) {
+        let format = self.rdr_config.log_dump_format;
+        self.input_state = InputState::FilePath {
+            input: Input::new(dump_file_path(resource_info, format)),
+        };
+        self.open_popup(
+            String::from("Dump the logs to a file, a .ndjson or .jsonl path writes them as JSON with their metadata. Press Tab to complete the path."),
+            PopupType::DumpLogsPopup,
+            None,
+        );
+    }
+    pub fn open_tee_logs_popup(&mut self, resource_info: String) {
+        let format = self.rdr_config.log_dump_format;
+        self.input_state = InputState::FilePath {
+            input: Input::new(dump_file_path(resource_info, format)),
+        };
+        self.open_popup(
+            String::from("Append the logs to a file as they stream in, until the tee is turned off or the logs are left. A .ndjson or .jsonl path writes them as JSON. Press Tab to complete the path."),
+            PopupType::TeeLogsPopup,
+            None,
+        );
+    }
+    /// Turns the tee off if it is on, otherwise asks for the file to tee the logs to.
+    pub async fn toggle_log_tee(&mut self, resource_info: String) {
+        if self.log_tee.is_some() {
+            self.dispatch(IoReqEvent::TeeLogs { file_path: None }).await;
+        } else {
+            self.open_tee_logs_popup(resource_info);
+        }
+    }
+    /// Takes a snapshot of the stats, the logs keep streaming while the popup is open.
+    pub fn open_view_log_stats_popup(&mut self) {
+        self.log_stats = log_stats(TOP_ERROR_MESSAGES);
+        self.open_popup(
+            String::from("Stats of the buffered logs"),
+            PopupType::ViewLogStatsPopup,
+            None,
+        );
+    }
+    pub fn open_view_log_line_popup(&mut self) {
+        let Some(detail) = self.logs_state.bottom_line_detail() else {
+            return;
+        };
+        self.log_line_detail = detail;
+        self.popup_scroll = 0;
+        self.open_popup(
+            String::from("Line at the bottom of the logs (<↑/↓>, <PageUp/Down> to scroll)"),
+            PopupType::ViewLogLinePopup,
+            None,
+        );
+    }
+    /// The logs are shown without the borders and stop following the new lines, so the
+    /// terminal's own selection can copy them. The main loop releases the mouse meanwhile.
+    pub fn enter_copy_mode(&mut self) {
+        self.copy_mode = Some(self.logs_state.hold());
+    }
+    pub fn exit_copy_mode(&mut self) {
+        if let Some(true) = self.copy_mode.take() {
+            self.logs_state.transition(TuiWidgetEvent::EscapeKey);
+        }
+    }
+    pub fn is_copy_mode(&self) -> bool {
+        self.copy_mode.is_some()
+    }
+    /// Whether the keys go to a secret value, which is masked and kept out of recordings.
+    pub fn is_typing_secret(&self) -> bool {
+        matches!(
+            self.input_state,
+            InputState::Secret {
+                value_focused: true,
+                ..
+            }
+        )
+    }
+    pub fn open_capture_packets_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        self.input_state = InputState::FilePath {
+            input: Input::new(capture_file_path(&machine.id)),
+        };
+        self.open_popup(
+            format!(
+                "Capture the packets of {} with tcpdump for up to 10 seconds or 1000 packets and save the pcap to a file. Press Tab to complete the path.",
+                machine.id
+            ),
+            PopupType::CapturePacketsPopup,
+            None,
+        );
+        Ok(())
+    }
+    pub fn open_export_view_popup(&mut self) {
+        let view = self.get_current_view();
+        self.input_state = InputState::FilePath {
+            input: Input::new(export_file_path(
+                format!("{}-{}", view.to_breadcrumb(), view.to_scope())
+                    .replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "-"),
+            )),
+        };
+        self.open_popup(
+            format!(
+                "Export the {} rows shown as a Markdown table. Press Tab to complete the path, leave it empty to copy the table to the clipboard.",
+                self.resource_list.filtered_items.len()
+            ),
+            PopupType::ExportViewPopup,
+            None,
+        );
+    }
+    pub fn process_export_view_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::FilePath { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let file_path = input.value().trim();
+        Ok(Some(IoReqEvent::ExportView {
+            table: to_markdown_table(
+                &self.get_current_view().headers(),
+                &self.resource_list.filtered_items,
+            ),
+            file_path: (!file_path.is_empty()).then(|| expand_file_path(file_path)),
+        }))
+    }
+    pub fn process_capture_packets_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let (View::Machines { app_name, .. }, InputState::FilePath { input }) =
+            (self.get_current_view(), &self.input_state)
+        else {
+            return Ok(None);
+        };
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        Ok(Some(IoReqEvent::CapturePackets {
+            app_name,
+            machine_id: machine.id,
+            file_path: expand_file_path(input.value().trim()),
+        }))
+    }
+    pub fn process_tee_logs_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::FilePath { input } = &self.input_state else {
+            return Ok(None);
+        };
+        Ok(Some(IoReqEvent::TeeLogs {
+            file_path: Some(expand_file_path(input.value().trim())),
+        }))
+    }
+    pub fn complete_dump_logs_file_path(&mut self) {
+        if let InputState::FilePath { input } = &mut self.input_state {
+            if let Some(path) = complete_file_path(input.value()) {
+                *input = Input::new(path);
+            }
+        }
+    }
+    pub fn process_dump_logs_popup(&self) -> RdrResult<Option<PathBuf>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            Ok(None)
+        } else if let InputState::FilePath { input } = &self.input_state {
+            Ok(Some(expand_file_path(input.value().trim())))
+        } else {
+            Ok(None)
+        }
+    }
+    //INFO: Asks for confirmation first if the file already exists
+    pub async fn dump_logs(&mut self, file_path: PathBuf) {
+        if file_path.exists() {
+            let message = format!(
+                "{} already exists. Do you want to overwrite it?",
+                file_path.to_string_lossy()
+            );
+            self.dump_logs_file_path = Some(file_path);
+            self.open_popup(message, PopupType::OverwriteLogsDumpPopup, None);
+        } else {
+            self.dispatch(IoReqEvent::DumpLogs { file_path }).await;
+        }
+    }
+    pub fn process_overwrite_logs_dump_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            Ok(None)
+        } else {
+            let file_path = self
+                .dump_logs_file_path
+                .clone()
+                .ok_or_eyre("No file path to dump the logs to")?;
+            Ok(Some(IoReqEvent::DumpLogs { file_path }))
+        }
+    }
+    /// Drops the cached responses and refetches the organizations, or the members/releases when
+    /// their popup is open.
+    pub async fn refresh(&mut self) -> RdrResult<()> {
+        let refetch = match self.popup.as_ref().map(|popup| &popup.popup_type) {
+            Some(PopupType::ViewOrganizationMembersPopup) => {
+                let org: ListOrganization = self.get_selected_resource()?.into();
+                self.clear_organization_members_list();
+                Some(IoReqEvent::ViewOrganizationMembers { org_slug: org.slug })
+            }
+            Some(PopupType::ViewAppReleasesPopup) => {
+                let app: ListApp = self.get_selected_resource()?.into();
+                self.clear_app_releases_list();
+                Some(IoReqEvent::ViewAppReleases { app_name: app.name })
+            }
+            Some(PopupType::ViewAppServicesPopup) => {
+                let app: ListApp = self.get_selected_resource()?.into();
+                Some(IoReqEvent::ViewAppServices { app_name: app.name })
+            }
+            Some(PopupType::ViewAppAutoscalingPopup) => {
+                let app: ListApp = self.get_selected_resource()?.into();
+                Some(IoReqEvent::ViewAppAutoscaling { app_name: app.name })
+            }
+            _ => match self.get_current_view() {
+                View::Organizations { filter } => Some(IoReqEvent::ListOrganizations {
+                    seq_id: self.get_seq_id(ResourceType::Organizations) + 1,
+                    filter,
+                }),
+                _ => None,
+            },
+        };
+        self.dispatch(IoReqEvent::ClearCache {
+            refetch: refetch.map(Box::new),
+        })
+        .await;
+        Ok(())
+    }
+    pub fn open_view_organization_members_popup(&mut self) -> RdrResult<()> {
         let org: ListOrganization = self.get_selected_resource()?.into();
         let message = format!("Members of {}", org.slug);
         self.open_popup(message, PopupType::ViewOrganizationMembersPopup, None);
@@ -1013,27 +2882,642 @@ impl State {
     }
     pub fn open_view_app_releases_popup(&mut self) -> RdrResult<()> {
         let app: ListApp = self.get_selected_resource()?.into();
-        let message = format!("Releases of {}", app.name);
+        let message = format!(
+            "Releases of {} (<Space> to mark two releases, <Enter> to compare them, <r> to roll back to the selected one)",
+            app.name
+        );
         self.open_popup(message, PopupType::ViewAppReleasesPopup, None);
         Ok(())
     }
     pub fn clear_app_releases_list(&mut self) {
         self.app_releases_list = vec![];
+        self.app_releases_cursor = 0;
+        self.app_releases_marked = vec![];
+    }
+    pub fn app_releases_next(&mut self) {
+        if !self.app_releases_list.is_empty() {
+            self.app_releases_cursor =
+                (self.app_releases_cursor + 1) % self.app_releases_list.len();
+        }
+    }
+    pub fn app_releases_previous(&mut self) {
+        if !self.app_releases_list.is_empty() {
+            self.app_releases_cursor = self
+                .app_releases_cursor
+                .checked_sub(1)
+                .unwrap_or(self.app_releases_list.len() - 1);
+        }
+    }
+    //INFO: Only two releases are compared, marking a third one drops the earliest mark
+    pub fn toggle_app_release_mark(&mut self) {
+        if let Some(release) = self.app_releases_list.get(self.app_releases_cursor) {
+            let version = release[0].clone();
+            match self.app_releases_marked.iter().position(|v| *v == version) {
+                Some(index) => {
+                    self.app_releases_marked.remove(index);
+                }
+                None => {
+                    if self.app_releases_marked.len() == 2 {
+                        self.app_releases_marked.remove(0);
+                    }
+                    self.app_releases_marked.push(version);
+                }
+            }
+        }
+    }
+    pub fn process_view_app_releases_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        match self.app_releases_marked.as_slice() {
+            [from, to] => {
+                let app: ListApp = self.get_selected_resource()?.into();
+                Ok(Some(IoReqEvent::DiffAppReleases {
+                    app_name: app.name,
+                    from: from.parse()?,
+                    to: to.parse()?,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+    pub fn open_rollback_release_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let release = self
+            .app_releases_list
+            .get(self.app_releases_cursor)
+            .ok_or_eyre("No release selected.")?;
+        let message = format!(
+            "Are you sure to roll {} back to v{}? Its machines are updated to {} one at a time.",
+            app.name, release[0], release[5]
+        );
+        self.open_popup(message, PopupType::RollbackReleasePopup, None);
+        Ok(())
+    }
+    pub fn process_rollback_release_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let app: ListApp = self.get_selected_resource()?.into();
+        let release = self
+            .app_releases_list
+            .get(self.app_releases_cursor)
+            .ok_or_eyre("No release selected.")?;
+        Ok(Some(IoReqEvent::RollbackRelease {
+            app_name: app.name,
+            version: release[0].parse()?,
+            image_ref: release[5].clone(),
+        }))
+    }
+    pub fn open_create_deploy_token_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!(
+            "Create a token that can only deploy {}, for CI or a teammate.",
+            app.name
+        );
+        self.open_popup(message, PopupType::CreateDeployTokenPopup, None);
+        Ok(())
+    }
+    pub fn process_create_deploy_token_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        let popup = self.popup.as_ref().unwrap();
+        if !self.should_take_action(&popup.actions) {
+            return Ok(None);
+        }
+        let fields = popup
+            .actions
+            .children
+            .iter()
+            .filter_map(|child| child.as_any().downcast_ref::<InputField>())
+            .map(|field| (field.label.as_str(), field.value()))
+            .collect::<HashMap<_, _>>();
+        let name = fields["Name"];
+        let expiry_days = self.popup_number("Expiry (days)")?;
+        //INFO: The popup stays open until the required fields are filled
+        if name.is_empty() {
+            return Err(eyre!("Name is required."));
+        }
+        let app: ListApp = self.get_selected_resource()?.into();
+        Ok(Some(IoReqEvent::CreateDeployToken {
+            app_name: app.name,
+            name: name.to_string(),
+            expiry_days,
+        }))
+    }
+    pub fn open_create_wire_guard_peer_popup(&mut self) {
+        let message = String::from(
+            "Add a WireGuard peer to the organization. Generate its key pair with `wg genkey | tee private.key | wg pubkey`, the private key never leaves your machine.",
+        );
+        self.open_popup(message, PopupType::CreateWireGuardPeerPopup, None);
+    }
+    pub fn process_create_wire_guard_peer_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        let popup = self.popup.as_ref().unwrap();
+        if !self.should_take_action(&popup.actions) {
+            return Ok(None);
+        }
+        let fields = popup
+            .actions
+            .children
+            .iter()
+            .filter_map(|child| child.as_any().downcast_ref::<InputField>())
+            .map(|field| (field.label.as_str(), field.value()))
+            .collect::<HashMap<_, _>>();
+        let (name, region, public_key) = (fields["Name"], fields["Region"], fields["Public key"]);
+        //INFO: The popup stays open until the required fields are filled
+        if name.is_empty() || region.is_empty() || public_key.is_empty() {
+            return Err(eyre!("Name, region and public key are required."));
+        }
+        let View::WireGuardPeers { org_id, org_slug } = self.get_current_view() else {
+            return Ok(None);
+        };
+        Ok(Some(IoReqEvent::CreateWireGuardPeer {
+            seq_id: self.get_seq_id(ResourceType::WireGuardPeers),
+            org_id,
+            org_slug,
+            name: name.to_string(),
+            region: region.to_string(),
+            public_key: public_key.to_string(),
+        }))
+    }
+    pub fn copy_deploy_token(&mut self) -> RdrResult<()> {
+        let token = self.deploy_token.as_ref().ok_or_eyre("No token to copy.")?;
+        self.clipboard = Some(token.clone());
+        if let Some(popup) = self.popup.as_mut() {
+            popup.message =
+                popup
+                    .message
+                    .replacen(&deploy_token_copy_hint(), DEPLOY_TOKEN_COPIED, 1);
+        }
+        Ok(())
+    }
+    pub fn open_view_app_releases_diff_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!(
+            "Changes of {} between v{} (<↑/↓>, <PageUp/Down> to scroll)",
+            app.name,
+            self.app_releases_marked
+                .iter()
+                .sorted_by_key(|version| version.parse::<i64>().unwrap_or_default())
+                .join(" and v")
+        );
+        self.app_releases_diff_list = vec![];
+        self.popup_scroll = 0;
+        self.open_popup(message, PopupType::ViewAppReleasesDiffPopup, None);
+        Ok(())
     }
     pub fn open_view_app_services_popup(&mut self) -> RdrResult<()> {
         let app: ListApp = self.get_selected_resource()?.into();
-        let message = format!("Services of {}", app.name);
+        let message = format!(
+            "Services of {} (<e> edit, <a> add a port, <d> remove the port)",
+            app.name
+        );
         self.open_popup(message, PopupType::ViewAppServicesPopup, None);
         Ok(())
     }
     pub fn clear_app_services_list(&mut self) {
         self.app_services_list = vec![];
+        self.app_services_cursor = 0;
+    }
+    pub fn app_services_next(&mut self) {
+        if !self.app_services_list.is_empty() {
+            self.app_services_cursor =
+                (self.app_services_cursor + 1) % self.app_services_list.len();
+        }
+    }
+    pub fn app_services_previous(&mut self) {
+        if !self.app_services_list.is_empty() {
+            self.app_services_cursor = self
+                .app_services_cursor
+                .checked_sub(1)
+                .unwrap_or(self.app_services_list.len() - 1);
+        }
+    }
+    fn selected_app_service(&self) -> RdrResult<ServiceEdit> {
+        let row = self
+            .app_services_list
+            .get(self.app_services_cursor)
+            .ok_or_eyre("No service selected.")?;
+        ServiceEdit::from_row(row)
+    }
+    /// Edits the selected port and the internal port of its service, or adds a port to the
+    /// service.
+    pub fn open_edit_app_service_popup(&mut self, add: bool) -> RdrResult<()> {
+        let mut edit = self.selected_app_service()?;
+        let message = if add {
+            edit.port = None;
+            format!(
+                "Add a port to the {} service on {}, the handlers are separated by commas.",
+                edit.protocol.to_uppercase(),
+                edit.internal_port
+            )
+        } else {
+            String::from("Edit the port, the handlers are separated by commas. Changing the internal port changes it for all the ports of the service.")
+        };
+        let port = edit.port.clone();
+        let port_field = NumberField::new("Port", 1, u16::MAX.into());
+        let port_field = match &port {
+            Some(port) => port_field.with_value(port.port as u32),
+            None => port_field,
+        };
+        let actions = Form::from_iter([
+            NumberField::new("Internal port", 1, u16::MAX.into())
+                .with_value(edit.internal_port as u32)
+                .boxed(),
+            port_field.boxed(),
+            InputField::new(
+                "Handlers",
+                &port
+                    .as_ref()
+                    .map(|port| port.handlers.join(","))
+                    .unwrap_or_default(),
+            )
+            .boxed(),
+            CheckBox::new("Force https", port.is_some_and(|port| port.force_https)).boxed(),
+            TextBox::new("Cancel").boxed(),
+            TextBox::new("OK").boxed(),
+        ]);
+        self.app_service_edit = Some(edit);
+        self.open_popup(message, PopupType::EditAppServicePopup, Some(actions));
+        Ok(())
+    }
+    //INFO: Nothing is dispatched yet, the staged edit is confirmed with its diff first
+    pub fn process_edit_app_service_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        let popup = self.popup.as_ref().unwrap();
+        if !self.should_take_action(&popup.actions) {
+            self.app_service_edit = None;
+            return Ok(None);
+        }
+        let fields = popup
+            .actions
+            .children
+            .iter()
+            .filter_map(|child| child.as_any().downcast_ref::<InputField>())
+            .map(|field| (field.label.as_str(), field.value()))
+            .collect::<HashMap<_, _>>();
+        let force_https = popup
+            .actions
+            .children
+            .iter()
+            .find_map(|child| child.as_any().downcast_ref::<CheckBox>())
+            .is_some_and(|checkbox| checkbox.is_check());
+        //INFO: The popup stays open until the ports are valid
+        let internal_port = self.popup_number("Internal port")?;
+        let port = self.popup_number("Port")?;
+        let handlers = fields["Handlers"]
+            .split(',')
+            .map(|handler| handler.trim().to_lowercase())
+            .filter(|handler| !handler.is_empty())
+            .collect();
+        let edit = self
+            .app_service_edit
+            .as_mut()
+            .ok_or_eyre("No service selected.")?;
+        edit.new_internal_port = internal_port as i32;
+        edit.new_port = Some(ServicePort {
+            port: port as i32,
+            handlers,
+            force_https,
+        });
+        if edit.is_noop() {
+            return Err(eyre!("Nothing changed."));
+        }
+        Ok(None)
+    }
+    pub fn has_app_service_edit(&self) -> bool {
+        self.app_service_edit.is_some()
+    }
+    pub fn open_remove_app_service_port_popup(&mut self) -> RdrResult<()> {
+        let mut edit = self.selected_app_service()?;
+        edit.new_port = None;
+        self.app_service_edit = Some(edit);
+        self.open_update_app_services_popup()
+    }
+    pub fn open_update_app_services_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let edit = self
+            .app_service_edit
+            .as_ref()
+            .ok_or_eyre("No service selected.")?;
+        let machines = self
+            .app_services_list
+            .get(self.app_services_cursor)
+            .map(|row| row[6].clone())
+            .unwrap_or_default();
+        let message = format!(
+            "Are you sure to update the {} service on {} across the machines of {} ({} machines)? They are updated one at a time.\n\n{}",
+            edit.protocol.to_uppercase(),
+            edit.internal_port,
+            app.name,
+            machines,
+            edit.diff().join("\n")
+        );
+        self.open_popup(message, PopupType::UpdateAppServicesPopup, None);
+        Ok(())
+    }
+    pub fn process_update_app_services_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        let edit = self.app_service_edit.take();
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let app: ListApp = self.get_selected_resource()?.into();
+        Ok(edit.map(|edit| IoReqEvent::UpdateAppServices {
+            app_name: app.name,
+            edit,
+        }))
+    }
+    pub fn open_view_app_env_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!("Env vars of {} across its machines", app.name);
+        self.open_popup(message, PopupType::ViewAppEnvPopup, None);
+        Ok(())
+    }
+    pub fn clear_app_env_list(&mut self) {
+        self.app_env_list = vec![];
+    }
+    pub fn open_view_app_ports_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!("Listen sockets of the started machines of {}", app.name);
+        self.open_popup(message, PopupType::ViewAppPortsPopup, None);
+        Ok(())
+    }
+    pub fn clear_app_ports_list(&mut self) {
+        self.app_ports_list = vec![];
+    }
+    pub fn open_app_cleanup_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!(
+            "Cleanup of {} (<Space> to mark, <a> to mark all, OK destroys the marked ones)",
+            app.name
+        );
+        self.open_popup(message, PopupType::AppCleanupPopup, None);
+        Ok(())
+    }
+    pub fn clear_app_cleanup_list(&mut self) {
+        self.app_cleanup_list = vec![];
+        self.app_cleanup_cursor = 0;
+        self.app_cleanup_marked = HashSet::new();
+    }
+    pub fn app_cleanup_next(&mut self) {
+        if !self.app_cleanup_list.is_empty() {
+            self.app_cleanup_cursor = (self.app_cleanup_cursor + 1) % self.app_cleanup_list.len();
+        }
+    }
+    pub fn app_cleanup_previous(&mut self) {
+        if !self.app_cleanup_list.is_empty() {
+            self.app_cleanup_cursor = self
+                .app_cleanup_cursor
+                .checked_sub(1)
+                .unwrap_or(self.app_cleanup_list.len() - 1);
+        }
+    }
+    pub fn toggle_app_cleanup_mark(&mut self) {
+        if let Some(finding) = self.app_cleanup_list.get(self.app_cleanup_cursor) {
+            if !self.app_cleanup_marked.remove(&finding[1]) {
+                self.app_cleanup_marked.insert(finding[1].clone());
+            }
+        }
+    }
+    pub fn toggle_app_cleanup_mark_all(&mut self) {
+        if self.app_cleanup_marked.len() == self.app_cleanup_list.len() {
+            self.app_cleanup_marked.clear();
+        } else {
+            self.app_cleanup_marked = self
+                .app_cleanup_list
+                .iter()
+                .map(|finding| finding[1].clone())
+                .collect();
+        }
+    }
+    pub fn process_app_cleanup_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions)
+            || self.app_cleanup_marked.is_empty()
+        {
+            return Ok(None);
+        }
+        let app: ListApp = self.get_selected_resource()?.into();
+        let marked = |kind: &str| {
+            self.app_cleanup_list
+                .iter()
+                .filter(|finding| {
+                    finding[0] == kind && self.app_cleanup_marked.contains(&finding[1])
+                })
+                .map(|finding| finding[1].clone())
+                .collect::<Vec<_>>()
+        };
+        Ok(Some(IoReqEvent::CleanupApp {
+            app_name: app.name,
+            machines: marked(CLEANUP_KIND_MACHINE),
+            volumes: marked(CLEANUP_KIND_VOLUME),
+        }))
+    }
+    pub fn open_view_app_autoscaling_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!(
+            "Autoscaling of {} (<a> autostop, <s> autostart, <+/-> min machines running, <Enter> to apply the changes)",
+            app.name
+        );
+        self.open_popup(message, PopupType::ViewAppAutoscalingPopup, None);
+        Ok(())
+    }
+    pub fn clear_app_autoscaling_list(&mut self) {
+        self.app_autoscaling_list = vec![];
+        self.app_autoscaling_cursor = 0;
+        self.app_autoscaling_changes.clear();
+    }
+    pub fn app_autoscaling_next(&mut self) {
+        if !self.app_autoscaling_list.is_empty() {
+            self.app_autoscaling_cursor =
+                (self.app_autoscaling_cursor + 1) % self.app_autoscaling_list.len();
+        }
+    }
+    pub fn app_autoscaling_previous(&mut self) {
+        if !self.app_autoscaling_list.is_empty() {
+            self.app_autoscaling_cursor = self
+                .app_autoscaling_cursor
+                .checked_sub(1)
+                .unwrap_or(self.app_autoscaling_list.len() - 1);
+        }
+    }
+    //INFO: The new value is derived from what the row shows, a group with mixed values is
+    //normalized to the first one
+    pub fn stage_app_autoscaling_change(&mut self, action: Action) {
+        let Some(row) = self
+            .app_autoscaling_list
+            .get_mut(self.app_autoscaling_cursor)
+        else {
+            return;
+        };
+        let first = |value: &str| value.split(',').next().unwrap_or_default().to_string();
+        let change = self
+            .app_autoscaling_changes
+            .entry((row[0].clone(), row[1].clone()))
+            .or_default();
+        match action {
+            Action::CycleAutostop => {
+                let autostop = match first(&row[3]).as_str() {
+                    "off" => "stop",
+                    "stop" => "suspend",
+                    _ => "off",
+                };
+                change.autostop = Some(autostop.to_string());
+            }
+            Action::ToggleAutostart => change.autostart = Some(first(&row[4]) != "on"),
+            Action::IncreaseMinMachines | Action::DecreaseMinMachines => {
+                let min_machines_running = first(&row[5]).parse::<i32>().unwrap_or_default();
+                change.min_machines_running = Some(if action == Action::IncreaseMinMachines {
+                    min_machines_running + 1
+                } else {
+                    (min_machines_running - 1).max(0)
+                });
+            }
+            _ => return,
+        }
+        change.apply_to_row(row);
+    }
+    pub fn has_app_autoscaling_changes(&self) -> bool {
+        !self.app_autoscaling_changes.is_empty()
+    }
+    pub fn open_update_app_autoscaling_popup(&mut self) -> RdrResult<()> {
+        let app: ListApp = self.get_selected_resource()?.into();
+        let message = format!(
+            "Are you sure to update the autoscaling of {}? The machines of these groups are updated, the stopped ones stay stopped.\n\n{}",
+            app.name,
+            self.app_autoscaling_changes
+                .iter()
+                .map(|((process_group, region), change)| format!(
+                    "{} {}: {}",
+                    process_group,
+                    region,
+                    change.diff().join(", ")
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        self.open_popup(message, PopupType::UpdateAppAutoscalingPopup, None);
+        Ok(())
+    }
+    pub fn process_update_app_autoscaling_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        let changes = std::mem::take(&mut self.app_autoscaling_changes);
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let app: ListApp = self.get_selected_resource()?.into();
+        Ok(Some(IoReqEvent::UpdateAppAutoscaling {
+            app_name: app.name,
+            changes,
+        }))
+    }
+    pub fn open_view_dedicated_host_machines_popup(&mut self) -> RdrResult<()> {
+        let host: ListDedicatedHost = self.get_selected_resource()?.into();
+        let message = format!("Machines on {}", host.id);
+        self.open_popup(message, PopupType::ViewDedicatedHostMachinesPopup, None);
+        Ok(())
+    }
+    pub fn clear_dedicated_host_machines_list(&mut self) {
+        self.dedicated_host_machines_list = vec![];
+    }
+    pub fn open_view_machine_checks_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let message = format!("Health checks of {}", machine.id);
+        self.open_popup(message, PopupType::ViewMachineChecksPopup, None);
+        Ok(())
+    }
+    pub fn clear_machine_checks_list(&mut self) {
+        self.machine_checks_list = vec![];
+    }
+    /// Renders the selected machine as YAML, as it was on the last poll.
+    pub fn open_view_machine_detail_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let detail = self
+            .machine_details
+            .get(&machine.id)
+            .ok_or_eyre("Machine not found.")?;
+        self.machine_detail = serde_yaml::to_string(detail)?
+            .lines()
+            .map(String::from)
+            .collect();
+        self.popup_scroll = 0;
+        let message = format!("Details of {} (<↑/↓>, <PageUp/Down> to scroll)", machine.id);
+        self.open_popup(message, PopupType::ViewMachineDetailPopup, None);
+        Ok(())
+    }
+    pub fn scroll_popup(&mut self, lines: i32) {
+        let line_count = match self.get_popup_type() {
+            PopupType::ViewMachineExecPopup => self.machine_exec_list.len(),
+            PopupType::ViewLogLinePopup => self.log_line_detail.len(),
+            PopupType::ViewAppReleasesDiffPopup => self.app_releases_diff_list.len(),
+            _ => self.machine_detail.len(),
+        };
+        self.popup_scroll =
+            (self.popup_scroll as i32 + lines).clamp(0, line_count.saturating_sub(1) as i32) as u16;
+    }
+    pub fn open_exec_machine_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        self.input_state = InputState::ShellCommand {
+            input: Input::default(),
+        };
+        self.open_popup(
+            format!(
+                "Run a command on {}, it times out after 30 seconds.",
+                machine.id
+            ),
+            PopupType::ExecMachinePopup,
+            None,
+        );
+        Ok(())
+    }
+    pub fn process_exec_machine_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::ShellCommand { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let command = input.value().trim();
+        if command.is_empty() {
+            return Err(eyre!("Command can't be empty."));
+        }
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        Ok(Some(IoReqEvent::ExecMachine {
+            app_name,
+            machine_id: machine.id,
+            command: command.to_string(),
+        }))
+    }
+    pub fn filter_machines_by_host(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        if machine.host_dedication_id.is_empty() {
+            self.open_popup(
+                String::from("This machine doesn't run on a dedicated host."),
+                PopupType::InfoPopup,
+                None,
+            );
+        } else {
+            self.resource_list
+                .apply_search_filter(&machine.host_dedication_id);
+        }
+        Ok(())
+    }
+    pub fn toggle_orphaned_volumes_filter(&mut self) {
+        if self.resource_list.search_filter == ORPHANED_VOLUME_MARKER {
+            self.resource_list.apply_search_filter("");
+        } else {
+            self.resource_list
+                .apply_search_filter(ORPHANED_VOLUME_MARKER);
+        }
     }
     pub fn open_view_commands_popup(&mut self) -> RdrResult<()> {
         let message = "Commands".to_string();
         self.open_popup(message, PopupType::ViewCommandsPopup, None);
         Ok(())
     }
+    pub fn open_view_keybindings_popup(&mut self) {
+        self.input_state = InputState::KeybindingSearch {
+            input: Input::default(),
+        };
+        self.open_popup(
+            String::from("Keybindings"),
+            PopupType::ViewKeybindingsPopup,
+            None,
+        );
+    }
     pub fn open_start_machines_popup(&mut self) {
         let machines = self
             .resource_list
@@ -1144,6 +3628,484 @@ impl State {
             }))
         }
     }
+    pub fn open_rename_machine_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let message = format!(
+            "Rename the machine {} ({}). It's restarted with its current config if it's started.",
+            machine.id, machine.name
+        );
+        self.input_state = InputState::Name {
+            input: Input::new(machine.name),
+        };
+        self.open_popup(message, PopupType::RenameMachinePopup, None);
+        Ok(())
+    }
+    pub fn process_rename_machine_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::Name { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        Ok(Some(IoReqEvent::RenameMachine {
+            seq_id: self.get_seq_id(ResourceType::Machines),
+            app_name,
+            machine_id: machine.id,
+            name: input.value().trim().to_string(),
+        }))
+    }
+    pub fn open_align_machines_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let message = format!(
+            "Are you sure to update the image and env of the machines in the {} process group to the ones of {}?",
+            machine.process_group, machine.id
+        );
+        self.open_popup(message, PopupType::AlignMachinesPopup, None);
+        Ok(())
+    }
+    pub fn process_align_machines_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            Ok(None)
+        } else {
+            let machine: ListMachine = self.get_selected_resource()?.into();
+            let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+            Ok(Some(IoReqEvent::AlignMachines {
+                app_name,
+                machine_id: machine.id,
+            }))
+        }
+    }
+    /// The console is opened by the main loop, which owns the terminal.
+    pub fn request_console(&mut self) -> RdrResult<()> {
+        if !flyctl_available() {
+            return Err(eyre!("flyctl is needed to open a console on a machine."));
+        }
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        if machine.state != MACHINE_STATE_STARTED {
+            self.open_popup(
+                format!(
+                    "{} is {}, start it to open a console.",
+                    machine.id, machine.state
+                ),
+                PopupType::InfoPopup,
+                None,
+            );
+            return Ok(());
+        }
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        self.console_request = Some(ConsoleRequest {
+            app_name,
+            machine_id: machine.id,
+        });
+        Ok(())
+    }
+    pub fn take_console_request(&mut self) -> Option<ConsoleRequest> {
+        self.console_request.take()
+    }
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+    pub fn open_scale_machine_popup(&mut self) -> RdrResult<()> {
+        let machine: ListMachine = self.get_selected_resource()?.into();
+        let guest = self
+            .machine_details
+            .get(&machine.id)
+            .map(|detail| serde_json::from_value::<MachineGuest>(detail["config"]["guest"].clone()))
+            .ok_or_eyre("Machine not found.")??;
+        let presets = scale::presets();
+        self.machine_scale_cursor = presets
+            .iter()
+            .position(|preset| {
+                preset.cpu_kind == guest.cpu_kind
+                    && preset.cpus == guest.cpus
+                    && preset.memory_mb == guest.memory_mb
+            })
+            .unwrap_or_default();
+        self.machine_scale_list = scale::preset_rows(&guest, &presets);
+        let message = format!(
+            "Size of {} (<↑/↓> to select, costs are monthly estimates, OK restarts the machine)",
+            machine.id
+        );
+        self.open_popup(message, PopupType::ScaleMachinePopup, None);
+        Ok(())
+    }
+    pub fn machine_scale_next(&mut self) {
+        if !self.machine_scale_list.is_empty() {
+            self.machine_scale_cursor =
+                (self.machine_scale_cursor + 1) % self.machine_scale_list.len();
+        }
+    }
+    pub fn machine_scale_previous(&mut self) {
+        if !self.machine_scale_list.is_empty() {
+            self.machine_scale_cursor = self
+                .machine_scale_cursor
+                .checked_sub(1)
+                .unwrap_or(self.machine_scale_list.len() - 1);
+        }
+    }
+    pub fn process_scale_machine_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            Ok(None)
+        } else {
+            let machine: ListMachine = self.get_selected_resource()?.into();
+            let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+            let preset = scale::presets()
+                .into_iter()
+                .nth(self.machine_scale_cursor)
+                .ok_or_eyre("Size not found.")?;
+            Ok(Some(IoReqEvent::ScaleMachine {
+                seq_id: self.get_seq_id(ResourceType::Machines),
+                app_name,
+                machine_id: machine.id,
+                preset,
+            }))
+        }
+    }
+    pub fn open_snapshot_volume_popup(&mut self) -> RdrResult<()> {
+        let volume: ListVolume = self.get_selected_resource()?.into();
+        let message = format!(
+            "Are you sure to take a snapshot of this volume: {}?",
+            volume.id
+        );
+        self.open_popup(message, PopupType::SnapshotVolumePopup, None);
+        Ok(())
+    }
+    pub fn process_snapshot_volume_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            Ok(None)
+        } else {
+            let volume: ListVolume = self.get_selected_resource()?.into();
+            let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+            Ok(Some(IoReqEvent::SnapshotVolume {
+                app_name,
+                volume_id: volume.id,
+            }))
+        }
+    }
+    pub fn open_detach_volume_popup(&mut self) -> RdrResult<()> {
+        let volume: ListVolume = self.get_selected_resource()?.into();
+        let machine_id = volume.attached_machine_id.unwrap_or_default();
+        if machine_id.is_empty() {
+            self.open_popup(
+                format!("{} is not attached to a machine.", volume.id),
+                PopupType::InfoPopup,
+                None,
+            );
+            return Ok(());
+        }
+        let message = format!(
+            "Detach the volume {} ({}) from {}? The machine is stopped if it's started, and it's left stopped without the volume in its mounts.",
+            volume.id, volume.name, machine_id
+        );
+        self.open_popup(message, PopupType::DetachVolumePopup, None);
+        Ok(())
+    }
+    pub fn process_detach_volume_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let volume: ListVolume = self.get_selected_resource()?.into();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        Ok(Some(IoReqEvent::DetachVolume {
+            seq_id: self.get_seq_id(ResourceType::Volumes),
+            app_name,
+            volume_id: volume.id,
+            machine_id: volume.attached_machine_id.unwrap_or_default(),
+        }))
+    }
+    pub fn open_fork_volume_popup(&mut self) -> RdrResult<()> {
+        let volume: ListVolume = self.get_selected_resource()?.into();
+        let message = format!(
+            "Fork the volume {} ({}) into a new volume with its data. Enter the region of the fork, leave it empty to keep {}.",
+            volume.id, volume.name, volume.region
+        );
+        self.input_state = InputState::Region {
+            input: Input::default(),
+        };
+        self.open_popup(message, PopupType::ForkVolumePopup, None);
+        Ok(())
+    }
+    pub fn process_fork_volume_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::Region { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let volume: ListVolume = self.get_selected_resource()?.into();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        let region = input.value().trim();
+        Ok(Some(IoReqEvent::ForkVolume {
+            seq_id: self.get_seq_id(ResourceType::Volumes),
+            app_name,
+            params: ForkVolumeInput {
+                id: volume.id,
+                name: volume.name,
+                region: (!region.is_empty()).then(|| region.to_string()),
+            },
+        }))
+    }
+    pub fn open_focus_logs_region_popup(&mut self) {
+        self.input_state = InputState::Region {
+            input: Input::default(),
+        };
+        self.open_popup(
+            String::from("Focus the logs on a region, leave it empty to show all the regions."),
+            PopupType::FocusLogsRegionPopup,
+            None,
+        );
+    }
+    pub fn process_focus_logs_region_popup(&mut self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::Region { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let region = input.value().trim().to_lowercase();
+        let region = (!region.is_empty()).then_some(region);
+        if !self.logs_state.focus_target(region.as_deref()) {
+            let message = format!("There are no logs from {} yet.", region.unwrap_or_default());
+            self.input_state = InputState::Hidden;
+            self.open_popup(message, PopupType::ErrorPopup, None);
+            return Err(eyre!("noop"));
+        }
+        Ok(None)
+    }
+    // Region picking
+    /// Lists the regions to pick from once, they're kept for the session.
+    pub async fn load_regions(&mut self) {
+        if self.region_picker.regions.is_empty() {
+            self.dispatch(IoReqEvent::ListRegions).await;
+        }
+    }
+    /// What's typed in the region input or the region field of the form, if one is being typed
+    /// in.
+    fn region_query(&self) -> Option<&str> {
+        if let InputState::Region { input } = &self.input_state {
+            return Some(input.value());
+        }
+        self.popup
+            .as_ref()?
+            .actions
+            .children
+            .iter()
+            .find(|child| child.is_focused())
+            .and_then(|child| child.as_any().downcast_ref::<InputField>())
+            .filter(|field| field.label == "Region")
+            .map(|field| field.value())
+    }
+    /// Filters the picker by what's typed, returns whether a region is being picked.
+    pub fn update_region_picker(&mut self) -> bool {
+        let Some(query) = self.region_query().map(str::to_string) else {
+            return false;
+        };
+        self.region_picker.set_query(&query);
+        !self.region_picker.regions.is_empty()
+    }
+    pub fn region_picker_next(&mut self) {
+        if self.update_region_picker() {
+            self.region_picker.next();
+        }
+    }
+    pub fn region_picker_previous(&mut self) {
+        if self.update_region_picker() {
+            self.region_picker.previous();
+        }
+    }
+    /// Fills the region input or field with the code of the selected region.
+    pub fn pick_region(&mut self) {
+        if !self.update_region_picker() {
+            return;
+        }
+        let Some(code) = self
+            .region_picker
+            .selected()
+            .map(|region| region.code.clone())
+        else {
+            return;
+        };
+        if let InputState::Region { input } = &mut self.input_state {
+            *input = Input::new(code);
+        } else if let Some(field) = self.focused_popup_field() {
+            field.input = Input::new(code);
+        }
+    }
+    pub fn open_create_volume_popup(&mut self) {
+        //INFO: Machines mount the volumes by name, so the selected one is a good default
+        let (name, region) = self
+            .resource_list
+            .selected()
+            .cloned()
+            .map(|volume| {
+                let volume: ListVolume = volume.into();
+                (volume.name, volume.region)
+            })
+            .unwrap_or_default();
+        let wizard = Wizard::new(vec![
+            WizardStep::new(
+                "Create a volume for the app.",
+                vec![
+                    InputField::new("Name", &name).boxed(),
+                    NumberField::new("Size (GB)", 1, VOLUME_MAX_SIZE_GB)
+                        .with_value(1)
+                        .boxed(),
+                    InputField::new("Region", &region).boxed(),
+                ],
+            )
+            .with_validate(|fields| {
+                let missing = fields
+                    .iter()
+                    .filter_map(|child| child.as_any().downcast_ref::<InputField>())
+                    .any(|field| field.value().is_empty());
+                if missing {
+                    Err(String::from("Name and region are required."))
+                } else {
+                    Ok(())
+                }
+            }),
+            WizardStep::new(
+                "Leave the snapshot id empty to create an empty volume.",
+                vec![
+                    CheckBox::new("Encrypted", true).boxed(),
+                    InputField::new("Snapshot id", "").boxed(),
+                ],
+            ),
+        ]);
+        self.open_wizard_popup(PopupType::CreateVolumePopup, wizard);
+    }
+    pub fn process_create_volume_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        let popup = self.popup.as_ref().unwrap();
+        if !self.should_take_action(&popup.actions) {
+            return Ok(None);
+        }
+        let fields = popup
+            .fields()
+            .filter_map(|child| child.as_any().downcast_ref::<InputField>())
+            .map(|field| (field.label.as_str(), field.value()))
+            .collect::<HashMap<_, _>>();
+        let encrypted = popup
+            .fields()
+            .find_map(|child| child.as_any().downcast_ref::<CheckBox>())
+            .is_some_and(|checkbox| checkbox.is_check());
+        let name = fields["Name"];
+        let region = fields["Region"];
+        let size_gb = self.popup_number("Size (GB)")? as i32;
+        //INFO: The popup stays open until the required fields are filled
+        if name.is_empty() || region.is_empty() {
+            return Err(eyre!("Name and region are required."));
+        }
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        let snapshot_id = fields["Snapshot id"];
+        Ok(Some(IoReqEvent::CreateVolume {
+            seq_id: self.get_seq_id(ResourceType::Volumes),
+            app_name,
+            params: CreateVolumeInput {
+                name: name.to_string(),
+                size_gb,
+                region: region.to_string(),
+                encrypted,
+                snapshot_id: (!snapshot_id.is_empty()).then(|| snapshot_id.to_string()),
+            },
+        }))
+    }
+    pub fn open_set_machines_metadata_popup(&mut self) {
+        let machines = self
+            .resource_list
+            .multi_select_state
+            .iter()
+            .map(|machine| machine.to_string())
+            .join(", ");
+        let message = format!(
+            "Set a metadata key=value on the selected machines: {}. Leave the value empty to remove the key.",
+            machines
+        );
+        self.input_state = InputState::Metadata {
+            input: Input::default(),
+        };
+        self.open_popup(message, PopupType::SetMachinesMetadataPopup, None);
+    }
+    pub fn process_set_machines_metadata_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::Metadata { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let (key, value) = input
+            .value()
+            .split_once('=')
+            .ok_or_eyre("Metadata should be in the key=value format.")?;
+        let machines = self
+            .resource_list
+            .multi_select_state
+            .clone()
+            .into_iter()
+            .collect();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        Ok(Some(IoReqEvent::SetMachinesMetadata {
+            seq_id: self.get_seq_id(ResourceType::Machines),
+            app_name,
+            machines,
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+        }))
+    }
+    pub fn open_update_machines_image_popup(&mut self) {
+        let machines = self
+            .resource_list
+            .multi_select_state
+            .iter()
+            .map(|machine| machine.to_string())
+            .join(", ");
+        let message = format!(
+            "Update the selected machines to a new image, one at a time: {}. Enter a tag to keep the repository of each machine's image, or a full image ref.",
+            machines
+        );
+        self.input_state = InputState::Image {
+            input: Input::default(),
+        };
+        self.open_popup(message, PopupType::UpdateMachinesImagePopup, None);
+    }
+    pub fn process_update_machines_image_popup(&self) -> RdrResult<Option<IoReqEvent>> {
+        if !self.should_take_action(&self.popup.as_ref().unwrap().actions) {
+            return Ok(None);
+        }
+        let InputState::Image { input } = &self.input_state else {
+            return Ok(None);
+        };
+        let tag = input.value().trim();
+        if tag.is_empty() {
+            return Err(eyre!("Image tag can't be empty."));
+        }
+        let machines = self
+            .resource_list
+            .multi_select_state
+            .clone()
+            .into_iter()
+            .sorted()
+            .collect();
+        let (_, app_name) = self.get_current_app().ok_or_eyre("App not found.")?;
+        Ok(Some(IoReqEvent::UpdateMachinesImage {
+            seq_id: self.get_seq_id(ResourceType::Machines),
+            app_name,
+            machines,
+            tag: tag.to_string(),
+        }))
+    }
+    pub fn open_view_machines_update_popup(&mut self, machines: &[String], tag: &str) {
+        self.machines_update_list = machines
+            .iter()
+            .map(|machine| vec![machine.clone(), String::from(UPDATE_PENDING)])
+            .collect();
+        self.open_popup(
+            format!("Rolling update to {}", tag),
+            PopupType::ViewMachinesUpdatePopup,
+            None,
+        );
+    }
     pub fn open_cordon_machines_popup(&mut self) {
         let machines = self
             .resource_list
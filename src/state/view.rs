@@ -2,30 +2,93 @@ use std::fmt::{self, Display};
 
 use crate::fly_rust::resource_organizations::OrganizationFilter;
 use crate::logs::LogOptions;
+use crate::plugins;
+
+/// org_slug of the Apps view aggregating the apps of every org
+pub const ALL_ORGS: &str = "*";
 
 #[derive(Clone, Debug)]
 pub enum View {
-    Organizations { filter: OrganizationFilter },
+    Organizations {
+        filter: OrganizationFilter,
+    },
     // org_id is used for highlighting the correct row navigating back,
-    // org_slug is used for filtering the apps and as part of breadcrumb
-    Apps { org_id: String, org_slug: String },
+    // org_slug is used for filtering the apps and as part of breadcrumb, ALL_ORGS lists every org
+    Apps {
+        org_id: String,
+        org_slug: String,
+    },
     // app_id is used for highlighting the correct row navigating back,
     // app_name is used for api calls and as part of breadcrumb
-    Machines { app_id: String, app_name: String },
-    Volumes { app_id: String, app_name: String },
-    Secrets { app_id: String, app_name: String },
+    Machines {
+        app_id: String,
+        app_name: String,
+    },
+    Volumes {
+        app_id: String,
+        app_name: String,
+    },
+    Secrets {
+        app_id: String,
+        app_name: String,
+    },
+    Certificates {
+        app_id: String,
+        app_name: String,
+    },
+    // Extensions are provisioned per org, apps are shown as a column
+    Extensions {
+        org_id: String,
+        org_slug: String,
+    },
+    // Dedicated hosts are resolved from the machines of the org's apps
+    DedicatedHosts {
+        org_id: String,
+        org_slug: String,
+    },
+    // WireGuard peers of the org, used for tunneling into its private network
+    WireGuardPeers {
+        org_id: String,
+        org_slug: String,
+    },
+    // Processes running on a machine of the app
+    Processes {
+        app_id: String,
+        app_name: String,
+        machine_id: String,
+    },
+    // Custom view listed by the plugin of the same name
+    Plugin {
+        name: String,
+    },
     // LogOptions already have app_name
-    AppLogs { app_id: String, opts: LogOptions },
+    AppLogs {
+        app_id: String,
+        opts: LogOptions,
+    },
     // LogOptions already have vm_id
-    MachineLogs { opts: LogOptions },
+    MachineLogs {
+        opts: LogOptions,
+    },
 }
 
 impl View {
-    pub fn headers(&self) -> &[&str] {
-        match self {
+    pub fn headers(&self) -> Vec<&str> {
+        let headers: &[&str] = match self {
             View::Organizations { .. } => &["Name", "Viewer Role", "Slug", "Type"],
             View::Apps { .. } => &["Name", "Organization", "Status", "Latest Deployment"],
-            View::Machines { .. } => &["Id", "Name", "State", "Region", "Updated At"],
+            View::Machines { .. } => &[
+                "Id",
+                "Name",
+                "State",
+                "Region",
+                "Updated At",
+                "Host",
+                "Restarts",
+                "Drift",
+                "Age",
+                "Image Age",
+            ],
             View::Volumes { .. } => &[
                 "Id",
                 "State",
@@ -36,10 +99,35 @@ impl View {
                 "Encrypted",
                 "Attached VM",
                 "Created At",
+                "Orphaned",
             ],
             View::Secrets { .. } => &["Name", "Digest", "Created At"],
+            View::Certificates { .. } => &[
+                "Hostname",
+                "Status",
+                "DNS Validation",
+                "Expires At",
+                "Created At",
+            ],
+            View::Extensions { .. } => &["Name", "Provider", "Plan", "App", "Status", "Region"],
+            View::DedicatedHosts { .. } => &[
+                "Id",
+                "Regions",
+                "Apps",
+                "Started Machines",
+                "Reserved CPUs",
+                "Reserved Memory",
+            ],
+            View::WireGuardPeers { .. } => &["Name", "Region", "Peer IP", "Public Key"],
+            View::Processes { .. } => &["Pid", "CPU", "RSS", "Command", "Listen Sockets"],
+            View::Plugin { name } => {
+                return plugins::get(name)
+                    .map(|plugin| plugin.headers().iter().map(String::as_str).collect())
+                    .unwrap_or_default()
+            }
             _ => &[],
-        }
+        };
+        headers.to_vec()
     }
 
     pub fn to_breadcrumb(&self) -> String {
@@ -49,25 +137,48 @@ impl View {
             View::Machines { .. } => String::from("machines"),
             View::Volumes { .. } => String::from("volumes"),
             View::Secrets { .. } => String::from("secrets"),
+            View::Certificates { .. } => String::from("certificates"),
+            View::Extensions { .. } => String::from("extensions"),
+            View::DedicatedHosts { .. } => String::from("hosts"),
+            View::WireGuardPeers { .. } => String::from("peers"),
+            View::Processes { .. } => String::from("processes"),
+            View::Plugin { name } => String::from(name),
             _ => String::from("logs"),
         }
     }
 
     pub fn to_scope(&self) -> String {
         match self {
-            View::Organizations { filter } => String::from(if filter.is_admin_only() {
-                "admin-only"
-            } else {
-                "all"
-            }),
+            View::Organizations { filter } => {
+                let scope = if filter.is_admin_only() {
+                    "admin-only"
+                } else {
+                    "all"
+                };
+                match filter.scoped_slug() {
+                    Some(slug) => format!("{}:{}", scope, slug),
+                    None => String::from(scope),
+                }
+            }
+            View::Apps { org_slug, .. } if org_slug == ALL_ORGS => String::from("all orgs"),
             View::Apps { org_slug, .. } => String::from(org_slug),
             View::Machines { app_name, .. } => String::from(app_name),
             View::Volumes { app_name, .. } => String::from(app_name),
             View::Secrets { app_name, .. } => String::from(app_name),
+            View::Certificates { app_name, .. } => String::from(app_name),
+            View::Extensions { org_slug, .. } => String::from(org_slug),
+            View::DedicatedHosts { org_slug, .. } => String::from(org_slug),
+            View::WireGuardPeers { org_slug, .. } => String::from(org_slug),
+            View::Processes { machine_id, .. } => String::from(machine_id),
+            View::Plugin { .. } => String::from("plugin"),
             View::AppLogs { opts, .. } => opts.clone().app_name,
             View::MachineLogs { opts, .. } => opts.clone().vm_id.unwrap(),
         }
     }
+
+    pub fn is_logs(&self) -> bool {
+        matches!(self, View::AppLogs { .. } | View::MachineLogs { .. })
+    }
 }
 
 impl Display for View {
@@ -78,6 +189,12 @@ impl Display for View {
             View::Machines { .. } => write!(f, "Machines"),
             View::Volumes { .. } => write!(f, "Volumes"),
             View::Secrets { .. } => write!(f, "Secrets"),
+            View::Certificates { .. } => write!(f, "Certificates"),
+            View::Extensions { .. } => write!(f, "Extensions"),
+            View::DedicatedHosts { .. } => write!(f, "Dedicated Hosts"),
+            View::WireGuardPeers { .. } => write!(f, "WireGuard Peers"),
+            View::Processes { .. } => write!(f, "Processes"),
+            View::Plugin { name } => write!(f, "{}", name),
             _ => write!(f, "logs"),
         }
     }
@@ -1,10 +1,17 @@
 use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
-use chrono::{DateTime, Utc};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, TimeZone, Utc};
 use chrono_humanize::HumanTime;
 use serde::Deserialize;
 use timeago::{Formatter, TimeUnit};
 
+use crate::config::{ByteUnits, FormattingConfig};
+
+static FORMATTING: OnceLock<FormattingConfig> = OnceLock::new();
+
 // INFO: Intermediary types to select fields to show in the table.
 // id is needed to be able to render the selected state optimistically in case of deletions happen in
 // between fetches
@@ -24,13 +31,27 @@ pub struct ListApp {
     pub status: String,
     pub latest_deploy: String,
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct ListMachine {
     pub id: String,
     pub name: String,
     pub state: String,
     pub region: String,
     pub updated_at: String,
+    pub host_dedication_id: String,
+    pub restart_count: i32,
+    pub crash_looping: bool,
+    // Image or env differing from the rest of the process group
+    pub drift: String,
+    // Kept as is, the age is computed from it on every draw
+    pub created_at: String,
+    pub image_age: String,
+    // Not shown, only searched
+    pub image_ref: String,
+    pub private_ip: String,
+    pub process_group: String,
+    // Space separated key=value pairs
+    pub metadata: String,
 }
 #[derive(Debug, Deserialize)]
 pub struct ListVolume {
@@ -43,6 +64,9 @@ pub struct ListVolume {
     pub encrypted: bool,
     pub attached_machine_id: Option<String>,
     pub created_at: String,
+    // Resolved against the app's machines, empty for the volumes that are in use
+    #[serde(skip)]
+    pub orphaned: String,
 }
 #[derive(Debug, Deserialize)]
 pub struct ListSecret {
@@ -50,6 +74,173 @@ pub struct ListSecret {
     pub digest: String,
     pub created_at: String,
 }
+#[derive(Debug)]
+pub struct ListCertificate {
+    pub hostname: String,
+    pub status: String,
+    pub dns_configured: bool,
+    pub expires_at: String,
+    pub created_at: String,
+}
+#[derive(Debug)]
+pub struct ListDedicatedHost {
+    pub id: String,
+    pub regions: String,
+    pub apps: usize,
+    pub machines: usize,
+    pub started_machines: usize,
+    pub cpus: i32,
+    pub started_cpus: i32,
+    pub memory_mb: i32,
+    pub started_memory_mb: i32,
+}
+#[derive(Debug)]
+pub struct ListWireGuardPeer {
+    pub name: String,
+    pub region: String,
+    pub peer_ip: String,
+    pub public_key: String,
+}
+#[derive(Debug, Clone)]
+pub struct ListRegion {
+    pub code: String,
+    /// City of the region
+    pub name: String,
+    pub gateway_available: bool,
+}
+#[derive(Debug)]
+pub struct ListExtension {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub plan: String,
+    pub app: String,
+    pub status: String,
+    pub region: String,
+}
+
+#[derive(Debug)]
+pub struct ListProcess {
+    pub pid: i32,
+    pub cpu: u64,
+    pub rss: String,
+    pub command: String,
+    pub listen_sockets: String,
+}
+
+pub const CRASH_LOOP_BADGE: &str = "⚠";
+
+/// Sets how the numbers, sizes and dates are shown for the whole session, the defaults are used
+/// until then.
+pub fn set_formatting(mut formatting: FormattingConfig) {
+    //INFO: Invalid formats would panic while drawing, the defaults are kept for them
+    let defaults = FormattingConfig::default();
+    let is_valid = |format: &str| StrftimeItems::new(format).all(|item| item != Item::Error);
+    if !is_valid(&formatting.date_format) {
+        formatting.date_format = defaults.date_format;
+    }
+    if !is_valid(&formatting.time_format) {
+        formatting.time_format = defaults.time_format;
+    }
+    let _ = FORMATTING.set(formatting);
+}
+
+pub fn formatting() -> &'static FormattingConfig {
+    FORMATTING.get_or_init(FormattingConfig::default)
+}
+
+pub fn format_number(number: impl Into<i64>) -> String {
+    let number = number.into();
+    let digits = number.unsigned_abs().to_string();
+    let separator = &formatting().thousands_separator;
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    if number < 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
+/// Parses the numbers formatted by `format_number`.
+pub fn parse_number<T: FromStr>(number: &str) -> Option<T> {
+    let separator = &formatting().thousands_separator;
+    if separator.is_empty() {
+        return number.trim().parse().ok();
+    }
+    number.trim().replace(separator.as_str(), "").parse().ok()
+}
+
+/// Label of the power of 1024, from bytes up to gigabytes.
+pub fn byte_unit(power: usize) -> &'static str {
+    let units = match formatting().byte_units {
+        ByteUnits::Gb => ["B", "KB", "MB", "GB"],
+        ByteUnits::Gib => ["B", "KiB", "MiB", "GiB"],
+    };
+    units[power.min(units.len() - 1)]
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < 3 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", format_number(bytes as i64), byte_unit(unit))
+    } else {
+        format!("{:.1}{}", value, byte_unit(unit))
+    }
+}
+
+pub fn format_date<Tz: TimeZone>(time: &DateTime<Tz>) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    time.format(&formatting().date_format).to_string()
+}
+
+pub fn format_time_of_day<Tz: TimeZone>(time: &DateTime<Tz>) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    time.format(&formatting().time_format).to_string()
+}
+
+/// Expiry date with the days left, the expired ones are marked.
+pub fn format_expiry(time: &str) -> String {
+    let time = DateTime::parse_from_rfc3339(time)
+        .unwrap()
+        .with_timezone(&Utc);
+    let days_left = time.signed_duration_since(Utc::now()).num_days();
+    if days_left < 0 {
+        return format!("{} (expired)", format_date(&time));
+    }
+    format!("{} ({}d left)", format_date(&time), days_left)
+}
+
+/// Compact time since the given time, e.g. 45s, 12m, 3h or 6d.
+pub fn format_age(time: &str) -> String {
+    let Ok(time) = DateTime::parse_from_rfc3339(time) else {
+        return String::new();
+    };
+    let seconds = Utc::now()
+        .signed_duration_since(time.with_timezone(&Utc))
+        .num_seconds()
+        .max(0);
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m", seconds / 60),
+        3600..=86399 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
 
 pub fn format_time(time: &str) -> String {
     let time = DateTime::parse_from_rfc3339(time)
@@ -59,7 +250,7 @@ pub fn format_time(time: &str) -> String {
     let duration = now.signed_duration_since(time);
 
     if duration.num_days() > 7 {
-        return time.format("%b %d %Y %H:%M").to_string();
+        return format!("{} {}", format_date(&time), format_time_of_day(&time));
     }
 
     Formatter::new()
@@ -131,6 +322,19 @@ impl From<&ListMachine> for Vec<String> {
             } else {
                 format_time(&machine.updated_at)
             },
+            machine.host_dedication_id.clone(),
+            if machine.crash_looping {
+                format!("{CRASH_LOOP_BADGE} {}", machine.restart_count)
+            } else {
+                machine.restart_count.to_string()
+            },
+            machine.drift.clone(),
+            machine.created_at.clone(),
+            machine.image_age.clone(),
+            machine.image_ref.clone(),
+            machine.private_ip.clone(),
+            machine.process_group.clone(),
+            machine.metadata.clone(),
         ]
     }
 }
@@ -143,6 +347,20 @@ impl From<Vec<String>> for ListMachine {
             state: vec[2].clone(),
             region: vec[3].clone(),
             updated_at: vec[4].clone(),
+            host_dedication_id: vec[5].clone(),
+            restart_count: vec[6]
+                .trim_start_matches(CRASH_LOOP_BADGE)
+                .trim()
+                .parse()
+                .unwrap_or_default(),
+            crash_looping: vec[6].starts_with(CRASH_LOOP_BADGE),
+            drift: vec[7].clone(),
+            created_at: vec[8].clone(),
+            image_age: vec[9].clone(),
+            image_ref: vec[10].clone(),
+            private_ip: vec[11].clone(),
+            process_group: vec[12].clone(),
+            metadata: vec[13].clone(),
         }
     }
 }
@@ -161,12 +379,13 @@ impl From<&ListVolume> for Vec<String> {
             volume.id.clone(),
             volume.state.clone(),
             volume.name.clone(),
-            volume.size_gb.to_string() + "GB",
+            format!("{}{}", format_number(volume.size_gb), byte_unit(3)),
             volume.region.clone(),
             volume.zone.clone(),
             volume.encrypted.to_string(),
             volume.attached_machine_id.clone().unwrap_or_default(),
             created_at,
+            volume.orphaned.clone(),
         ]
     }
 }
@@ -177,12 +396,13 @@ impl From<Vec<String>> for ListVolume {
             id: vec[0].clone(),
             state: vec[1].clone(),
             name: vec[2].clone(),
-            size_gb: vec[3].trim_end_matches("GB").parse::<i32>().unwrap(),
+            size_gb: parse_number(vec[3].trim_end_matches(char::is_alphabetic)).unwrap(),
             region: vec[4].clone(),
             zone: vec[5].clone(),
             encrypted: vec[6].parse::<bool>().unwrap(),
             attached_machine_id: Some(vec[7].clone()),
             created_at: vec[8].clone(),
+            orphaned: vec[9].clone(),
         }
     }
 }
@@ -211,6 +431,168 @@ impl From<Vec<String>> for ListSecret {
     }
 }
 
+impl From<&ListCertificate> for Vec<String> {
+    fn from(certificate: &ListCertificate) -> Self {
+        vec![
+            certificate.hostname.clone(),
+            certificate.status.clone(),
+            String::from(if certificate.dns_configured {
+                "configured"
+            } else {
+                "pending"
+            }),
+            if certificate.expires_at.is_empty() {
+                certificate.expires_at.clone()
+            } else {
+                format_expiry(&certificate.expires_at)
+            },
+            if certificate.created_at.is_empty() {
+                certificate.created_at.clone()
+            } else {
+                format_time(&certificate.created_at)
+            },
+        ]
+    }
+}
+
+impl From<Vec<String>> for ListCertificate {
+    fn from(vec: Vec<String>) -> Self {
+        ListCertificate {
+            hostname: vec[0].clone(),
+            status: vec[1].clone(),
+            dns_configured: vec[2] == "configured",
+            expires_at: vec[3].clone(),
+            created_at: vec[4].clone(),
+        }
+    }
+}
+
+impl From<&ListDedicatedHost> for Vec<String> {
+    fn from(host: &ListDedicatedHost) -> Self {
+        vec![
+            host.id.clone(),
+            host.regions.clone(),
+            format_number(host.apps as i64),
+            format!(
+                "{}/{}",
+                format_number(host.started_machines as i64),
+                format_number(host.machines as i64)
+            ),
+            format!(
+                "{}/{}",
+                format_number(host.started_cpus),
+                format_number(host.cpus)
+            ),
+            format!(
+                "{}/{}{}",
+                format_number(host.started_memory_mb),
+                format_number(host.memory_mb),
+                byte_unit(2)
+            ),
+        ]
+    }
+}
+
+impl From<Vec<String>> for ListDedicatedHost {
+    fn from(vec: Vec<String>) -> Self {
+        let split_usage = |usage: &str| -> (i32, i32) {
+            usage
+                .trim_end_matches(char::is_alphabetic)
+                .split_once('/')
+                .map(|(used, total)| (parse_number(used).unwrap(), parse_number(total).unwrap()))
+                .unwrap()
+        };
+        let (started_machines, machines) = split_usage(&vec[3]);
+        let (started_cpus, cpus) = split_usage(&vec[4]);
+        let (started_memory_mb, memory_mb) = split_usage(&vec[5]);
+        ListDedicatedHost {
+            id: vec[0].clone(),
+            regions: vec[1].clone(),
+            apps: parse_number(&vec[2]).unwrap(),
+            machines: machines as usize,
+            started_machines: started_machines as usize,
+            cpus,
+            started_cpus,
+            memory_mb,
+            started_memory_mb,
+        }
+    }
+}
+
+impl From<&ListExtension> for Vec<String> {
+    fn from(extension: &ListExtension) -> Self {
+        vec![
+            extension.id.clone(),
+            extension.name.clone(),
+            extension.provider.clone(),
+            extension.plan.clone(),
+            extension.app.clone(),
+            extension.status.clone(),
+            extension.region.clone(),
+        ]
+    }
+}
+
+impl From<Vec<String>> for ListExtension {
+    fn from(vec: Vec<String>) -> Self {
+        ListExtension {
+            id: vec[0].clone(),
+            name: vec[1].clone(),
+            provider: vec[2].clone(),
+            plan: vec[3].clone(),
+            app: vec[4].clone(),
+            status: vec[5].clone(),
+            region: vec[6].clone(),
+        }
+    }
+}
+
+impl From<&ListWireGuardPeer> for Vec<String> {
+    fn from(peer: &ListWireGuardPeer) -> Self {
+        vec![
+            peer.name.clone(),
+            peer.region.clone(),
+            peer.peer_ip.clone(),
+            peer.public_key.clone(),
+        ]
+    }
+}
+
+impl From<Vec<String>> for ListWireGuardPeer {
+    fn from(vec: Vec<String>) -> Self {
+        ListWireGuardPeer {
+            name: vec[0].clone(),
+            region: vec[1].clone(),
+            peer_ip: vec[2].clone(),
+            public_key: vec[3].clone(),
+        }
+    }
+}
+
+impl From<&ListProcess> for Vec<String> {
+    fn from(process: &ListProcess) -> Self {
+        vec![
+            process.pid.to_string(),
+            format_number(process.cpu as i64),
+            process.rss.clone(),
+            process.command.clone(),
+            process.listen_sockets.clone(),
+        ]
+    }
+}
+
+impl From<Vec<String>> for ListProcess {
+    fn from(vec: Vec<String>) -> Self {
+        ListProcess {
+            pid: vec[0].parse().unwrap(),
+            cpu: parse_number(&vec[1]).unwrap(),
+            rss: vec[2].clone(),
+            command: vec[3].clone(),
+            listen_sockets: vec[4].clone(),
+        }
+    }
+}
+
 /// items of SelectableList
 pub trait ResourceList: fmt::Debug + Send + Sync {
     fn transform(&self) -> Vec<Vec<String>>;
@@ -245,3 +627,33 @@ impl ResourceList for Vec<ListSecret> {
         self.iter().map(Vec::<String>::from).collect()
     }
 }
+
+impl ResourceList for Vec<ListCertificate> {
+    fn transform(&self) -> Vec<Vec<String>> {
+        self.iter().map(Vec::<String>::from).collect()
+    }
+}
+
+impl ResourceList for Vec<ListDedicatedHost> {
+    fn transform(&self) -> Vec<Vec<String>> {
+        self.iter().map(Vec::<String>::from).collect()
+    }
+}
+
+impl ResourceList for Vec<ListExtension> {
+    fn transform(&self) -> Vec<Vec<String>> {
+        self.iter().map(Vec::<String>::from).collect()
+    }
+}
+
+impl ResourceList for Vec<ListWireGuardPeer> {
+    fn transform(&self) -> Vec<Vec<String>> {
+        self.iter().map(Vec::<String>::from).collect()
+    }
+}
+
+impl ResourceList for Vec<ListProcess> {
+    fn transform(&self) -> Vec<Vec<String>> {
+        self.iter().map(Vec::<String>::from).collect()
+    }
+}
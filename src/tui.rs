@@ -1,12 +1,21 @@
+use std::io::Write;
+use std::process::ExitStatus;
 use std::{io, panic};
 
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use crossterm::event::{
+    DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+    EnableFocusChange, EnableMouseCapture,
+};
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, {self},
 };
 use ratatui::backend::Backend;
 use ratatui::Terminal;
+use tokio::process::Command;
 
+use crate::auth::TokenProblem;
 use crate::event::EventHandler;
 use crate::state::{RdrResult, State};
 use crate::ui;
@@ -21,20 +30,25 @@ pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Whether the mouse events come to flyradar instead of the terminal's selection.
+    mouse_captured: bool,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self {
+            terminal,
+            events,
+            mouse_captured: true,
+        }
     }
 
     /// Initializes the terminal interface.
     ///
     /// It enables the raw mode and sets terminal properties.
     pub fn init(&mut self) -> RdrResult<()> {
-        terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Self::enter(self.mouse_captured)?;
 
         // Define a custom panic hook to reset the terminal properties.
         // This way, you won't have your terminal messed up if an unexpected error happens.
@@ -49,12 +63,95 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    fn enter(capture_mouse: bool) -> RdrResult<()> {
+        terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            EnableFocusChange
+        )?;
+        if capture_mouse {
+            crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+        }
+        Ok(())
+    }
+
+    /// Leaves the mouse to the terminal while not captured, so text can be selected and copied.
+    pub fn capture_mouse(&mut self, capture: bool) -> RdrResult<()> {
+        if capture == self.mouse_captured {
+            return Ok(());
+        }
+        if capture {
+            crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+        } else {
+            crossterm::execute!(io::stdout(), DisableMouseCapture)?;
+        }
+        self.mouse_captured = capture;
+        Ok(())
+    }
+
+    /// Stops flyradar like a shell job and takes the terminal back once it's continued.
+    pub fn suspend(&mut self) -> RdrResult<()> {
+        Self::reset()?;
+        //INFO: Raw mode turns Ctrl-Z into a key, so the stop signal is raised by hand
+        #[cfg(unix)]
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+        Self::enter(self.mouse_captured)?;
+        self.terminal.hide_cursor()?;
+        self.terminal.clear()?;
+        Ok(())
+    }
+
+    /// Copies the text to the clipboard, called between the frames so the sequence isn't written
+    /// in the middle of one.
+    pub fn copy_to_clipboard(&mut self, text: &str) -> RdrResult<()> {
+        //INFO: The clipboard is set with the OSC 52 escape sequence, the terminal does the copying
+        //so it works over ssh as well without a clipboard dependency. Terminals that don't support
+        //it ignore it.
+        let mut stdout = io::stdout().lock();
+        write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Hands the terminal over to the command until it exits, e.g. for an interactive shell.
+    pub async fn attach(&mut self, mut command: Command) -> RdrResult<ExitStatus> {
+        self.events.pause();
+        Self::reset()?;
+        self.terminal.show_cursor()?;
+        let status = command.status().await;
+        Self::enter(self.mouse_captured)?;
+        self.terminal.hide_cursor()?;
+        self.terminal.clear()?;
+        self.events.resume();
+        Ok(status?)
+    }
+
     /// [`Draw`] the terminal interface by [`rendering`] the widgets.
     ///
     /// [`Draw`]: ratatui::Terminal::draw
     /// [`rendering`]: crate::ui::render
     pub fn draw(&mut self, state: &mut State) -> RdrResult<()> {
-        self.terminal.draw(|frame| ui::render(state, frame))?;
+        self.terminal.draw(|frame| {
+            ui::render(state, frame);
+            ui::adapt_colors(frame.buffer_mut());
+        })?;
+        Ok(())
+    }
+
+    /// Draws the startup screen shown while the session can't start.
+    pub fn draw_token_problem(
+        &mut self,
+        problem: &TokenProblem,
+        status: Option<&str>,
+    ) -> RdrResult<()> {
+        self.terminal.draw(|frame| {
+            ui::render_token_problem(frame, problem, status);
+            ui::adapt_colors(frame.buffer_mut());
+        })?;
         Ok(())
     }
 
@@ -64,7 +161,13 @@ impl<B: Backend> Tui<B> {
     /// the terminal properties if unexpected errors occur.
     fn reset() -> RdrResult<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+        crossterm::execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste,
+            DisableFocusChange
+        )?;
         Ok(())
     }
 
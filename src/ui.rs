@@ -1,28 +1,302 @@
-use std::sync::atomic::Ordering;
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
+use chrono::Local;
 use itertools::Itertools;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
-use ratatui::style::{Color, Style, Stylize};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Constraint, Direction, Flex, Layout, Margin, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
 use ratatui::symbols::border;
 use ratatui::text::{Line, Span, Text, ToSpan, ToText};
 use ratatui::widgets::{Block, Borders, Cell, Padding, Paragraph, Row, Table, TableState, Wrap};
 use ratatui::Frame;
+use tracing::error;
 use tui_big_text::{BigText, PixelSize};
 use tui_input::Input;
 use unicode_width::UnicodeWidthStr;
 
-use crate::build;
-use crate::command::{Command, COMMANDS};
+use crate::agent::{flyctl_available, FLYCTL_UNAVAILABLE_MESSAGE};
+use crate::auth::TokenProblem;
+use crate::command::{match_command, Command, COMMANDS};
+use crate::config::{ColorDepth, ThemeConfig, ThemePreset};
+use crate::keybindings::{Action, Context};
+use crate::logs::LogTransport;
+use crate::ops::machines::exec::{EXEC_EXIT_CODE, EXEC_STDERR};
 use crate::state::view::View;
 use crate::state::{
     InputState, MultiSelectMode, MultiSelectModeReason, PopupType, RdrPopup, State,
 };
-use crate::widgets::focusable_check_box::CheckBox;
+use crate::transformations::{format_age, format_number, format_time_of_day};
 use crate::widgets::focusable_text::TextBox;
-use crate::widgets::log_viewer::{TuiLoggerLevelOutput, TuiLoggerSmartWidget, TuiLoggerWidget};
+use crate::widgets::focusable_widget::FocusableWidget;
+use crate::widgets::log_viewer::{
+    Level, TuiLoggerLevelOutput, TuiLoggerSmartWidget, TuiLoggerWidget,
+};
 use crate::widgets::popup::render_popup;
+use crate::widgets::region_picker::RegionPickerWidget;
+use crate::widgets::selectable_list::SearchQuery;
 use crate::widgets::{fly_balloon, fly_visual};
+use crate::{build, keybindings, plugins};
+
+const MAX_ALERT_ROWS: usize = 3;
+const MAX_LOG_STATS_MINUTES: usize = 15;
+
+static LOW_BANDWIDTH: AtomicBool = AtomicBool::new(false);
+
+/// Borders drawn with plain ASCII in the low-bandwidth mode.
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Skips the splash and the banner and renders with ASCII only, so fewer bytes are sent per frame
+/// on high-latency remote terminals.
+pub fn set_low_bandwidth(enabled: bool) {
+    LOW_BANDWIDTH.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_low_bandwidth() -> bool {
+    LOW_BANDWIDTH.load(Ordering::SeqCst)
+}
+
+pub fn glyph(fancy: &'static str, ascii: &'static str) -> &'static str {
+    if is_low_bandwidth() {
+        ascii
+    } else {
+        fancy
+    }
+}
+
+pub fn border_set(fancy: border::Set) -> border::Set {
+    if is_low_bandwidth() {
+        ASCII_BORDER
+    } else {
+        fancy
+    }
+}
+
+/// Drops the emoji-only spans of a title in the low-bandwidth mode.
+fn ascii_title(line: Line) -> Line {
+    if !is_low_bandwidth() {
+        return line;
+    }
+    let spans = line
+        .spans
+        .into_iter()
+        .filter(|span| span.content.trim().is_ascii())
+        .collect::<Vec<_>>();
+    Line::from(spans).style(line.style)
+}
+
+static COLOR_DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Names the colors are overridden by in the theme, black and white being the background and the
+/// text all over the UI.
+const THEME_COLOR_NAMES: [(&str, Color); 16] = [
+    ("background", Color::Black),
+    ("text", Color::White),
+    ("dark_purple", Palette::DARK_PURPLE),
+    ("purple", Palette::PURPLE),
+    ("light_purple", Palette::LIGHT_PURPLE),
+    ("dark_blue", Palette::DARK_BLUE),
+    ("blue", Palette::BLUE),
+    ("light_blue", Palette::LIGHT_BLUE),
+    ("dark_teal", Palette::DARK_TEAL),
+    ("teal", Palette::TEAL),
+    ("light_teal", Palette::LIGHT_TEAL),
+    ("dark_pink", Palette::DARK_PINK),
+    ("pink", Palette::PINK),
+    ("light_pink", Palette::LIGHT_PINK),
+    ("gray", Palette::GRAY),
+    ("dark_gray", Palette::DARK_GRAY),
+];
+
+/// The light preset swaps the background and the text, and darkens the colors used for text.
+const LIGHT_THEME: [(Color, Color); 14] = [
+    (Color::Black, Color::Indexed(255)),
+    (Color::White, Color::Indexed(235)),
+    (Color::Yellow, Color::Indexed(136)),
+    (Color::Cyan, Color::Indexed(31)),
+    (Palette::DARK_PURPLE, Color::Indexed(189)),
+    (Palette::LIGHT_PURPLE, Color::Indexed(97)),
+    (Palette::BLUE, Color::Indexed(26)),
+    (Palette::LIGHT_BLUE, Color::Indexed(25)),
+    (Palette::TEAL, Color::Indexed(30)),
+    (Palette::LIGHT_TEAL, Color::Indexed(29)),
+    (Palette::PINK, Color::Indexed(162)),
+    (Palette::LIGHT_PINK, Color::Indexed(168)),
+    (Palette::GRAY, Color::Indexed(242)),
+    (Palette::DARK_GRAY, Color::Indexed(252)),
+];
+
+/// Colors swapped in the drawn frames, before they're brought down to the color depth.
+#[derive(Debug, Default)]
+struct Theme {
+    colors: HashMap<Color, Color>,
+    monochrome: bool,
+}
+
+/// Sets the colors to draw with from the preset and the overrides, the ones that can't be parsed
+/// are left out.
+pub fn set_theme(theme: &ThemeConfig) {
+    let mut colors = match theme.preset {
+        ThemePreset::Light => HashMap::from(LIGHT_THEME),
+        ThemePreset::Default | ThemePreset::Monochrome => HashMap::new(),
+    };
+    for (name, value) in &theme.colors {
+        let Some((_, color)) = THEME_COLOR_NAMES.iter().find(|(n, _)| n == name) else {
+            error!("Unknown theme color {:?}", name);
+            continue;
+        };
+        match value.parse::<Color>() {
+            Ok(value) => {
+                colors.insert(*color, value);
+            }
+            Err(_) => error!("Invalid color {:?} for {:?}", value, name),
+        }
+    }
+    let _ = THEME.set(Theme {
+        colors,
+        monochrome: theme.preset == ThemePreset::Monochrome,
+    });
+}
+
+/// The 16 ANSI colors with their usual xterm values, to find the closest one to a palette color.
+const BASIC_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Sets the colors to draw with, `Auto` is resolved from the environment once here.
+pub fn set_color_depth(color_depth: ColorDepth) {
+    let color_depth = match color_depth {
+        ColorDepth::Auto => detect_color_depth(),
+        color_depth => color_depth,
+    };
+    let _ = COLOR_DEPTH.set(color_depth);
+}
+
+fn detect_color_depth() -> ColorDepth {
+    //INFO: https://no-color.org, set to anything but an empty string
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return ColorDepth::Monochrome;
+    }
+    if env::var_os("COLORTERM").is_some_and(|value| !value.is_empty()) {
+        return ColorDepth::Indexed;
+    }
+    let Ok(term) = env::var("TERM") else {
+        return ColorDepth::Indexed;
+    };
+    if term == "dumb" {
+        ColorDepth::Monochrome
+    } else if term.contains("256color") || term.contains("direct") {
+        ColorDepth::Indexed
+    } else if [
+        "linux", "ansi", "vt100", "vt220", "xterm", "screen", "tmux", "rxvt",
+    ]
+    .contains(&term.as_str())
+        || term.starts_with("xterm-16color")
+    {
+        ColorDepth::Basic
+    } else {
+        ColorDepth::Indexed
+    }
+}
+
+fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(index @ 0..=15) => Some(BASIC_COLORS[index as usize].1),
+        Color::Indexed(index @ 16..=231) => {
+            let index = index - 16;
+            Some((
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index % 36 / 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            ))
+        }
+        Color::Indexed(index) => {
+            let level = 8 + 10 * (index - 232);
+            Some((level, level, level))
+        }
+        _ => None,
+    }
+}
+
+fn to_basic(color: Color) -> Color {
+    let Some((r, g, b)) = to_rgb(color) else {
+        return color;
+    };
+    BASIC_COLORS
+        .iter()
+        .min_by_key(|(_, (br, bg, bb))| {
+            let distance = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            distance(r, *br) + distance(g, *bg) + distance(b, *bb)
+        })
+        .map_or(color, |(basic, _)| *basic)
+}
 
+/// Swaps the colors of the drawn frame for the theme's and brings them down to what the terminal
+/// can show.
+pub fn adapt_colors(buffer: &mut Buffer) {
+    let theme = THEME.get_or_init(Theme::default);
+    if !theme.colors.is_empty() {
+        for cell in buffer.content.iter_mut() {
+            cell.fg = theme.colors.get(&cell.fg).copied().unwrap_or(cell.fg);
+            cell.bg = theme.colors.get(&cell.bg).copied().unwrap_or(cell.bg);
+        }
+    }
+    let color_depth = match COLOR_DEPTH.get().copied() {
+        _ if theme.monochrome => ColorDepth::Monochrome,
+        Some(color_depth) => color_depth,
+        None => ColorDepth::Indexed,
+    };
+    match color_depth {
+        ColorDepth::Auto | ColorDepth::Indexed => {}
+        ColorDepth::Basic => {
+            for cell in buffer.content.iter_mut() {
+                cell.fg = to_basic(cell.fg);
+                cell.bg = to_basic(cell.bg);
+            }
+        }
+        ColorDepth::Monochrome => {
+            for cell in buffer.content.iter_mut() {
+                //INFO: Black is the background of the whole screen, the rest marks a highlight
+                if !matches!(cell.bg, Color::Reset | Color::Black) {
+                    cell.modifier.insert(Modifier::REVERSED);
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
+    }
+}
+
+/// Colors the UI is drawn with, the theme can swap them when the frame is drawn.
 pub struct Palette;
 
 impl Palette {
@@ -87,51 +361,128 @@ fn render_splash(frame: &mut Frame) {
     frame.render_widget(fly_visual, visual_area);
 }
 
-fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
-    let header_layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(vec![Constraint::Min(0), Constraint::Length(24)])
-        .split(area);
+/// Shown instead of the views while the session can't start, with the status of the action taken.
+pub fn render_token_problem(frame: &mut Frame, problem: &TokenProblem, status: Option<&str>) {
+    let (title, explanation, detail) = match problem {
+        TokenProblem::Missing(message) => ("No access token", message.as_str(), None),
+        TokenProblem::Invalid(detail) => (
+            "The access token is expired or revoked",
+            "Log in again to get a new one.",
+            Some(detail),
+        ),
+        TokenProblem::Unreachable(detail) => (
+            "Fly.io can't be reached",
+            "Check your network connection and retry.",
+            Some(detail),
+        ),
+    };
+    let mut lines = vec![
+        Line::from(title.fg(Palette::LIGHT_PINK).bold()),
+        Line::default(),
+        Line::from(explanation),
+    ];
+    if let Some(detail) = detail {
+        lines.push(Line::default());
+        lines.push(Line::from(detail.as_str().fg(Palette::GRAY)));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(vec![
+        "<l>".fg(Palette::LIGHT_PURPLE).bold(),
+        " Log in  ".to_span(),
+        "<r>".fg(Palette::LIGHT_PURPLE).bold(),
+        " Retry  ".to_span(),
+        "<q>".fg(Palette::LIGHT_PURPLE).bold(),
+        " Quit".to_span(),
+    ]));
+    if !flyctl_available() {
+        lines.push(Line::from(
+            "flyctl is not found in PATH, log in with it and retry.".fg(Color::Yellow),
+        ));
+    }
+    if let Some(status) = status {
+        lines.push(Line::default());
+        lines.push(Line::from(status.fg(Palette::BLUE)));
+    }
+
+    let outer = Block::default().bg(Color::Black);
+    frame.render_widget(outer, frame.area());
+    let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 4)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+    let content = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(ascii_title(Line::from(vec![
+                    "🎈 ".to_span(),
+                    "flyradar".fg(Palette::PURPLE).bold(),
+                    " 🎈".to_span(),
+                ])))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_set(border_set(border::ROUNDED))
+                .border_style(Style::new().bold().fg(Palette::PURPLE))
+                .padding(Padding::uniform(1)),
+        );
+    frame.render_widget(content, area);
+}
 
+/// Keybindings of the current view, the header shows as many as fit and `?` lists them all.
+fn keymap(state: &State) -> Vec<(String, &'static str)> {
+    let view = state.get_current_view();
+    let key = |actions: &[Action]| keybindings::label(Context::View(&view), actions);
+    let select = || (key(&[Action::Previous, Action::Next]), "Select");
+    let search = || (key(&[Action::Search]), "Search");
+    let mark = || (key(&[Action::Mark]), "Toggle checkbox");
     let mut keymap = vec![
-        ("<Ctrl-a>", "View commands"),
-        (":cmd", "Command mode"),
-        ("<Esc>", "Back/Cancel"),
+        (key(&[Action::ViewCommands]), "View commands"),
+        (key(&[Action::CommandMode]), "Command mode"),
+        (key(&[Action::NavigateBack]), "Back/Cancel"),
     ];
 
-    let current_view = state.get_current_view();
-    match current_view {
+    match view {
         View::Organizations { ref filter } => {
             keymap = [
                 &[
-                    ("<Enter>", "List apps"),
-                    ("<m>", "View members"),
-                    ("<Shift-a>", "Toggle admin-only"),
-                    ("<↑/↓>", "Select"),
-                    ("</>", "Search"),
+                    (key(&[Action::Select]), "List apps"),
+                    (key(&[Action::ListAllApps]), "List apps of all orgs"),
+                    (key(&[Action::ViewMembers]), "View members"),
+                    (key(&[Action::ToggleAdminOnly]), "Toggle admin-only"),
+                    (key(&[Action::Refresh]), "Refresh"),
+                    select(),
+                    search(),
                 ],
                 &keymap[..],
             ]
             .concat();
             if filter.is_admin_only() {
-                keymap.push(("<Ctrl-d>", "Delete"));
-                keymap.push(("<i>", "Invite"));
-                keymap.push(("<r>", "Remove"));
+                keymap.push((key(&[Action::Destroy]), "Delete"));
+                keymap.push((key(&[Action::Invite]), "Invite"));
+                keymap.push((key(&[Action::RemoveMember]), "Remove"));
             }
         }
         View::Apps { .. } => {
             keymap = [
                 &[
-                    ("<Enter>", "List machines"),
-                    ("<o>", "Open"),
-                    ("<l>", "Logs"),
-                    ("<r>", "View releases"),
-                    ("<s>", "View services"),
-                    ("<Ctrl-r>", "Restart"),
-                    ("<Ctrl-d>", "Destroy"),
-                    ("<↑/↓>", "Select"),
-                    ("</>", "Search"),
-                    ("<Space>", "Toggle checkbox"),
+                    (key(&[Action::Select]), "List machines"),
+                    (key(&[Action::Open]), "Open"),
+                    (key(&[Action::Logs]), "Logs"),
+                    (key(&[Action::ViewReleases]), "View releases"),
+                    (key(&[Action::ViewServices]), "View services"),
+                    (key(&[Action::ViewEnv]), "View env"),
+                    (key(&[Action::ViewPorts]), "View listening ports"),
+                    (key(&[Action::ViewAutoscaling]), "View autoscaling"),
+                    (key(&[Action::Cleanup]), "Cleanup"),
+                    (key(&[Action::CreateDeployToken]), "Create deploy token"),
+                    (key(&[Action::Restart]), "Restart"),
+                    (key(&[Action::Destroy]), "Destroy"),
+                    select(),
+                    search(),
+                    mark(),
                 ],
                 &keymap[..],
             ]
@@ -140,18 +491,31 @@ fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
         View::Machines { .. } => {
             keymap = [
                 &[
-                    ("<Enter>, <l>", "Logs"),
-                    ("<r>", "Restart"),
-                    ("<s>", "Start"),
-                    ("<u>", "Suspend"),
-                    ("<t>", "Stop"),
-                    ("<Ctrl-k>", "Kill"),
-                    ("<Ctrl-d>", "Destroy"),
-                    ("<c>", "Cordon"),
-                    ("<Shift-c>", "Uncordon"),
-                    ("<↑/↓>", "Select"),
-                    ("</>", "Search"),
-                    ("<Space>", "Toggle checkbox"),
+                    (key(&[Action::Select, Action::Logs]), "Logs"),
+                    (key(&[Action::Restart]), "Restart"),
+                    (key(&[Action::Start]), "Start"),
+                    (key(&[Action::Suspend]), "Suspend"),
+                    (key(&[Action::Stop]), "Stop"),
+                    (key(&[Action::Kill]), "Kill"),
+                    (key(&[Action::Destroy]), "Destroy"),
+                    (key(&[Action::Cordon]), "Cordon"),
+                    (key(&[Action::Uncordon]), "Uncordon"),
+                    (key(&[Action::SetMetadata]), "Set metadata"),
+                    (key(&[Action::UpdateImage]), "Update image"),
+                    (key(&[Action::Rename]), "Rename"),
+                    (key(&[Action::Align]), "Align config to selected"),
+                    (key(&[Action::Scale]), "Scale"),
+                    (key(&[Action::Console]), "SSH console"),
+                    (key(&[Action::FilterByHost]), "Filter by host"),
+                    (key(&[Action::ViewChecks]), "View health checks"),
+                    (key(&[Action::Describe]), "Describe"),
+                    (key(&[Action::Exec]), "Exec command"),
+                    (key(&[Action::CapturePackets]), "Capture packets"),
+                    (key(&[Action::ViewProcesses]), "View processes"),
+                    (key(&[Action::Pin]), "Pin/Unpin"),
+                    select(),
+                    search(),
+                    mark(),
                 ],
                 &keymap[..],
             ]
@@ -160,10 +524,16 @@ fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
         View::Volumes { .. } => {
             keymap = [
                 &[
-                    ("<Ctrl-d>", "Destroy"),
-                    ("<↑/↓>", "Select"),
-                    ("</>", "Search"),
-                    ("<Space>", "Toggle checkbox"),
+                    (key(&[Action::Destroy]), "Destroy"),
+                    (key(&[Action::ToggleOrphaned]), "Show orphans only"),
+                    (key(&[Action::Snapshot]), "Snapshot now"),
+                    (key(&[Action::Fork]), "Fork"),
+                    (key(&[Action::Create]), "Create"),
+                    (key(&[Action::Detach]), "Detach"),
+                    (key(&[Action::Pin]), "Pin/Unpin"),
+                    select(),
+                    search(),
+                    mark(),
                 ],
                 &keymap[..],
             ]
@@ -172,48 +542,137 @@ fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
         View::Secrets { .. } => {
             keymap = [
                 &[
-                    ("<u>", "Stage Unset"),
-                    ("<↑/↓>", "Select"),
-                    ("</>", "Search"),
-                    ("<Space>", "Toggle checkbox"),
+                    (key(&[Action::StageSet]), "Stage Set"),
+                    (key(&[Action::StageUnset]), "Stage Unset"),
+                    (key(&[Action::DeploySecrets]), "Deploy staged"),
+                    (key(&[Action::PendingSecrets]), "Pending changes"),
+                    (key(&[Action::Unstage]), "Un-stage last"),
+                    (key(&[Action::Pin]), "Pin/Unpin"),
+                    select(),
+                    search(),
+                    mark(),
                 ],
                 &keymap[..],
             ]
             .concat();
         }
-        View::AppLogs { .. } => {
+        View::Certificates { .. } | View::Processes { .. } => {
+            keymap = [&[select(), search()], &keymap[..]].concat();
+        }
+        View::Extensions { .. } => {
+            keymap = [
+                &[(key(&[Action::Open]), "Open dashboard"), select(), search()],
+                &keymap[..],
+            ]
+            .concat();
+        }
+        View::DedicatedHosts { .. } => {
             keymap = [
                 &[
-                    ("<t>", "Toggle region selector"),
-                    ("<↑/↓>", "Select region"),
-                    ("<f>", "Focus region"),
-                    ("<←/→>", "Change display filter level"),
-                    ("<+/->", "Change filter level"),
-                    ("<Ctrl-s>", "Dump logs"),
-                    ("<PageUp/Down>", "Scroll"),
-                    ("<r>", "Reset scroll"),
+                    (key(&[Action::Select]), "View machines"),
+                    select(),
+                    search(),
                 ],
                 &keymap[..],
             ]
             .concat();
         }
-        View::MachineLogs { .. } => {
+        View::WireGuardPeers { .. } => {
+            keymap = [
+                &[
+                    (key(&[Action::Create]), "Create peer"),
+                    (key(&[Action::Destroy]), "Remove peer"),
+                    select(),
+                    search(),
+                ],
+                &keymap[..],
+            ]
+            .concat();
+        }
+        View::Plugin { ref name } => {
+            let actions = plugins::get(name)
+                .map(|plugin| {
+                    plugin
+                        .actions()
+                        .iter()
+                        .map(|action| (action.keybinding.clone(), action.description.as_str()))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            keymap = [&actions[..], &[select(), search()], &keymap[..]].concat();
+        }
+        View::AppLogs { .. } => {
             keymap = [
                 &[
-                    ("<Ctrl-s>", "Dump logs"),
-                    ("<PageUp/Down>", "Scroll"),
-                    ("<r>", "Reset scroll"),
+                    (key(&[Action::ToggleRegions]), "Toggle region selector"),
+                    (key(&[Action::Previous, Action::Next]), "Select region"),
+                    (key(&[Action::FocusRegion]), "Focus region"),
+                    (key(&[Action::FocusRegionSearch]), "Focus region by search"),
+                    (key(&[Action::CycleMachineFilter]), "Cycle machine filter"),
+                    (
+                        key(&[Action::DecreaseDisplayLevel, Action::IncreaseDisplayLevel]),
+                        "Change display filter level",
+                    ),
+                    (
+                        key(&[Action::IncreaseFilterLevel, Action::DecreaseFilterLevel]),
+                        "Change filter level",
+                    ),
                 ],
+                &logs_keymap(&key)[..],
                 &keymap[..],
             ]
             .concat();
         }
+        View::MachineLogs { .. } => {
+            keymap = [&logs_keymap(&key)[..], &keymap[..]].concat();
+        }
+    }
+
+    if !view.is_logs() {
+        keymap.push((key(&[Action::Export]), "Export as Markdown"));
     }
 
     if matches!(state.multi_select_mode, MultiSelectMode::On(..)) {
-        keymap = [&keymap[..], &[("<Enter>", "Apply")]].concat();
+        keymap.push((key(&[Action::Select]), "Apply"));
     }
 
+    //INFO: First so it stays visible when the header can't fit the rest
+    [
+        &[(key(&[Action::ViewKeybindings]), "All keybindings")],
+        &keymap[..],
+    ]
+    .concat()
+}
+
+/// Keybindings shared by the app and the machine logs.
+fn logs_keymap(key: &dyn Fn(&[Action]) -> String) -> Vec<(String, &'static str)> {
+    vec![
+        (key(&[Action::ToggleRawJson]), "Toggle raw JSON"),
+        (key(&[Action::Search]), "Search"),
+        (
+            key(&[Action::NextMatch, Action::PrevMatch]),
+            "Next/previous match",
+        ),
+        (key(&[Action::DumpLogs]), "Dump logs"),
+        (key(&[Action::TeeLogs]), "Tee logs to a file"),
+        (key(&[Action::LogStats]), "View stats"),
+        (key(&[Action::Select]), "View bottom line"),
+        (key(&[Action::CopyMode]), "Copy mode"),
+        (key(&[Action::SplitByLevel]), "Split errors/warnings"),
+        (key(&[Action::PreviousPage, Action::NextPage]), "Scroll"),
+        (key(&[Action::ResetScroll]), "Reset scroll"),
+    ]
+}
+
+fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
+    let header_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Min(0), Constraint::Length(24)])
+        .split(area);
+
+    let current_view = state.get_current_view();
+    let keymap = keymap(state);
+
     let max_item_width = keymap
         .iter()
         .map(|(key, action)| {
@@ -244,7 +703,7 @@ fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
                 .skip(row_length * col_idx)
                 .zip(rows.iter())
                 .enumerate() // Add enumerate to track position
-                .for_each(|(i, (&(key, action), row))| {
+                .for_each(|(i, ((key, action), row))| {
                     let multi_select_action = i + row_length * col_idx == (keymap.len() - 1);
                     let color = if matches!(state.multi_select_mode, MultiSelectMode::On(..))
                         && multi_select_action
@@ -262,9 +721,9 @@ fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
                     };
 
                     let line = Line::from(vec![
-                        Span::styled(key, Style::default().fg(color)),
+                        Span::styled(key.as_str(), Style::default().fg(color)),
                         Span::raw(": "),
-                        Span::raw(String::from(action) + " "),
+                        Span::raw(String::from(*action) + " "),
                     ]);
                     frame.render_widget(Paragraph::new(line), *row);
                 });
@@ -275,19 +734,56 @@ fn render_header(state: &mut State, frame: &mut Frame, area: Rect) {
         .constraints([Constraint::Min(0), Constraint::Length(2)])
         .split(header_layout[1]);
 
-    frame.render_widget(
-        Block::default()
-            .title(vec![
-                "★ ".fg(Palette::TEAL),
-                build::PROJECT_NAME.bold(),
-                "-".fg(Color::White),
-                build::PKG_VERSION.into(),
-                " ★".fg(Palette::TEAL),
+    let mut header_block = Block::default()
+        .title(vec![
+            glyph("★ ", "* ").fg(Palette::TEAL),
+            build::PROJECT_NAME.bold(),
+            "-".fg(Color::White),
+            build::PKG_VERSION.into(),
+            glyph(" ★", " *").fg(Palette::TEAL),
+        ])
+        .title_alignment(Alignment::Center);
+    if let Some(viewer) = &state.viewer {
+        let mut spans = vec![
+            Span::raw(glyph(" 👤 ", " ")),
+            viewer.email.clone().fg(Palette::LIGHT_PURPLE),
+        ];
+        if let Some(org) = &viewer.scoped_org {
+            spans.push(" @ ".fg(Color::White));
+            spans.push(org.clone().fg(Palette::BLUE));
+        }
+        spans.push(" ".to_span());
+        header_block = header_block.title_bottom(Line::from(spans).right_aligned());
+    }
+    if !flyctl_available() {
+        header_block = header_block.title_bottom(
+            Line::from(vec![
+                glyph(" ⚠ ", " ! ").fg(Color::Yellow),
+                FLYCTL_UNAVAILABLE_MESSAGE.fg(Color::Yellow),
+                " ".to_span(),
             ])
-            .title_alignment(Alignment::Center),
-        area,
-    );
+            .left_aligned(),
+        );
+    }
+    if let Some(transport) = state.log_transport {
+        let (icon, text, color) = match transport {
+            LogTransport::Nats => (glyph(" ● ", " * "), "Logs over NATS", Palette::LIGHT_TEAL),
+            LogTransport::Polling => (glyph(" ● ", " * "), "Logs polled", Palette::BLUE),
+            LogTransport::Reconnecting => (
+                glyph(" ⟳ ", " ~ "),
+                "NATS dropped, polling while reconnecting",
+                Color::Yellow,
+            ),
+        };
+        header_block = header_block.title_bottom(
+            Line::from(vec![icon.fg(color), text.fg(color), " ".to_span()]).left_aligned(),
+        );
+    }
+    frame.render_widget(header_block, area);
 
+    if is_low_bandwidth() {
+        return;
+    }
     let banner_logo = fly_balloon::FlyBalloonWidget::default();
     let banner_text = Paragraph::new("Manage your Fly.io resources")
         .centered()
@@ -311,10 +807,44 @@ pub fn render_input(frame: &mut Frame, area: Rect, input: &Input, content: Line)
     frame.render_widget(input_bar, area);
 }
 
+/// Alerts raised by the watches, the ones not fitting are summarized on the last row.
+fn render_alerts(state: &State, frame: &mut Frame, area: Rect) {
+    let mut lines = state
+        .alerts
+        .iter()
+        .take(MAX_ALERT_ROWS)
+        .map(|alert| Line::from(format!(" {} {}", glyph("⚠", "!"), alert)))
+        .collect::<Vec<_>>();
+    if state.alerts.len() > MAX_ALERT_ROWS {
+        lines[MAX_ALERT_ROWS - 1] = Line::from(format!(
+            " {} {} more alerts",
+            glyph("⚠", "!"),
+            state.alerts.len() - MAX_ALERT_ROWS + 1
+        ));
+    }
+    let alerts = Paragraph::new(lines)
+        .bold()
+        .fg(Palette::DARK_GRAY)
+        .bg(Palette::DARK_PINK);
+    frame.render_widget(alerts, area);
+}
+
+/// Recent restarts and OOM kills of the machines on a single row, newest first.
+fn render_machine_events(state: &State, frame: &mut Frame, area: Rect) {
+    let events = Paragraph::new(format!(
+        " Events: {}",
+        state.machine_events.join(glyph(" · ", " | "))
+    ))
+    .fg(Palette::LIGHT_TEAL)
+    .bg(Palette::DARK_GRAY);
+    frame.render_widget(events, area);
+}
+
 fn render_input_bar(state: &mut State, frame: &mut Frame, area: Rect) {
     let search_mode = matches!(state.input_state, InputState::Search { .. });
-    let outer = Block::default()
+    let mut outer = Block::default()
         .borders(Borders::all())
+        .border_set(border_set(border::PLAIN))
         .border_style(Style::new().fg({
             if search_mode {
                 Palette::BLUE
@@ -322,6 +852,36 @@ fn render_input_bar(state: &mut State, frame: &mut Frame, area: Rect) {
                 Palette::PINK
             }
         }));
+    if let InputState::Command { input, .. } = &state.input_state {
+        let name = input.value().split_whitespace().next().unwrap_or_default();
+        if let Ok(command) = match_command(name).parse::<Command>() {
+            let spec = command.spec();
+            let usage = spec.usage();
+            let hint = if usage.is_empty() {
+                format!(" {} ", spec.description)
+            } else {
+                format!(
+                    " {} {}: {} ",
+                    command.to_aliases().last().unwrap(),
+                    usage,
+                    spec.description
+                )
+            };
+            outer = outer.title_bottom(Line::from(hint).dim());
+        }
+    }
+    if search_mode && state.get_current_view().is_logs() {
+        let mode = if state.log_search_regex {
+            "regex"
+        } else {
+            "plain"
+        };
+        let mut hint = vec![format!(" {} <Ctrl-r> Toggle regex ", mode).dim()];
+        if state.log_search_invalid {
+            hint.push(Span::from("invalid regex ").fg(Palette::DARK_PINK));
+        }
+        outer = outer.title_bottom(Line::from(hint));
+    }
     let outer_area = outer.inner(area);
     frame.render_widget(outer, area);
 
@@ -330,12 +890,19 @@ fn render_input_bar(state: &mut State, frame: &mut Frame, area: Rect) {
         .constraints([Constraint::Length(4), Constraint::Min(0)])
         .split(outer_area);
     frame.render_widget(
-        format!("{}> ", if search_mode { "🌞" } else { "🪁" }),
+        format!(
+            "{}> ",
+            if search_mode {
+                glyph("🌞", "/")
+            } else {
+                glyph("🪁", ":")
+            }
+        ),
         layout[0],
     );
 
     match &state.input_state {
-        InputState::Command { input, command } => {
+        InputState::Command { input, command, .. } => {
             let mut input_text = vec![input.value().into()];
             input_text.push(command.strip_prefix(input.value()).unwrap_or("").dim());
             render_input(frame, layout[1], input, Line::from(input_text));
@@ -372,6 +939,12 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                 | View::Machines { .. }
                 | View::Volumes { .. }
                 | View::Secrets { .. }
+                | View::Certificates { .. }
+                | View::Extensions { .. }
+                | View::DedicatedHosts { .. }
+                | View::WireGuardPeers { .. }
+                | View::Processes { .. }
+                | View::Plugin { .. }
         );
     if is_multi_select_shown {
         layout.insert(0, Constraint::Length(2));
@@ -419,7 +992,13 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
         | View::Apps { .. }
         | View::Machines { .. }
         | View::Volumes { .. }
-        | View::Secrets { .. } => {
+        | View::Secrets { .. }
+        | View::Certificates { .. }
+        | View::Extensions { .. }
+        | View::DedicatedHosts { .. }
+        | View::WireGuardPeers { .. }
+        | View::Processes { .. }
+        | View::Plugin { .. } => {
             if is_multi_select_shown {
                 let multi_select_reason_feedback_text = match state.multi_select_mode {
                     MultiSelectMode::On(MultiSelectModeReason::RestartMachines) => {
@@ -440,6 +1019,12 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                     MultiSelectMode::On(MultiSelectModeReason::UncordonMachines) => {
                         "Select the machines you want to reactivate the services on."
                     }
+                    MultiSelectMode::On(MultiSelectModeReason::SetMachinesMetadata) => {
+                        "Select the machines you want to set the metadata on."
+                    }
+                    MultiSelectMode::On(MultiSelectModeReason::UpdateMachinesImage) => {
+                        "Select the machines you want to update the image of, in the order to update them."
+                    }
                     MultiSelectMode::On(MultiSelectModeReason::UnsetSecrets) => {
                         "Select the secrets you want to stage unset."
                     }
@@ -465,18 +1050,32 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
             let headers = current_view.headers();
             let max_cell_width = (layout[0].width as usize).saturating_sub(4) / headers.len();
 
-            // Skip ids for orgs and apps as we don't show them.
+            // Skip ids for orgs, apps and extensions as we don't show them.
             let data_skip_index = match current_view {
-                View::Organizations { .. } | View::Apps { .. } => 1,
+                View::Organizations { .. } | View::Apps { .. } | View::Extensions { .. } => 1,
                 _ => 0,
             };
 
+            // The machines keep their creation time, the age is computed on every draw.
+            let age_index = match current_view {
+                View::Machines { .. } => headers.iter().position(|header| *header == "Age"),
+                _ => None,
+            };
+
+            let search_query = SearchQuery::parse(&resource_list.search_filter);
+            // Rows can carry trailing columns that are only there to be searched.
             let filtered_rows = resource_list.filtered_items.iter().map(|row| {
                 let cells = row
                     .iter()
                     .skip(data_skip_index)
+                    .take(headers.len())
                     .enumerate()
                     .map(|(i, value)| {
+                        let value = &if age_index == Some(i) {
+                            format_age(value)
+                        } else {
+                            value.clone()
+                        };
                         let content = if value.width() > max_cell_width {
                             let truncated: String = value
                                 .chars()
@@ -488,7 +1087,7 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                         };
 
                         let mut spans = if !resource_list.search_filter.is_empty() {
-                            highlight_search_result(content.into(), &resource_list.search_filter)
+                            highlight_search_result(content.into(), &search_query.include)
                         } else {
                             Line::from(content).spans
                         };
@@ -501,6 +1100,9 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                             };
                             spans.insert(0, prefix);
                         }
+                        if i == 0 && resource_list.is_pinned(row) {
+                            spans.insert(0, Span::from(glyph("📌 ", "^ ")));
+                        }
 
                         Cell::from(Line::from(spans))
                     });
@@ -549,10 +1151,14 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                             ));
                             spans.push(Span::raw(" "));
                         }
+                        if let Some(span) = pending_secrets_span(state) {
+                            spans.push(span);
+                        }
                         spans
                     }))
                     .title_alignment(Alignment::Center)
                     .borders(Borders::all())
+                    .border_set(border_set(border::PLAIN))
                     .border_style(Style::new().fg({
                         if !resource_list.search_filter.is_empty() {
                             Palette::BLUE
@@ -565,14 +1171,18 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                     .padding(Padding::horizontal(1)),
             )
             .row_highlight_style(Style::default().bg(Palette::LIGHT_PURPLE).fg(Color::Black));
-            frame.render_stateful_widget(
-                table,
-                layout[if is_multi_select_shown { 1 } else { 0 }],
-                &mut table_state,
-            );
+            let mut table_area = layout[if is_multi_select_shown { 1 } else { 0 }];
+            if matches!(current_view, View::Machines { .. }) && !state.machine_events.is_empty() {
+                let [area, events_area] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(table_area);
+                table_area = area;
+                render_machine_events(state, frame, events_area);
+            }
+            frame.render_stateful_widget(table, table_area, &mut table_state);
         }
         View::AppLogs { .. } => {
             let logs = TuiLoggerSmartWidget::default()
+                .border_set(border_set(border::PLAIN))
                 .border_style(Style::new().fg({
                     // if !resource_list.search_filter.is_empty() {
                     //     Palette::BLUE
@@ -587,11 +1197,20 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                 .title_target(Line::from(" Regions ").fg(Palette::PINK))
                 .title_log(Line::from({
                     let scopes = state.get_scopes().iter().skip(1).join("/");
-                    let spans = vec![
+                    let mut spans = vec![
                         Span::from(" App logs(").bold().fg(Palette::PINK),
                         Span::from(scopes).bold().fg(Palette::LIGHT_PURPLE),
                         Span::from(") ").bold().fg(Palette::PINK),
                     ];
+                    if let Some(instance) = state.logs_state.focused_instance() {
+                        spans.push(Span::styled(
+                            format!("machine={}", instance),
+                            Style::default()
+                                .bg(Palette::DARK_GRAY)
+                                .fg(Palette::LIGHT_BLUE),
+                        ));
+                        spans.push(Span::raw(" "));
+                    }
                     // if !resource_list.search_filter.is_empty() {
                     //     spans.push(Span::styled(
                     //         format!("/{}", resource_list.search_filter),
@@ -601,6 +1220,7 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                     //     ));
                     //     spans.push(Span::raw(" "));
                     // }
+                    spans.extend(log_tee_span(state));
                     spans
                 }))
                 .style_error(Style::default().fg(Color::Red))
@@ -616,13 +1236,15 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                 .output_line(false)
                 .state(&state.logs_state);
 
-            frame.render_widget(logs, layout[0]);
+            let logs_area = render_pinned_logs(state, frame, layout[0]);
+            frame.render_widget(logs, logs_area);
         }
         View::MachineLogs { .. } => {
             // info!("Logs opts: {:#?}", opts);
             let logs = TuiLoggerWidget::default()
                 .block(
                     Block::bordered()
+                        .border_set(border_set(border::PLAIN))
                         .border_style(Style::new().fg({
                             // if !resource_list.search_filter.is_empty() {
                             //     Palette::BLUE
@@ -635,7 +1257,7 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                         }))
                         .title(Line::from({
                             let scopes = state.get_scopes().iter().skip(1).join("/");
-                            let spans = vec![
+                            let mut spans = vec![
                                 Span::from(" Machine logs(").bold().fg(Palette::PINK),
                                 Span::from(scopes).bold().fg(Palette::LIGHT_PURPLE),
                                 Span::from(") ").bold().fg(Palette::PINK),
@@ -649,6 +1271,7 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                             //     ));
                             //     spans.push(Span::raw(" "));
                             // }
+                            spans.extend(log_tee_span(state));
                             spans
                         })),
                 )
@@ -665,11 +1288,70 @@ fn render_current_view(state: &mut State, frame: &mut Frame, area: Rect) {
                 .output_line(false)
                 .state(&state.logs_state);
 
-            frame.render_widget(logs, layout[0]);
+            let logs_area = render_pinned_logs(state, frame, layout[0]);
+            frame.render_widget(logs, logs_area);
         }
     }
 }
 
+/// Shows the newest errors and warnings above the logs while they're split, returns the area
+/// left for all the logs.
+fn render_pinned_logs(state: &State, frame: &mut Frame, area: Rect) -> Rect {
+    if !state.logs_split {
+        return area;
+    }
+    let [pinned_area, area] =
+        Layout::vertical([Constraint::Percentage(30), Constraint::Min(0)]).areas(area);
+    let pinned = TuiLoggerWidget::default()
+        .block(
+            Block::bordered()
+                .border_set(border_set(border::PLAIN))
+                .border_style(Style::new().fg(Palette::DARK_PINK))
+                .title(Line::from(" Errors & warnings ").bold().fg(Palette::PINK)),
+        )
+        .style_error(Style::default().fg(Color::Red))
+        .style_warn(Style::default().fg(Color::Yellow))
+        .output_separator(' ')
+        .output_timestamp(Some("%H:%M:%S".to_string()))
+        .output_level(Some(TuiLoggerLevelOutput::Long))
+        .output_target(true)
+        .output_file(false)
+        .output_line(false)
+        .pinned_level(Level::Warn)
+        .state(&state.logs_state);
+    frame.render_widget(pinned, pinned_area);
+    area
+}
+
+/// Badge of the Secrets view while changes are staged but not deployed yet.
+fn pending_secrets_span(state: &State) -> Option<Span<'static>> {
+    if !matches!(state.get_current_view(), View::Secrets { .. }) {
+        return None;
+    }
+    match state.pending_secret_changes().len() {
+        0 => None,
+        count => Some(
+            Span::from(format!(" {} pending ", count))
+                .bold()
+                .fg(Palette::DARK_GRAY)
+                .bg(Palette::LIGHT_PINK),
+        ),
+    }
+}
+
+/// Shows where the streamed logs are teed to, while the tee is on
+fn log_tee_span(state: &State) -> Option<Span<'static>> {
+    let path = state.log_tee.as_ref()?;
+    Some(
+        Span::from(format!(
+            "{}tee {} ",
+            glyph("● ", ""),
+            path.to_string_lossy()
+        ))
+        .fg(Palette::LIGHT_TEAL),
+    )
+}
+
 fn render_view_list_popup(
     frame: &mut Frame,
     area: Rect,
@@ -681,7 +1363,7 @@ fn render_view_list_popup(
     percent_y: u16,
     with_title: bool,
     custom_widths: Option<Vec<usize>>,
-    op_actions: Vec<&CheckBox>,
+    op_actions: Vec<&dyn FocusableWidget>,
     popup_actions: Vec<&TextBox>,
 ) {
     // Calculate constraints based on custom widths or uniform distribution
@@ -843,6 +1525,38 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 ]),
                 0,
             ),
+            PopupType::CreateDeployTokenPopup => (
+                Line::from(vec![
+                    "🔑 ".to_span(),
+                    "Create a deploy token".fg(Palette::TEAL).bold(),
+                    " 🔑".to_span(),
+                ]),
+                2,
+            ),
+            PopupType::CreateWireGuardPeerPopup => (
+                Line::from(vec![
+                    "🔒 ".to_span(),
+                    "Create a WireGuard peer".fg(Palette::TEAL).bold(),
+                    " 🔒".to_span(),
+                ]),
+                3,
+            ),
+            PopupType::ViewDeployTokenPopup => (
+                Line::from(vec![
+                    "🔑 ".to_span(),
+                    "Deploy token".fg(Palette::TEAL).bold(),
+                    " 🔑".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::RollbackReleasePopup => (
+                Line::from(vec![
+                    "⏪ ".to_span(),
+                    "Roll back".fg(Palette::PINK).bold(),
+                    " ⏪".to_span(),
+                ]),
+                0,
+            ),
             PopupType::ViewAppServicesPopup => (
                 Line::from(vec![
                     "🌟 ".to_span(),
@@ -851,6 +1565,142 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 ]),
                 0,
             ),
+            PopupType::EditAppServicePopup => (
+                Line::from(vec![
+                    "🌟 ".to_span(),
+                    "Edit the service".fg(Color::Yellow).bold(),
+                    " 🌟".to_span(),
+                ]),
+                4,
+            ),
+            PopupType::UpdateAppServicesPopup => (
+                Line::from(vec![
+                    "🌟 ".to_span(),
+                    "Update the services".fg(Color::Yellow).bold(),
+                    " 🌟".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::UpdateAppAutoscalingPopup => (
+                Line::from(vec![
+                    "⚖️ ".to_span(),
+                    "Update the autoscaling".fg(Color::Yellow).bold(),
+                    " ⚖️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ConfirmOperationPopup => (
+                Line::from(vec![
+                    "⚠️ ".to_span(),
+                    "Confirm".fg(Color::Yellow).bold(),
+                    " ⚠️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewAppEnvPopup => (
+                Line::from(vec![
+                    "🌱 ".to_span(),
+                    "App env".fg(Palette::LIGHT_TEAL).bold(),
+                    " 🌱".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::AppCleanupPopup => (
+                Line::from(vec![
+                    "🧹 ".to_span(),
+                    "Cleanup".fg(Palette::LIGHT_PINK).bold(),
+                    " 🧹".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ScaleMachinePopup => (
+                Line::from(vec![
+                    "📐 ".to_span(),
+                    "Scale".fg(Color::Yellow).bold(),
+                    " 📐".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewAppAutoscalingPopup => (
+                Line::from(vec![
+                    "⚖️ ".to_span(),
+                    "Autoscaling".fg(Color::Yellow).bold(),
+                    " ⚖️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewAppPortsPopup => (
+                Line::from(vec![
+                    "🔌 ".to_span(),
+                    "Listening ports".fg(Color::Yellow).bold(),
+                    " 🔌".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewDedicatedHostMachinesPopup => (
+                Line::from(vec![
+                    "🖥️ ".to_span(),
+                    "Dedicated host machines".fg(Palette::TEAL).bold(),
+                    " 🖥️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewAppReleasesDiffPopup => (
+                Line::from(vec![
+                    "🤖 ".to_span(),
+                    "Release changes".fg(Palette::PINK).bold(),
+                    " 🤖".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ExecMachinePopup => (
+                Line::from(vec![
+                    "⌨️ ".to_span(),
+                    "Exec".fg(Palette::BLUE).bold(),
+                    " ⌨️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewMachineExecPopup => (
+                Line::from(vec![
+                    "⌨️ ".to_span(),
+                    "Exec output".fg(Palette::BLUE).bold(),
+                    " ⌨️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewMachineDetailPopup => (
+                Line::from(vec![
+                    "🔎 ".to_span(),
+                    "Machine details".fg(Palette::LIGHT_PINK).bold(),
+                    " 🔎".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewMachineChecksPopup => (
+                Line::from(vec![
+                    "🩺 ".to_span(),
+                    "Health checks".fg(Color::LightGreen).bold(),
+                    " 🩺".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewLogLinePopup => (
+                Line::from(vec![
+                    "🔎 ".to_span(),
+                    "Log line".fg(Palette::LIGHT_PINK).bold(),
+                    " 🔎".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewLogStatsPopup => (
+                Line::from(vec![
+                    "📊 ".to_span(),
+                    "Log stats".fg(Palette::PINK).bold(),
+                    " 📊".to_span(),
+                ]),
+                0,
+            ),
             PopupType::ViewCommandsPopup => (
                 Line::from(vec![
                     "🪁 ".to_span(),
@@ -859,6 +1709,14 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 ]),
                 0,
             ),
+            PopupType::ViewKeybindingsPopup => (
+                Line::from(vec![
+                    "⌨️ ".to_span(),
+                    "Keybindings".fg(Palette::PINK).bold(),
+                    " ⌨️".to_span(),
+                ]),
+                0,
+            ),
             PopupType::StartMachinesPopup => (
                 Line::from(vec![
                     "▶️ ".to_span(),
@@ -891,14 +1749,127 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 ]),
                 0,
             ),
-            PopupType::CordonMachinesPopup => (
+            PopupType::AlignMachinesPopup => (
                 Line::from(vec![
-                    "🚧 ".to_span(),
-                    "Cordon machines".fg(Palette::TEAL).bold(),
+                    "🧬 ".to_span(),
+                    "Align the machines".fg(Palette::DARK_PINK).bold(),
+                    " 🧬".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::RenameMachinePopup => (
+                Line::from(vec![
+                    "🏷️ ".to_span(),
+                    "Rename the machine".fg(Palette::LIGHT_BLUE).bold(),
+                    " 🏷️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::SnapshotVolumePopup => (
+                Line::from(vec![
+                    "📸 ".to_span(),
+                    "Snapshot the volume".fg(Palette::LIGHT_BLUE).bold(),
+                    " 📸".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ForkVolumePopup => (
+                Line::from(vec![
+                    "🍴 ".to_span(),
+                    "Fork the volume".fg(Palette::LIGHT_BLUE).bold(),
+                    " 🍴".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::FocusLogsRegionPopup => (
+                Line::from(vec![
+                    "🌍 ".to_span(),
+                    "Focus a region".fg(Palette::LIGHT_BLUE).bold(),
+                    " 🌍".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::DetachVolumePopup => (
+                Line::from(vec![
+                    "🔌 ".to_span(),
+                    "Detach the volume".fg(Palette::LIGHT_BLUE).bold(),
+                    " 🔌".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::CreateVolumePopup => (
+                Line::from(vec![
+                    "💾 ".to_span(),
+                    "Create a volume".fg(Palette::LIGHT_BLUE).bold(),
+                    " 💾".to_span(),
+                ]),
+                5,
+            ),
+            PopupType::DeploySecretsPopup => (
+                Line::from(vec![
+                    "🚀 ".to_span(),
+                    "Deploy the secrets".fg(Palette::DARK_PINK).bold(),
+                    " 🚀".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::SetSecretPopup => (
+                Line::from(vec![
+                    "🔑 ".to_span(),
+                    "Stage Set the secret".fg(Palette::LIGHT_BLUE).bold(),
+                    " 🔑".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::PendingSecretsPopup => (
+                Line::from(vec![
+                    "🔑 ".to_span(),
+                    "Pending changes".fg(Palette::LIGHT_BLUE).bold(),
+                    " 🔑".to_span(),
+                ]),
+                //INFO: A checkbox per change, then Cancel and OK
+                popup_state.actions.children.len().saturating_sub(2),
+            ),
+            PopupType::CordonMachinesPopup => (
+                Line::from(vec![
+                    "🚧 ".to_span(),
+                    "Cordon machines".fg(Palette::TEAL).bold(),
                     " 🚧".to_span(),
                 ]),
                 0,
             ),
+            PopupType::ExportViewPopup => (
+                Line::from(vec![
+                    "📋 ".to_span(),
+                    "Export as Markdown".fg(Palette::TEAL).bold(),
+                    " 📋".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::SetMachinesMetadataPopup => (
+                Line::from(vec![
+                    "🏷️ ".to_span(),
+                    "Set metadata".fg(Palette::TEAL).bold(),
+                    " 🏷️".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::UpdateMachinesImagePopup => (
+                Line::from(vec![
+                    "📦 ".to_span(),
+                    "Update image".fg(Palette::TEAL).bold(),
+                    " 📦".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::ViewMachinesUpdatePopup => (
+                Line::from(vec![
+                    "📦 ".to_span(),
+                    "Rolling update".fg(Palette::TEAL).bold(),
+                    " 📦".to_span(),
+                ]),
+                0,
+            ),
             PopupType::UncordonMachinesPopup => (
                 Line::from(vec![
                     "🆓 ".to_span(),
@@ -907,20 +1878,64 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 ]),
                 0,
             ),
+            PopupType::DumpLogsPopup => (
+                Line::from(vec![
+                    "💾 ".to_span(),
+                    "Dump logs".fg(Palette::BLUE).bold(),
+                    " 💾".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::TeeLogsPopup => (
+                Line::from(vec![
+                    "📝 ".to_span(),
+                    "Tee logs".fg(Palette::BLUE).bold(),
+                    " 📝".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::CapturePacketsPopup => (
+                Line::from(vec![
+                    "📡 ".to_span(),
+                    "Capture packets".fg(Palette::BLUE).bold(),
+                    " 📡".to_span(),
+                ]),
+                0,
+            ),
+            PopupType::OverwriteLogsDumpPopup => (
+                Line::from(vec![
+                    "⚠️ ".to_span(),
+                    "Overwrite the file".fg(Color::Yellow).bold(),
+                    " ⚠️".to_span(),
+                ]),
+                0,
+            ),
         };
+        let mut title = title;
+        if let Some(wizard) = &popup_state.wizard {
+            title.spans.push(format!(" ({})", wizard.progress()).dim());
+        }
         let popup = Block::default()
-            .title(title.alignment(Alignment::Center))
+            .title(ascii_title(title).alignment(Alignment::Center))
             .style(Style::default().white().on_black())
             .borders(Borders::ALL)
-            .border_set(border::ROUNDED)
+            .border_set(border_set(border::ROUNDED))
             .border_style(Style::new().bold().fg(Palette::PURPLE));
 
+        //INFO: The pages of a wizard have their own number of fields
+        let popup_actions_index = match &popup_state.wizard {
+            Some(_) => popup_state
+                .actions
+                .children
+                .iter()
+                .position(|action| action.as_any().is::<TextBox>())
+                .unwrap_or_default(),
+            None => popup_actions_index,
+        };
         let (op_actions, popup_actions) =
             popup_state.actions.children.split_at(popup_actions_index);
-        let op_actions: Vec<&CheckBox> = op_actions
-            .iter()
-            .filter_map(|action| action.as_any().downcast_ref::<CheckBox>())
-            .collect();
+        let op_actions: Vec<&dyn FocusableWidget> =
+            op_actions.iter().map(|action| action.as_ref()).collect();
         let popup_actions: Vec<&TextBox> = popup_actions
             .iter()
             .filter_map(|action| action.as_any().downcast_ref::<TextBox>())
@@ -936,7 +1951,7 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                     "Date",
                     "Docker Image",
                 ];
-                let mut max_cell_widths = vec![10, 10, 12, 12, 20];
+                let mut max_cell_widths = vec![12, 10, 12, 12, 20];
 
                 // Calculate last column width
                 let last_col_max_cell_width = ((area.width as usize) * 100 / 100_usize)
@@ -944,13 +1959,38 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                     .saturating_sub(max_cell_widths.iter().sum());
                 max_cell_widths.push(last_col_max_cell_width);
 
+                // Mark the cursor and the releases marked to be diffed on the version column
+                let app_releases_list = state
+                    .app_releases_list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, release)| {
+                        let mut release = release.clone();
+                        release[0] = format!(
+                            "{}{} {}",
+                            if i == state.app_releases_cursor {
+                                glyph("▶", ">")
+                            } else {
+                                " "
+                            },
+                            if state.app_releases_marked.contains(&release[0]) {
+                                "[x]"
+                            } else {
+                                "[ ]"
+                            },
+                            release[0]
+                        );
+                        release
+                    })
+                    .collect::<Vec<_>>();
+
                 render_view_list_popup(
                     frame,
                     area,
                     popup,
                     popup_state,
                     headers,
-                    &state.app_releases_list,
+                    &app_releases_list,
                     100,
                     75,
                     true,
@@ -969,15 +2009,186 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                     "Process Group",
                     "Regions",
                     "Machines",
+                    "Warnings",
+                ];
+
+                let app_services_list = state
+                    .app_services_list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, service)| {
+                        let mut service = service.clone();
+                        service[0] = format!(
+                            "{} {}",
+                            if i == state.app_services_cursor {
+                                glyph("▶", ">")
+                            } else {
+                                " "
+                            },
+                            service[0]
+                        );
+                        service
+                    })
+                    .collect::<Vec<_>>();
+
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    headers,
+                    &app_services_list,
+                    100,
+                    75,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewAppEnvPopup => {
+                let headers = &["Key", "Value", "Machines", "Drift"];
+
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    headers,
+                    &state.app_env_list,
+                    100,
+                    75,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::AppCleanupPopup => {
+                let headers = &["Kind", "Id", "Region", "Reason"];
+
+                // Mark the cursor and the findings marked to be removed on the kind column
+                let app_cleanup_list = state
+                    .app_cleanup_list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, finding)| {
+                        let mut finding = finding.clone();
+                        finding[0] = format!(
+                            "{}{} {}",
+                            if i == state.app_cleanup_cursor {
+                                glyph("▶", ">")
+                            } else {
+                                " "
+                            },
+                            if state.app_cleanup_marked.contains(&finding[1]) {
+                                "[x]"
+                            } else {
+                                "[ ]"
+                            },
+                            finding[0]
+                        );
+                        finding
+                    })
+                    .collect::<Vec<_>>();
+
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    headers,
+                    &app_cleanup_list,
+                    100,
+                    75,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ScaleMachinePopup => {
+                let headers = &["Size", "CPUs", "Memory", "Est. Monthly", "Difference"];
+
+                let machine_scale_list = state
+                    .machine_scale_list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, preset)| {
+                        let mut preset = preset.clone();
+                        preset[0] = format!(
+                            "{} {}",
+                            if i == state.machine_scale_cursor {
+                                glyph("▶", ">")
+                            } else {
+                                " "
+                            },
+                            preset[0]
+                        );
+                        preset
+                    })
+                    .collect::<Vec<_>>();
+
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    headers,
+                    &machine_scale_list,
+                    100,
+                    75,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewAppAutoscalingPopup => {
+                let headers = &[
+                    "Process Group",
+                    "Region",
+                    "Started",
+                    "Autostop",
+                    "Autostart",
+                    "Min Running",
+                    "Metrics",
                 ];
 
+                let app_autoscaling_list = state
+                    .app_autoscaling_list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, group)| {
+                        let mut group = group.clone();
+                        let staged = state
+                            .app_autoscaling_changes
+                            .contains_key(&(group[0].clone(), group[1].clone()));
+                        group[0] = format!(
+                            "{} {}{}",
+                            if i == state.app_autoscaling_cursor {
+                                glyph("▶", ">")
+                            } else {
+                                " "
+                            },
+                            group[0],
+                            if staged { " *" } else { "" }
+                        );
+                        group
+                    })
+                    .collect::<Vec<_>>();
+
                 render_view_list_popup(
                     frame,
                     area,
                     popup,
                     popup_state,
                     headers,
-                    &state.app_services_list,
+                    &app_autoscaling_list,
                     100,
                     75,
                     true,
@@ -987,15 +2198,316 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 );
             }
 
+            PopupType::ViewAppPortsPopup => {
+                let headers = &["Port", "Addresses", "Machines", "Public Ports", "Status"];
+
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    headers,
+                    &state.app_ports_list,
+                    100,
+                    75,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewDedicatedHostMachinesPopup => {
+                let headers = &["App", "Id", "Name", "State", "Region", "CPUs", "Memory"];
+
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    headers,
+                    &state.dedicated_host_machines_list,
+                    100,
+                    75,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewAppReleasesDiffPopup => {
+                let lines = state
+                    .app_releases_diff_list
+                    .iter()
+                    .map(|row| {
+                        let line = row[0].clone() + " " + row[1].as_str();
+                        match row[0].as_str() {
+                            "@" => Line::from(row[1].clone().fg(Palette::LIGHT_PINK).bold()),
+                            "+" => Line::from(line.fg(Color::LightGreen)),
+                            "-" => Line::from(line.fg(Color::Red)),
+                            "~" => Line::from(row[1].clone().fg(Palette::LIGHT_PURPLE)),
+                            _ => Line::from(line),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let content = Paragraph::new(lines).scroll((state.popup_scroll, 0)).block(
+                    Block::default()
+                        .title(
+                            Line::from(Span::from(&popup_state.message))
+                                .bold()
+                                .fg(Palette::LIGHT_PURPLE),
+                        )
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::vertical(1)),
+                );
+
+                render_popup(
+                    frame,
+                    area,
+                    100,
+                    75,
+                    popup,
+                    content,
+                    None,
+                    String::from(""),
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewMachinesUpdatePopup => {
+                render_view_list_popup(
+                    frame,
+                    area,
+                    popup,
+                    popup_state,
+                    &["Machine", "Status"],
+                    &state.machines_update_list,
+                    60,
+                    50,
+                    true,
+                    None,
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewMachineChecksPopup => {
+                let lines = state
+                    .machine_checks_list
+                    .iter()
+                    .flat_map(|check| {
+                        let status_color = match check[1].as_str() {
+                            "critical" => Color::Red,
+                            "warning" => Color::Yellow,
+                            _ => Color::LightGreen,
+                        };
+                        let mut lines = vec![Line::from(vec![
+                            check[0].clone().fg(Palette::LIGHT_PINK).bold(),
+                            " ".to_span(),
+                            check[1].clone().fg(status_color).bold(),
+                            " ".to_span(),
+                            check[2].clone().fg(Palette::LIGHT_PURPLE),
+                        ])];
+                        lines.extend(check[3].lines().map(|line| Line::from(line.to_string())));
+                        lines.push(Line::default());
+                        lines
+                    })
+                    .collect::<Vec<_>>();
+                let content = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                    Block::default()
+                        .title(
+                            Line::from(Span::from(&popup_state.message))
+                                .bold()
+                                .fg(Palette::LIGHT_PURPLE),
+                        )
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::vertical(1)),
+                );
+
+                render_popup(
+                    frame,
+                    area,
+                    80,
+                    75,
+                    popup,
+                    content,
+                    None,
+                    String::from(""),
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewMachineDetailPopup | PopupType::ViewLogLinePopup => {
+                let detail = match popup_state.popup_type {
+                    PopupType::ViewLogLinePopup => &state.log_line_detail,
+                    _ => &state.machine_detail,
+                };
+                let lines = detail
+                    .iter()
+                    .map(|line| match line.split_once(':') {
+                        Some((key, value)) if !key.trim_start().starts_with("- ") => {
+                            Line::from(vec![
+                                key.fg(Palette::LIGHT_PURPLE),
+                                ":".to_span(),
+                                Span::raw(value),
+                            ])
+                        }
+                        _ => Line::from(line.as_str()),
+                    })
+                    .collect::<Vec<_>>();
+                let content = Paragraph::new(lines).scroll((state.popup_scroll, 0)).block(
+                    Block::default()
+                        .title(
+                            Line::from(Span::from(&popup_state.message))
+                                .bold()
+                                .fg(Palette::LIGHT_PURPLE),
+                        )
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::new(2, 2, 1, 1)),
+                );
+
+                render_popup(
+                    frame,
+                    area,
+                    80,
+                    75,
+                    popup,
+                    content,
+                    None,
+                    String::from(""),
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewMachineExecPopup => {
+                let lines = state
+                    .machine_exec_list
+                    .iter()
+                    .map(|line| match line[0].as_str() {
+                        EXEC_STDERR => Line::from(line[1].clone().fg(Color::Red)),
+                        EXEC_EXIT_CODE => Line::from(vec![
+                            "exit code ".fg(Palette::LIGHT_PURPLE),
+                            if line[1] == "0" {
+                                line[1].clone().fg(Color::LightGreen).bold()
+                            } else {
+                                line[1].clone().fg(Color::Red).bold()
+                            },
+                        ]),
+                        _ => Line::from(line[1].clone()),
+                    })
+                    .collect::<Vec<_>>();
+                let content = Paragraph::new(lines).scroll((state.popup_scroll, 0)).block(
+                    Block::default()
+                        .title(
+                            Line::from(Span::from(&popup_state.message))
+                                .bold()
+                                .fg(Palette::LIGHT_PURPLE),
+                        )
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::new(2, 2, 1, 1)),
+                );
+
+                render_popup(
+                    frame,
+                    area,
+                    80,
+                    75,
+                    popup,
+                    content,
+                    None,
+                    String::from(""),
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
+            PopupType::ViewLogStatsPopup => {
+                let stats = &state.log_stats;
+                let mut lines = vec![
+                    "Per minute".fg(Palette::LIGHT_PINK).bold().into(),
+                    Line::from(
+                        format!(
+                            "{:<8}{:>7}{:>7}{:>7}{:>7}{:>7}",
+                            "Minute", "Error", "Warn", "Info", "Debug", "Trace"
+                        )
+                        .fg(Palette::LIGHT_PURPLE),
+                    ),
+                ];
+                lines.extend(
+                    stats
+                        .per_minute
+                        .iter()
+                        .skip(stats.per_minute.len().saturating_sub(MAX_LOG_STATS_MINUTES))
+                        .map(|(minute, counts)| {
+                            let mut spans = vec![Span::from(format!(
+                                "{:<8}",
+                                format_time_of_day(&minute.with_timezone(&Local))
+                            ))];
+                            spans.extend(counts.iter().enumerate().map(|(i, count)| {
+                                let count = format!("{:>7}", format_number(*count as i64));
+                                match i {
+                                    0 if counts[0] > 0 => count.fg(Color::Red).bold(),
+                                    1 if counts[1] > 0 => count.fg(Color::Yellow),
+                                    _ => Span::from(count),
+                                }
+                            }));
+                            Line::from(spans)
+                        }),
+                );
+                lines.push(Line::default());
+                lines.push("Top errors".fg(Palette::LIGHT_PINK).bold().into());
+                if stats.top_errors.is_empty() {
+                    lines.push(Line::from("No error logs.".fg(Palette::GRAY)));
+                }
+                lines.extend(stats.top_errors.iter().map(|(message, count)| {
+                    Line::from(vec![
+                        format!("{:>6}× ", count).fg(Color::Red).bold(),
+                        message.to_span(),
+                    ])
+                }));
+                let content = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                    Block::default()
+                        .title(
+                            Line::from(Span::from(&popup_state.message))
+                                .bold()
+                                .fg(Palette::LIGHT_PURPLE),
+                        )
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::new(2, 2, 1, 1)),
+                );
+
+                render_popup(
+                    frame,
+                    area,
+                    80,
+                    75,
+                    popup,
+                    content,
+                    None,
+                    String::from(""),
+                    op_actions,
+                    popup_actions,
+                );
+            }
+
             PopupType::ViewCommandsPopup => {
-                let headers = &["Name", "Aliases"];
+                let headers = &["Name", "Aliases", "Arguments", "Description"];
                 let commands_list = COMMANDS
                     .iter()
                     .filter_map(|&cmd_str| {
-                        cmd_str
-                            .parse::<Command>()
-                            .ok()
-                            .map(|cmd| vec![cmd_str.to_string(), cmd.to_aliases().join(", ")])
+                        cmd_str.parse::<Command>().ok().map(|cmd| {
+                            let spec = cmd.spec();
+                            vec![
+                                cmd_str.to_string(),
+                                cmd.to_aliases().join(", "),
+                                spec.usage(),
+                                spec.description.to_string(),
+                            ]
+                        })
                     })
                     .collect::<Vec<Vec<String>>>();
 
@@ -1034,6 +2546,41 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 );
             }
 
+            PopupType::ViewKeybindingsPopup => {
+                if let InputState::KeybindingSearch { input } = &state.input_state {
+                    let search = input.value().to_lowercase();
+                    let rows = keymap(state)
+                        .into_iter()
+                        .filter(|(key, action)| {
+                            key.to_lowercase().contains(&search)
+                                || action.to_lowercase().contains(&search)
+                        })
+                        .map(|(key, action)| {
+                            Row::new([
+                                Cell::from(key.fg(Palette::LIGHT_PURPLE)),
+                                Cell::from(action),
+                            ])
+                        });
+                    let table = Table::new(rows, [Constraint::Length(16), Constraint::Min(0)])
+                        .header(Row::new(
+                            ["Key", "Action"].map(|v| Cell::from(v.fg(Palette::LIGHT_PINK).bold())),
+                        ))
+                        .column_spacing(0);
+                    render_popup(
+                        frame,
+                        area,
+                        60,
+                        75,
+                        popup,
+                        table,
+                        Some(input),
+                        String::from("Search: "),
+                        op_actions,
+                        popup_actions,
+                    );
+                }
+            }
+
             // Default case for other popup types
             _ => {
                 let percent_x = 50;
@@ -1042,7 +2589,7 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                 let mut max_line_width = (area.width as usize) * percent_x / 100_usize;
                 max_line_width = max_line_width.saturating_sub(4);
 
-                let lines = [popup_state.message.to_string()];
+                let lines = popup_state.message.lines().map(str::to_string);
                 let lines: Vec<Line> = lines
                     .into_iter()
                     .flat_map(|v| {
@@ -1072,6 +2619,74 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                         input_label = String::from("Email: ");
                     }
                 }
+                if let (
+                    PopupType::DumpLogsPopup
+                    | PopupType::TeeLogsPopup
+                    | PopupType::CapturePacketsPopup
+                    | PopupType::ExportViewPopup,
+                    InputState::FilePath { input },
+                ) = (&popup_state.popup_type, &state.input_state)
+                {
+                    render_input = Some(input);
+                    input_label = String::from("Path: ");
+                }
+                if let InputState::Confirm { input, .. } = &state.input_state {
+                    render_input = Some(input);
+                    input_label = String::from("Name: ");
+                }
+                if let (PopupType::SetMachinesMetadataPopup, InputState::Metadata { input }) =
+                    (&popup_state.popup_type, &state.input_state)
+                {
+                    render_input = Some(input);
+                    input_label = String::from("Metadata: ");
+                }
+                if let (PopupType::UpdateMachinesImagePopup, InputState::Image { input }) =
+                    (&popup_state.popup_type, &state.input_state)
+                {
+                    render_input = Some(input);
+                    input_label = String::from("Image: ");
+                }
+                if let (PopupType::RenameMachinePopup, InputState::Name { input }) =
+                    (&popup_state.popup_type, &state.input_state)
+                {
+                    render_input = Some(input);
+                    input_label = String::from("Name: ");
+                }
+                if let (
+                    PopupType::ForkVolumePopup | PopupType::FocusLogsRegionPopup,
+                    InputState::Region { input },
+                ) = (&popup_state.popup_type, &state.input_state)
+                {
+                    render_input = Some(input);
+                    input_label = String::from("Region: ");
+                }
+                if let (PopupType::ExecMachinePopup, InputState::ShellCommand { input }) =
+                    (&popup_state.popup_type, &state.input_state)
+                {
+                    render_input = Some(input);
+                    input_label = String::from("$ ");
+                }
+                //INFO: The value is masked, the key is shown as the label while it's entered
+                let masked_value;
+                if let (
+                    PopupType::SetSecretPopup,
+                    InputState::Secret {
+                        key,
+                        value,
+                        value_focused,
+                    },
+                ) = (&popup_state.popup_type, &state.input_state)
+                {
+                    if *value_focused {
+                        masked_value = Input::new("*".repeat(value.value().chars().count()))
+                            .with_cursor(value.cursor());
+                        render_input = Some(&masked_value);
+                        input_label = format!("{}=", key.value().trim());
+                    } else {
+                        render_input = Some(key);
+                        input_label = String::from("Key: ");
+                    }
+                }
 
                 render_popup(
                     frame,
@@ -1085,6 +2700,16 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
                     op_actions,
                     popup_actions,
                 );
+                if state.update_region_picker() {
+                    frame.render_widget(
+                        RegionPickerWidget {
+                            picker: &state.region_picker,
+                            percent_x: percent_x as u16,
+                            percent_y: percent_y as u16,
+                        },
+                        area,
+                    );
+                }
             }
         }
     }
@@ -1092,19 +2717,29 @@ fn render_radar_popup(state: &mut State, frame: &mut Frame, area: Rect) {
 
 /// Renders the user interface widgets.
 pub fn render(state: &mut State, frame: &mut Frame) {
-    if state.splash_shown.load(Ordering::SeqCst) {
+    if state.is_copy_mode() {
+        render_copy_mode(state, frame);
+    } else if state.splash_shown.load(Ordering::SeqCst) {
         let mut main_layout = vec![Constraint::Min(0)];
         if cfg!(debug_assertions) {
             main_layout.push(Constraint::Percentage(40));
         }
         let main_layout = Layout::horizontal(main_layout).split(frame.area());
-        let mut layout = vec![Constraint::Length(8), Constraint::Min(0)];
-        if matches!(
+        let alerts_shown = !state.alerts.is_empty();
+        let input_bar_shown = matches!(
             state.input_state,
             InputState::Command { .. } | InputState::Search { .. }
-        ) {
-            layout.insert(1, Constraint::Length(3));
+        );
+        let mut layout = vec![Constraint::Length(8)];
+        if alerts_shown {
+            layout.push(Constraint::Length(
+                state.alerts.len().min(MAX_ALERT_ROWS) as u16
+            ));
+        }
+        if input_bar_shown {
+            layout.push(Constraint::Length(3));
         }
+        layout.push(Constraint::Min(0));
         let outer = Block::default().bg(Color::Black);
         let outer_area = outer.inner(frame.area());
         frame.render_widget(outer, frame.area());
@@ -1119,11 +2754,11 @@ pub fn render(state: &mut State, frame: &mut Frame) {
         render_debugger(state, frame, main_layout[1]);
 
         render_header(state, frame, layout[0]);
-        if matches!(
-            state.input_state,
-            InputState::Command { .. } | InputState::Search { .. }
-        ) {
-            render_input_bar(state, frame, layout[1]);
+        if alerts_shown {
+            render_alerts(state, frame, layout[1]);
+        }
+        if input_bar_shown {
+            render_input_bar(state, frame, layout[layout.len() - 2]);
         }
         render_current_view(state, frame, layout.last().unwrap().to_owned());
         render_radar_popup(state, frame, outer_area);
@@ -1132,8 +2767,104 @@ pub fn render(state: &mut State, frame: &mut Frame) {
     }
 }
 
+/// Only the log lines and a hint, so the terminal's selection doesn't pick up the borders.
+fn render_copy_mode(state: &State, frame: &mut Frame) {
+    let [logs_area, hint_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let logs = TuiLoggerWidget::default()
+        .style_error(Style::default().fg(Color::Red))
+        .style_debug(Style::default().fg(Color::Green))
+        .style_warn(Style::default().fg(Color::Yellow))
+        .style_trace(Style::default().fg(Color::Magenta))
+        .style_info(Style::default().fg(Color::Cyan))
+        .output_separator(' ')
+        .output_timestamp(Some("%H:%M:%S".to_string()))
+        .output_level(Some(TuiLoggerLevelOutput::Long))
+        .output_target(true)
+        .output_file(false)
+        .output_line(false)
+        .state(&state.logs_state);
+    frame.render_widget(logs, logs_area);
+
+    let key = |actions: &[Action]| keybindings::label(Context::CopyMode, actions);
+    let hint = Line::from(vec![
+        Span::from(" Copy mode ")
+            .bold()
+            .fg(Color::Black)
+            .bg(Palette::PINK),
+        Span::from(" Select the lines with the mouse  ").fg(Palette::LIGHT_PURPLE),
+        Span::from(format!("{}/<Esc>", key(&[Action::CopyMode]))).fg(Palette::PINK),
+        Span::from(" Exit  ").fg(Palette::LIGHT_PURPLE),
+        Span::from(key(&[Action::PreviousPage, Action::NextPage])).fg(Palette::PINK),
+        Span::from(" Scroll").fg(Palette::LIGHT_PURPLE),
+    ]);
+    frame.render_widget(hint, hint_area);
+}
+
 #[cfg(debug_assertions)]
 fn render_debugger(state: &mut State, frame: &mut Frame, area: Rect) {
+    let api_stats = crate::fly_rust::api_stats::api_stats();
+    let captured_calls = if crate::fly_rust::capture::is_capture_enabled() {
+        crate::fly_rust::capture::captured_calls()
+    } else {
+        vec![]
+    };
+    let [api_stats_area, capture_area, area] = Layout::vertical([
+        Constraint::Length(api_stats.len().min(10) as u16 + 3),
+        Constraint::Length(if captured_calls.is_empty() {
+            0
+        } else {
+            captured_calls.len().min(10) as u16 + 2
+        }),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+    let rows = api_stats.iter().map(|(endpoint, stats)| {
+        let style = if stats.failed > 0 {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        Row::new(vec![
+            endpoint.to_string(),
+            stats.succeeded.to_string(),
+            stats.failed.to_string(),
+            format!("{:.0}%", stats.error_rate() * 100.0),
+        ])
+        .style(style)
+    });
+    let api_stats_table = Table::new(
+        rows,
+        [
+            Constraint::Min(0),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(6),
+        ],
+    )
+    .header(Row::new(vec!["Endpoint", "Ok", "Failed", "Errors"]).fg(Color::Yellow))
+    .block(Block::bordered().title("API calls"));
+    frame.render_widget(api_stats_table, api_stats_area);
+
+    // The latest captured calls, the full capture is exported with :capture export
+    let lines = captured_calls
+        .iter()
+        .rev()
+        .take(10)
+        .rev()
+        .map(|call| {
+            let line = Line::from(call.to_line());
+            match call.outcome {
+                Ok(status) if status < 400 => line.fg(Color::White),
+                _ => line.fg(Color::Red),
+            }
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("Captured API calls")),
+        capture_area,
+    );
+
     let logger = tui_logger::TuiLoggerWidget::default()
         .block(Block::bordered().title("Debugger"))
         .output_separator('|')
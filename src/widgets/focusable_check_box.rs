@@ -7,7 +7,7 @@ use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::WidgetRef;
 
-use super::focusable_widget::FocusableWidget;
+use super::focusable_widget::{focus_marker, FocusableWidget};
 use crate::ui::Palette;
 
 #[derive(Debug, Clone, Focus)]
@@ -59,7 +59,7 @@ impl WidgetRef for CheckBox {
         };
 
         let content = Span::styled(&self.content, style);
-        let check_box = Line::from(vec![checkbox, content]);
+        let check_box = Line::from(vec![focus_marker(self.is_focused), checkbox, content]);
         check_box.render_ref(area, buf);
     }
 }
@@ -0,0 +1,79 @@
+use std::any::Any;
+
+use crossterm::event::{Event as CrostermEvent, KeyEvent};
+use focusable::Focus;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::WidgetRef;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::{Input, InputRequest};
+use unicode_width::UnicodeWidthStr;
+
+use super::focusable_widget::{focus_marker, FocusableWidget};
+use crate::ui::Palette;
+
+/// Width the value is padded to, so the focused empty fields are visible
+const MIN_VALUE_WIDTH: usize = 24;
+
+#[derive(Debug, Clone, Focus)]
+pub struct InputField {
+    pub is_focused: bool,
+    pub label: String,
+    pub input: Input,
+}
+
+impl InputField {
+    pub fn new(label: &str, value: &str) -> Self {
+        Self {
+            is_focused: false,
+            label: label.to_string(),
+            input: Input::new(value.to_string()),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        self.input.value().trim()
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) {
+        self.input.handle_event(&CrostermEvent::Key(key_event));
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.input.handle(InputRequest::InsertChar(c));
+        }
+    }
+}
+
+impl FocusableWidget for InputField {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl WidgetRef for InputField {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let style = if self.is_focused {
+            Style::new().bg(Palette::LIGHT_PURPLE).underlined().bold()
+        } else {
+            Style::new().white().on_black().underlined()
+        };
+        let value = self.input.value();
+        let padding = " ".repeat(MIN_VALUE_WIDTH.saturating_sub(value.width()));
+        let field = Line::from(vec![
+            focus_marker(self.is_focused),
+            Span::styled(
+                format!("{}: ", self.label),
+                Style::new().fg(Palette::LIGHT_PINK),
+            ),
+            Span::styled(format!("{}{}", value, padding), style),
+        ]);
+        field.render_ref(area, buf);
+    }
+}
@@ -0,0 +1,136 @@
+use std::any::Any;
+
+use crossterm::event::{Event as CrostermEvent, KeyCode, KeyEvent};
+use focusable::Focus;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::WidgetRef;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::{Input, InputRequest};
+use unicode_width::UnicodeWidthStr;
+
+use super::focusable_widget::{focus_marker, FocusableWidget};
+use crate::ui::{glyph, Palette};
+
+/// Width the value is padded to, narrower than the text fields as the numbers are short
+const MIN_VALUE_WIDTH: usize = 8;
+
+/// Number typed in or spun with the arrow keys, kept within its bounds.
+#[derive(Debug, Clone, Focus)]
+pub struct NumberField {
+    pub is_focused: bool,
+    pub label: String,
+    pub input: Input,
+    pub min: u32,
+    pub max: u32,
+    pub step: u32,
+}
+
+impl NumberField {
+    /// Starts empty, so a value has to be typed or spun before it passes.
+    pub fn new(label: &str, min: u32, max: u32) -> Self {
+        Self {
+            is_focused: false,
+            label: label.to_string(),
+            input: Input::default(),
+            min,
+            max,
+            step: 1,
+        }
+    }
+
+    pub fn with_value(mut self, value: u32) -> Self {
+        self.input = Input::new(value.clamp(self.min, self.max).to_string());
+        self
+    }
+
+    pub fn with_step(mut self, step: u32) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    /// The value if it's within the bounds, otherwise why it isn't.
+    pub fn value(&self) -> Result<u32, String> {
+        self.input
+            .value()
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|value| (self.min..=self.max).contains(value))
+            .ok_or_else(|| {
+                format!(
+                    "{} must be between {} and {}.",
+                    self.label, self.min, self.max
+                )
+            })
+    }
+
+    /// Moves the value by a step, an empty or invalid value starts from the closest bound.
+    pub fn spin(&mut self, up: bool) {
+        let value = match self.input.value().trim().parse::<u32>() {
+            Ok(value) if up => value.saturating_add(self.step),
+            Ok(value) => value.saturating_sub(self.step),
+            Err(_) if up => self.min,
+            Err(_) => self.max,
+        };
+        self.input = Input::new(value.clamp(self.min, self.max).to_string());
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Up => self.spin(true),
+            KeyCode::Down => self.spin(false),
+            KeyCode::Char(c) if !c.is_ascii_digit() => {}
+            _ => {
+                self.input.handle_event(&CrostermEvent::Key(key_event));
+            }
+        }
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        for c in text.chars().filter(char::is_ascii_digit) {
+            self.input.handle(InputRequest::InsertChar(c));
+        }
+    }
+}
+
+impl FocusableWidget for NumberField {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl WidgetRef for NumberField {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let is_valid = self.value().is_ok();
+        let style = match (self.is_focused, is_valid) {
+            (true, true) => Style::new().bg(Palette::LIGHT_PURPLE).underlined().bold(),
+            (true, false) => Style::new().bg(Color::Red).underlined().bold(),
+            (false, true) => Style::new().white().on_black().underlined(),
+            (false, false) => Style::new().red().on_black().underlined(),
+        };
+        let value = self.input.value();
+        let padding = " ".repeat(MIN_VALUE_WIDTH.saturating_sub(value.width()));
+        let mut spans = vec![
+            focus_marker(self.is_focused),
+            Span::styled(
+                format!("{}: ", self.label),
+                Style::new().fg(Palette::LIGHT_PINK),
+            ),
+            Span::styled(format!("{}{}", value, padding), style),
+            Span::styled(format!(" {}-{}", self.min, self.max), Style::new().dim()),
+        ];
+        if self.is_focused {
+            spans.push(Span::styled(
+                format!(" {}", glyph("↑/↓", "Up/Down")),
+                Style::new().dim(),
+            ));
+        }
+        Line::from(spans).render_ref(area, buf);
+    }
+}
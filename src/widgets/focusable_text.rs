@@ -41,6 +41,12 @@ impl WidgetRef for TextBox {
         } else {
             Style::new().white().on_black()
         };
-        Span::styled(&self.content, style).render_ref(area, buf);
+        //INFO: Bracketed when focused, so the focus shows without the colors too
+        let content = if self.is_focused {
+            format!("[{}]", self.content)
+        } else {
+            format!(" {} ", self.content)
+        };
+        Span::styled(content, style).render_ref(area, buf);
     }
 }
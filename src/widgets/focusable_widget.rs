@@ -2,8 +2,12 @@ use std::any::Any;
 use std::fmt::Debug;
 
 use focusable::Focus;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Span;
 use ratatui::widgets::WidgetRef;
 
+use crate::ui::{glyph, Palette};
+
 pub trait FocusableWidget: Debug + WidgetRef + Focus + Send + Any {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
@@ -14,3 +18,15 @@ pub trait FocusableWidget: Debug + WidgetRef + Focus + Send + Any {
         Box::new(self)
     }
 }
+
+/// Marks the focused field of a form, so the focus shows without the colors too.
+pub fn focus_marker(is_focused: bool) -> Span<'static> {
+    if is_focused {
+        Span::styled(
+            glyph("▸ ", "> "),
+            Style::new().fg(Palette::LIGHT_PURPLE).bold(),
+        )
+    } else {
+        Span::raw("  ")
+    }
+}
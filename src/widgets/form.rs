@@ -15,6 +15,21 @@ impl Form {
     pub fn reset_focus(&mut self) {
         self.children.iter_mut().for_each(|c| c.blur());
     }
+    /// Moves the focus to the next or the previous child, wrapping around at the ends so the
+    /// focus never leaves the form.
+    pub fn cycle_focus(&mut self, forward: bool) {
+        let len = self.children.len();
+        if len == 0 {
+            return;
+        }
+        let next = match self.children.iter().position(|c| c.is_focused()) {
+            Some(index) if forward => (index + 1) % len,
+            Some(index) => (index + len - 1) % len,
+            None => 0,
+        };
+        self.reset_focus();
+        self.children[next].focus();
+    }
 }
 
 impl FromIterator<Box<dyn FocusableWidget>> for Form {
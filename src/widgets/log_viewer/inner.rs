@@ -1,11 +1,16 @@
+use std::collections::BTreeSet;
+use std::ops::Bound;
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
 use parking_lot::Mutex;
+use regex::Regex;
 
 use crate::widgets::log_viewer::file::TuiLoggerFile;
+use crate::widgets::log_viewer::json_columns::JsonColumns;
 use crate::widgets::log_viewer::{
     set_level_for_target, CircularBuffer, ExtLogRecord, LevelConfig, LevelFilter, TuiWidgetEvent,
+    TUI_LOGGER,
 };
 
 pub struct TuiLoggerInner {
@@ -15,6 +20,7 @@ pub struct TuiLoggerInner {
     pub total_events: usize,
     pub default: LevelFilter,
     pub targets: LevelConfig,
+    pub json_columns: JsonColumns,
 }
 
 /// This struct contains the shared state of a TuiLoggerWidget and a TuiLoggerTargetWidget.
@@ -40,6 +46,78 @@ impl TuiWidgetState {
     pub fn transition(&mut self, event: TuiWidgetEvent) {
         self.inner.lock().transition(event);
     }
+    /// Highlights the matches of the search, starting over from the bottom.
+    pub fn set_search(&mut self, search: Option<Regex>) {
+        let mut inner = self.inner.lock();
+        inner.search = search;
+        inner.search_match = None;
+    }
+    /// Focuses the logs on the target, or shows all of them without one. Returns false if there
+    /// are no logs of the target.
+    pub fn focus_target(&mut self, target: Option<&str>) -> bool {
+        let mut inner = self.inner.lock();
+        let Some(target) = target else {
+            inner.focus_selected = false;
+            return true;
+        };
+        //INFO: The target selector keeps the targets sorted and picks the selected one by index
+        let mut targets = TUI_LOGGER
+            .inner
+            .lock()
+            .targets
+            .keys()
+            .filter(|t| !(inner.hide_off && inner.config.get(t) == Some(LevelFilter::Off)))
+            .cloned()
+            .collect::<Vec<_>>();
+        targets.sort();
+        let Some(index) = targets.iter().position(|t| t == target) else {
+            return false;
+        };
+        inner.selected = index;
+        inner.opt_selected_target = Some(target.to_string());
+        inner.focus_selected = true;
+        true
+    }
+    pub fn has_search(&self) -> bool {
+        self.inner.lock().search.is_some()
+    }
+    /// Details of the line at the bottom of the logs, which is the match once jumped to one.
+    pub fn bottom_line_detail(&self) -> Option<Vec<String>> {
+        let inner = self.inner.lock();
+        let tui_lock = TUI_LOGGER.inner.lock();
+        let detail = tui_lock
+            .events
+            .rev_iter()
+            .filter(|evt| inner.shows(evt))
+            .find(|evt| {
+                inner
+                    .opt_timestamp_bottom
+                    .map_or(true, |ts| evt.timestamp <= ts)
+            })
+            .map(ExtLogRecord::detail);
+        detail
+    }
+    /// Holds the logs at the newest shown line so the new ones don't scroll them. Returns false
+    /// if they were held already, like when scrolled up.
+    pub fn hold(&mut self) -> bool {
+        let mut inner = self.inner.lock();
+        if inner.opt_timestamp_bottom.is_some() {
+            return false;
+        }
+        let newest = TUI_LOGGER
+            .inner
+            .lock()
+            .events
+            .rev_iter()
+            .find(|evt| inner.shows(evt))
+            .map(|evt| evt.timestamp);
+        inner.opt_timestamp_bottom = newest;
+        true
+    }
+    /// Machine the logs are filtered to, if any.
+    pub fn focused_instance(&self) -> Option<String> {
+        self.inner.lock().focus_instance.clone()
+    }
 }
 
 #[derive(Default)]
@@ -59,11 +137,100 @@ pub struct TuiWidgetInnerState {
     pub hide_off: bool,
     pub hide_target: bool,
     pub focus_selected: bool,
+    // Shows the JSON messages as they are instead of in columns
+    pub show_raw: bool,
+    pub search: Option<Regex>,
+    // Timestamp of the match jumped to last
+    pub search_match: Option<DateTime<Utc>>,
+    // Only the logs of this machine are shown, on top of the region focus
+    pub focus_instance: Option<String>,
 }
 impl TuiWidgetInnerState {
     pub fn new() -> TuiWidgetInnerState {
         TuiWidgetInnerState::default()
     }
+    /// Whether the event passes the display level of its target and the focus.
+    pub(super) fn shows(&self, evt: &ExtLogRecord) -> bool {
+        if let Some(level) = self.config.get(&evt.target) {
+            if level < evt.level {
+                return false;
+            }
+        } else if let Some(level) = self.config.default_display_level {
+            if level < evt.level {
+                return false;
+            }
+        }
+        if self.focus_selected {
+            if let Some(target) = self.opt_selected_target.as_ref() {
+                if target != &evt.target {
+                    return false;
+                }
+            }
+        }
+        if let Some(instance) = self.focus_instance.as_ref() {
+            if instance != &evt.instance {
+                return false;
+            }
+        }
+        true
+    }
+    /// Filters to the next machine seen in the buffered logs, after the last one it shows all
+    /// of them again.
+    fn cycle_instance(&mut self) {
+        let instances = TUI_LOGGER
+            .inner
+            .lock()
+            .events
+            .iter()
+            .filter(|evt| !evt.instance.is_empty())
+            .map(|evt| evt.instance.clone())
+            .collect::<BTreeSet<_>>();
+        self.focus_instance = match self.focus_instance.as_ref() {
+            Some(current) => instances
+                .range::<String, _>((Bound::Excluded(current), Bound::Unbounded))
+                .next()
+                .cloned(),
+            None => instances.into_iter().next(),
+        };
+        self.search_match = None;
+    }
+    /// Pages to the next older match of the search, or to the next newer one. Going past the
+    /// newest match returns to the bottom.
+    fn jump_to_match(&mut self, older: bool) {
+        let Some(search) = self.search.as_ref() else {
+            return;
+        };
+        let tui_lock = TUI_LOGGER.inner.lock();
+        let is_match = |evt: &&ExtLogRecord| self.shows(evt) && search.is_match(&evt.msg);
+        let found = if older {
+            tui_lock
+                .events
+                .rev_iter()
+                .filter(|evt| self.search_match.map_or(true, |ts| evt.timestamp < ts))
+                .find(is_match)
+        } else {
+            let Some(current) = self.search_match else {
+                return;
+            };
+            tui_lock
+                .events
+                .iter()
+                .filter(|evt| evt.timestamp > current)
+                .find(is_match)
+        }
+        .map(|evt| evt.timestamp);
+        match found {
+            Some(timestamp) => {
+                self.search_match = Some(timestamp);
+                self.opt_timestamp_bottom = Some(timestamp);
+            }
+            None if !older => {
+                self.search_match = None;
+                self.opt_timestamp_bottom = None;
+            }
+            None => {}
+        }
+    }
     fn transition(&mut self, event: TuiWidgetEvent) {
         use TuiWidgetEvent::*;
         match event {
@@ -76,6 +243,12 @@ impl TuiWidgetInnerState {
             FocusKey => {
                 self.focus_selected ^= true;
             }
+            RawKey => {
+                self.show_raw ^= true;
+            }
+            InstanceKey => self.cycle_instance(),
+            NextMatchKey => self.jump_to_match(true),
+            PrevMatchKey => self.jump_to_match(false),
             UpKey => {
                 if !self.hide_target && self.selected > 0 {
                     self.selected -= 1;
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use crate::logs::entry::JsonMessage;
+
+/// Wider values are shown as they are instead of widening their column further.
+const MAX_COLUMN_WIDTH: usize = 40;
+const COLUMN_SEPARATOR: &str = "  ";
+
+/// Widths of the message and of every key of the JSON logs seen so far, so that the same keys
+/// line up across the lines without the columns jumping around between frames.
+#[derive(Default)]
+pub struct JsonColumns {
+    msg: usize,
+    fields: HashMap<String, usize>,
+}
+
+impl JsonColumns {
+    pub fn record(&mut self, json: &JsonMessage) {
+        self.msg = self.msg.max(json.msg.chars().count().min(MAX_COLUMN_WIDTH));
+        for (key, value) in &json.fields {
+            let width = self.fields.entry(key.clone()).or_default();
+            *width = (*width).max(field_width(key, value).min(MAX_COLUMN_WIDTH));
+        }
+    }
+
+    pub fn format(&self, json: &JsonMessage) -> String {
+        let mut columns = vec![format!("{:<width$}", json.msg, width = self.msg)];
+        columns.extend(json.fields.iter().map(|(key, value)| {
+            let width = self.fields.get(key).copied().unwrap_or_default();
+            let padding = width.saturating_sub(field_width(key, value));
+            format!("{}={}{}", key, value, " ".repeat(padding))
+        }));
+        columns.join(COLUMN_SEPARATOR).trim_end().to_string()
+    }
+}
+
+fn field_width(key: &str, value: &str) -> usize {
+    key.chars().count() + 1 + value.chars().count()
+}
@@ -1,34 +1,38 @@
 mod circular_buffer;
 mod inner;
+mod json_columns;
 mod smart;
 mod standard;
 
 use std::collections::hash_map::{Iter, Keys};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Error;
 use std::path::PathBuf;
 use std::sync::{mpsc, Arc};
 use std::{cmp, mem, thread};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, Local, TimeDelta, Utc};
 use circular_buffer::CircularBuffer;
 pub use inner::TuiWidgetState;
 use inner::{TuiLoggerInner, TuiWidgetInnerState};
+use json_columns::JsonColumns;
 use lazy_static::lazy_static;
 use parking_lot::Mutex;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Widget};
 // use log::{Log, Metadata, Record, SetLoggerError};
-use ratatui::{
-    buffer::Buffer,
-    layout::Rect,
-    style::{Modifier, Style},
-    widgets::{Block, Widget},
-};
+use serde::{Deserialize, Serialize};
 pub use smart::TuiLoggerSmartWidget;
 pub use standard::TuiLoggerWidget;
 use strip_ansi_escapes::strip;
 use tracing::info;
 
-use crate::logs::entry::{Error as SetLoggerError, Event, LogEntry as Record, Meta};
+use crate::config::LogDumpFormat;
+use crate::logs::entry::{
+    map_level, Error as SetLoggerError, Event, JsonMessage, LogEntry as Record, Meta,
+};
 
 pub mod file;
 pub use file::TuiLoggerFile;
@@ -73,7 +77,8 @@ impl PartialOrd<LevelFilter> for Level {
     }
 }
 #[repr(usize)]
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LevelFilter {
     Off,
     Error,
@@ -130,9 +135,73 @@ struct ExtLogRecord {
     level: Level,
     target: String,
     msg: String,
+    // Set when the message is a JSON object
+    json: Option<JsonMessage>,
     meta: Meta,
 }
 
+impl ExtLogRecord {
+    /// Rows of the detail popup as `key: value`, the nested ones indented under their key.
+    fn detail(&self) -> Vec<String> {
+        let mut rows = vec![
+            format!(
+                "timestamp: {}",
+                self.timestamp.with_timezone(&Local).to_rfc3339()
+            ),
+            format!("level: {}", format!("{:?}", self.level).to_lowercase()),
+        ];
+        if !self.instance.is_empty() {
+            rows.push(format!("machine: {}", self.instance));
+        }
+        if !self.target.is_empty() {
+            rows.push(format!("region: {}", self.target));
+        }
+        if !self.meta.event.provider.is_empty() {
+            rows.push(format!("provider: {}", self.meta.event.provider));
+        }
+        if let Some(http) = &self.meta.http {
+            rows.push(String::from("http:"));
+            rows.push(format!("  method: {}", http.request.method));
+            rows.push(format!("  status: {}", http.response.status_code));
+            if let Some(path) = self
+                .meta
+                .url
+                .as_ref()
+                .and_then(|url| reqwest::Url::parse(&url.full).ok())
+            {
+                rows.push(format!("  path: {}", path.path()));
+            }
+            rows.push(format!("  version: {}", http.request.version));
+            rows.push(format!("  request_id: {}", http.request.id));
+        }
+        if let Some(url) = &self.meta.url {
+            rows.push(format!("url: {}", url.full));
+        }
+        if let Some(error) = &self.meta.error {
+            rows.push(String::from("error:"));
+            rows.push(format!("  code: {}", error.code));
+            rows.push(format!("  message: {}", error.message));
+        }
+        let msg = match &self.json {
+            Some(json) => {
+                if !json.fields.is_empty() {
+                    rows.push(String::from("fields:"));
+                    rows.extend(
+                        json.fields
+                            .iter()
+                            .map(|(key, value)| format!("  {}: {}", key, value)),
+                    );
+                }
+                &json.msg
+            }
+            None => &self.msg,
+        };
+        rows.push(String::from("message:"));
+        rows.extend(msg.lines().map(|line| format!("  {}", line)));
+        rows
+    }
+}
+
 fn advance_levelfilter(levelfilter: LevelFilter) -> (Option<LevelFilter>, Option<LevelFilter>) {
     match levelfilter {
         LevelFilter::Trace => (None, Some(LevelFilter::Debug)),
@@ -277,6 +346,7 @@ impl TuiLogger {
                     elements,
                     total
                 ),
+                json: None,
                 meta: Meta {
                     instance: "".to_string(),
                     region: "".to_string(),
@@ -295,6 +365,9 @@ impl TuiLogger {
             if tli.targets.get(&log_entry.target).is_none() {
                 tli.targets.set(&log_entry.target, default_level);
             }
+            if let Some(json) = &log_entry.json {
+                tli.json_columns.record(json);
+            }
             // if let Some(ref mut file_options) = tli.dump {
             //     let mut output = String::new();
             //     let (lev_long, lev_abbr, with_loc) = match log_entry.level {
@@ -360,6 +433,7 @@ lazy_static! {
             dump: None,
             default: LevelFilter::Info,
             targets: LevelConfig::new(),
+            json_columns: JsonColumns::default(),
         };
         TuiLogger {
             hot_select: Mutex::new(hs),
@@ -443,6 +517,7 @@ pub fn cleanup_logger() {
     tli.events.clear();
     tli.total_events = 0;
     tli.targets = LevelConfig::new();
+    tli.json_columns = JsonColumns::default();
     hl.events.clear();
     hs.hashtable.clear();
 }
@@ -450,7 +525,7 @@ pub fn cleanup_logger() {
 // INFO: making this part of inner state and dumping whats on the screen (display filter+focus) would require me to make this part of shared state at app side (behind arcmutex) which would lead to holding the lock on every transition that disregards the whole point of double buffering. but I prob. don't need double buffering for flyradar.
 // TODO: I prob don't need the double buffering of tui-logger for this app.
 // INFO: the only async func here cuz i dont want to block tokio's thread pool while dumping.
-pub async fn dump_logs(file_path: &PathBuf) -> Result<(), Error> {
+pub async fn dump_logs(file_path: &PathBuf, format: LogDumpFormat) -> Result<(), Error> {
     let file = tokio::fs::OpenOptions::new()
         .create(true)
         .truncate(true)
@@ -487,6 +562,9 @@ pub async fn dump_logs(file_path: &PathBuf) -> Result<(), Error> {
                 };
 
                 should_dump.then(|| {
+                    if format == LogDumpFormat::Ndjson {
+                        return ndjson_line(evt);
+                    }
                     let mut output = String::new();
                     let (lev_long, lev_abbr) = match evt.level {
                         Level::Error => ("ERROR", "E"),
@@ -534,6 +612,62 @@ pub async fn dump_logs(file_path: &PathBuf) -> Result<(), Error> {
     Ok(())
 }
 
+/// The record as the log entry it came from, with its metadata, on a line of its own.
+fn ndjson_line(evt: &ExtLogRecord) -> String {
+    let entry = Record {
+        level: format!("{:?}", evt.level).to_lowercase(),
+        instance: evt.instance.clone(),
+        message: evt.msg.clone(),
+        region: evt.target.clone(),
+        timestamp: evt.timestamp.to_rfc3339(),
+        meta: evt.meta.clone(),
+    };
+    let mut line = serde_json::to_string(&entry).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+/// Counts of the buffered logs by minute and level, with the most frequent error messages.
+#[derive(Debug, Default)]
+pub struct LogStats {
+    /// Counts of the error, warn, info, debug and trace logs of each minute, oldest first
+    pub per_minute: Vec<(DateTime<Utc>, [usize; 5])>,
+    /// Error messages with their counts, most frequent first
+    pub top_errors: Vec<(String, usize)>,
+}
+
+pub fn log_stats(top_errors: usize) -> LogStats {
+    let tli = TUI_LOGGER.inner.lock();
+    let mut per_minute: BTreeMap<DateTime<Utc>, [usize; 5]> = BTreeMap::new();
+    let mut errors: HashMap<&str, usize> = HashMap::new();
+    // Skip the records of lost events
+    for evt in tli.events.iter().filter(|evt| evt.target != "TuiLogger") {
+        let minute = evt
+            .timestamp
+            .duration_trunc(TimeDelta::minutes(1))
+            .unwrap_or(evt.timestamp);
+        per_minute.entry(minute).or_default()[evt.level as usize - 1] += 1;
+        if evt.level == Level::Error {
+            *errors.entry(evt.msg.as_str()).or_default() += 1;
+        }
+    }
+    let mut errors = errors.into_iter().collect::<Vec<_>>();
+    errors.sort_by(|(msg1, count1), (msg2, count2)| count2.cmp(count1).then(msg1.cmp(msg2)));
+    LogStats {
+        per_minute: per_minute.into_iter().collect(),
+        top_errors: errors
+            .into_iter()
+            .take(top_errors)
+            .map(|(msg, count)| (msg.to_string(), count))
+            .collect(),
+    }
+}
+
+/// Count of the events moved to the buffer so far, changes whenever there are new logs to show.
+pub fn total_log_events() -> usize {
+    TUI_LOGGER.inner.lock().total_events
+}
+
 /// Set the depth of the hot buffer in order to avoid message loss.
 /// This is effective only after a call to move_events()
 pub fn set_hot_buffer_depth(depth: usize) {
@@ -567,14 +701,21 @@ pub fn set_level_for_target(target: &str, levelfilter: LevelFilter) {
 
 impl TuiLogger {
     fn raw_log(&self, record: &Record) {
+        let msg = String::from_utf8_lossy(&strip(record.message.as_bytes())).to_string();
+        let json = JsonMessage::parse(&msg);
         let log_entry = ExtLogRecord {
             timestamp: DateTime::parse_from_rfc3339(&record.timestamp)
                 .unwrap()
                 .with_timezone(&Utc),
             instance: record.instance.clone(),
-            level: record.map_level(),
+            //INFO: The level of a JSON message wins over the one the platform assigned
+            level: json
+                .as_ref()
+                .and_then(|json| json.level.as_deref())
+                .map_or_else(|| record.map_level(), map_level),
             target: record.region.clone(),
-            msg: String::from_utf8_lossy(&strip(record.message.as_bytes())).to_string(),
+            msg,
+            json,
             meta: Meta {
                 instance: record.meta.instance.clone(),
                 region: record.region.clone(),
@@ -636,6 +777,10 @@ pub enum TuiWidgetEvent {
     MinusKey,
     HideKey,
     FocusKey,
+    RawKey,
+    InstanceKey,
+    NextMatchKey,
+    PrevMatchKey,
     PrevPageKey,
     NextPageKey,
     EscapeKey,
@@ -4,6 +4,7 @@ use parking_lot::Mutex;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::Style;
+use ratatui::symbols::border;
 use ratatui::text::Line;
 use ratatui::widgets::{Block, BorderType, Borders, Padding, Widget};
 
@@ -22,7 +23,7 @@ pub struct TuiLoggerSmartWidget<'a> {
     title_target: Line<'a>,
     style: Option<Style>,
     border_style: Style,
-    border_type: BorderType,
+    border_set: border::Set,
     highlight_style: Option<Style>,
     style_error: Option<Style>,
     style_warn: Option<Style>,
@@ -48,7 +49,7 @@ impl Default for TuiLoggerSmartWidget<'_> {
             title_target: Line::from("Tui Target Selector"),
             style: None,
             border_style: Style::default(),
-            border_type: BorderType::Plain,
+            border_set: border::PLAIN,
             highlight_style: None,
             style_error: None,
             style_warn: None,
@@ -78,7 +79,11 @@ impl<'a> TuiLoggerSmartWidget<'a> {
         self
     }
     pub fn border_type(mut self, border_type: BorderType) -> Self {
-        self.border_type = border_type;
+        self.border_set = border_type.to_border_set();
+        self
+    }
+    pub fn border_set(mut self, border_set: border::Set) -> Self {
+        self.border_set = border_set;
         self
     }
     pub fn style(mut self, style: Style) -> Self {
@@ -229,7 +234,7 @@ impl Widget for TuiLoggerSmartWidget<'_> {
                     Block::default()
                         .title(title_log)
                         .border_style(self.border_style)
-                        .border_type(self.border_type)
+                        .border_set(self.border_set)
                         .borders(Borders::ALL),
                 )
                 .opt_style(self.style)
@@ -278,7 +283,7 @@ impl Widget for TuiLoggerSmartWidget<'_> {
                         .padding(Padding::horizontal(1))
                         .title(self.title_target)
                         .border_style(self.border_style)
-                        .border_type(self.border_type)
+                        .border_set(self.border_set)
                         .borders(Borders::ALL),
                 )
                 .opt_style(self.style)
@@ -293,7 +298,7 @@ impl Widget for TuiLoggerSmartWidget<'_> {
                     Block::default()
                         .title(title_log)
                         .border_style(self.border_style)
-                        .border_type(self.border_type)
+                        .border_set(self.border_set)
                         .borders(Borders::ALL),
                 )
                 .opt_style(self.style)
@@ -4,8 +4,9 @@ use chrono::Local;
 use parking_lot::Mutex;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Style;
+use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Block, Widget};
+use unicode_width::UnicodeWidthStr;
 
 use super::inner::TuiWidgetInnerState;
 use crate::widgets::log_viewer::{
@@ -22,12 +23,16 @@ pub struct TuiLoggerWidget<'b> {
     style_debug: Option<Style>,
     style_trace: Option<Style>,
     style_info: Option<Style>,
+    /// Style of the matches of the search
+    style_match: Style,
     format_separator: char,
     format_timestamp: Option<String>,
     format_output_level: Option<TuiLoggerLevelOutput>,
     format_output_target: bool,
     format_output_file: bool,
     format_output_line: bool,
+    /// Only the logs at this level or more severe, see `pinned_level`
+    pinned_level: Option<Level>,
     state: Arc<Mutex<TuiWidgetInnerState>>,
 }
 impl<'b> Default for TuiLoggerWidget<'b> {
@@ -41,12 +46,14 @@ impl<'b> Default for TuiLoggerWidget<'b> {
             style_debug: None,
             style_trace: None,
             style_info: None,
+            style_match: Style::new().add_modifier(Modifier::REVERSED),
             format_separator: ':',
             format_timestamp: Some("%H:%M:%S".to_string()),
             format_output_level: Some(TuiLoggerLevelOutput::Long),
             format_output_target: true,
             format_output_file: true,
             format_output_line: true,
+            pinned_level: None,
             state: Arc::new(Mutex::new(TuiWidgetInnerState::new())),
         }
     }
@@ -200,6 +207,12 @@ impl<'b> TuiLoggerWidget<'b> {
         self.format_output_line = enabled;
         self
     }
+    /// Only shows the logs at the level or more severe and always the newest of them, leaving the
+    /// paging of the shared state to the widget showing all the logs.
+    pub fn pinned_level(mut self, level: Level) -> Self {
+        self.pinned_level = Some(level);
+        self
+    }
     pub fn inner_state(mut self, state: Arc<Mutex<TuiWidgetInnerState>>) -> Self {
         self.state = state;
         self
@@ -301,30 +314,20 @@ impl Widget for TuiLoggerWidget<'_> {
         }
 
         let mut state = self.state.lock();
+        let is_paging = self.pinned_level.is_none();
         let la_height = list_area.height as usize;
         let mut lines: Vec<(Option<Style>, u16, String)> = vec![];
         {
-            state.opt_timestamp_next_page = None;
-            let opt_timestamp_bottom = state.opt_timestamp_bottom;
+            let opt_timestamp_bottom = state.opt_timestamp_bottom.filter(|_| is_paging);
+            if is_paging {
+                state.opt_timestamp_next_page = None;
+            }
             let mut opt_timestamp_prev_page = None;
             let tui_lock = TUI_LOGGER.inner.lock();
             let mut circular = CircularBuffer::new(10); // MAGIC constant
             for evt in tui_lock.events.rev_iter() {
-                if let Some(level) = state.config.get(&evt.target) {
-                    if level < evt.level {
-                        continue;
-                    }
-                } else if let Some(level) = state.config.default_display_level {
-                    if level < evt.level {
-                        continue;
-                    }
-                }
-                if state.focus_selected {
-                    if let Some(target) = state.opt_selected_target.as_ref() {
-                        if target != &evt.target {
-                            continue;
-                        }
-                    }
+                if !state.shows(evt) || self.pinned_level.is_some_and(|level| evt.level > level) {
+                    continue;
                 }
                 // Here all filters have been applied,
                 // So check, if user is paging through history
@@ -334,12 +337,16 @@ impl Widget for TuiLoggerWidget<'_> {
                         continue;
                     }
                 }
-                if !circular.is_empty() {
+                if !circular.is_empty() && is_paging {
                     state.opt_timestamp_next_page = circular.take().first().cloned();
                 }
                 let (mut output, col_style) = self.format_event(evt);
-                let mut sublines: Vec<&str> = evt.msg.lines().rev().collect();
-                output.push_str(sublines.pop().unwrap());
+                let msg = match &evt.json {
+                    Some(json) if !state.show_raw => tui_lock.json_columns.format(json),
+                    _ => evt.msg.clone(),
+                };
+                let mut sublines: Vec<&str> = msg.lines().rev().collect();
+                output.push_str(sublines.pop().unwrap_or_default());
                 for subline in sublines {
                     lines.push((col_style, indent, subline.to_string()));
                 }
@@ -351,7 +358,10 @@ impl Widget for TuiLoggerWidget<'_> {
                     opt_timestamp_prev_page = Some(evt.timestamp);
                 }
             }
-            state.opt_timestamp_prev_page = opt_timestamp_prev_page.or(state.opt_timestamp_bottom);
+            if is_paging {
+                state.opt_timestamp_prev_page =
+                    opt_timestamp_prev_page.or(state.opt_timestamp_bottom);
+            }
         }
         let la_left = list_area.left();
         let la_top = list_area.top();
@@ -376,7 +386,7 @@ impl Widget for TuiLoggerWidget<'_> {
             }
         }
 
-        let offset: u16 = if state.opt_timestamp_bottom.is_none() {
+        let offset: u16 = if state.opt_timestamp_bottom.is_none() || !is_paging {
             0
         } else {
             let lines_cnt = wrapped_lines.len();
@@ -384,13 +394,16 @@ impl Widget for TuiLoggerWidget<'_> {
         };
 
         for (i, (sty, left, l)) in wrapped_lines.iter().enumerate() {
-            buf.set_stringn(
-                la_left + left,
-                la_top + i as u16 + offset,
-                l,
-                l.len(),
-                sty.unwrap_or(self.style),
-            );
+            let y = la_top + i as u16 + offset;
+            buf.set_stringn(la_left + left, y, l, l.len(), sty.unwrap_or(self.style));
+            //INFO: Matches split by the wrapping aren't highlighted
+            if let Some(search) = state.search.as_ref() {
+                for found in search.find_iter(l) {
+                    let x = la_left + left + l[..found.start()].width() as u16;
+                    let area = Rect::new(x, y, found.as_str().width() as u16, 1);
+                    buf.set_style(area.intersection(list_area), self.style_match);
+                }
+            }
         }
     }
 }
@@ -1,9 +1,13 @@
 pub mod fly_balloon;
 pub mod fly_visual;
 pub mod focusable_check_box;
+pub mod focusable_input;
+pub mod focusable_number;
 pub mod focusable_text;
 pub mod focusable_widget;
 pub mod form;
 pub mod log_viewer;
 pub mod popup;
+pub mod region_picker;
 pub mod selectable_list;
+pub mod wizard;
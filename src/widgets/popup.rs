@@ -2,6 +2,7 @@ use std::iter::zip;
 
 use ratatui::layout::{Constraint, Direction, Flex, Layout, Rect};
 use ratatui::style::Style;
+use ratatui::symbols::border;
 use ratatui::text::Line;
 use ratatui::widgets::{Block, Borders, Clear, Padding, Widget};
 use ratatui::Frame;
@@ -9,9 +10,9 @@ use tracing::info;
 use tui_input::Input;
 use unicode_width::UnicodeWidthStr;
 
-use super::focusable_check_box::CheckBox;
 use super::focusable_text::TextBox;
-use crate::ui::{render_input, Palette};
+use super::focusable_widget::FocusableWidget;
+use crate::ui::{border_set, render_input, Palette};
 
 pub fn render_popup<C: Widget>(
     frame: &mut Frame,
@@ -22,7 +23,7 @@ pub fn render_popup<C: Widget>(
     main_content: C,
     input: Option<&Input>,
     input_label: String,
-    op_actions: Vec<&CheckBox>,
+    op_actions: Vec<&dyn FocusableWidget>,
     popup_actions: Vec<&TextBox>,
 ) {
     let area = popup_area(area, percent_x, percent_y);
@@ -45,6 +46,7 @@ pub fn render_popup<C: Widget>(
     if let Some(input) = &input {
         let outer = Block::default()
             .borders(Borders::all())
+            .border_set(border_set(border::PLAIN))
             .border_style(Style::new().fg(Palette::BLUE));
         let outer_area = outer.inner(content_layout[1]);
         frame.render_widget(outer, content_layout[1]);
@@ -65,7 +67,7 @@ pub fn render_popup<C: Widget>(
     render_popup_actions(frame, layout[layout.len() - 1], popup_actions);
 }
 
-fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
     let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
     let [area] = vertical.areas(area);
@@ -73,23 +75,24 @@ fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
     area
 }
 
-fn render_op_actions(frame: &mut Frame, area: Rect, actions: Vec<&CheckBox>) {
-    let mut layout = vec![];
-    actions.iter().for_each(|action| {
-        let width = action.content.width();
-        layout.push(Constraint::Length((width + 2) as u16));
-    });
-    let areas = Layout::vertical(layout).flex(Flex::Center).split(area);
+/// The checkboxes and the fields of the form, one per row.
+fn render_op_actions(frame: &mut Frame, area: Rect, actions: Vec<&dyn FocusableWidget>) {
+    let layout = vec![Constraint::Length(1); actions.len()];
+    let areas = Layout::vertical(layout)
+        .spacing(1)
+        .flex(Flex::Center)
+        .split(area);
 
     zip(actions.iter(), areas.iter())
-        .for_each(|(&action, &area)| frame.render_widget(action, area));
+        .for_each(|(&action, &area)| action.render_ref(area, frame.buffer_mut()));
 }
 
 fn render_popup_actions(frame: &mut Frame, area: Rect, actions: Vec<&TextBox>) {
     let mut layout = vec![Constraint::Min(0)];
     actions.iter().for_each(|action| {
+        //INFO: The brackets of the focused action and a space between the actions
         let width = action.content.width();
-        layout.push(Constraint::Length((width + 2) as u16));
+        layout.push(Constraint::Length((width + 3) as u16));
     });
     let areas = Layout::horizontal(layout).flex(Flex::End).split(area);
 
@@ -0,0 +1,136 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::symbols::border;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Widget};
+
+use super::popup::popup_area;
+use crate::transformations::ListRegion;
+use crate::ui::{border_set, glyph, Palette};
+
+/// Matches shown at once, the list scrolls with the selection
+const MAX_ROWS: usize = 6;
+
+/// Regions matching the typed text by their code or city, to pick the region of a resource from.
+#[derive(Debug, Default)]
+pub struct RegionPicker {
+    pub regions: Vec<ListRegion>,
+    query: String,
+    selected: usize,
+}
+
+impl RegionPicker {
+    /// Filters the regions, the selection goes back to the first match when the query changes.
+    pub fn set_query(&mut self, query: &str) {
+        let query = query.trim().to_lowercase();
+        if query != self.query {
+            self.query = query;
+            self.selected = 0;
+        }
+    }
+
+    /// Codes starting with the query come first, then the cities containing it.
+    pub fn matches(&self) -> Vec<&ListRegion> {
+        let (mut by_code, by_name): (Vec<_>, Vec<_>) = self
+            .regions
+            .iter()
+            .filter(|region| {
+                region.code.starts_with(&self.query)
+                    || region.name.to_lowercase().contains(&self.query)
+            })
+            .partition(|region| region.code.starts_with(&self.query));
+        by_code.extend(by_name);
+        by_code
+    }
+
+    pub fn next(&mut self) {
+        let len = self.matches().len();
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.matches().len();
+        if len > 0 {
+            self.selected = self.selected.checked_sub(1).unwrap_or(len - 1);
+        }
+    }
+
+    pub fn selected(&self) -> Option<&ListRegion> {
+        self.matches().get(self.selected).copied()
+    }
+}
+
+/// Shows the matches of the picker right below the popup it picks the region for.
+pub struct RegionPickerWidget<'a> {
+    pub picker: &'a RegionPicker,
+    pub percent_x: u16,
+    pub percent_y: u16,
+}
+
+impl Widget for RegionPickerWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let matches = self.picker.matches();
+        let rows = matches.len().clamp(1, MAX_ROWS);
+        let popup = popup_area(area, self.percent_x, self.percent_y);
+        let height = (rows as u16 + 2).min(area.bottom().saturating_sub(popup.bottom()));
+        if height < 3 {
+            return;
+        }
+        let picker_area = Rect::new(popup.x, popup.bottom(), popup.width, height);
+        let block = Block::default()
+            .borders(Borders::all())
+            .border_set(border_set(border::PLAIN))
+            .border_style(Style::new().fg(Palette::BLUE))
+            .title_bottom(Line::from(" <↑/↓> Select <Tab> Pick ").dim());
+        let list_area = block.inner(picker_area);
+        Clear.render(picker_area, buf);
+        block.render(picker_area, buf);
+
+        if matches.is_empty() {
+            Line::from("No matching regions")
+                .dim()
+                .render(list_area, buf);
+            return;
+        }
+        let offset = (self.picker.selected + 1).saturating_sub(list_area.height as usize);
+        for (i, region) in matches
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(list_area.height as usize)
+        {
+            let is_selected = i == self.picker.selected;
+            let line = Line::from(vec![
+                Span::from(if is_selected {
+                    glyph("▶ ", "> ")
+                } else {
+                    "  "
+                }),
+                Span::from(format!("{:<4}", region.code)).bold(),
+                Span::from(format!(" {}", region.name)),
+                if region.gateway_available {
+                    Span::from(" gateway").fg(Palette::LIGHT_TEAL)
+                } else {
+                    Span::from("")
+                },
+            ]);
+            let line = if is_selected {
+                line.bg(Palette::DARK_PURPLE)
+            } else {
+                line
+            };
+            line.render(
+                Rect::new(
+                    list_area.x,
+                    list_area.y + (i - offset) as u16,
+                    list_area.width,
+                    1,
+                ),
+                buf,
+            );
+        }
+    }
+}
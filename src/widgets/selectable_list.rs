@@ -1,10 +1,47 @@
 use dashmap::DashSet;
 use ratatui::widgets::TableState as State;
 
+/// Parsed search filter. Terms prefixed with `!` exclude the rows matching them, the rest of the
+/// filter is matched as is.
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    pub include: String,
+    pub exclude: Vec<String>,
+}
+
+impl SearchQuery {
+    pub fn parse(search_filter: &str) -> Self {
+        let (exclude, include): (Vec<&str>, Vec<&str>) = search_filter
+            .split_whitespace()
+            .partition(|term| term.len() > 1 && term.starts_with('!'));
+        // Keep the filter intact when there is nothing to exclude so spaces still match
+        let include = if exclude.is_empty() {
+            search_filter.to_string()
+        } else {
+            include.join(" ")
+        };
+        Self {
+            include,
+            exclude: exclude
+                .into_iter()
+                .map(|term| term[1..].to_string())
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, row: &[String]) -> bool {
+        row.iter().any(|s| s.contains(&self.include))
+            && !self
+                .exclude
+                .iter()
+                .any(|term| row.iter().any(|s| s.contains(term)))
+    }
+}
+
 /// List widget with TUI controlled states.
 #[derive(Debug)]
 pub struct SelectableList {
-    /// List items.
+    /// List items. A row can hold more columns than shown so that search matches against them too.
     pub items: Vec<Vec<String>>,
     /// Current filtered items.
     // INFO: Not owning this propagates lifetimes up to the background task which I dont feel like
@@ -19,6 +56,8 @@ pub struct SelectableList {
     /// State that's used when the multi-select mode is on to act on multiple items for certain
     /// use-cases.
     pub multi_select_state: DashSet<String>,
+    /// Ids of the rows that are kept at the top of the list.
+    pub pinned: Vec<String>,
 }
 
 impl Default for SelectableList {
@@ -43,6 +82,7 @@ impl SelectableList {
             search_filter,
             state,
             multi_select_state,
+            pinned: Vec::new(),
         }
     }
 
@@ -65,13 +105,39 @@ impl SelectableList {
         self.state = State::default();
         self.state.select(Some(0));
         self.multi_select_state = DashSet::new();
+        self.pinned = Vec::new();
+    }
+
+    /// Moves the pinned rows to the top, keeping the order of the rest.
+    fn pin_to_top(&self, rows: &mut [Vec<String>]) {
+        rows.sort_by_key(|row| !self.pinned.contains(&row[0]));
+    }
+
+    pub fn is_pinned(&self, row: &[String]) -> bool {
+        self.pinned.contains(&row[0])
+    }
+
+    pub fn set_pinned(&mut self, pinned: Vec<String>) {
+        let selected_id = self.selected().map(|row| row[0].clone());
+        self.pinned = pinned;
+        let mut items = std::mem::take(&mut self.items);
+        let mut filtered_items = std::mem::take(&mut self.filtered_items);
+        self.pin_to_top(&mut items);
+        self.pin_to_top(&mut filtered_items);
+        self.items = items;
+        self.filtered_items = filtered_items;
+        if let Some(id) = selected_id {
+            self.state
+                .select(self.filtered_items.iter().position(|row| row[0] == id));
+        }
     }
 
     pub fn apply_search_filter(&mut self, new_search_filter: &str) {
+        let query = SearchQuery::parse(new_search_filter);
         let new_filtered_items: Vec<Vec<String>> = self
             .items
             .iter()
-            .filter(|&row| row.iter().any(|s| s.contains(new_search_filter)))
+            .filter(|&row| query.matches(row))
             .cloned()
             .collect();
 
@@ -81,10 +147,12 @@ impl SelectableList {
         self.search_filter = new_search_filter.to_string();
     }
 
-    pub fn set_items(&mut self, new_items: Vec<Vec<String>>, prev_selected_id: Option<String>) {
+    pub fn set_items(&mut self, mut new_items: Vec<Vec<String>>, prev_selected_id: Option<String>) {
+        self.pin_to_top(&mut new_items);
+        let query = SearchQuery::parse(&self.search_filter);
         let new_filtered_items: Vec<Vec<String>> = new_items
             .iter()
-            .filter(|&row| row.iter().any(|s| s.contains(&self.search_filter)))
+            .filter(|&row| query.matches(row))
             .cloned()
             .collect();
 
@@ -0,0 +1,149 @@
+use super::focusable_check_box::CheckBox;
+use super::focusable_input::InputField;
+use super::focusable_number::NumberField;
+use super::focusable_text::TextBox;
+use super::focusable_widget::FocusableWidget;
+use super::form::Form;
+
+/// Checks the fields of a step before moving past it, the error is shown under its message.
+pub type Validate = fn(&[Box<dyn FocusableWidget>]) -> Result<(), String>;
+
+#[derive(Debug)]
+pub struct WizardStep {
+    pub message: String,
+    pub fields: Vec<Box<dyn FocusableWidget>>,
+    pub validate: Option<Validate>,
+}
+
+impl WizardStep {
+    pub fn new(message: &str, fields: Vec<Box<dyn FocusableWidget>>) -> Self {
+        Self {
+            message: message.to_string(),
+            fields,
+            validate: None,
+        }
+    }
+
+    pub fn with_validate(mut self, validate: Validate) -> Self {
+        self.validate = Some(validate);
+        self
+    }
+}
+
+/// A form split into pages gone through with Next and Back, ending with a summary of the values
+/// that OK submits.
+///
+/// The fields of the current page live in the popup's form, the rest are kept in their steps.
+#[derive(Debug)]
+pub struct Wizard {
+    steps: Vec<WizardStep>,
+    // The summary once past the last step
+    current: usize,
+}
+
+impl Wizard {
+    pub fn new(steps: Vec<WizardStep>) -> Self {
+        Self { steps, current: 0 }
+    }
+
+    pub fn is_summary(&self) -> bool {
+        self.current == self.steps.len()
+    }
+
+    /// Position shown in the title, the summary counts as the last page.
+    pub fn progress(&self) -> String {
+        format!("{}/{}", self.current + 1, self.steps.len() + 1)
+    }
+
+    /// Message and form of the current page, its fields are moved out of the step.
+    pub fn page(&mut self) -> (String, Form) {
+        let mut buttons: Vec<Box<dyn FocusableWidget>> = vec![TextBox::new("Cancel").boxed()];
+        if self.current > 0 {
+            buttons.push(TextBox::new("Back").boxed());
+        }
+        if self.is_summary() {
+            buttons.push(TextBox::new("OK").boxed());
+            return (self.summary(), Form::new(buttons));
+        }
+        buttons.push(TextBox::new("Next").boxed());
+        let step = &mut self.steps[self.current];
+        let mut children = std::mem::take(&mut step.fields);
+        children.extend(buttons);
+        (step.message.clone(), Form::new(children))
+    }
+
+    /// Puts the fields of the page back into its step and moves to the next or the previous
+    /// page. Doesn't move forward while the step is invalid, the error comes with its message.
+    pub fn turn(&mut self, page: Form, forward: bool) -> (String, Form) {
+        let mut error = None;
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.fields = page
+                .children
+                .into_iter()
+                .filter(|child| !child.as_any().is::<TextBox>())
+                .collect();
+            if forward {
+                error = validate(step).err();
+            }
+        }
+        match (forward, &error) {
+            (true, None) => self.current = (self.current + 1).min(self.steps.len()),
+            (false, _) => self.current = self.current.saturating_sub(1),
+            _ => {}
+        }
+        let (message, form) = self.page();
+        match error {
+            Some(error) => (format!("{}\n{}", message, error), form),
+            None => (message, form),
+        }
+    }
+
+    /// The fields of all the steps, complete once on the summary.
+    pub fn fields(&self) -> impl Iterator<Item = &Box<dyn FocusableWidget>> {
+        self.steps.iter().flat_map(|step| step.fields.iter())
+    }
+
+    fn summary(&self) -> String {
+        let rows = self
+            .fields()
+            .filter_map(|field| describe(field.as_ref()))
+            .map(|(label, value)| format!("{}: {}", label, value));
+        std::iter::once(String::from("Submit with these values?"))
+            .chain(rows)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The bounds of the number fields are checked for every step, before its own validation.
+fn validate(step: &WizardStep) -> Result<(), String> {
+    for field in &step.fields {
+        if let Some(number) = field.as_any().downcast_ref::<NumberField>() {
+            number.value()?;
+        }
+    }
+    step.validate
+        .map_or(Ok(()), |validate| validate(&step.fields))
+}
+
+/// Label and value of a field as listed on the summary.
+fn describe(field: &dyn FocusableWidget) -> Option<(String, String)> {
+    let field = field.as_any();
+    if let Some(input) = field.downcast_ref::<InputField>() {
+        let value = match input.value() {
+            "" => "-",
+            value => value,
+        };
+        Some((input.label.clone(), value.to_string()))
+    } else if let Some(number) = field.downcast_ref::<NumberField>() {
+        Some((
+            number.label.clone(),
+            number.input.value().trim().to_string(),
+        ))
+    } else {
+        field.downcast_ref::<CheckBox>().map(|checkbox| {
+            let value = if checkbox.is_check() { "yes" } else { "no" };
+            (checkbox.content.clone(), value.to_string())
+        })
+    }
+}
@@ -18,6 +18,9 @@ pub struct Peer {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WireGuardState {
     pub peer: Peer,
+    /// The rest of the state flyctl keeps, e.g. the private key, so it survives a rewrite
+    #[serde(flatten)]
+    pub rest: HashMap<String, serde_json::Value>,
 }
 
 pub type WireGuardStates = HashMap<String, WireGuardState>;